@@ -0,0 +1,259 @@
+//! Dispatch of calls into libsamplerate, either to the library linked into the crate or,
+//! with the ``runtime-loading`` feature, to one loaded at runtime.
+//!
+//! Every converter remembers the library it was created with, since a state created by one
+//! copy of libsamplerate must never be passed to another.
+
+#[cfg(feature = "system")]
+use core::ffi::c_void;
+use core::ffi::{c_char, c_double, c_float, c_int, c_short};
+#[cfg(feature = "system")]
+use core::{mem, ptr};
+#[cfg(feature = "runtime-loading")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "runtime-loading")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "runtime-loading")]
+use libloading::Library;
+use samplerate_sys::{SRC_DATA, SRC_STATE};
+
+#[cfg(feature = "runtime-loading")]
+use {Error, ErrorKind, LibraryVersion, MINIMUM_LIBRARY_VERSION, Result};
+
+macro_rules! api {
+    (required { $( $name:ident: fn($($arg:ty),*) -> $ret:ty; )* }
+     optional { $( $opt_name:ident: fn($($opt_arg:ty),*) -> $opt_ret:ty; )* }) => {
+        /// Table of the libsamplerate functions used by the bindings. Optional functions are
+        /// retrieved with the method of the same name, which returns ``None`` if the library
+        /// lacks them.
+        pub(crate) struct Api {
+            $( pub $name: unsafe extern "C" fn($($arg),*) -> $ret, )*
+            $( $opt_name: Option<unsafe extern "C" fn($($opt_arg),*) -> $opt_ret>, )*
+        }
+
+        impl Api {
+            $(
+                pub fn $opt_name(&self)
+                        -> Option<unsafe extern "C" fn($($opt_arg),*) -> $opt_ret> {
+                    #[cfg(feature = "system")]
+                    if ptr::eq(self, &LINKED) {
+                        let symbol = find_linked(concat!(stringify!($opt_name), "\0"));
+                        return if symbol.is_null() {
+                            None
+                        } else {
+                            Some(unsafe {
+                                mem::transmute::<*mut c_void,
+                                                 unsafe extern "C" fn($($opt_arg),*)
+                                                    -> $opt_ret>(symbol)
+                            })
+                        }
+                    }
+                    self.$opt_name
+                }
+            )*
+        }
+
+        /// Functions of the library linked into the crate. Calls through this table are
+        /// resolved at compile time. The optional functions are only linked from the vendored
+        /// sources; the system library may predate them, so they are looked up in it at
+        /// runtime instead.
+        pub(crate) static LINKED: Api = Api {
+            $( $name: samplerate_sys::$name, )*
+            $(
+                #[cfg(not(feature = "system"))]
+                $opt_name: Some(samplerate_sys::$opt_name),
+                #[cfg(feature = "system")]
+                $opt_name: None,
+            )*
+        };
+
+        /// Resolve every function in ``library``.
+        #[cfg(feature = "runtime-loading")]
+        unsafe fn resolve(library: &Library) -> Result<Api> {
+            Ok(Api {
+                $( $name: *library
+                    .get::<unsafe extern "C" fn($($arg),*) -> $ret>(
+                        concat!(stringify!($name), "\0").as_bytes())
+                    .map_err(|_| {
+                        Error::new(ErrorKind::SymbolNotFound { symbol: stringify!($name) })
+                    })?, )*
+                $( $opt_name: library
+                    .get::<unsafe extern "C" fn($($opt_arg),*) -> $opt_ret>(
+                        concat!(stringify!($opt_name), "\0").as_bytes())
+                    .ok().map(|symbol| *symbol), )*
+            })
+        }
+    }
+}
+
+api! {
+    required {
+        src_new: fn(c_int, c_int, *mut c_int) -> *mut SRC_STATE;
+        src_delete: fn(*mut SRC_STATE) -> *mut SRC_STATE;
+        src_process: fn(*mut SRC_STATE, *mut SRC_DATA) -> c_int;
+        src_simple: fn(*mut SRC_DATA, c_int, c_int) -> c_int;
+        src_get_version: fn() -> *const c_char;
+        src_set_ratio: fn(*mut SRC_STATE, c_double) -> c_int;
+        src_get_channels: fn(*mut SRC_STATE) -> c_int;
+        src_reset: fn(*mut SRC_STATE) -> c_int;
+        src_strerror: fn(c_int) -> *const c_char;
+        src_get_name: fn(c_int) -> *const c_char;
+        src_get_description: fn(c_int) -> *const c_char;
+        src_short_to_float_array: fn(*const c_short, *mut c_float, c_int) -> ();
+        src_float_to_short_array: fn(*const c_float, *mut c_short, c_int) -> ();
+        src_int_to_float_array: fn(*const c_int, *mut c_float, c_int) -> ();
+        src_float_to_int_array: fn(*const c_float, *mut c_int, c_int) -> ();
+    }
+    optional {
+        // Added in libsamplerate 0.2.0, and backported to the vendored 0.1.9 by build.rs.
+        src_clone: fn(*mut SRC_STATE, *mut c_int) -> *mut SRC_STATE;
+    }
+}
+
+/// Names of the system libsamplerate DLL, which the process has already loaded.
+#[cfg(all(feature = "system", windows))]
+const SYSTEM_DLL_NAMES: &[&str] = &["libsamplerate-0.dll\0", "samplerate.dll\0"];
+
+/// Look up the function ``name``, which is nul-terminated, in the system libsamplerate that
+/// the crate is linked to, or return null if it lacks the function.
+#[cfg(all(feature = "system", unix))]
+fn find_linked(name: &str) -> *mut c_void {
+    // The library is loaded with the process, so the default search order finds it.
+    unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr() as *const c_char) }
+}
+
+#[cfg(all(feature = "system", windows))]
+fn find_linked(name: &str) -> *mut c_void {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleA(module_name: *const c_char) -> *mut c_void;
+        fn GetProcAddress(module: *mut c_void, proc_name: *const c_char) -> *mut c_void;
+    }
+    for module_name in SYSTEM_DLL_NAMES {
+        let module = unsafe { GetModuleHandleA(module_name.as_ptr() as *const c_char) };
+        if !module.is_null() {
+            return unsafe { GetProcAddress(module, name.as_ptr() as *const c_char) }
+        }
+    }
+    ptr::null_mut()
+}
+
+#[cfg(all(feature = "system", not(any(unix, windows))))]
+fn find_linked(_name: &str) -> *mut c_void {
+    ptr::null_mut()
+}
+
+/// Copy of libsamplerate that converters call into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// The library linked into the crate, vendored or from the system as selected by the
+    /// cargo features.
+    Linked,
+    /// The library loaded with [``load_library``](fn.load_library.html).
+    Loaded,
+}
+
+#[cfg(feature = "runtime-loading")]
+struct Loaded {
+    api: Api,
+    version: Option<LibraryVersion>,
+    /// The path it was requested from, or ``None`` if it was found under its usual names.
+    path: Option<PathBuf>,
+    // Never unloaded, since converters may use it until the end of the process.
+    _library: Library,
+}
+
+#[cfg(feature = "runtime-loading")]
+impl Loaded {
+    /// Check that a request to load the library from ``path`` is satisfied by this one.
+    fn check_path(&self, path: Option<&Path>) -> Result<()> {
+        if self.path.as_deref() == path {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::LibraryAlreadyLoaded))
+        }
+    }
+}
+
+#[cfg(feature = "runtime-loading")]
+static LOADED: OnceLock<Loaded> = OnceLock::new();
+
+/// Names that libsamplerate is installed under, tried in order if no path is given.
+#[cfg(all(feature = "runtime-loading", target_os = "windows"))]
+const LIBRARY_NAMES: &[&str] = &["libsamplerate-0.dll", "samplerate.dll"];
+#[cfg(all(feature = "runtime-loading", target_vendor = "apple"))]
+const LIBRARY_NAMES: &[&str] = &["libsamplerate.0.dylib", "libsamplerate.dylib"];
+#[cfg(all(feature = "runtime-loading", not(target_os = "windows"),
+          not(target_vendor = "apple")))]
+const LIBRARY_NAMES: &[&str] = &["libsamplerate.so.0", "libsamplerate.so"];
+
+/// Retrieve the functions that new converters call.
+pub(crate) fn current() -> &'static Api {
+    #[cfg(feature = "runtime-loading")]
+    if let Some(loaded) = LOADED.get() {
+        return &loaded.api
+    }
+    &LINKED
+}
+
+/// Retrieve the version of the library loaded at runtime, or ``None`` if there is none.
+#[cfg(feature = "runtime-loading")]
+pub(crate) fn loaded_version() -> Option<Option<LibraryVersion>> {
+    LOADED.get().map(|loaded| loaded.version)
+}
+
+/// Retrieve the copy of libsamplerate that converters created from now on call into.
+pub fn backend() -> Backend {
+    #[cfg(feature = "runtime-loading")]
+    if LOADED.get().is_some() {
+        return Backend::Loaded
+    }
+    Backend::Linked
+}
+
+/// Load libsamplerate from ``path``, or from the locations the platform searches for shared
+/// libraries under its usual names if ``path`` is ``None``, and make converters created from
+/// now on call into it instead of the linked library. Converters created before keep using
+/// the linked library.
+///
+/// Returns an error of kind [``LibraryNotFound``](enum.ErrorKind.html#variant.LibraryNotFound)
+/// if the library cannot be loaded, of kind
+/// [``SymbolNotFound``](enum.ErrorKind.html#variant.SymbolNotFound) if it lacks one of the
+/// functions the bindings use, and of kind
+/// [``IncompatibleLibrary``](enum.ErrorKind.html#variant.IncompatibleLibrary) if it is too
+/// old; the linked library stays in use in that case. A library can be loaded only once:
+/// loading it again from the same ``path`` does nothing, and any other ``path`` returns an
+/// error of kind [``LibraryAlreadyLoaded``](enum.ErrorKind.html#variant.LibraryAlreadyLoaded).
+///
+/// # Safety
+///
+/// The library is trusted to be libsamplerate: loading it runs its initialization code, and
+/// its functions are called with the signatures declared in ``samplerate.h``.
+#[cfg(feature = "runtime-loading")]
+pub unsafe fn load_library(path: Option<&Path>) -> Result<()> {
+    if let Some(loaded) = LOADED.get() {
+        return loaded.check_path(path)
+    }
+    let library = match path {
+        Some(path) => Library::new(path).ok(),
+        None => LIBRARY_NAMES.iter().find_map(|name| Library::new(name).ok()),
+    };
+    let library = library.ok_or_else(|| Error::new(ErrorKind::LibraryNotFound))?;
+    let api = resolve(&library)?;
+    let version = ::version::parse_library_version(&api);
+    if let Some(found) = version {
+        if found < MINIMUM_LIBRARY_VERSION {
+            return Err(Error::new(ErrorKind::IncompatibleLibrary {
+                found,
+                required: MINIMUM_LIBRARY_VERSION,
+            }))
+        }
+    }
+    // If another thread loaded a library in the meantime, this one is dropped.
+    let loaded = Loaded { api, version, path: path.map(Path::to_path_buf), _library: library };
+    match LOADED.set(loaded) {
+        Ok(()) => Ok(()),
+        Err(_) => LOADED.get().unwrap().check_path(path),
+    }
+}
@@ -0,0 +1,249 @@
+use core::time::Duration;
+
+use {Clock, Converter, Result, RATIO_RAMP_THRESHOLD, SRC_MAX_RATIO};
+#[cfg(feature = "std")]
+use SystemClock;
+
+/// Number of input frames [``Converter::process_with_budget``](
+/// struct.Converter.html#method.process_with_budget) converts between reading the clock.
+pub const BUDGET_CHUNK_FRAMES: usize = 128;
+
+/// Outcome of [``Converter::process_with_budget``](
+/// struct.Converter.html#method.process_with_budget).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetResult {
+    /// Number of used input samples.
+    pub input_used: usize,
+    /// Number of generated output samples.
+    pub output_generated: usize,
+    /// Whether the conversion stopped because the budget ran out, rather than because
+    /// the input was used up or the output was filled.
+    pub stopped_early: bool,
+}
+
+impl Converter {
+    /// Convert samples like [``convert``](#method.convert), but stop once ``budget`` of
+    /// real time has elapsed.
+    ///
+    /// The input is converted in chunks of
+    /// [``BUDGET_CHUNK_FRAMES``](constant.BUDGET_CHUNK_FRAMES.html) frames, and the time is
+    /// checked before each chunk, so a call can take longer than ``budget`` by the time it
+    /// takes to convert one chunk. A change of ratio is interpolated over all of ``output``,
+    /// as in a single call to ``convert``, rather than over each chunk. The converter is left
+    /// in the same state as after an equivalent call to ``convert``, so the next call, given
+    /// the rest of the input, continues the stream seamlessly. A clock that goes backwards
+    /// counts as no time elapsed.
+    ///
+    /// If the clock panics, the chunks converted so far have been written to ``output``,
+    /// and the converter remains usable; the change in
+    /// [``frames_in``](#method.frames_in) and [``frames_out``](#method.frames_out) tells how
+    /// far the conversion got.
+    #[cfg(feature = "std")]
+    pub fn process_with_budget(&mut self, ratio: f64, input: &[f32], output: &mut [f32],
+                               budget: Duration) -> Result<BudgetResult> {
+        self.process_with_budget_clock(ratio, input, output, budget, &mut SystemClock::new())
+    }
+
+    /// Like [``process_with_budget``](#method.process_with_budget), with time measured
+    /// by ``clock``.
+    pub fn process_with_budget_clock<C: Clock>(&mut self, ratio: f64, input: &[f32],
+                                               output: &mut [f32], budget: Duration,
+                                               clock: &mut C) -> Result<BudgetResult> {
+        let channels = self.channels();
+        let chunk_len = BUDGET_CHUNK_FRAMES * channels;
+        // libsamplerate ramps the ratio from that of the last frame it generated, so each
+        // chunk after the first starts from the ratio a single call reaches at its first frame.
+        let first_ratio = if self.last_ratio < 1.0 / SRC_MAX_RATIO {
+            ratio
+        } else {
+            self.last_ratio
+        };
+        let output_frames = (output.len() / channels) as f64;
+        let start = clock.now();
+        let mut result = BudgetResult { input_used: 0, output_generated: 0,
+                                        stopped_early: false };
+        while result.input_used < input.len() && result.output_generated < output.len() {
+            if clock.now().saturating_sub(start) >= budget {
+                result.stopped_early = true;
+                break
+            }
+            let ramps = (ratio - first_ratio).abs() > RATIO_RAMP_THRESHOLD;
+            if ramps && result.output_generated > 0 {
+                let generated_frames = (result.output_generated / channels) as f64;
+                self.set_ratio(first_ratio +
+                               generated_frames * (ratio - first_ratio) / output_frames)?;
+            }
+            let rest = &input[result.input_used..];
+            let (used, generated) = self.convert(ratio, Some(&rest[..rest.len().min(chunk_len)]),
+                                                 &mut output[result.output_generated..])?;
+            result.input_used += used;
+            result.output_generated += generated;
+            if used == 0 && generated == 0 {
+                break
+            }
+        }
+        if let Some((_, last)) = self.applied_ratio {
+            if result.output_generated > 0 {
+                self.applied_ratio = Some((first_ratio, last));
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use {Interpolator, convert};
+    use super::*;
+
+    /// Clock that advances by a fixed step whenever it is read.
+    struct SteppingClock {
+        now: Duration,
+        step: Duration,
+        reads: usize,
+    }
+
+    impl Clock for SteppingClock {
+        fn now(&mut self) -> Duration {
+            self.now += self.step;
+            self.reads += 1;
+            self.now
+        }
+    }
+
+    fn make_input(frames: usize) -> Vec<f32> {
+        (0..frames * 2).map(|i| ((i / 2) as f32 * 0.02 + (i % 2) as f32).sin()).collect()
+    }
+
+    /// Clock that goes back by a millisecond whenever it is read.
+    struct BackwardsClock(Duration);
+
+    impl Clock for BackwardsClock {
+        fn now(&mut self) -> Duration {
+            self.0 -= Duration::from_millis(1);
+            self.0
+        }
+    }
+
+    fn clock() -> SteppingClock {
+        SteppingClock { now: Duration::ZERO, step: Duration::from_millis(1), reads: 0 }
+    }
+
+    #[test]
+    fn test_stops_early() {
+        let input = make_input(10000);
+        let mut output = std::vec![0.0; 30000];
+        let mut converter = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let mut clock = clock();
+        let result = converter.process_with_budget_clock(1.5, &input, &mut output,
+                                                         Duration::from_millis(4),
+                                                         &mut clock).unwrap();
+        // The clock is read once at the start and once before each chunk; the budget is
+        // used up before the fourth chunk.
+        assert!(result.stopped_early);
+        assert_eq!(clock.reads, 5);
+        assert_eq!(result.input_used, 3 * BUDGET_CHUNK_FRAMES * 2);
+        assert!(result.output_generated > 0);
+
+        // Without a meaningful budget, nothing is converted.
+        let result = converter.process_with_budget_clock(1.5, &input, &mut output,
+                                                         Duration::ZERO, &mut clock).unwrap();
+        assert_eq!(result, BudgetResult { input_used: 0, output_generated: 0,
+                                          stopped_early: true });
+    }
+
+    #[test]
+    fn test_not_early_when_done() {
+        let input = make_input(100);
+        let mut output = std::vec![0.0; 1000];
+        let mut converter = Converter::new(Interpolator::Linear, 2).unwrap();
+        let result = converter.process_with_budget_clock(2.0, &input, &mut output,
+                                                         Duration::from_secs(1),
+                                                         &mut clock()).unwrap();
+        assert_eq!(result.input_used, input.len());
+        assert!(!result.stopped_early);
+
+        // A full output buffer is not an early stop either.
+        let mut output = std::vec![0.0; 20];
+        let result = converter.process_with_budget_clock(2.0, &input, &mut output,
+                                                         Duration::from_secs(1),
+                                                         &mut clock()).unwrap();
+        assert_eq!(result.output_generated, 20);
+        assert!(!result.stopped_early);
+    }
+
+    #[test]
+    fn test_budgeted_matches_unbudgeted() {
+        let input = make_input(5000);
+        let mut expected = std::vec![0.0; 12000];
+        let (_, generated) = convert(Interpolator::SincMediumQuality, 2, 1.2, &input,
+                                     &mut expected).unwrap();
+        expected.truncate(generated);
+
+        let mut converter = Converter::new(Interpolator::SincMediumQuality, 2).unwrap();
+        let mut clock = clock();
+        let mut rest = &input[..];
+        let mut actual = Vec::new();
+        let mut buffer = std::vec![0.0; 2000];
+        let mut calls = 0;
+        loop {
+            let result = converter.process_with_budget_clock(1.2, rest, &mut buffer,
+                                                             Duration::from_millis(3),
+                                                             &mut clock).unwrap();
+            actual.extend_from_slice(&buffer[..result.output_generated]);
+            rest = &rest[result.input_used..];
+            calls += 1;
+            if rest.is_empty() {
+                break
+            }
+        }
+        loop {
+            let (_, generated) = converter.convert(1.2, None, &mut buffer).unwrap();
+            if generated == 0 {
+                break
+            }
+            actual.extend_from_slice(&buffer[..generated]);
+        }
+        assert!(calls > 5);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_changing_ratio_matches_unbudgeted() {
+        // The ratio ramps from 1 to 1.5 over all of the output, not over each chunk.
+        let input = make_input(5000);
+        for &interpolator in &[Interpolator::SincMediumQuality, Interpolator::Linear] {
+            let mut expected = std::vec![0.0; 16000];
+            let mut unbudgeted = Converter::with_ratio(interpolator, 2, 1.0).unwrap();
+            let (used, generated) = unbudgeted.convert(1.5, Some(&input), &mut expected)
+                .unwrap();
+
+            let mut output = std::vec![0.0; 16000];
+            let mut converter = Converter::with_ratio(interpolator, 2, 1.0).unwrap();
+            let result = converter.process_with_budget_clock(1.5, &input, &mut output,
+                                                             Duration::from_secs(1),
+                                                             &mut clock()).unwrap();
+            assert_eq!((result.input_used, result.output_generated), (used, generated));
+            assert_eq!(output, expected, "{:?}", interpolator);
+            let (first, last) = converter.last_applied_ratio().unwrap();
+            let (expected_first, expected_last) = unbudgeted.last_applied_ratio().unwrap();
+            assert_eq!(first, expected_first);
+            assert!((last - expected_last).abs() < 1e-12, "{} vs {}", last, expected_last);
+        }
+    }
+
+    #[test]
+    fn test_backwards_clock() {
+        let input = make_input(1000);
+        let mut output = std::vec![0.0; 5000];
+        let mut converter = Converter::new(Interpolator::Linear, 2).unwrap();
+        let result = converter.process_with_budget_clock(2.0, &input, &mut output,
+                                                         Duration::from_millis(1),
+                                                         &mut BackwardsClock(
+                                                             Duration::from_secs(1)))
+            .unwrap();
+        assert_eq!(result.input_used, input.len());
+        assert!(!result.stopped_early);
+    }
+}
@@ -0,0 +1,125 @@
+//! Clipping of the output and measurement of its peak.
+//!
+//! The sinc interpolators ring around steps in the input, so full-scale input converts to
+//! output that overshoots ±1.0 by up to about a tenth. Integer formats saturate it when
+//! converted with this crate, but other encoders may wrap around instead.
+
+use Converter;
+
+/// Handling of output samples beyond ±1.0; see
+/// [``Converter::set_clipping``](struct.Converter.html#method.set_clipping).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Clipping {
+    /// Leave the output as libsamplerate generates it, and measure nothing.
+    #[default]
+    None,
+    /// Clamp the output to [-1.0, 1.0], measuring its peak beforehand.
+    Clamp,
+    /// Leave the output as libsamplerate generates it, measuring its peak.
+    Report,
+}
+
+/// Handle ``samples`` like ``clipping`` does, returning their largest magnitude before
+/// clamping, or 0 if there are none.
+fn clip(clipping: Clipping, samples: &mut [f32]) -> f32 {
+    let mut peak = 0.0f32;
+    if clipping == Clipping::Clamp {
+        for sample in samples {
+            peak = peak.max(sample.abs());
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    } else {
+        for sample in samples {
+            peak = peak.max(sample.abs());
+        }
+    }
+    peak
+}
+
+impl Converter {
+    /// Set how output samples beyond ±1.0 are handled, which is
+    /// [``Clipping::None``](enum.Clipping.html#variant.None) by default.
+    ///
+    /// With [``Clamp``](enum.Clipping.html#variant.Clamp) or
+    /// [``Report``](enum.Clipping.html#variant.Report), every call to
+    /// [``convert``](#method.convert) goes once over the output it generated, and no
+    /// further, to measure its peak and to clamp it if requested. The mode is kept by
+    /// [``reset``](#method.reset).
+    pub fn set_clipping(&mut self, clipping: Clipping) {
+        self.clipping = clipping;
+        self.peak = None;
+    }
+
+    /// Retrieve how output samples beyond ±1.0 are handled.
+    pub fn clipping(&self) -> Clipping {
+        self.clipping
+    }
+
+    /// Retrieve the largest magnitude of the output generated by the most recent call to
+    /// [``convert``](#method.convert), before clamping, or 0 if it generated nothing.
+    ///
+    /// Returns ``None`` if the peak is not measured, or if there was no such call since the
+    /// mode was [set](#method.set_clipping) or the converter [reset](#method.reset). Samples
+    /// in other formats, such as with [``convert_i16``](#method.convert_i16), are converted
+    /// a chunk at a time, and the peak is that of the last chunk.
+    pub fn last_peak(&self) -> Option<f32> {
+        self.peak
+    }
+
+    /// Handle the ``output`` just generated as set by [``set_clipping``](#method.set_clipping).
+    pub(crate) fn clip_output(&mut self, output: &mut [f32]) {
+        if self.clipping != Clipping::None {
+            self.peak = Some(clip(self.clipping, output));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use Interpolator;
+    use super::*;
+
+    /// Convert a full-scale square wave at 48000 / 44100, with ``output`` longer than needed
+    /// and filled with 2.0, returning the output and the number of samples generated.
+    fn convert_square(conv: &mut Converter) -> (Vec<f32>, usize) {
+        let input: Vec<f32> = (0..10000).map(|n| if n / 50 % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        let mut output = vec![2.0; 12000];
+        let (_, generated) = conv.convert(48000.0 / 44100.0, Some(&input), &mut output)
+            .unwrap();
+        (output, generated)
+    }
+
+    #[test]
+    fn test_clipping() {
+        let mut conv = Converter::new(Interpolator::SincBestQuality, 1).unwrap();
+        let (plain, generated) = convert_square(&mut conv);
+        assert_eq!(conv.last_peak(), None);
+        let expected = plain[..generated].iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(expected > 1.05, "{}", expected);
+
+        let mut conv = Converter::new(Interpolator::SincBestQuality, 1).unwrap();
+        conv.set_clipping(Clipping::Report);
+        assert_eq!(convert_square(&mut conv), (plain.clone(), generated));
+        assert_eq!(conv.last_peak(), Some(expected));
+
+        let mut conv = Converter::new(Interpolator::SincBestQuality, 1).unwrap();
+        conv.set_clipping(Clipping::Clamp);
+        let (clamped, g) = convert_square(&mut conv);
+        assert_eq!(g, generated);
+        assert_eq!(conv.last_peak(), Some(expected));
+        for (&c, &p) in clamped[..generated].iter().zip(&plain) {
+            assert_eq!(c, p.clamp(-1.0, 1.0));
+        }
+        // The rest of the output buffer is left alone.
+        assert!(clamped[generated..].iter().all(|&sample| sample == 2.0));
+
+        conv.reset().unwrap();
+        assert_eq!(conv.last_peak(), None);
+        assert_eq!(conv.clipping(), Clipping::Clamp);
+        assert_eq!(conv.convert(1.0, Some(&[]), &mut [0.0; 16]).unwrap(), (0, 0));
+        assert_eq!(conv.last_peak(), Some(0.0));
+    }
+}
@@ -0,0 +1,36 @@
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Source of time for measuring how long conversion takes.
+pub trait Clock {
+    /// Retrieve the time elapsed since an arbitrary fixed point.
+    fn now(&mut self) -> Duration;
+}
+
+/// Clock measuring real time.
+#[cfg(feature = "std")]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+#[cfg(feature = "std")]
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { origin: Instant::now() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&mut self) -> Duration {
+        self.origin.elapsed()
+    }
+}
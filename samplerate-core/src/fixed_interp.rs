@@ -0,0 +1,341 @@
+//! Linear and zero order hold conversion in fixed point, for targets without an FPU.
+//!
+//! libsamplerate only converts ``f32`` samples, which a microcontroller without a floating
+//! point unit, such as a Cortex-M0+, has to emulate in software; even linear interpolation
+//! cannot keep up there. [``FixedConverter``](struct.FixedConverter.html) implements the
+//! ``Linear`` and ``ZeroOrderHold`` interpolators in pure Rust with integer arithmetic only,
+//! treating ``i16`` samples as Q15 and ``i32`` samples as Q31 fractions. For a constant
+//! ratio, it generates the same stream as libsamplerate, up to these precision limits:
+//!
+//!   * The position in the input is a phase accumulator with 32 fractional bits, advanced by
+//!     ``source_rate / target_rate`` rounded down to a multiple of 2<sup>-32</sup> frames.
+//!     The output falls behind the exact ratio by less than one input frame every
+//!     2<sup>32</sup> output frames (27 hours at 44.1 kHz), and is exact for ratios whose
+//!     reciprocal is a multiple of 2<sup>-32</sup>, such as 2, 0.5 or 1.6. Around the
+//!     positions where the exact phase is an integer, a zero order hold may therefore
+//!     repeat a sample one frame earlier or later than libsamplerate.
+//!   * Linear interpolation uses the full 32-bit fraction and rounds down, so every sample is
+//!     less than one LSB below the exact interpolation between two input samples.
+//!   * The ratio is given as a pair of sample rates; a change of rates takes effect at the
+//!     next output frame rather than being slewed over the next call.
+
+use {Error, Interpolator, Result, SRC_ERR_BAD_CHANNEL_COUNT, SRC_ERR_BAD_CONVERTER,
+     SRC_ERR_BAD_SRC_RATIO, check_frames};
+
+/// Largest number of channels a [``FixedConverter``](struct.FixedConverter.html) converts.
+/// The converter keeps one frame of state on the stack, so it has a fixed capacity.
+pub const MAX_FIXED_CHANNELS: usize = 8;
+
+/// Bounds of the conversion ratio, as for libsamplerate.
+const MAX_RATIO: u64 = 256;
+
+/// Fixed-point sample format converted by [``FixedConverter``](struct.FixedConverter.html).
+pub trait FixedSample: Copy + Default {
+    /// Interpolate between ``self`` and ``next`` at ``frac / 2^32`` of the way, rounding
+    /// down.
+    fn lerp(self, next: Self, frac: u32) -> Self;
+}
+
+impl FixedSample for i16 {
+    fn lerp(self, next: i16, frac: u32) -> i16 {
+        let delta = next as i64 - self as i64;
+        (self as i64 + ((delta * frac as i64) >> 32)) as i16
+    }
+}
+
+impl FixedSample for i32 {
+    fn lerp(self, next: i32, frac: u32) -> i32 {
+        // The difference takes 33 bits, so the product is split in two to fit in 64 bits.
+        let delta = next as i64 - self as i64;
+        let high = delta * (frac >> 16) as i64;
+        let low = delta * (frac & 0xffff) as i64;
+        (self as i64 + ((high + (low >> 16)) >> 16)) as i32
+    }
+}
+
+/// Retrieve the distance between output frames in input frames, with 32 fractional bits.
+fn step(source_rate: u32, target_rate: u32) -> Result<u64> {
+    let (source, target) = (source_rate as u64, target_rate as u64);
+    if source == 0 || target == 0 || source > target * MAX_RATIO || target > source * MAX_RATIO {
+        return Err(Error::from_code(SRC_ERR_BAD_SRC_RATIO))
+    }
+    Ok((source << 32) / target)
+}
+
+/// Streaming ``Linear`` or ``ZeroOrderHold`` converter for ``i16`` or ``i32`` samples that
+/// uses no floating point arithmetic; see the [module documentation](index.html).
+///
+/// The interface mirrors [``Converter``](struct.Converter.html): sample counts are in
+/// samples, buffers are interleaved, and converting a stream in chunks of any size gives
+/// the same output as converting it at once. Like libsamplerate, these interpolators have
+/// nothing to flush at the end of the stream.
+pub struct FixedConverter<S: FixedSample> {
+    interpolator: Interpolator,
+    channels: usize,
+    step: u64,
+    /// Position of the next output frame, with 32 fractional bits, relative to ``last``.
+    position: u64,
+    /// Last input frame used, or the first input frame after a reset.
+    last: [S; MAX_FIXED_CHANNELS],
+    reset: bool,
+}
+
+impl<S: FixedSample> FixedConverter<S> {
+    /// Create a converter from ``source_rate`` to ``target_rate``.
+    ///
+    /// Returns an error if ``interpolator`` is neither ``Linear`` nor ``ZeroOrderHold``, if
+    /// there are more than [``MAX_FIXED_CHANNELS``](constant.MAX_FIXED_CHANNELS.html)
+    /// channels, or if the ratio is outside of the range libsamplerate accepts.
+    pub fn new(interpolator: Interpolator, channels: usize, source_rate: u32,
+               target_rate: u32) -> Result<FixedConverter<S>> {
+        match interpolator {
+            Interpolator::Linear | Interpolator::ZeroOrderHold => (),
+            _ => return Err(Error::from_code(SRC_ERR_BAD_CONVERTER))
+        }
+        if !(1..=MAX_FIXED_CHANNELS).contains(&channels) {
+            return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+        }
+        Ok(FixedConverter {
+            interpolator,
+            channels,
+            step: step(source_rate, target_rate)?,
+            position: 0,
+            last: [S::default(); MAX_FIXED_CHANNELS],
+            reset: true,
+        })
+    }
+
+    /// Retrieve the interpolator used by the converter.
+    pub fn interpolator(&self) -> Interpolator {
+        self.interpolator
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Change the sample rates, starting from the next output frame.
+    pub fn set_rates(&mut self, source_rate: u32, target_rate: u32) -> Result<()> {
+        self.step = step(source_rate, target_rate)?;
+        Ok(())
+    }
+
+    /// Reset the internal state to the same state it had after [``new``](#method.new).
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.last = [S::default(); MAX_FIXED_CHANNELS];
+        self.reset = true;
+    }
+
+    /// Convert samples using internal state.
+    ///
+    /// The size of both ``input`` and ``output`` must be a multiple of the converter's
+    /// channel count, or an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) is returned. ``None``
+    /// as ``input`` marks the end of the stream, which generates no output.
+    ///
+    /// Returns the number of used input samples and generated output samples, respectively.
+    pub fn convert(&mut self, input: Option<&[S]>, output: &mut [S])
+            -> Result<(usize, usize)> {
+        let channels = self.channels;
+        check_frames(channels, input.map_or(0, |input| input.len()), output.len())?;
+        let input = match input {
+            Some(input) if !input.is_empty() => input,
+            _ => return Ok((0, 0))
+        };
+        if self.reset {
+            // Like libsamplerate, start from the first frame rather than from silence.
+            self.last[..channels].copy_from_slice(&input[..channels]);
+            self.reset = false;
+        }
+
+        let frames = (input.len() / channels) as u64;
+        let mut generated = 0;
+        for frame in output.chunks_exact_mut(channels) {
+            let index = (self.position >> 32) as usize;
+            let last = &self.last;
+            let previous = |channel: usize| {
+                if index == 0 { last[channel] } else { input[(index - 1) * channels + channel] }
+            };
+            match self.interpolator {
+                Interpolator::Linear => {
+                    if index as u64 >= frames {
+                        break
+                    }
+                    let frac = self.position as u32;
+                    for (channel, sample) in frame.iter_mut().enumerate() {
+                        *sample = previous(channel).lerp(input[index * channels + channel], frac);
+                    }
+                }
+                _ => {
+                    if self.position > frames << 32 {
+                        break
+                    }
+                    for (channel, sample) in frame.iter_mut().enumerate() {
+                        *sample = previous(channel);
+                    }
+                }
+            }
+            self.position += self.step;
+            generated += channels;
+        }
+
+        let used = (self.position >> 32).min(frames);
+        if used > 0 {
+            let start = (used as usize - 1) * channels;
+            self.last[..channels].copy_from_slice(&input[start..start + channels]);
+        }
+        self.position -= used << 32;
+        Ok((used as usize * channels, generated))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use {ErrorKind, PcmConverter};
+    use super::*;
+
+    fn make_input(frames: usize, channels: usize) -> Vec<i16> {
+        (0..frames * channels)
+            .map(|i| {
+                let phase = (i / channels) as f32 * 0.013 * (1 + i % channels) as f32;
+                (phase.sin() * 30000.0) as i16
+            })
+            .collect()
+    }
+
+    fn convert_fixed<S: FixedSample>(converter: &mut FixedConverter<S>, input: &[S],
+                                     chunk: usize) -> Vec<S> {
+        let channels = converter.channels();
+        let mut output = Vec::new();
+        let mut scratch = vec![S::default(); chunk * channels];
+        let mut rest = input;
+        loop {
+            let (used, generated) = converter.convert(Some(rest), &mut scratch).unwrap();
+            output.extend_from_slice(&scratch[..generated]);
+            rest = &rest[used..];
+            if rest.is_empty() || (used == 0 && generated == 0) {
+                break
+            }
+        }
+        assert_eq!(converter.convert(None, &mut scratch).unwrap(), (0, 0));
+        output
+    }
+
+    fn convert_float(interpolator: Interpolator, channels: usize, ratio: f64,
+                     input: &[i16]) -> Vec<i16> {
+        let mut converter = PcmConverter::<i16>::new(interpolator, channels).unwrap();
+        let mut output = vec![0; ((input.len() / channels) as f64 * ratio) as usize * channels +
+                                 64 * channels];
+        let (used, generated) = converter.convert(ratio, Some(input), &mut output).unwrap();
+        assert_eq!(used, input.len());
+        output.truncate(generated);
+        output
+    }
+
+    #[test]
+    fn test_linear_matches_float() {
+        for &(source, target, channels) in &[(44100, 48000, 2), (48000, 44100, 1),
+                                              (22050, 44100, 3), (96000, 8000, 2)] {
+            let ratio = target as f64 / source as f64;
+            let input = make_input(20000, channels);
+            let expected = convert_float(Interpolator::Linear, channels, ratio, &input);
+            let mut converter =
+                FixedConverter::new(Interpolator::Linear, channels, source, target).unwrap();
+            let output = convert_fixed(&mut converter, &input, 100000);
+            // The float and fixed positions may fall on either side of the end of the input.
+            assert!(output.len().abs_diff(expected.len()) <= channels,
+                    "{} -> {}: {} samples instead of {}", source, target, output.len(),
+                    expected.len());
+            for (index, (&fixed, &float)) in output.iter().zip(&expected).enumerate() {
+                assert!((fixed as i32 - float as i32).abs() <= 1,
+                        "{} -> {}: sample {} is {} instead of {}", source, target, index,
+                        fixed, float);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_order_hold_matches_float() {
+        // The reciprocals of these ratios are exact in binary, so the positions are too.
+        for &(source, target) in &[(44100, 88200), (48000, 24000), (25, 40), (40, 32)] {
+            let input = make_input(10000, 2);
+            let expected = convert_float(Interpolator::ZeroOrderHold, 2,
+                                         target as f64 / source as f64, &input);
+            let mut converter =
+                FixedConverter::new(Interpolator::ZeroOrderHold, 2, source, target).unwrap();
+            assert_eq!(convert_fixed(&mut converter, &input, 100000), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunk_invariance() {
+        let input = make_input(5000, 2);
+        let wide: Vec<i32> = input.iter().map(|&sample| (sample as i32) << 16 | 0x1234).collect();
+        for &interpolator in &[Interpolator::Linear, Interpolator::ZeroOrderHold] {
+            for &(source, target) in &[(44100, 48000), (48000, 44100), (8000, 48000)] {
+                let mut converter = FixedConverter::new(interpolator, 2, source, target).unwrap();
+                let whole = convert_fixed(&mut converter, &input, 100000);
+                let mut converter = FixedConverter::<i32>::new(interpolator, 2, source,
+                                                               target).unwrap();
+                let wide_whole = convert_fixed(&mut converter, &wide, 100000);
+
+                for &(in_chunk, out_chunk) in &[(1, 1), (7, 3), (64, 1000), (333, 17)] {
+                    converter.reset();
+                    let mut chunked = Vec::new();
+                    for chunk in wide.chunks(in_chunk * 2) {
+                        chunked.extend(convert_fixed(&mut converter, chunk, out_chunk));
+                    }
+                    assert_eq!(chunked, wide_whole, "{:?} in chunks of {} and {} frames",
+                               interpolator, in_chunk, out_chunk);
+                }
+                assert_eq!(wide_whole.len(), whole.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_lerp_rounds_down() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..100000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let (a, b, frac) = (state as i32, (state >> 32) as i32, (state >> 16) as u32);
+            let exact = a as i128 + (((b as i128 - a as i128) * frac as i128) >> 32);
+            assert_eq!(a.lerp(b, frac) as i128, exact);
+            let (a, b) = (a as i16, b as i16);
+            let exact = a as i64 + (((b as i64 - a as i64) * frac as i64) >> 32);
+            assert_eq!(a.lerp(b, frac) as i64, exact);
+        }
+        assert_eq!(i16::MIN.lerp(i16::MAX, u32::MAX), i16::MAX - 1);
+        assert_eq!(i32::MAX.lerp(i32::MIN, u32::MAX), i32::MIN);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(FixedConverter::<i16>::new(Interpolator::SincFastest, 1, 1, 1).err(),
+                   Some(Error::from_code(SRC_ERR_BAD_CONVERTER)));
+        for &channels in &[0, MAX_FIXED_CHANNELS + 1] {
+            assert_eq!(FixedConverter::<i16>::new(Interpolator::Linear, channels, 1, 1).err(),
+                       Some(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT)));
+        }
+        for &(source, target) in &[(0, 48000), (48000, 0), (1, 257), (257, 1)] {
+            assert_eq!(FixedConverter::<i16>::new(Interpolator::Linear, 1, source,
+                                                  target).err(),
+                       Some(Error::from_code(SRC_ERR_BAD_SRC_RATIO)));
+        }
+        let mut converter = FixedConverter::<i16>::new(Interpolator::Linear, 1, 1, 256).unwrap();
+        assert!(converter.set_rates(256, 1).is_ok());
+        assert!(converter.set_rates(1, 1000).is_err());
+
+        let mut converter = FixedConverter::<i16>::new(Interpolator::Linear, 2, 1, 2).unwrap();
+        let error = converter.convert(Some(&[0; 3]), &mut [0; 8]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 2 });
+        let error = converter.convert(None, &mut [0; 7]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 7, channels: 2 });
+    }
+}
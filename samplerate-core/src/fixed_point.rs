@@ -0,0 +1,104 @@
+//! [``Sample``](trait.Sample.html) implementations for the [fixed](https://crates.io/crates/fixed)
+//! crate's signed fractional types.
+//!
+//! These have the same range and scaling as the integer types of the same width, so they are
+//! converted exactly like ``i16`` and ``i32``: ``I1F15`` is widened to ``f32`` losslessly,
+//! ``I1F31`` is rounded to the 24 bits of precision that ``f32`` has, and both saturate on
+//! the way back.
+
+use fixed::types::{I1F15, I1F31};
+
+use Sample;
+
+impl Sample for I1F15 {
+    fn to_f32(self) -> f32 {
+        self.to_bits().to_f32()
+    }
+
+    fn from_f32(sample: f32) -> I1F15 {
+        I1F15::from_bits(i16::from_f32(sample))
+    }
+}
+
+impl Sample for I1F31 {
+    fn to_f32(self) -> f32 {
+        self.to_bits().to_f32()
+    }
+
+    fn from_f32(sample: f32) -> I1F31 {
+        I1F31::from_bits(i32::from_f32(sample))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use {Interpolator, PcmConverter, convert_generic};
+    use super::*;
+
+    #[test]
+    fn test_fixed_scaling() {
+        assert_eq!(I1F15::MAX.to_f32(), I1F15::MAX.to_num::<f32>());
+        assert_eq!(I1F15::MIN.to_f32(), -1.0);
+        assert_eq!(I1F15::from_f32(0.25), I1F15::from_num(0.25));
+        assert_eq!(I1F15::from_f32(2.0), I1F15::MAX);
+        assert_eq!(I1F31::from_f32(-2.0), I1F31::MIN);
+        assert_eq!(I1F31::from_f32(1.0), I1F31::MAX);
+    }
+
+    fn round_trip<S: Sample>(samples: &[S]) -> Vec<S> {
+        let mut conv = PcmConverter::<S>::new(Interpolator::ZeroOrderHold, 1).unwrap();
+        let mut output = vec![samples[0]; samples.len() + 1];
+        let (_, generated) = conv.convert(1.0, Some(samples), &mut output).unwrap();
+        conv.convert(1.0, None, &mut output[generated..]).unwrap();
+        // The zero order hold converter repeats the first sample.
+        output.remove(0);
+        output
+    }
+
+    #[test]
+    fn test_fixed_round_trip() {
+        let input = [I1F15::MAX, I1F15::MIN, I1F15::DELTA, -I1F15::DELTA, I1F15::ZERO,
+                     I1F15::from_num(0.5)];
+        let output = round_trip(&input);
+        for (o, i) in output.iter().zip(input.iter()) {
+            assert!((o.to_bits() as i32 - i.to_bits() as i32).abs() <= 1);
+        }
+
+        let input = [I1F31::MAX, I1F31::MIN, I1F31::DELTA, -I1F31::DELTA, I1F31::ZERO,
+                     I1F31::from_num(0.5)];
+        let output = round_trip(&input);
+        for (o, i) in output.iter().zip(input.iter()) {
+            assert!((o.to_bits() as i64 - i.to_bits() as i64).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_fixed_matches_i16() {
+        let input: Vec<i16> = (0..2000)
+            .map(|i| i16::from_f32((i as f32 * 0.02).sin() * 0.7))
+            .collect();
+        let mut expected = vec![0i16; 4000];
+        let result = convert_generic(Interpolator::SincMediumQuality, 1, 2.0,
+                                     &input, &mut expected).unwrap();
+
+        let input_fixed: Vec<I1F15> = input.iter().map(|&s| I1F15::from_bits(s)).collect();
+        let mut output = vec![I1F15::ZERO; 4000];
+        assert_eq!(convert_generic(Interpolator::SincMediumQuality, 1, 2.0,
+                                   &input_fixed, &mut output).unwrap(), result);
+        for (o, &e) in output.iter().zip(expected.iter()) {
+            assert_eq!(o.to_bits(), e);
+        }
+
+        let input_fixed: Vec<I1F31> = input.iter()
+            .map(|&s| I1F31::from_bits((s as i32) << 16))
+            .collect();
+        let mut output = vec![I1F31::ZERO; 4000];
+        assert_eq!(convert_generic(Interpolator::SincMediumQuality, 1, 2.0,
+                                   &input_fixed, &mut output).unwrap(), result);
+        for (o, &e) in output.iter().zip(expected.iter()) {
+            assert!((o.to_bits() - ((e as i32) << 16)).abs() <= 1 << 15);
+        }
+    }
+}
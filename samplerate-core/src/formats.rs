@@ -0,0 +1,728 @@
+//! Conversion of non-``f32`` sample formats.
+//!
+//! libsamplerate only operates on ``f32`` samples, so these functions convert the input to
+//! ``f32`` and the output back in fixed-size chunks on the stack, feeding the streaming
+//! converter chunk by chunk. Memory use does not depend on the buffer sizes, and the result
+//! is the same as converting the whole buffer at once, except that when the ratio changes,
+//! it is interpolated over the first chunk rather than over the whole output.
+
+use core::ffi::c_int;
+use core::marker::PhantomData;
+
+use backend::{self, Api};
+use {Converter, Error, ErrorKind, Interpolator, Result, SRC_ERR_BAD_CHANNEL_COUNT,
+     check_frames};
+
+/// Number of samples converted per chunk.
+const CHUNK_SAMPLES: usize = 2048;
+
+/// Convert as much of ``input`` into ``output`` as possible through the ``f32`` converter.
+pub(crate) fn convert_with<T, I, O>(converter: &mut Converter, ratio: f64, input: Option<&[T]>,
+                                    output: &mut [T], to_f32: I, from_f32: O)
+        -> Result<(usize, usize)>
+        where I: Fn(&[T], &mut [f32]), O: Fn(&[f32], &mut [T]) {
+    let channels = converter.channels();
+    if channels > CHUNK_SAMPLES {
+        return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+    }
+    check_frames(channels, input.map_or(0, <[T]>::len), output.len())?;
+
+    let chunk = CHUNK_SAMPLES / channels * channels;
+    let mut input_f32 = [0.0; CHUNK_SAMPLES];
+    let mut output_f32 = [0.0; CHUNK_SAMPLES];
+
+    let (mut used, mut generated) = (0, 0);
+    loop {
+        let output_len = chunk.min(output.len() - generated);
+        let (u, g) = match input {
+            Some(input) => {
+                let input_len = chunk.min(input.len() - used);
+                to_f32(&input[used..used + input_len], &mut input_f32[..input_len]);
+                converter.convert(ratio, Some(&input_f32[..input_len]),
+                                  &mut output_f32[..output_len])?
+            }
+            None => converter.convert(ratio, None, &mut output_f32[..output_len])?
+        };
+        from_f32(&output_f32[..g], &mut output[generated..generated + g]);
+        used += u;
+        generated += g;
+        if u == 0 && g == 0 {
+            break
+        }
+    }
+    Ok((used, generated))
+}
+
+/// Perform a single conversion of ``input`` into ``output`` through the ``f32`` converter.
+pub(crate) fn convert_once_with<T, I, O>(interpolator: Interpolator, channels: usize, ratio: f64,
+                                         input: &[T], output: &mut [T], to_f32: I, from_f32: O)
+        -> Result<(usize, usize)>
+        where I: Fn(&[T], &mut [f32]), O: Fn(&[f32], &mut [T]) {
+    let mut converter = Converter::new(interpolator, channels)?;
+    let (used, mut generated) =
+        convert_with(&mut converter, ratio, Some(input), output, &to_f32, &from_f32)?;
+    if used == input.len() {
+        let (_, g) = convert_with(&mut converter, ratio, None, &mut output[generated..],
+                                  &to_f32, &from_f32)?;
+        generated += g;
+    }
+    Ok((used, generated))
+}
+
+/// Sample format that can be converted to and from ``f32`` for processing.
+///
+/// Integer formats are scaled so that full scale corresponds to ±1.0, rounded to the nearest
+/// value, and saturated at full scale on the way back.
+pub trait Sample: Copy {
+    /// Convert the sample to ``f32``.
+    fn to_f32(self) -> f32;
+    /// Convert an ``f32`` sample back, saturating if it is out of range.
+    fn from_f32(sample: f32) -> Self;
+
+    /// Convert as many samples as fit in both slices to ``f32``, like
+    /// [``to_f32``](#tymethod.to_f32) for every sample. Implementations may override this
+    /// with a vectorized version that gives the same results.
+    fn slice_to_f32(input: &[Self], output: &mut [f32]) {
+        for (o, &i) in output.iter_mut().zip(input) {
+            *o = i.to_f32();
+        }
+    }
+
+    /// Convert as many samples as fit in both slices back from ``f32``, like
+    /// [``from_f32``](#tymethod.from_f32) for every sample.
+    fn slice_from_f32(input: &[f32], output: &mut [Self]) {
+        for (o, &i) in output.iter_mut().zip(input) {
+            *o = Self::from_f32(i);
+        }
+    }
+}
+
+impl Sample for f32 {
+    fn to_f32(self) -> f32 { self }
+    fn from_f32(sample: f32) -> f32 { sample }
+}
+
+impl Sample for f64 {
+    fn to_f32(self) -> f32 { self as f32 }
+    fn from_f32(sample: f32) -> f64 { sample as f64 }
+}
+
+/// Scale ``sample`` to an integer with full scale ``scale``, rounding half away from zero
+/// and saturating.
+pub(crate) fn f32_to_int(sample: f32, scale: f64) -> f64 {
+    let scaled = sample as f64 * scale;
+    if scaled >= scale - 1.0 {
+        scale - 1.0
+    } else if scaled <= -scale {
+        -scale
+    } else if scaled >= 0.0 {
+        (scaled + 0.5) as i64 as f64
+    } else {
+        (scaled - 0.5) as i64 as f64
+    }
+}
+
+macro_rules! int_sample {
+    ($ty:ty, $bits:expr $(, $item:item)*) => {
+        impl Sample for $ty {
+            fn to_f32(self) -> f32 {
+                (self as f64 / (1u64 << ($bits - 1)) as f64) as f32
+            }
+            fn from_f32(sample: f32) -> $ty {
+                f32_to_int(sample, (1u64 << ($bits - 1)) as f64) as $ty
+            }
+            $($item)*
+        }
+    }
+}
+
+int_sample!(i8, 8);
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+int_sample!(i16, 16);
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+int_sample!(i16, 16,
+    fn slice_to_f32(input: &[i16], output: &mut [f32]) {
+        ::wasm_simd::i16_to_f32(input, output)
+    },
+    fn slice_from_f32(input: &[f32], output: &mut [i16]) {
+        ::wasm_simd::f32_to_i16(input, output)
+    });
+int_sample!(i32, 32);
+
+fn slice_to_f32<S: Sample>(input: &[S], output: &mut [f32]) {
+    S::slice_to_f32(input, output)
+}
+
+fn slice_from_f32<S: Sample>(input: &[f32], output: &mut [S]) {
+    S::slice_from_f32(input, output)
+}
+
+/// Perform a single conversion of samples in any [``Sample``](trait.Sample.html) format with
+/// a fixed conversion ratio.
+///
+/// Otherwise, this function behaves like [``convert``](fn.convert.html).
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_generic<S: Sample>(interpolator: Interpolator, channels: usize, ratio: f64,
+                                  input: &[S], output: &mut [S]) -> Result<(usize, usize)> {
+    convert_once_with(interpolator, channels, ratio, input, output,
+                      slice_to_f32::<S>, slice_from_f32::<S>)
+}
+
+/// Perform a single conversion of ``f64`` samples with a fixed conversion ratio.
+///
+/// The samples are converted to ``f32`` and back a few thousand at a time, so no buffer the
+/// size of the input or the output is needed, and the result is the same as converting all
+/// of them as ``f32`` at once. This is a convenience: libsamplerate still resamples in single
+/// precision, so the output is no more precise than ``f32`` samples. The
+/// ``double-precision`` feature of the ``samplerate`` crate adds ``convert_f64_precise``,
+/// which filters in double precision instead. Otherwise, this function behaves like
+/// [``convert``](fn.convert.html).
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_f64(interpolator: Interpolator, channels: usize, ratio: f64, input: &[f64],
+                   output: &mut [f64]) -> Result<(usize, usize)> {
+    convert_generic(interpolator, channels, ratio, input, output)
+}
+
+impl Converter {
+    /// Convert ``f64`` samples using internal state, smoothly interpolating ratio.
+    ///
+    /// The samples are converted through ``f32`` in chunks like in
+    /// [``convert_f64``](fn.convert_f64.html), so a change of ratio is interpolated over the
+    /// first chunk rather than over all of ``output``; otherwise, this method behaves like
+    /// [``convert``](#method.convert).
+    pub fn convert_f64(&mut self, ratio: f64, input: Option<&[f64]>, output: &mut [f64])
+            -> Result<(usize, usize)> {
+        convert_with(self, ratio, input, output, slice_to_f32::<f64>, slice_from_f32::<f64>)
+    }
+}
+
+/// Interface for performing a continuous conversion of samples in any
+/// [``Sample``](trait.Sample.html) format, like [``Converter``](struct.Converter.html).
+///
+/// If the [``Sample``](trait.Sample.html) implementation panics during a conversion, the
+/// output libsamplerate generated for the chunk being converted is lost, so the stream
+/// cannot continue seamlessly. The converter is then poisoned: every later conversion
+/// returns an error of kind [``Poisoned``](enum.ErrorKind.html#variant.Poisoned) until it is
+/// [``reset``](#method.reset).
+pub struct PcmConverter<S: Sample> {
+    converter: Converter,
+    poisoned: bool,
+    marker: PhantomData<fn(S) -> S>,
+}
+
+impl<S: Sample> PcmConverter<S> {
+    /// Create a converter.
+    pub fn new(interpolator: Interpolator, channels: usize) -> Result<PcmConverter<S>> {
+        Ok(PcmConverter::from_converter(Converter::new(interpolator, channels)?))
+    }
+
+    /// Wrap an existing ``f32`` converter.
+    pub fn from_converter(converter: Converter) -> PcmConverter<S> {
+        PcmConverter { converter, poisoned: false, marker: PhantomData }
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        self.converter.channels()
+    }
+
+    /// Reset the internal state, like [``Converter::reset``](struct.Converter.html#method.reset),
+    /// which also clears the poisoning after a panic.
+    pub fn reset(&mut self) -> Result<()> {
+        self.converter.reset()?;
+        self.poisoned = false;
+        Ok(())
+    }
+
+    /// Set the starting conversion ratio, like
+    /// [``Converter::set_ratio``](struct.Converter.html#method.set_ratio).
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        self.converter.set_ratio(ratio)
+    }
+
+    /// Convert samples using internal state, smoothly interpolating ratio, like
+    /// [``Converter::convert``](struct.Converter.html#method.convert).
+    pub fn convert(&mut self, ratio: f64, input: Option<&[S]>, output: &mut [S])
+            -> Result<(usize, usize)> {
+        if self.poisoned {
+            return Err(Error::new(ErrorKind::Poisoned))
+        }
+        // Only cleared if the conversion returns rather than unwinds.
+        self.poisoned = true;
+        let result = convert_with(&mut self.converter, ratio, input, output,
+                                  slice_to_f32::<S>, slice_from_f32::<S>);
+        self.poisoned = false;
+        result
+    }
+
+    /// Unwrap the ``f32`` converter.
+    pub fn into_inner(self) -> Converter {
+        self.converter
+    }
+}
+
+/// Placement of a 24-bit sample within a 32-bit word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Justification {
+    /// The sample occupies the 24 most significant bits, and the 8 least significant bits
+    /// are ignored (and written as zero).
+    Left,
+    /// The sample occupies the 24 least significant bits (as in ALSA ``S24_LE``), and the
+    /// 8 most significant bits are ignored (and written as the sign extension).
+    Right,
+}
+
+const S24_SCALE: f64 = (1 << 23) as f64;
+
+fn s24_to_f32(sample: i32, justification: Justification) -> f32 {
+    let value = match justification {
+        Justification::Left => sample >> 8,
+        Justification::Right => (sample << 8) >> 8,
+    };
+    (value as f64 / S24_SCALE) as f32
+}
+
+fn f32_to_s24(sample: f32, justification: Justification) -> i32 {
+    let value = f32_to_int(sample, S24_SCALE) as i32;
+    match justification {
+        Justification::Left => value << 8,
+        Justification::Right => value,
+    }
+}
+
+fn s24_slice_to_f32(justification: Justification) -> impl Fn(&[i32], &mut [f32]) {
+    move |input, output| {
+        for (o, &i) in output.iter_mut().zip(input) {
+            *o = s24_to_f32(i, justification);
+        }
+    }
+}
+
+fn f32_slice_to_s24(justification: Justification) -> impl Fn(&[f32], &mut [i32]) {
+    move |input, output| {
+        for (o, &i) in output.iter_mut().zip(input) {
+            *o = f32_to_s24(i, justification);
+        }
+    }
+}
+
+/// Perform a single conversion of 24-bit samples stored in 32-bit words with a fixed
+/// conversion ratio.
+///
+/// Samples are scaled so that 24-bit full scale corresponds to ±1.0, and saturated at 24-bit
+/// full scale on the way back. Otherwise, this function behaves like
+/// [``convert``](fn.convert.html).
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_s24_in_i32(interpolator: Interpolator, channels: usize, ratio: f64,
+                          justification: Justification,
+                          input: &[i32], output: &mut [i32]) -> Result<(usize, usize)> {
+    convert_once_with(interpolator, channels, ratio, input, output,
+                      s24_slice_to_f32(justification), f32_slice_to_s24(justification))
+}
+
+impl Converter {
+    /// Convert 24-bit samples stored in 32-bit words using internal state, smoothly
+    /// interpolating ratio.
+    ///
+    /// Samples are scaled and saturated like in
+    /// [``convert_s24_in_i32``](fn.convert_s24_in_i32.html); otherwise, this method behaves
+    /// like [``convert``](#method.convert).
+    pub fn convert_s24_in_i32(&mut self, ratio: f64, justification: Justification,
+                              input: Option<&[i32]>, output: &mut [i32])
+            -> Result<(usize, usize)> {
+        convert_with(self, ratio, input, output,
+                     s24_slice_to_f32(justification), f32_slice_to_s24(justification))
+    }
+}
+
+/// Perform a single conversion of ``i16`` samples with a fixed conversion ratio.
+///
+/// Samples are scaled with libsamplerate's ``src_short_to_float_array`` and
+/// ``src_float_to_short_array``, which saturate at full scale and round the output toward
+/// negative infinity, to a multiple of 2<sup>-16</sup> LSB first; this differs from the
+/// [``Sample``](trait.Sample.html) implementation for ``i16``, which rounds to the nearest
+/// value. Otherwise, this function behaves like [``convert``](fn.convert.html).
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_i16(interpolator: Interpolator, channels: usize, ratio: f64, input: &[i16],
+                   output: &mut [i16]) -> Result<(usize, usize)> {
+    let api = backend::current();
+    convert_once_with(interpolator, channels, ratio, input, output,
+                      short_to_float(api), float_to_short(api))
+}
+
+fn short_to_float(api: &'static Api) -> impl Fn(&[i16], &mut [f32]) {
+    move |input, output| unsafe {
+        (api.src_short_to_float_array)(input.as_ptr(), output.as_mut_ptr(), input.len() as c_int)
+    }
+}
+
+fn float_to_short(api: &'static Api) -> impl Fn(&[f32], &mut [i16]) {
+    move |input, output| unsafe {
+        (api.src_float_to_short_array)(input.as_ptr(), output.as_mut_ptr(), input.len() as c_int)
+    }
+}
+
+impl Converter {
+    /// Convert ``i16`` samples using internal state, smoothly interpolating ratio.
+    ///
+    /// Samples are scaled and saturated like in [``convert_i16``](fn.convert_i16.html);
+    /// otherwise, this method behaves like [``convert``](#method.convert).
+    pub fn convert_i16(&mut self, ratio: f64, input: Option<&[i16]>, output: &mut [i16])
+            -> Result<(usize, usize)> {
+        let api = self.api();
+        convert_with(self, ratio, input, output, short_to_float(api), float_to_short(api))
+    }
+}
+
+/// Perform a single conversion of ``i32`` samples with a fixed conversion ratio.
+///
+/// Samples are scaled with libsamplerate's ``src_int_to_float_array`` and
+/// ``src_float_to_int_array``, so that ``i32::MIN`` corresponds to -1.0, rounded to the nearest
+/// value, and saturated at full scale on the way back. Left-justified 24-bit samples can be
+/// converted this way as well. Otherwise, this function behaves like
+/// [``convert``](fn.convert.html).
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_i32(interpolator: Interpolator, channels: usize, ratio: f64, input: &[i32],
+                   output: &mut [i32]) -> Result<(usize, usize)> {
+    let api = backend::current();
+    convert_once_with(interpolator, channels, ratio, input, output,
+                      int_to_float(api), float_to_int(api))
+}
+
+fn int_to_float(api: &'static Api) -> impl Fn(&[i32], &mut [f32]) {
+    move |input, output| unsafe {
+        (api.src_int_to_float_array)(input.as_ptr(), output.as_mut_ptr(), input.len() as c_int)
+    }
+}
+
+fn float_to_int(api: &'static Api) -> impl Fn(&[f32], &mut [i32]) {
+    move |input, output| unsafe {
+        (api.src_float_to_int_array)(input.as_ptr(), output.as_mut_ptr(), input.len() as c_int)
+    }
+}
+
+impl Converter {
+    /// Convert ``i32`` samples using internal state, smoothly interpolating ratio.
+    ///
+    /// Samples are scaled and saturated like in [``convert_i32``](fn.convert_i32.html);
+    /// otherwise, this method behaves like [``convert``](#method.convert).
+    pub fn convert_i32(&mut self, ratio: f64, input: Option<&[i32]>, output: &mut [i32])
+            -> Result<(usize, usize)> {
+        let api = self.api();
+        convert_with(self, ratio, input, output, int_to_float(api), float_to_int(api))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use convert;
+    use super::*;
+
+    const S24_MAX: i32 = (1 << 23) - 1;
+    const S24_MIN: i32 = -(1 << 23);
+
+    const FULL_SCALE_LEFT: [i32; 6] =
+        [0x7fffff00, -0x80000000, 0x00000100, -0x00000100, 0, 0x12345600];
+    const FULL_SCALE_RIGHT: [i32; 6] =
+        [0x007fffff, -0x00800000, 0x00000001, -0x00000001, 0, 0x00123456];
+
+    #[test]
+    fn test_s24_layouts_agree() {
+        for (&left, &right) in FULL_SCALE_LEFT.iter().zip(FULL_SCALE_RIGHT.iter()) {
+            assert_eq!(s24_to_f32(left, Justification::Left),
+                       s24_to_f32(right, Justification::Right));
+        }
+        assert_eq!(s24_to_f32(0x7fffff00, Justification::Left), 8388607.0 / 8388608.0);
+        assert_eq!(s24_to_f32(-0x80000000, Justification::Left), -1.0);
+    }
+
+    #[test]
+    fn test_s24_ignores_padding() {
+        assert_eq!(s24_to_f32(0x7fffffff, Justification::Left),
+                   s24_to_f32(0x7fffff00, Justification::Left));
+        // Right-justified S24_LE with a zero (rather than sign-extended) top byte.
+        assert_eq!(s24_to_f32(0x00800000, Justification::Right), -1.0);
+        assert_eq!(s24_to_f32(0x7f000001, Justification::Right),
+                   s24_to_f32(0x00000001, Justification::Right));
+    }
+
+    #[test]
+    fn test_s24_round_trip() {
+        for &(samples, justification) in &[(FULL_SCALE_LEFT, Justification::Left),
+                                           (FULL_SCALE_RIGHT, Justification::Right)] {
+            for &sample in samples.iter() {
+                assert_eq!(f32_to_s24(s24_to_f32(sample, justification), justification),
+                           sample);
+            }
+        }
+    }
+
+    #[test]
+    fn test_s24_saturates() {
+        assert_eq!(f32_to_s24(1.5, Justification::Right), S24_MAX);
+        assert_eq!(f32_to_s24(-1.5, Justification::Right), S24_MIN);
+        assert_eq!(f32_to_s24(1.5, Justification::Left), 0x7fffff00);
+        assert_eq!(f32_to_s24(-1.5, Justification::Left), -0x80000000);
+    }
+
+    fn test_convert_s24(justification: Justification, channels: usize) {
+        let input: Vec<f32> = (0..3000 * channels)
+            .map(|i| ((i / channels) as f32 * 0.01 + (i % channels) as f32).sin() * 0.9)
+            .collect();
+        let input_s24: Vec<i32> = input.iter()
+            .map(|&s| f32_to_s24(s, justification))
+            .collect();
+        let input: Vec<f32> = input_s24.iter()
+            .map(|&s| s24_to_f32(s, justification))
+            .collect();
+
+        let mut expected = vec![0.; 6000 * channels];
+        let (used, gen) = convert(Interpolator::SincMediumQuality, channels, 2.0,
+                                  &input, &mut expected).unwrap();
+        let mut output = vec![0; 6000 * channels];
+        assert_eq!(convert_s24_in_i32(Interpolator::SincMediumQuality, channels, 2.0,
+                                      justification, &input_s24, &mut output).unwrap(),
+                   (used, gen));
+        for (&o, &e) in output.iter().zip(expected.iter()) {
+            assert_eq!(o, f32_to_s24(e, justification));
+        }
+    }
+
+    #[test]
+    fn test_convert_s24_left() {
+        test_convert_s24(Justification::Left, 1);
+        test_convert_s24(Justification::Left, 2);
+    }
+
+    #[test]
+    fn test_convert_s24_right() {
+        test_convert_s24(Justification::Right, 1);
+        test_convert_s24(Justification::Right, 2);
+    }
+
+    #[test]
+    fn test_int_samples() {
+        assert_eq!(i16::MAX.to_f32(), 32767.0 / 32768.0);
+        assert_eq!(i16::MIN.to_f32(), -1.0);
+        assert_eq!(i16::from_f32(1.5), i16::MAX);
+        assert_eq!(i16::from_f32(-1.5), i16::MIN);
+        assert_eq!(i8::from_f32(0.5 / 128.0), 1);
+        assert_eq!(i8::from_f32(-0.5 / 128.0), -1);
+        assert_eq!(i32::from_f32(1.0), i32::MAX);
+        for &sample in &[i16::MIN, -1, 0, 1, 12345, i16::MAX] {
+            assert_eq!(i16::from_f32(sample.to_f32()), sample);
+        }
+    }
+
+    #[test]
+    fn test_convert_generic_i16() {
+        let input: Vec<i16> = (0..3000)
+            .map(|i| i16::from_f32((i as f32 * 0.01).sin() * 0.9))
+            .collect();
+        let input_f32: Vec<f32> = input.iter().map(|&s| s.to_f32()).collect();
+        let mut expected = vec![0.; 6000];
+        let result = convert(Interpolator::SincFastest, 1, 2.0, &input_f32, &mut expected)
+            .unwrap();
+        let mut output = vec![0i16; 6000];
+        assert_eq!(convert_generic(Interpolator::SincFastest, 1, 2.0, &input, &mut output)
+                       .unwrap(), result);
+        for (&o, &e) in output.iter().zip(expected.iter()) {
+            assert_eq!(o, i16::from_f32(e));
+        }
+
+        let mut conv = PcmConverter::<i16>::new(Interpolator::SincFastest, 1).unwrap();
+        let mut streamed = vec![0i16; 6000];
+        let mut generated = 0;
+        for chunk in input.chunks(1000) {
+            generated += conv.convert(2.0, Some(chunk), &mut streamed[generated..]).unwrap().1;
+        }
+        generated += conv.convert(2.0, None, &mut streamed[generated..]).unwrap().1;
+        assert_eq!(generated, result.1);
+        assert_eq!(streamed, output);
+    }
+
+    #[test]
+    fn test_convert_f64_matches_f32() {
+        // More samples than fit in a chunk, in both directions.
+        let input: Vec<f64> = (0..10000).map(|i| (i as f64 * 0.013).sin() * 0.8).collect();
+        let input_f32: Vec<f32> = input.iter().map(|&sample| sample as f32).collect();
+        for &(channels, ratio) in &[(1, 2.0), (2, 0.75)] {
+            let mut expected = vec![0.0; 20100];
+            let (used, generated) = convert(Interpolator::SincFastest, channels, ratio,
+                                            &input_f32, &mut expected).unwrap();
+            let mut output = vec![0.0; 20100];
+            assert_eq!(convert_f64(Interpolator::SincFastest, channels, ratio, &input,
+                                   &mut output).unwrap(), (used, generated));
+            for (&o, &e) in output[..generated].iter().zip(&expected) {
+                assert!((o - e as f64).abs() <= f32::EPSILON as f64, "{} != {}", o, e);
+            }
+
+            let mut conv = Converter::new(Interpolator::SincFastest, channels).unwrap();
+            let mut streamed = vec![0.0; 20100];
+            let mut generated = 0;
+            for chunk in input.chunks(3000) {
+                let (_, g) = conv.convert_f64(ratio, Some(chunk), &mut streamed[generated..])
+                    .unwrap();
+                generated += g;
+            }
+            generated += conv.convert_f64(ratio, None, &mut streamed[generated..]).unwrap().1;
+            assert_eq!(streamed[..generated], output[..generated]);
+        }
+    }
+
+    #[test]
+    fn test_convert_i16_matches_f32() {
+        for &channels in &[1, 2] {
+            let input: Vec<i16> = (0..3000 * channels)
+                .map(|i| ((i / channels) as f32 * 0.01 * (1 + i % channels) as f32).sin())
+                .map(|sample| (sample * 0.9 * 32767.0).round() as i16)
+                .collect();
+            let input_f32: Vec<f32> = input.iter().map(|&s| s as f32 / 32768.0).collect();
+            let mut expected = vec![0.; 6000 * channels];
+            let result = convert(Interpolator::SincFastest, channels, 44100.0 / 48000.0,
+                                 &input_f32, &mut expected).unwrap();
+            let mut output = vec![0i16; 6000 * channels];
+            assert_eq!(convert_i16(Interpolator::SincFastest, channels, 44100.0 / 48000.0,
+                                   &input, &mut output).unwrap(), result);
+            for (&o, &e) in output[..result.1].iter().zip(expected.iter()) {
+                assert!((o as f32 - e * 32768.0).abs() <= 1.0, "{} vs {}", o, e * 32768.0);
+            }
+
+            let mut converter = Converter::new(Interpolator::SincFastest, channels).unwrap();
+            let mut streamed = vec![0i16; 6000 * channels];
+            let mut generated = 0;
+            for chunk in input.chunks(777 * channels) {
+                generated += converter.convert_i16(44100.0 / 48000.0, Some(chunk),
+                                                   &mut streamed[generated..]).unwrap().1;
+            }
+            generated += converter.convert_i16(44100.0 / 48000.0, None,
+                                               &mut streamed[generated..]).unwrap().1;
+            assert_eq!(generated, result.1);
+            assert_eq!(streamed, output);
+        }
+    }
+
+    #[test]
+    fn test_convert_i32_square_saturates() {
+        // A full-scale square wave overshoots at every edge, which must saturate, not wrap.
+        let input: Vec<i32> = (0..4000)
+            .map(|i| if (i / 50) % 2 == 0 { i32::MAX } else { i32::MIN })
+            .collect();
+        let mut output = vec![0; 8000];
+        let (used, generated) = convert_i32(Interpolator::SincBestQuality, 1, 1.5, &input,
+                                            &mut output).unwrap();
+        assert_eq!(used, 4000);
+        let output = &output[..generated];
+        assert!(output.contains(&i32::MAX) && output.contains(&i32::MIN));
+        // Wrapping would turn a sample just past either rail into one near the other rail,
+        // in the middle of a run of the opposite sign.
+        for (i, window) in output.windows(3).enumerate() {
+            let (before, sample, after) = (window[0], window[1], window[2]);
+            if before > i32::MAX / 2 && after > i32::MAX / 2 {
+                assert!(sample > 0, "wrapped sample {} at {}", sample, i + 1);
+            }
+            if before < i32::MIN / 2 && after < i32::MIN / 2 {
+                assert!(sample < 0, "wrapped sample {} at {}", sample, i + 1);
+            }
+        }
+
+        let mut converter = Converter::new(Interpolator::SincBestQuality, 1).unwrap();
+        let mut streamed = vec![0; 8000];
+        let mut generated = 0;
+        for chunk in input.chunks(333) {
+            generated += converter.convert_i32(1.5, Some(chunk), &mut streamed[generated..])
+                .unwrap().1;
+        }
+        generated += converter.convert_i32(1.5, None, &mut streamed[generated..]).unwrap().1;
+        assert_eq!(&streamed[..generated], output);
+    }
+
+    #[test]
+    fn test_i32_scaling_matches_libsamplerate() {
+        let mut floats = [0.0; 3];
+        int_to_float(backend::current())(&[i32::MIN, i32::MAX, 0], &mut floats);
+        assert_eq!(floats, [-1.0, 1.0, 0.0]);
+        let overs = [-1.5, -1.0, 1.0, 1.5, 0.0, 0.4 / 2147483648.0, 256.0 / 2147483648.0];
+        let mut output = [0; 7];
+        float_to_int(backend::current())(&overs, &mut output);
+        assert_eq!(output, [i32::MIN, i32::MIN, i32::MAX, i32::MAX, 0, 0, 256]);
+    }
+
+    #[test]
+    fn test_i16_scaling_matches_libsamplerate() {
+        let lsb = 1.0 / 32768.0;
+        let input = [1.5, 1.0, -1.0, -1.5, 0.75 * lsb, -0.25 * lsb, 100.5 * lsb, 0.0];
+        let mut output = [0i16; 8];
+        float_to_short(backend::current())(&input, &mut output);
+        assert_eq!(output, [i16::MAX, i16::MAX, i16::MIN, i16::MIN, 0, -1, 100, 0]);
+        let mut back = [0.0; 8];
+        short_to_float(backend::current())(&output, &mut back);
+        assert_eq!(back[..4], [32767.0 * lsb, 32767.0 * lsb, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_converter_s24_streaming() {
+        let input: Vec<i32> = (0..4000).map(|i| f32_to_s24((i as f32 * 0.02).sin(),
+                                                           Justification::Right)).collect();
+        let mut expected = vec![0; 2000];
+        let (used, gen) = convert_s24_in_i32(Interpolator::Linear, 1, 0.5, Justification::Right,
+                                             &input, &mut expected).unwrap();
+
+        let mut conv = Converter::new(Interpolator::Linear, 1).unwrap();
+        let mut output = vec![0; 2000];
+        let (mut u, mut g) = (0, 0);
+        for chunk in input.chunks(333) {
+            let (cu, cg) = conv.convert_s24_in_i32(0.5, Justification::Right, Some(chunk),
+                                                   &mut output[g..]).unwrap();
+            assert_eq!(cu, chunk.len());
+            u += cu;
+            g += cg;
+        }
+        g += conv.convert_s24_in_i32(0.5, Justification::Right, None, &mut output[g..])
+            .unwrap().1;
+        assert_eq!((u, g), (used, gen));
+        assert_eq!(output, expected);
+    }
+
+    /// Sample that panics when converted back from a sample above ``0.9``.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Fragile(f32);
+
+    impl Sample for Fragile {
+        fn to_f32(self) -> f32 { self.0 }
+        fn from_f32(sample: f32) -> Fragile {
+            assert!(sample <= 0.9, "sample out of range");
+            Fragile(sample)
+        }
+    }
+
+    #[test]
+    fn test_panic_poisons() {
+        let input: Vec<Fragile> = (0..1000).map(|i| Fragile((i as f32 * 0.05).sin() * 0.5))
+            .collect();
+        let mut output = vec![Fragile(0.0); 2000];
+        let mut converter = PcmConverter::<Fragile>::new(Interpolator::Linear, 1).unwrap();
+        let loud = [Fragile(1.0); 100];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            converter.convert(1.0, Some(&loud), &mut output)
+        }));
+        assert!(result.is_err());
+        assert_eq!(converter.convert(2.0, Some(&input), &mut output).unwrap_err().kind(),
+                   ErrorKind::Poisoned);
+
+        // After a reset, the converter is as good as new.
+        converter.reset().unwrap();
+        let (used, generated) = converter.convert(2.0, Some(&input), &mut output).unwrap();
+        let mut expected = vec![Fragile(0.0); 2000];
+        let mut fresh = PcmConverter::<Fragile>::new(Interpolator::Linear, 1).unwrap();
+        assert_eq!(fresh.convert(2.0, Some(&input), &mut expected).unwrap(), (used, generated));
+        assert_eq!(output, expected);
+    }
+}
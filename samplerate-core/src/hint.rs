@@ -0,0 +1,154 @@
+//! Sizing of output buffers.
+//!
+//! libsamplerate generates less than one frame more than the input length times the ratio,
+//! depending on how the positions of the output frames round; the sinc interpolators, which
+//! compensate for their delay, stay within that even though they hold input back.
+
+use {Converter, Interpolator};
+
+/// Retrieve the number of frames sufficient for the output of ``input_frames`` frames at
+/// ``ratio``: one frame for rounding, and one more so that the output never fills the buffer,
+/// which tells it apart from output that was cut short.
+fn output_frames_hint(ratio: f64, input_frames: usize) -> usize {
+    ((input_frames as f64 * ratio) as usize).saturating_add(2)
+}
+
+/// Retrieve a length of the output buffer, in samples, that is sufficient for the output of
+/// a [``convert``](fn.convert.html) call with ``input_len`` samples of input.
+///
+/// With an output buffer of this length, ``convert`` uses as much of the input and generates
+/// as much output as it would with any longer buffer, and generates less than the length of
+/// the buffer. The same length suffices for every interpolator of the vendored
+/// libsamplerate; ``interpolator`` is taken so that a tighter length can be given for one
+/// that needs less. Returns 0 if ``channels`` is zero.
+pub fn output_len_hint(interpolator: Interpolator, channels: usize, ratio: f64,
+                       input_len: usize) -> usize {
+    let _ = interpolator;
+    if channels == 0 {
+        return 0
+    }
+    output_frames_hint(ratio, input_len.div_ceil(channels)).saturating_mul(channels)
+}
+
+impl Converter {
+    /// Retrieve a length of the output buffer, in samples, that is sufficient for the output
+    /// of a [``convert``](#method.convert) call at ``ratio`` with ``input_len`` samples of
+    /// input, like [``output_len_hint``](fn.output_len_hint.html) does for a single
+    /// conversion.
+    ///
+    /// This accounts for the ratio being ramped from that of the previous call, or from the
+    /// one given to [``set_ratio``](#method.set_ratio), with ``input_len`` including the
+    /// input that calls before left unused. It holds as long as those calls did not fill
+    /// their output: the sinc interpolators take input into a buffer of their own, which
+    /// makes room for more output than the input of a single call. Flushing the filter tail
+    /// with ``None`` as input may need several calls.
+    pub fn output_len_hint(&self, ratio: f64, input_len: usize) -> usize {
+        let channels = self.channels();
+        output_frames_hint(ratio.max(self.last_ratio), input_len.div_ceil(channels))
+            .saturating_mul(channels)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use convert;
+    use super::*;
+
+    /// Deterministic source of test cases.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, limit: u64) -> u64 {
+            self.next() % limit
+        }
+
+        /// Pick a ratio spread evenly over octaves, including both ends of the range.
+        fn ratio(&mut self) -> f64 {
+            match self.below(16) {
+                0 => 1.0 / 256.0,
+                1 => 256.0,
+                _ => 2f64.powf(self.below(1 << 20) as f64 / (1 << 16) as f64 - 8.0),
+            }
+        }
+
+        /// Pick a number of input frames, few enough to keep the output small at ``ratio``.
+        fn frames(&mut self, ratio: f64) -> usize {
+            let frames = self.below(3000) as usize + 1;
+            frames.min((10000.0 / ratio) as usize).max(1)
+        }
+
+        fn samples(&mut self, len: usize) -> Vec<f32> {
+            (0..len).map(|_| (self.below(2000) as f32 - 1000.0) / 1000.0).collect()
+        }
+    }
+
+    #[test]
+    fn test_one_shot() {
+        let mut random = XorShift(0x5851_f42d_4c95_7f2d);
+        for interpolator in Interpolator::all() {
+            for _ in 0..100 {
+                let channels = random.below(2) as usize + 1;
+                let ratio = random.ratio();
+                let frames = random.frames(ratio);
+                let input = random.samples(frames * channels);
+                let hint = output_len_hint(interpolator, channels, ratio, input.len());
+                let mut output = vec![0.0; hint];
+                let result = convert(interpolator, channels, ratio, &input, &mut output)
+                    .unwrap();
+                // The output not filling the buffer means that it was not what limited it.
+                assert!(result.1 < hint, "{:?} {} {} {:?}", interpolator, ratio, input.len(),
+                        result);
+                let mut unbounded = vec![0.0; 2 * hint + 16];
+                assert_eq!(convert(interpolator, channels, ratio, &input, &mut unbounded)
+                               .unwrap(), result);
+            }
+        }
+        assert_eq!(output_len_hint(Interpolator::Linear, 0, 1.0, 100), 0);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let mut random = XorShift(0x2127_599b_f432_5c37);
+        for interpolator in Interpolator::all() {
+            for _ in 0..50 {
+                let channels = random.below(2) as usize + 1;
+                let mut conv = Converter::new(interpolator, channels).unwrap();
+                // Start the stream at another ratio, or at the same one.
+                let ratio = random.ratio();
+                let first_ratio = if random.below(2) == 0 { ratio } else { random.ratio() };
+                let frames = random.frames(first_ratio);
+                let input = random.samples(frames * channels);
+                let mut output = vec![0.0; conv.output_len_hint(first_ratio, input.len())];
+                let (used, generated) =
+                    conv.convert(first_ratio, Some(&input), &mut output).unwrap();
+                assert!(generated < output.len());
+
+                // Unused input is passed again with the next call.
+                let mut input = input[used..].to_vec();
+                let frames = random.frames(ratio.max(first_ratio));
+                input.extend(random.samples(frames * channels));
+                let hint = conv.output_len_hint(ratio, input.len());
+                let mut unbounded = conv.try_clone().unwrap();
+                let mut output = vec![0.0; hint];
+                let result = conv.convert(ratio, Some(&input), &mut output).unwrap();
+                assert!(result.1 < hint, "{:?} {} {} {} {:?}", interpolator, first_ratio, ratio,
+                        input.len(), result);
+                // A ramp is spread over the length of the output buffer, and so is the output.
+                if ratio == first_ratio {
+                    let mut output = vec![0.0; 2 * hint + 16];
+                    assert_eq!(unbounded.convert(ratio, Some(&input), &mut output).unwrap(),
+                               result);
+                }
+            }
+        }
+    }
+}
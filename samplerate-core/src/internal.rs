@@ -0,0 +1,64 @@
+//! Internals of this crate needed by the ``samplerate`` crate, which builds on it.
+//!
+//! This is not a stable interface, and may change in any release. The traits are sealed, so
+//! they cannot be implemented outside of this crate.
+
+use {Converter, Error, Result};
+
+mod private {
+    pub trait Sealed {}
+}
+
+impl private::Sealed for Converter {}
+impl private::Sealed for Error {}
+
+/// Conversion methods of [``Converter``](../struct.Converter.html) that are not in its public
+/// interface.
+pub trait ConverterInternals: private::Sealed {
+    /// Convert ``input``, flushing the internal state afterwards if ``end`` is set.
+    fn process(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32])
+        -> Result<(usize, usize)>;
+}
+
+impl ConverterInternals for Converter {
+    fn process(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        Converter::process(self, ratio, input, end, output)
+    }
+}
+
+/// Constructors of [``Error``](../struct.Error.html) for errors that are not reported by
+/// libsamplerate.
+pub trait ErrorInternals: private::Sealed {
+    /// Create an error of kind ``BadRatio`` reporting that ``ratio`` was rejected.
+    fn bad_ratio(ratio: f64) -> Self;
+
+    /// Create an error reporting that ``requested_bytes`` could not be allocated.
+    fn out_of_memory(requested_bytes: usize) -> Self;
+
+    /// Create an error reporting that a queue holding at most ``capacity_frames`` frames has
+    /// no room for more.
+    fn queue_full(capacity_frames: usize) -> Self;
+
+    /// Create an error reporting that a buffer of ``len`` samples does not hold a whole
+    /// number of frames of ``channels`` samples.
+    fn bad_buffer_length(len: usize, channels: usize) -> Self;
+}
+
+impl ErrorInternals for Error {
+    fn bad_ratio(ratio: f64) -> Error {
+        Error::bad_ratio(ratio)
+    }
+
+    fn out_of_memory(requested_bytes: usize) -> Error {
+        Error::out_of_memory(requested_bytes)
+    }
+
+    fn queue_full(capacity_frames: usize) -> Error {
+        Error::queue_full(capacity_frames)
+    }
+
+    fn bad_buffer_length(len: usize, channels: usize) -> Error {
+        Error::bad_buffer_length(len, channels)
+    }
+}
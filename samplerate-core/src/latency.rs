@@ -0,0 +1,129 @@
+//! Latency of the interpolators.
+//!
+//! libsamplerate compensates for the delay of its sinc filters, so that the output stays
+//! aligned with the input: an impulse at an input frame comes out at the corresponding output
+//! frame. To do so, it holds back half of the filter length of input, which it only converts
+//! once the input that follows arrives, or at the end of the stream. ``Linear`` and
+//! ``ZeroOrderHold`` instead interpolate towards the newest input frame, which delays the
+//! output by one input frame.
+
+use {Converter, Interpolator};
+
+/// Number of coefficients of the sinc filters, and the number of coefficients per input
+/// frame, as in ``fastest_coeffs.h``, ``mid_qual_coeffs.h`` and ``high_qual_coeffs.h``.
+fn sinc_coeffs(interpolator: Interpolator) -> Option<(f64, f64)> {
+    match interpolator {
+        Interpolator::SincFastest => Some((2464.0, 128.0)),
+        Interpolator::SincMediumQuality => Some((22438.0, 491.0)),
+        Interpolator::SincBestQuality => Some((340239.0, 2381.0)),
+        _ => None,
+    }
+}
+
+impl Converter {
+    /// Retrieve the delay of the output relative to the input, in output frames, rounded to
+    /// the nearest frame.
+    ///
+    /// This is zero for the sinc interpolators, which compensate for the delay of their
+    /// filter, and one input frame for ``Linear`` and ``ZeroOrderHold``. It depends on the
+    /// ratio applied to the last generated frame, or given to
+    /// [``set_ratio``](#method.set_ratio); before either, the ratio is taken to be 1. It is
+    /// zero for a converter created with [``from_raw``](#method.from_raw), whose interpolator
+    /// is unknown.
+    pub fn output_latency(&self) -> usize {
+        match self.interpolator() {
+            Some(Interpolator::Linear) | Some(Interpolator::ZeroOrderHold) =>
+                (self.current_ratio() + 0.5) as usize,
+            _ => 0,
+        }
+    }
+
+    /// Retrieve the number of input frames that the converter holds back, rounded up: the
+    /// output for them is only generated once more input arrives, or at the end of the
+    /// stream.
+    ///
+    /// For the sinc interpolators, this is half of the filter length, which grows as the
+    /// ratio falls below 1, since the filter is stretched to cut off below the output
+    /// sample rate. ``Linear`` and ``ZeroOrderHold`` hold back one frame. The ratio is taken
+    /// like for [``output_latency``](#method.output_latency), and this is zero as well for
+    /// a converter whose interpolator is unknown.
+    pub fn input_latency(&self) -> usize {
+        let interpolator = match self.interpolator() {
+            Some(interpolator) => interpolator,
+            None => return 0,
+        };
+        match sinc_coeffs(interpolator) {
+            // The number of frames the sinc interpolators keep on either side of the
+            // current one, as in ``sinc_vari_process``, which rounds with ``lrint``.
+            Some((len, increment)) => {
+                let count = len / increment / self.current_ratio().min(1.0);
+                let (whole, fraction) = (count as usize, count - (count as usize) as f64);
+                if fraction > 0.5 || fraction == 0.5 && whole % 2 == 1 {
+                    whole + 2
+                } else {
+                    whole + 1
+                }
+            }
+            None => 1,
+        }
+    }
+
+    fn current_ratio(&self) -> f64 {
+        if self.last_ratio > 0.0 { self.last_ratio } else { 1.0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use super::*;
+
+    #[test]
+    fn test_impulse() {
+        for interpolator in Interpolator::all() {
+            // When downsampling, ``Linear`` and ``ZeroOrderHold`` may skip over an impulse.
+            for &ratio in &[1.0, 1.5, 2.0, 3.0] {
+                let mut conv = Converter::new(interpolator, 1).unwrap();
+                assert_eq!(conv.interpolator(), Some(interpolator));
+                let mut input: Vec<f32> = vec![0.0; 2000];
+                input[500] = 1.0;
+                let mut output = vec![0.0; 6100];
+                let (_, generated) = conv.convert(ratio, Some(&input), &mut output).unwrap();
+                let (_, flushed) = conv.convert(ratio, None, &mut output[generated..]).unwrap();
+                let peak = output[..generated + flushed].iter().enumerate()
+                    .fold((0, 0.0), |(index, peak), (i, &sample)| {
+                        if sample > peak { (i, sample) } else { (index, peak) }
+                    }).0;
+                let expected = (500.0 * ratio) as usize + conv.output_latency();
+                assert!(peak.abs_diff(expected) <= 1, "{:?} at {}: peak at {}, expected {}",
+                        interpolator, ratio, peak, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_held_back() {
+        for interpolator in Interpolator::all() {
+            for &ratio in &[0.25, 0.5, 1.0, 2.0] {
+                let mut conv = Converter::new(interpolator, 1).unwrap();
+                let input = vec![0.5; 20000];
+                let mut output = vec![0.0; 80100];
+                let (used, generated) = conv.convert(ratio, Some(&input), &mut output)
+                    .unwrap();
+                // The sinc interpolators use all of the input, and generate the output for
+                // all but the frames they hold back. An output frame covers several input
+                // frames when downsampling. The others report the frame as unused.
+                let held_back = if sinc_coeffs(interpolator).is_some() {
+                    input.len() as f64 - generated as f64 / ratio
+                } else {
+                    (input.len() - used) as f64
+                };
+                let tolerance = 1.0f64.max(1.0 / ratio);
+                assert!((held_back - conv.input_latency() as f64).abs() <= tolerance,
+                        "{:?} at {}: {} held back, latency {}", interpolator, ratio,
+                        held_back, conv.input_latency());
+            }
+        }
+    }
+}
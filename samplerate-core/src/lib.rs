@@ -0,0 +1,1671 @@
+//! Allocation-free core of the [``samplerate``](https://docs.rs/samplerate/) bindings for
+//! [libsamplerate](http://www.mega-nerd.com/libsamplerate/): the converters, sample formats
+//! and errors.
+//!
+//! This crate never allocates and has no optional dependencies enabled by default. It only
+//! uses ``std`` for the ``std`` feature, which is enabled by default; without the default
+//! features, it is suitable for bare-metal targets. Everything in it is re-exported by
+//! ``samplerate``, which also provides helpers that need ``alloc`` or ``std``; depend on
+//! ``samplerate`` unless those helpers are unwanted.
+
+// It's impossible to usefully expose the callback-based libsamplerate API because it captures
+// a pointer provided by the callback indefinitely, effectively leaking the buffer until the end
+// of the converter lifetime.
+
+#![no_std]
+
+#[cfg(any(feature = "std", test, doctest))]
+extern crate std;
+extern crate samplerate_sys;
+#[cfg(feature = "fixed")]
+extern crate fixed;
+#[cfg(all(feature = "system", unix))]
+extern crate libc;
+#[cfg(feature = "runtime-loading")]
+extern crate libloading;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+use core::{str, fmt};
+use core::convert::TryFrom;
+use core::ffi::{CStr, c_char, c_int, c_long};
+use samplerate_sys::*;
+
+use backend::Api;
+use passthrough::PassthroughTracker;
+use rates::RateTracker;
+use silence::SilenceTracker;
+
+mod backend;
+mod budget;
+mod clip;
+mod clock;
+#[cfg(feature = "fixed-point")]
+mod fixed_interp;
+#[cfg(feature = "fixed")]
+mod fixed_point;
+mod formats;
+mod hint;
+#[doc(hidden)]
+pub mod internal;
+mod latency;
+mod passthrough;
+mod pingpong;
+mod planar;
+mod ramp;
+mod rates;
+#[cfg(feature = "serde")]
+mod serialize;
+mod silence;
+mod status;
+mod strided;
+pub mod time;
+mod version;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm_simd;
+
+#[cfg(feature = "runtime-loading")]
+pub use backend::load_library;
+pub use backend::{Backend, backend};
+pub use budget::{BUDGET_CHUNK_FRAMES, BudgetResult};
+pub use clip::Clipping;
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+#[cfg(feature = "fixed-point")]
+pub use fixed_interp::{FixedConverter, FixedSample, MAX_FIXED_CHANNELS};
+pub use formats::{Justification, PcmConverter, Sample, convert_f64, convert_generic,
+                  convert_i16, convert_i32, convert_s24_in_i32};
+pub use hint::output_len_hint;
+pub use pingpong::{Half, PingPongResampler};
+pub use planar::convert_planar;
+pub use rates::{convert_rates, ratio_for_rates};
+pub use status::StreamStatus;
+pub use strided::convert_strided;
+pub use version::{CLONE_LIBRARY_VERSION, LibraryVersion, MINIMUM_LIBRARY_VERSION,
+                  check_library_compatibility, library_version};
+
+/// Interpolator type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+#[allow(clippy::manual_non_exhaustive)]
+pub enum Interpolator {
+    SincBestQuality = SRC_SINC_BEST_QUALITY,
+    SincMediumQuality = SRC_SINC_MEDIUM_QUALITY,
+    SincFastest = SRC_SINC_FASTEST,
+    ZeroOrderHold = SRC_ZERO_ORDER_HOLD,
+    Linear = SRC_LINEAR,
+    #[doc(hidden)]
+    __Nonexhaustive
+}
+
+/// Every interpolator, in the order of the libsamplerate constants.
+const INTERPOLATORS: [Interpolator; 5] = [
+    Interpolator::SincBestQuality,
+    Interpolator::SincMediumQuality,
+    Interpolator::SincFastest,
+    Interpolator::ZeroOrderHold,
+    Interpolator::Linear,
+];
+
+/// Names of the interpolators in configuration files, in the order of ``INTERPOLATORS``.
+const INTERPOLATOR_NAMES: [&str; 5] = [
+    "sinc_best_quality",
+    "sinc_medium_quality",
+    "sinc_fastest",
+    "zero_order_hold",
+    "linear",
+];
+
+impl Interpolator {
+    /// Iterate over every interpolator, from the best quality to the fastest.
+    pub fn all() -> impl Iterator<Item = Interpolator> {
+        INTERPOLATORS.iter().cloned()
+    }
+
+    /// Retrieve the name libsamplerate gives the interpolator, such as
+    /// "Best Sinc Interpolator".
+    pub fn name(self) -> &'static str {
+        self.text(backend::current().src_get_name)
+    }
+
+    /// Retrieve the description libsamplerate gives the interpolator, such as
+    /// "Band limited sinc interpolation, fastest, 97dB SNR, 80% BW.".
+    pub fn description(self) -> &'static str {
+        self.text(backend::current().src_get_description)
+    }
+
+    fn text(self, get: unsafe extern "C" fn(c_int) -> *const c_char) -> &'static str {
+        if self == Interpolator::__Nonexhaustive {
+            return ""
+        }
+        unsafe { static_str(get(self as c_int)).unwrap_or("") }
+    }
+}
+
+/// Short names of the interpolators, in the order of ``INTERPOLATORS``.
+const INTERPOLATOR_ALIASES: [&str; 5] = ["best", "medium", "fastest", "zoh", "linear"];
+
+/// Check whether ``name`` is ``expected``, ignoring ASCII case and taking hyphens for
+/// underscores.
+fn matches_name(name: &str, expected: &str) -> bool {
+    name.len() == expected.len() && name.bytes().zip(expected.bytes())
+        .all(|(byte, expected)| byte.to_ascii_lowercase() == expected ||
+                                 byte == b'-' && expected == b'_')
+}
+
+impl str::FromStr for Interpolator {
+    type Err = Error;
+
+    /// Parse the name of an interpolator, such as ``"sinc_fastest"`` or ``"Sinc-Fastest"``,
+    /// or its short name, such as ``"fastest"``; see the ``Display`` of
+    /// [``UnknownInterpolator``](enum.ErrorKind.html#variant.UnknownInterpolator) for all of
+    /// them. ASCII case is ignored, and hyphens may stand for underscores.
+    fn from_str(name: &str) -> Result<Interpolator> {
+        INTERPOLATOR_NAMES.iter().zip(&INTERPOLATOR_ALIASES)
+            .position(|(canonical, alias)| matches_name(name, canonical) ||
+                                           matches_name(name, alias))
+            .map(|index| INTERPOLATORS[index])
+            .ok_or(Error::new(ErrorKind::UnknownInterpolator))
+    }
+}
+
+impl TryFrom<u32> for Interpolator {
+    type Error = Error;
+
+    /// Convert the value of an ``SRC_*`` interpolator constant, returning an error of kind
+    /// [``BadConverter``](enum.ErrorKind.html#variant.BadConverter) for any other value.
+    fn try_from(value: u32) -> Result<Interpolator> {
+        Interpolator::all().find(|&interpolator| interpolator as u32 == value)
+            .ok_or_else(|| Error::from_code(SRC_ERR_BAD_CONVERTER))
+    }
+}
+
+/// Borrow a string returned by libsamplerate, which lives as long as the library.
+unsafe fn static_str(ptr: *const c_char) -> Option<&'static str> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(str::from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()))
+    }
+}
+
+/// Conversion error.
+///
+/// Errors are cheap to clone and can be freely sent between threads, so they work with
+/// `anyhow`, `tokio::spawn` and similar:
+///
+/// ```edition2018
+/// use samplerate_core::{convert, Interpolator};
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let task = tokio::spawn(async {
+///         let input = [0.0; 64];
+///         let mut output = [0.0; 128];
+///         convert(Interpolator::Linear, 1, 2.0, &input, &mut output)?;
+///         Ok::<_, samplerate_core::Error>(())
+///     });
+///     task.await.unwrap().unwrap();
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Error {
+    kind: ErrorKind,
+    code: c_int,
+    desc: Option<&'static str>,
+    ratio: Option<f64>,
+}
+
+/// Category of a conversion error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::manual_non_exhaustive)]
+pub enum ErrorKind {
+    /// libsamplerate could not allocate memory (``SRC_ERR_MALLOC_FAILED``).
+    MallocFailed,
+    /// The state pointer is null (``SRC_ERR_BAD_STATE``).
+    BadState,
+    /// The data pointer is null (``SRC_ERR_BAD_DATA``).
+    BadData,
+    /// An input or output buffer pointer is null (``SRC_ERR_BAD_DATA_PTR``).
+    BadDataPtr,
+    /// The state has no private data (``SRC_ERR_NO_PRIVATE``).
+    NoPrivate,
+    /// The conversion ratio is outside of [1/256, 256] (``SRC_ERR_BAD_SRC_RATIO``), or not
+    /// a number; see [``is_valid_ratio``](fn.is_valid_ratio.html). The bindings check ratios
+    /// before passing them to libsamplerate, and [``Error::ratio``](struct.Error.html#method.ratio)
+    /// retrieves the rejected one.
+    BadRatio,
+    /// The state has no process function (``SRC_ERR_BAD_PROC_PTR``).
+    BadProcPtr,
+    /// Internal error (``SRC_ERR_SHIFT_BITS``).
+    ShiftBits,
+    /// Internal error (``SRC_ERR_FILTER_LEN``).
+    FilterLen,
+    /// The interpolator is unknown (``SRC_ERR_BAD_CONVERTER``).
+    BadConverter,
+    /// The channel count is zero or too large (``SRC_ERR_BAD_CHANNEL_COUNT``).
+    BadChannelCount,
+    /// Internal error (``SRC_ERR_SINC_BAD_BUFFER_LEN``).
+    SincBadBufferLen,
+    /// Internal error (``SRC_ERR_SIZE_INCOMPATIBILITY``).
+    SizeIncompatibility,
+    /// Internal error (``SRC_ERR_BAD_PRIV_PTR``).
+    BadPrivPtr,
+    /// Input was converted after the end of the stream without a
+    /// [``reset``](struct.Converter.html#method.reset) (``SRC_ERR_BAD_SINC_STATE``).
+    BadSincState,
+    /// The input and output buffers overlap (``SRC_ERR_DATA_OVERLAP``). The bindings check
+    /// buffers before passing them to libsamplerate, which rejects them too.
+    DataOverlap,
+    /// The callback is null (``SRC_ERR_BAD_CALLBACK``).
+    BadCallback,
+    /// The state was used in a mode other than the one it was created for
+    /// (``SRC_ERR_BAD_MODE``).
+    BadMode,
+    /// The callback is null (``SRC_ERR_NULL_CALLBACK``).
+    NullCallback,
+    /// The interpolator only supports a constant ratio (``SRC_ERR_NO_VARIABLE_RATIO``).
+    NoVariableRatio,
+    /// Internal error (``SRC_ERR_SINC_PREPARE_DATA_BAD_LEN``).
+    SincPrepareDataBadLen,
+    /// The internal state is corrupted (``SRC_ERR_BAD_INTERNAL_STATE``).
+    BadInternalState,
+    /// libsamplerate reported an error code that the bindings do not know.
+    Unknown(i32),
+    /// The libsamplerate in use is older than the functionality requires; see
+    /// [``check_library_compatibility``](fn.check_library_compatibility.html).
+    IncompatibleLibrary {
+        found: LibraryVersion,
+        required: LibraryVersion,
+    },
+    /// The library could not be loaded at runtime; see
+    /// [``load_library``](fn.load_library.html).
+    LibraryNotFound,
+    /// A library other than the one requested was already loaded at runtime, which cannot
+    /// be replaced; see [``load_library``](fn.load_library.html).
+    LibraryAlreadyLoaded,
+    /// The library loaded at runtime lacks a function that the bindings use.
+    SymbolNotFound {
+        symbol: &'static str,
+    },
+    /// An earlier conversion panicked partway, so the converter cannot continue the stream;
+    /// see [``PcmConverter``](struct.PcmConverter.html).
+    Poisoned,
+    /// A buffer could not be allocated. The helpers that allocate report this instead of
+    /// aborting the process, and leave their outputs as they were before the call.
+    OutOfMemory {
+        requested_bytes: usize,
+    },
+    /// A bounded queue has no room for the samples; see
+    /// [``QueueConverter``](../samplerate/struct.QueueConverter.html) in the ``samplerate``
+    /// crate.
+    QueueFull {
+        capacity_frames: usize,
+    },
+    /// The producer of a [``PingPongResampler``](struct.PingPongResampler.html) overwrote
+    /// halves of the buffer before they were converted.
+    Overrun {
+        lost_halves: u32,
+    },
+    /// A buffer of ``len`` samples is not a whole number of frames of ``channels`` samples,
+    /// or ``channels`` is zero.
+    BadBufferLength {
+        len: usize,
+        channels: usize,
+    },
+    /// A sample rate is zero, or the rates are more than 256 times apart; see
+    /// [``ratio_for_rates``](fn.ratio_for_rates.html).
+    BadSampleRates {
+        from_hz: u32,
+        to_hz: u32,
+    },
+    /// A buffer of one channel holds ``len`` samples, while that of the first channel holds
+    /// ``expected``; see [``convert_planar``](fn.convert_planar.html).
+    BadPlaneLength {
+        len: usize,
+        expected: usize,
+    },
+    /// Input was converted after the end of the stream was signalled, without a
+    /// [``reset``](struct.Converter.html#method.reset) in between.
+    StreamEnded,
+    /// The output buffer given to flush a stream is empty, so the stream cannot finish; see
+    /// [``Converter::convert_with_status``](struct.Converter.html#method.convert_with_status).
+    EmptyOutput,
+    /// A string is not the name of an interpolator; see the implementation of ``FromStr``
+    /// for [``Interpolator``](enum.Interpolator.html).
+    UnknownInterpolator,
+    /// A passthrough threshold is negative or NaN; see
+    /// [``set_passthrough_threshold``](struct.Converter.html#method.set_passthrough_threshold).
+    BadThreshold,
+    #[doc(hidden)]
+    __Nonexhaustive
+}
+
+impl Error {
+    /// Create an error from a libsamplerate error code.
+    pub fn from_code(code: c_int) -> Error {
+        let desc = unsafe { static_str((backend::current().src_strerror)(code)) };
+        Error { kind: ErrorKind::from_code(code), code, desc, ratio: None }
+    }
+
+    /// Create an error that is not reported by libsamplerate.
+    fn new(kind: ErrorKind) -> Error {
+        Error { kind, code: 0, desc: None, ratio: None }
+    }
+
+    /// Create an error of kind [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) reporting
+    /// that ``ratio`` was rejected. Its code is ``SRC_ERR_BAD_SRC_RATIO``, as if libsamplerate
+    /// reported it.
+    pub(crate) fn bad_ratio(ratio: f64) -> Error {
+        Error { ratio: Some(ratio), ..Error::from_code(SRC_ERR_BAD_SRC_RATIO) }
+    }
+
+    /// Create an error reporting that ``requested_bytes`` could not be allocated.
+    pub(crate) fn out_of_memory(requested_bytes: usize) -> Error {
+        Error::new(ErrorKind::OutOfMemory { requested_bytes })
+    }
+
+    /// Create an error reporting that a queue holding at most ``capacity_frames`` frames has
+    /// no room for more.
+    pub(crate) fn queue_full(capacity_frames: usize) -> Error {
+        Error::new(ErrorKind::QueueFull { capacity_frames })
+    }
+
+    /// Create an error reporting that ``lost_halves`` halves of a double buffer were
+    /// overwritten before they were read.
+    pub(crate) fn overrun(lost_halves: u32) -> Error {
+        Error::new(ErrorKind::Overrun { lost_halves })
+    }
+
+    /// Create an error reporting that a buffer of ``len`` samples does not hold a whole
+    /// number of frames of ``channels`` samples.
+    pub(crate) fn bad_buffer_length(len: usize, channels: usize) -> Error {
+        Error::new(ErrorKind::BadBufferLength { len, channels })
+    }
+
+    /// Create an error reporting that there is no conversion ratio from ``from_hz`` to
+    /// ``to_hz``.
+    pub(crate) fn bad_sample_rates(from_hz: u32, to_hz: u32) -> Error {
+        Error::new(ErrorKind::BadSampleRates { from_hz, to_hz })
+    }
+
+    /// Create an error reporting that the buffer of a channel holds ``len`` samples, while
+    /// that of the first channel holds ``expected``.
+    pub(crate) fn bad_plane_length(len: usize, expected: usize) -> Error {
+        Error::new(ErrorKind::BadPlaneLength { len, expected })
+    }
+
+    /// Retrieve the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Retrieve the libsamplerate error code, or 0 if the error was not reported by
+    /// libsamplerate.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// Retrieve the conversion ratio that was rejected, if the bindings rejected it before it
+    /// reached libsamplerate.
+    pub fn ratio(&self) -> Option<f64> {
+        self.ratio
+    }
+}
+
+impl ErrorKind {
+    fn from_code(code: c_int) -> ErrorKind {
+        match code {
+            1 => ErrorKind::MallocFailed,
+            2 => ErrorKind::BadState,
+            3 => ErrorKind::BadData,
+            4 => ErrorKind::BadDataPtr,
+            5 => ErrorKind::NoPrivate,
+            6 => ErrorKind::BadRatio,
+            7 => ErrorKind::BadProcPtr,
+            8 => ErrorKind::ShiftBits,
+            9 => ErrorKind::FilterLen,
+            10 => ErrorKind::BadConverter,
+            11 => ErrorKind::BadChannelCount,
+            12 => ErrorKind::SincBadBufferLen,
+            13 => ErrorKind::SizeIncompatibility,
+            14 => ErrorKind::BadPrivPtr,
+            15 => ErrorKind::BadSincState,
+            16 => ErrorKind::DataOverlap,
+            17 => ErrorKind::BadCallback,
+            18 => ErrorKind::BadMode,
+            19 => ErrorKind::NullCallback,
+            20 => ErrorKind::NoVariableRatio,
+            21 => ErrorKind::SincPrepareDataBadLen,
+            22 => ErrorKind::BadInternalState,
+            code => ErrorKind::Unknown(code),
+        }
+    }
+}
+
+impl PartialEq for Error {
+    fn eq(&self, other: &Error) -> bool {
+        self.kind == other.kind && self.code == other.code
+    }
+}
+
+impl Eq for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ratio) = self.ratio {
+            return write!(f, "conversion ratio {} is outside of [1/256, 256]", ratio)
+        }
+        match (self.kind, self.desc) {
+            (ErrorKind::IncompatibleLibrary { found, required }, _) =>
+                write!(f, "libsamplerate {} is too old; {} or newer is required", found, required),
+            (ErrorKind::LibraryNotFound, _) => write!(f, "libsamplerate could not be loaded"),
+            (ErrorKind::LibraryAlreadyLoaded, _) =>
+                write!(f, "a different libsamplerate is already loaded"),
+            (ErrorKind::SymbolNotFound { symbol }, _) =>
+                write!(f, "libsamplerate has no function {}", symbol),
+            (ErrorKind::Poisoned, _) =>
+                write!(f, "an earlier conversion panicked; reset the converter to continue"),
+            (ErrorKind::OutOfMemory { requested_bytes }, _) =>
+                write!(f, "cannot allocate {} bytes", requested_bytes),
+            (ErrorKind::QueueFull { capacity_frames }, _) =>
+                write!(f, "queue of {} frames is full", capacity_frames),
+            (ErrorKind::Overrun { lost_halves }, _) =>
+                write!(f, "overrun, {} half-buffers lost", lost_halves),
+            (ErrorKind::BadBufferLength { channels: 0, .. }, _) =>
+                write!(f, "channel count must not be zero"),
+            (ErrorKind::BadBufferLength { len, channels }, _) =>
+                write!(f, "buffer of {} samples is not a whole number of {}-channel frames",
+                       len, channels),
+            (ErrorKind::BadSampleRates { from_hz, to_hz }, _) =>
+                write!(f, "cannot convert from {} Hz to {} Hz", from_hz, to_hz),
+            (ErrorKind::BadPlaneLength { len, expected }, _) =>
+                write!(f, "channel buffer of {} samples does not match the first one of {}",
+                       len, expected),
+            (ErrorKind::StreamEnded, _) =>
+                write!(f, "input after the end of the stream; reset the converter to start \
+                           another"),
+            (ErrorKind::EmptyOutput, _) =>
+                write!(f, "output buffer is empty, so the stream cannot be flushed"),
+            (ErrorKind::UnknownInterpolator, _) =>
+                write!(f, "unknown interpolator; expected sinc_best_quality, \
+                           sinc_medium_quality, sinc_fastest, zero_order_hold or linear, or \
+                           best, medium, fastest or zoh"),
+            (ErrorKind::BadThreshold, _) =>
+                write!(f, "passthrough threshold must not be negative or NaN"),
+            (_, Some(desc)) => write!(f, "{}", desc),
+            (_, None) => write!(f, "unknown ({})", self.code)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Information about the libsamplerate build in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildInfo {
+    vendored_version: Option<&'static str>,
+    native_lrint: Option<bool>,
+    lrint_note: Option<&'static str>,
+}
+
+impl BuildInfo {
+    /// Version of the vendored libsamplerate compiled into the crate, as selected by the
+    /// ``vendored-*`` features, or ``None`` if the system libsamplerate is linked instead.
+    pub fn vendored_version(&self) -> Option<&'static str> {
+        self.vendored_version
+    }
+
+    /// Whether the vendored libsamplerate rounds with the C99 ``lrint`` and ``lrintf``
+    /// functions, which were detected when building it, rather than with the replacement it
+    /// falls back to, which usually truncates and so biases the output slightly. ``None`` if
+    /// no vendored sources are compiled in.
+    pub fn native_lrint(&self) -> Option<bool> {
+        self.native_lrint
+    }
+
+    /// Describe how the vendored libsamplerate rounds and whether that was detected or set
+    /// with the ``SAMPLERATE_SYS_HAVE_LRINT`` environment variable, e.g. for bug reports.
+    pub fn lrint_note(&self) -> Option<&'static str> {
+        self.lrint_note
+    }
+}
+
+/// Retrieve information about how libsamplerate was built.
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        vendored_version: samplerate_sys::VENDORED_VERSION,
+        native_lrint: samplerate_sys::NATIVE_LRINT,
+        lrint_note: samplerate_sys::LRINT_NOTE,
+    }
+}
+
+/// Conversion result.
+pub type Result<T> = core::result::Result<T, Error>;
+
+// Error codes of libsamplerate that the bindings report themselves.
+pub(crate) const SRC_ERR_BAD_SRC_RATIO: c_int = 6;
+pub(crate) const SRC_ERR_BAD_CONVERTER: c_int = 10;
+pub(crate) const SRC_ERR_BAD_CHANNEL_COUNT: c_int = 11;
+pub(crate) const SRC_ERR_DATA_OVERLAP: c_int = 16;
+
+/// Check whether libsamplerate accepts ``ratio``, like ``src_is_valid_ratio``: it must be
+/// within [1/256, 256]. Unlike ``src_is_valid_ratio``, this also rejects NaN.
+pub fn is_valid_ratio(ratio: f64) -> bool {
+    (1.0 / SRC_MAX_RATIO..=SRC_MAX_RATIO).contains(&ratio)
+}
+
+/// Check that libsamplerate accepts ``ratio``.
+pub(crate) fn check_ratio(ratio: f64) -> Result<()> {
+    if !is_valid_ratio(ratio) {
+        return Err(Error::bad_ratio(ratio))
+    }
+    Ok(())
+}
+
+/// Check that buffers of ``input_len`` and ``output_len`` samples hold whole frames.
+pub(crate) fn check_frames(channels: usize, input_len: usize, output_len: usize) -> Result<()> {
+    for &len in &[input_len, output_len] {
+        if channels == 0 || len % channels != 0 {
+            return Err(Error::bad_buffer_length(len, channels))
+        }
+    }
+    Ok(())
+}
+
+/// Check that ``input`` and ``output`` share no samples, since libsamplerate reads the input
+/// while it writes the output. Adjacent buffers, such as the halves of ``split_at_mut``, and
+/// empty ones never overlap.
+fn check_overlap(input: &[f32], output: &[f32]) -> Result<()> {
+    let (input, output) = (input.as_ptr_range(), output.as_ptr_range());
+    if !input.is_empty() && !output.is_empty() &&
+            input.start < output.end && output.start < input.end {
+        return Err(Error::from_code(SRC_ERR_DATA_OVERLAP))
+    }
+    Ok(())
+}
+
+/// Largest number of samples passed to libsamplerate in a single call. It counts frames, and
+/// the samples in them, in a ``long``, which is only 32 bits wide on Windows and on 32-bit
+/// targets; longer buffers are converted in several calls.
+pub(crate) const MAX_CALL_SAMPLES: usize = c_long::MAX as usize;
+
+/// Build the arguments of a call; the buffers must hold at most ``MAX_CALL_SAMPLES`` samples.
+fn make_data(channels: usize, ratio: f64, end: bool,
+             input: &[f32], output: &mut [f32]) -> SRC_DATA {
+    debug_assert!(input.len() <= MAX_CALL_SAMPLES && output.len() <= MAX_CALL_SAMPLES);
+    SRC_DATA {
+        data_in:            input.as_ptr(),
+        data_out:           output.as_mut_ptr(),
+        input_frames:       (input.len() / channels) as c_long,
+        output_frames:      (output.len() / channels) as c_long,
+        input_frames_used:  0,
+        output_frames_gen:  0,
+        end_of_input:       end as c_int,
+        src_ratio:          ratio,
+    }
+}
+
+/// Perform a single conversion from input buffer to output buffer with a fixed conversion ratio.
+///
+/// This function should only be used to convert a complete buffer at once; to convert a buffer
+/// chunk by chunk, use [``Converter``](struct.Converter.html). Otherwise, artifacts will appear
+/// at chunk boundaries.
+///
+/// Returns the number of used input samples and generated output samples, respectively, that
+/// is, frames multiplied by ``channels``; [``convert_frames``](fn.convert_frames.html) returns
+/// frames instead. Returns an error of kind
+/// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``channels`` is zero
+/// or either buffer is not a whole number of frames, of kind
+/// [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if ``ratio`` is not
+/// [valid](fn.is_valid_ratio.html), and of kind
+/// [``DataOverlap``](enum.ErrorKind.html#variant.DataOverlap) if the buffers overlap, which
+/// only unsafe code can make them do.
+///
+/// libsamplerate counts frames in a ``long``, which is 32 bits wide on Windows and on 32-bit
+/// targets. Buffers of more samples than it holds are converted in several calls, through a
+/// [``Converter``](struct.Converter.html) so that no artifacts appear between them.
+pub fn convert(interpolator: Interpolator, channels: usize, ratio: f64,
+               input: &[f32], output: &mut [f32]) -> Result<(usize, usize)> {
+    convert_chunked(interpolator, channels, ratio, input, output, MAX_CALL_SAMPLES)
+}
+
+/// Convert like [``convert``](fn.convert.html), passing at most ``max_samples`` samples of
+/// either buffer to libsamplerate at once.
+fn convert_chunked(interpolator: Interpolator, channels: usize, ratio: f64, input: &[f32],
+                   output: &mut [f32], max_samples: usize) -> Result<(usize, usize)> {
+    check_frames(channels, input.len(), output.len())?;
+    check_overlap(input, output)?;
+    check_ratio(ratio)?;
+    if input.len() > max_samples || output.len() > max_samples {
+        // src_simple converts in a single call, but a state carries over between calls.
+        return Converter::new(interpolator, channels)?
+            .process_chunked(ratio, input, true, output, max_samples)
+    }
+    let mut data = make_data(channels, ratio, /*end=*/true, input, output);
+    let error = unsafe { (backend::current().src_simple)(&mut data as *mut _, interpolator as c_int,
+                                    channels as c_int) };
+    if error != 0 {
+        return Err(Error::from_code(error))
+    }
+    Ok((data.input_frames_used as usize * channels,
+        data.output_frames_gen as usize * channels))
+}
+
+/// Outcome of a conversion, in frames rather than in samples.
+///
+/// A frame holds one sample for each channel; the sample counts needed to partition
+/// interleaved buffers are returned by [``input_samples_used``](#method.input_samples_used)
+/// and [``output_samples_generated``](#method.output_samples_generated).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConversionResult {
+    /// Number of input frames consumed.
+    pub input_frames_used: usize,
+    /// Number of output frames written.
+    pub output_frames_generated: usize,
+}
+
+impl ConversionResult {
+    fn from_samples((used, generated): (usize, usize), channels: usize) -> ConversionResult {
+        ConversionResult {
+            input_frames_used: used / channels,
+            output_frames_generated: generated / channels,
+        }
+    }
+
+    /// Retrieve the number of input samples consumed, for interleaved ``channels``.
+    pub fn input_samples_used(&self, channels: usize) -> usize {
+        self.input_frames_used * channels
+    }
+
+    /// Retrieve the number of output samples written, for interleaved ``channels``.
+    pub fn output_samples_generated(&self, channels: usize) -> usize {
+        self.output_frames_generated * channels
+    }
+}
+
+/// Perform a single conversion like [``convert``](fn.convert.html), returning the number of
+/// used input frames and generated output frames.
+pub fn convert_frames(interpolator: Interpolator, channels: usize, ratio: f64,
+                      input: &[f32], output: &mut [f32]) -> Result<ConversionResult> {
+    convert(interpolator, channels, ratio, input, output)
+        .map(|samples| ConversionResult::from_samples(samples, channels))
+}
+
+/// Interface for performing a continuous conversion from input stream to output stream with
+/// a variable, smoothly interpolated conversion ratio.
+///
+/// # Panics and unwinding
+///
+/// libsamplerate never calls back into Rust, so a panic can never unwind through its
+/// frames. The methods return an error on invalid arguments (such as buffers that are not a
+/// whole number of frames) before the state is touched, and do not panic partway through
+/// updating it; the converter is ``UnwindSafe`` and ``RefUnwindSafe`` accordingly.
+/// The helpers that run user code between calls into libsamplerate, such as a
+/// [``Clock``](trait.Clock.html) or a [``Sample``](trait.Sample.html) implementation,
+/// document what a panic in that code leaves behind.
+///
+/// # Threads
+///
+/// A converter is ``Send``, so it can be created on one thread and moved to another, such
+/// as an audio callback, but not ``Sync``: libsamplerate state may be used from any thread,
+/// but only from one at a time.
+pub struct Converter {
+    state: *mut SRC_STATE,
+    #[cfg(feature = "runtime-loading")]
+    api: &'static Api,
+    /// Interpolator the state was created with, unless it came from ``from_raw``.
+    interpolator: Option<Interpolator>,
+    frames_in: u64,
+    frames_out: u64,
+    /// Ratio libsamplerate applied to the last generated frame, or 0 if it has none yet.
+    last_ratio: f64,
+    applied_ratio: Option<(f64, f64)>,
+    /// Ratio set by [``with_ratio``](#method.with_ratio), and again by every reset.
+    initial_ratio: Option<f64>,
+    silence: Option<SilenceTracker>,
+    passthrough: PassthroughTracker,
+    rates: Option<RateTracker>,
+    clipping: Clipping,
+    /// Peak of the output of the last call, if ``clipping`` measures it.
+    peak: Option<f32>,
+    /// Whether the end of the stream was signalled to libsamplerate.
+    ended: bool,
+    /// Whether everything libsamplerate held back was generated after the end.
+    done: bool,
+}
+
+/// libsamplerate ramps the ratio only if it is further than this from the target. (This is
+/// the threshold of the sinc interpolators; the others use a far smaller one, which makes
+/// no difference at the precision of the ratio.)
+const RATIO_RAMP_THRESHOLD: f64 = 1e-10;
+
+const SRC_MAX_RATIO: f64 = 256.0;
+
+impl Converter {
+    /// Create a converter.
+    ///
+    /// Returns an error of kind
+    /// [``IncompatibleLibrary``](enum.ErrorKind.html#variant.IncompatibleLibrary) if the
+    /// libsamplerate in use is too old for the bindings.
+    pub fn new(interpolator: Interpolator, channels: usize) -> Result<Converter> {
+        version::require(MINIMUM_LIBRARY_VERSION)?;
+        let api = backend::current();
+        let mut error: c_int = 0;
+        let state = unsafe { (api.src_new)(interpolator as c_int, channels as c_int,
+                                           &mut error as *mut _) };
+        if state.is_null() {
+            return Err(Error::from_code(error))
+        }
+        let mut converter = unsafe { Converter::from_raw_in(state, api) };
+        converter.interpolator = Some(interpolator);
+        Ok(converter)
+    }
+
+    /// Create a converter like [``new``](#method.new) that starts converting at ``ratio``,
+    /// as if [``set_ratio``](#method.set_ratio) was called before the first conversion and
+    /// after every [``reset``](#method.reset).
+    ///
+    /// Conversions at another ratio then ramp to it from ``ratio``, rather than starting
+    /// at it. Returns an error of kind [``BadRatio``](enum.ErrorKind.html#variant.BadRatio)
+    /// if ``ratio`` is not [valid](fn.is_valid_ratio.html).
+    pub fn with_ratio(interpolator: Interpolator, channels: usize, ratio: f64)
+            -> Result<Converter> {
+        check_ratio(ratio)?;
+        let mut converter = Converter::new(interpolator, channels)?;
+        converter.set_ratio(ratio)?;
+        converter.initial_ratio = Some(ratio);
+        Ok(converter)
+    }
+
+    /// Create a converter from a raw libsamplerate state.
+    ///
+    /// # Safety
+    ///
+    /// ``state`` must be a valid, non-null pointer returned by ``src_new`` (or
+    /// [``into_raw``](#method.into_raw)) that is not used by anything else afterwards.
+    /// The returned converter takes ownership of the state and frees it with ``src_delete``
+    /// when dropped. Its [``frames_in``](#method.frames_in) and
+    /// [``frames_out``](#method.frames_out) counters start at zero.
+    ///
+    /// With the ``runtime-loading`` feature, the state must come from the library that
+    /// [``backend``](fn.backend.html) reports.
+    pub unsafe fn from_raw(state: *mut SRC_STATE) -> Converter {
+        Converter::from_raw_in(state, backend::current())
+    }
+
+    #[cfg_attr(not(feature = "runtime-loading"), allow(unused_variables))]
+    unsafe fn from_raw_in(state: *mut SRC_STATE, api: &'static Api) -> Converter {
+        Converter {
+            state,
+            #[cfg(feature = "runtime-loading")]
+            api,
+            interpolator: None,
+            frames_in: 0,
+            frames_out: 0,
+            last_ratio: 0.0,
+            applied_ratio: None,
+            initial_ratio: None,
+            silence: None,
+            passthrough: PassthroughTracker::new(),
+            rates: None,
+            clipping: Clipping::None,
+            peak: None,
+            ended: false,
+            done: false,
+        }
+    }
+
+    /// Retrieve the ratios libsamplerate applied to the first and the last frame generated
+    /// by the most recent call to [``convert``](#method.convert), or ``None`` if there was
+    /// none since the converter was created or [``reset``](#method.reset).
+    ///
+    /// When the ratio changes, libsamplerate ramps it linearly from the ratio of the last
+    /// frame it generated to the new one, over the *size* of the output buffer; the last
+    /// frame of a call is generated before the ramp reaches its end, and a call that does not
+    /// fill its output stops partway. The ratio then keeps ramping in the following calls,
+    /// e.g. a call at 2 after one at 1 with a full output of 100 frames returns ``(1, 1.99)``,
+    /// and another one with the same output returns ``(1.99, 1.9999)``. Calls that generate
+    /// nothing return the same ratio twice. The ratios are tracked on the Rust side, and
+    /// match those of libsamplerate to within 10<sup>-10</sup>; for a converter created with
+    /// [``from_raw``](#method.from_raw), the state is assumed to be new.
+    pub fn last_applied_ratio(&self) -> Option<(f64, f64)> {
+        self.applied_ratio
+    }
+
+    /// Update the ratio trajectory after libsamplerate converted ``output_frames`` with
+    /// ``target``, generating ``generated_frames``.
+    fn track_ratio(&mut self, target: f64, generated_frames: f64, output_frames: f64) {
+        let first = if self.last_ratio < 1.0 / SRC_MAX_RATIO { target } else { self.last_ratio };
+        let last = if generated_frames > 0.0 && (target - first).abs() > RATIO_RAMP_THRESHOLD {
+            first + (generated_frames - 1.0) * (target - first) / output_frames
+        } else {
+            first
+        };
+        self.last_ratio = last;
+        self.applied_ratio = Some((first, last));
+    }
+
+    /// Retrieve the functions of the library that created the state.
+    fn api(&self) -> &'static Api {
+        #[cfg(feature = "runtime-loading")]
+        return self.api;
+        #[cfg(not(feature = "runtime-loading"))]
+        &backend::LINKED
+    }
+
+    /// Consume the converter, returning the raw libsamplerate state.
+    ///
+    /// The caller becomes responsible for the state, and must eventually free it with
+    /// ``src_delete`` of the library that created it, or convert it back with
+    /// [``from_raw``](#method.from_raw).
+    pub fn into_raw(self) -> *mut SRC_STATE {
+        let converter = core::mem::ManuallyDrop::new(self);
+        converter.state
+    }
+
+    /// Retrieve the raw libsamplerate state without giving up ownership.
+    ///
+    /// The pointer is valid for as long as the converter is alive, and must not be passed
+    /// to ``src_delete``.
+    pub fn as_raw(&self) -> *mut SRC_STATE {
+        self.state
+    }
+
+    /// Create an independent copy of the converter, including its filter state, so that the
+    /// copy continues the stream exactly as the original would.
+    ///
+    /// Returns an error of kind [``MallocFailed``](enum.ErrorKind.html#variant.MallocFailed)
+    /// if the copy cannot be allocated, of kind
+    /// [``IncompatibleLibrary``](enum.ErrorKind.html#variant.IncompatibleLibrary) if the
+    /// library of the converter lacks ``src_clone`` and is older than
+    /// [``CLONE_LIBRARY_VERSION``](constant.CLONE_LIBRARY_VERSION.html), and of kind
+    /// [``SymbolNotFound``](enum.ErrorKind.html#variant.SymbolNotFound) if it lacks
+    /// ``src_clone`` otherwise. The vendored sources carry a backport of it; with the
+    /// ``system`` feature, it is looked up in the linked library at runtime.
+    pub fn try_clone(&self) -> Result<Converter> {
+        let api = self.api();
+        let src_clone = match api.src_clone() {
+            Some(src_clone) => src_clone,
+            None => {
+                version::require_in(api, CLONE_LIBRARY_VERSION)?;
+                return Err(Error::new(ErrorKind::SymbolNotFound { symbol: "src_clone" }))
+            }
+        };
+        let mut error: c_int = 0;
+        let state = unsafe { src_clone(self.state, &mut error as *mut _) };
+        if state.is_null() {
+            return Err(Error::from_code(error))
+        }
+        let mut clone = unsafe { Converter::from_raw_in(state, api) };
+        clone.interpolator = self.interpolator;
+        clone.frames_in = self.frames_in;
+        clone.frames_out = self.frames_out;
+        clone.last_ratio = self.last_ratio;
+        clone.applied_ratio = self.applied_ratio;
+        clone.initial_ratio = self.initial_ratio;
+        clone.silence = self.silence.clone();
+        clone.passthrough = self.passthrough;
+        clone.rates = self.rates;
+        clone.clipping = self.clipping;
+        clone.peak = self.peak;
+        clone.ended = self.ended;
+        clone.done = self.done;
+        Ok(clone)
+    }
+
+    /// Retrieve the interpolator used by the converter, or ``None`` if it was created with
+    /// [``from_raw``](#method.from_raw), since libsamplerate does not record it in the state.
+    pub fn interpolator(&self) -> Option<Interpolator> {
+        self.interpolator
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        unsafe { (self.api().src_get_channels)(self.state) as usize }
+    }
+
+    /// Retrieve the total number of input frames used since the converter was created or
+    /// [``reset``](#method.reset).
+    pub fn frames_in(&self) -> u64 {
+        self.frames_in
+    }
+
+    /// Retrieve the total number of output frames generated since the converter was created or
+    /// [``reset``](#method.reset).
+    pub fn frames_out(&self) -> u64 {
+        self.frames_out
+    }
+
+    /// Retrieve the ratio the converter was created with by
+    /// [``with_ratio``](#method.with_ratio), if any.
+    pub fn initial_ratio(&self) -> Option<f64> {
+        self.initial_ratio
+    }
+
+    /// Reset the internal state to the same state it had after [``new``](#method.new), or
+    /// after [``with_ratio``](#method.with_ratio), including its ratio.
+    pub fn reset(&mut self) -> Result<()> {
+        let error = unsafe { (self.api().src_reset)(self.state) };
+        if error != 0 {
+            return Err(Error::from_code(error))
+        }
+        self.frames_in = 0;
+        self.frames_out = 0;
+        self.last_ratio = 0.0;
+        self.applied_ratio = None;
+        self.rates = None;
+        self.peak = None;
+        self.ended = false;
+        self.done = false;
+        if let Some(ref mut silence) = self.silence {
+            silence.reset();
+        }
+        self.passthrough.reset();
+        if let Some(ratio) = self.initial_ratio {
+            self.set_ratio(ratio)?;
+        }
+        Ok(())
+    }
+
+    /// Set the starting conversion ratio for the next call to [``convert``](#method.convert).
+    ///
+    /// Calling this function achieves a step response in conversion ratio instead of smooth
+    /// interpolation. Returns an error of kind
+    /// [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if ``ratio`` is not
+    /// [valid](fn.is_valid_ratio.html), leaving the converter as it was.
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        check_ratio(ratio)?;
+        let error = unsafe { (self.api().src_set_ratio)(self.state, ratio) };
+        if error != 0 {
+            return Err(Error::from_code(error))
+        }
+        self.last_ratio = ratio;
+        if let Some(ref mut silence) = self.silence {
+            silence.set_ratio(ratio);
+        }
+        Ok(())
+    }
+
+    /// Convert samples using internal state, smoothly interpolating ratio.
+    ///
+    /// The size of both ``input`` and ``output`` must be a multiple of the converter's channel
+    /// count, or an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) is returned. If there
+    /// is no more input data, provide ``None`` as ``input``, and the converter will flush its
+    /// internal state.
+    ///
+    /// Returns the number of used input samples and generated output samples, respectively.
+    /// The sample numbers, which are frames multiplied by the channel count, may be used to
+    /// partition the input and output arrays; [``convert_frames``](#method.convert_frames)
+    /// returns frames instead. Returns an error of kind
+    /// [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if ``ratio`` is not
+    /// [valid](fn.is_valid_ratio.html), or of kind
+    /// [``DataOverlap``](enum.ErrorKind.html#variant.DataOverlap) if the buffers overlap, leaving
+    /// the converter as it was. Once the internal state was flushed, further input is rejected
+    /// with an error of kind [``StreamEnded``](enum.ErrorKind.html#variant.StreamEnded) until
+    /// the converter is [reset](#method.reset).
+    ///
+    /// Buffers of more samples than libsamplerate can count in a ``long`` are converted in
+    /// several calls, like [``convert``](fn.convert.html) does, with a change of ratio
+    /// interpolated over each of them.
+    pub fn convert(&mut self, ratio: f64, input: Option<&[f32]>, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        self.process(ratio, input.unwrap_or(&[]), input.is_none(), output)
+    }
+
+    /// Convert samples like [``convert``](#method.convert), returning the number of used
+    /// input frames and generated output frames.
+    pub fn convert_frames(&mut self, ratio: f64, input: Option<&[f32]>, output: &mut [f32])
+            -> Result<ConversionResult> {
+        let channels = self.channels();
+        self.convert(ratio, input, output)
+            .map(|samples| ConversionResult::from_samples(samples, channels))
+    }
+
+    /// Convert ``input``, flushing the internal state afterwards if ``end`` is set.
+    pub(crate) fn process(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        self.process_chunked(ratio, input, end, output, MAX_CALL_SAMPLES)
+    }
+
+    /// Check the arguments of [``process``](#method.process), returning the errors that it
+    /// reports before converting anything.
+    fn check_process(&self, ratio: f64, input: &[f32], end: bool, output: &[f32]) -> Result<()> {
+        check_frames(self.channels(), input.len(), output.len())?;
+        check_overlap(input, output)?;
+        check_ratio(ratio)?;
+        if self.ended && !end && !input.is_empty() {
+            return Err(Error::new(ErrorKind::StreamEnded))
+        }
+        Ok(())
+    }
+
+    /// Convert like [``process``](#method.process), passing at most ``max_samples`` samples
+    /// of either buffer to libsamplerate at once, and ending the stream with the last chunk
+    /// of input.
+    fn process_chunked(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32],
+                       max_samples: usize) -> Result<(usize, usize)> {
+        let channels = self.channels();
+        self.check_process(ratio, input, end, output)?;
+        let chunk = max_samples / channels * channels;
+        let (mut used, mut generated) = (0, 0);
+        loop {
+            let output_len = chunk.min(output.len() - generated);
+            let mut input_len = chunk.min(input.len() - used);
+            if output_len < output.len() - generated {
+                // Pass no more input than fills this chunk of output, so that libsamplerate
+                // stops for want of input, as in a single call, rather than with the chunk
+                // full, which at the end of the input leaves it in a different state.
+                let frames = (output_len / channels).saturating_sub(1) as f64 /
+                    ratio.max(self.last_ratio);
+                input_len = input_len.min((frames as usize).max(1) * channels);
+            }
+            let rest = used + input_len == input.len();
+            let (u, g) = self.process_call(ratio, &input[used..used + input_len], end && rest,
+                                           &mut output[generated..generated + output_len])?;
+            used += u;
+            generated += g;
+            // What libsamplerate held back at the end of the input, a single call would have
+            // held back too.
+            if u == 0 && g == 0 || rest && g < output_len ||
+                    input.len() <= chunk && output.len() <= chunk {
+                break
+            }
+        }
+        self.clip_output(&mut output[..generated]);
+        if end {
+            // libsamplerate only stops short of filling the output once nothing is left.
+            self.ended = true;
+            self.done |= generated < output.len();
+        }
+        Ok((used, generated))
+    }
+
+    /// Convert ``input`` in a single call to libsamplerate, or without it on a fast path.
+    fn process_call(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        if !end && self.passes_through(ratio) {
+            return self.pass_through(input, output)
+        }
+        let channels = self.channels();
+        let all_input = input;
+        let (mut discard, mut dropped) = (self.leave_passthrough()?, 0);
+        // Generate again, and drop, what the passthrough fast path already generated, at the
+        // ratio it left libsamplerate at; the conversion proper then ramps from there.
+        while discard > 0 {
+            let frames = discard.min(output.len() / channels);
+            let (u, g) = self.call_src(self.last_ratio, &input[dropped..], end,
+                                       &mut output[..frames * channels])?;
+            dropped += u;
+            discard -= g / channels;
+            self.frames_out -= (g / channels) as u64;
+            if u == 0 && g == 0 {
+                break
+            }
+        }
+        self.passthrough.discard = discard;
+        let input = &input[dropped..];
+
+        let (mut skipped_in, mut skipped_out) = (0, 0);
+        if let Some(ref mut silence) = self.silence {
+            if !end && discard == 0 && !input.is_empty() &&
+                    input.iter().all(|&sample| sample == 0.0) {
+                let (frames_in, frames_out) =
+                    silence.skippable(ratio, input.len() / channels, output.len() / channels);
+                silence.skipped(frames_in, frames_out);
+                skipped_in = frames_in * channels;
+                skipped_out = frames_out * channels;
+                for sample in &mut output[..skipped_out] {
+                    *sample = 0.0;
+                }
+            }
+        }
+        let (input, output) = (&input[skipped_in..], &mut output[skipped_out..]);
+
+        let (used, generated) = self.call_src(ratio, input, end, output)?;
+        if let Some(ref mut silence) = self.silence {
+            silence.processed(ratio, channels, &input[..used], &output[..generated]);
+        }
+        self.passthrough.converted(channels, ratio, self.last_ratio,
+                                   &all_input[..dropped + skipped_in + used]);
+        self.frames_in += (skipped_in / channels) as u64;
+        self.frames_out += (skipped_out / channels) as u64;
+        Ok((dropped + skipped_in + used, skipped_out + generated))
+    }
+
+    /// Call libsamplerate once to convert ``input``, counting the frames used and generated.
+    fn call_src(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        let channels = self.channels();
+        let mut data = make_data(channels, ratio, end, input, output);
+        let error = unsafe { (self.api().src_process)(self.state, &mut data as *mut _) };
+        if error != 0 {
+            return Err(Error::from_code(error))
+        }
+        self.track_ratio(ratio, data.output_frames_gen as f64, data.output_frames as f64);
+        self.frames_in += data.input_frames_used as u64;
+        self.frames_out += data.output_frames_gen as u64;
+        Ok((data.input_frames_used as usize * channels,
+            data.output_frames_gen as usize * channels))
+    }
+}
+
+// The converter state is owned exclusively, and libsamplerate keeps no global state.
+// It is not Sync on purpose: src_process mutates the state, and nothing may read it
+// meanwhile from another thread.
+unsafe impl Send for Converter {}
+
+impl Drop for Converter {
+    fn drop(&mut self) {
+        #[cfg(test)]
+        test::DELETED_STATES.with(|deleted| deleted.set(deleted.get() + 1));
+        unsafe { (self.api().src_delete)(self.state); }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::f32;
+    use std::vec::Vec;
+    use std::string::ToString;
+    use std::{slice, vec};
+    use super::*;
+
+    std::thread_local! {
+        /// Number of states this thread freed by dropping a ``Converter``.
+        pub(crate) static DELETED_STATES: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn deleted_states() -> usize {
+        DELETED_STATES.with(Cell::get)
+    }
+
+    pub(crate) fn make_fixture(size: usize, cos: bool) -> Vec<f32> {
+        let step = f32::consts::PI * 2.0 / size as f32;
+        let mut data = Vec::new();
+        let mut value = 0.0f32;
+        for _ in 0..size {
+            data.push(value.sin());
+            if cos { data.push(value.cos()); }
+            value += step;
+        }
+        data
+    }
+
+    fn test_convert_ch(ch2: bool) {
+        let input = make_fixture(1000, ch2);
+        let expect = make_fixture(2000, ch2);
+        let mut output = vec![0.; expect.len()];
+        let channels = if ch2 { 2 } else { 1 };
+        let (used, gen) = convert(Interpolator::SincBestQuality, channels, 2.0,
+                                  &input, &mut output).unwrap();
+        assert_eq!(used, input.len());
+        assert_eq!(gen, output.len());
+        for (o, e) in output.iter().zip(expect.iter())
+                .skip(10 * channels)
+                .take(output.len() - 20 * channels) {
+            assert!((o - e).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_interpolator_names() {
+        let values: Vec<u32> = Interpolator::all().map(|interpolator| interpolator as u32)
+            .collect();
+        assert_eq!(values, [SRC_SINC_BEST_QUALITY, SRC_SINC_MEDIUM_QUALITY, SRC_SINC_FASTEST,
+                            SRC_ZERO_ORDER_HOLD, SRC_LINEAR]);
+        for interpolator in Interpolator::all() {
+            assert!(!interpolator.name().is_empty(), "{:?}", interpolator);
+            assert!(!interpolator.description().is_empty(), "{:?}", interpolator);
+        }
+        assert_eq!(Interpolator::SincBestQuality.name(), "Best Sinc Interpolator");
+        assert_eq!(Interpolator::__Nonexhaustive.name(), "");
+        assert_eq!(Interpolator::__Nonexhaustive.description(), "");
+    }
+
+    #[test]
+    fn test_parse_interpolator() {
+        let names = ["sinc_best_quality", "sinc_medium_quality", "sinc_fastest",
+                     "zero_order_hold", "linear"];
+        let aliases = ["best", "medium", "fastest", "zoh", "linear"];
+        for ((interpolator, name), alias) in Interpolator::all().zip(&names).zip(&aliases) {
+            assert_eq!(name.parse::<Interpolator>().unwrap(), interpolator);
+            assert_eq!(alias.parse::<Interpolator>().unwrap(), interpolator);
+            assert!(Error::new(ErrorKind::UnknownInterpolator).to_string().contains(alias));
+            assert!(Error::new(ErrorKind::UnknownInterpolator).to_string().contains(name));
+        }
+        assert_eq!("Sinc-Fastest".parse::<Interpolator>().unwrap(), Interpolator::SincFastest);
+        assert_eq!("ZOH".parse::<Interpolator>().unwrap(), Interpolator::ZeroOrderHold);
+        assert_eq!("sinc_Medium-QUALITY".parse::<Interpolator>().unwrap(),
+                   Interpolator::SincMediumQuality);
+        for name in &["cubic", "", "sinc", "best_", "__nonexhaustive", "sinc_fastest "] {
+            let error = name.parse::<Interpolator>().unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::UnknownInterpolator, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_interpolator_try_from() {
+        for interpolator in Interpolator::all() {
+            assert_eq!(Interpolator::try_from(interpolator as u32).unwrap(), interpolator);
+        }
+        assert_eq!(Interpolator::try_from(SRC_LINEAR).unwrap(), Interpolator::Linear);
+        for &value in &[Interpolator::__Nonexhaustive as u32, 5, 6, u32::MAX] {
+            let error = Interpolator::try_from(value).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::BadConverter, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_bad_ratio() {
+        assert!(is_valid_ratio(256.0) && is_valid_ratio(1.0 / 256.0) && is_valid_ratio(1.0));
+        let input = make_fixture(256, true);
+        for &ratio in &[0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 256.0001,
+                        1.0 / 256.0001] {
+            assert!(!is_valid_ratio(ratio), "{}", ratio);
+            let error = convert(Interpolator::Linear, 2, ratio, &input, &mut [0.0; 64])
+                .unwrap_err();
+            assert_eq!((error.kind(), error.code()), (ErrorKind::BadRatio, 6));
+            assert_eq!(error.ratio().map(f64::to_bits), Some(ratio.to_bits()));
+            assert!(error.to_string().contains(&ratio.to_string()), "{}", error);
+
+            // A rejected ratio leaves the converter as it was, without a reset.
+            let mut conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+            let mut output = vec![0.0; 1024];
+            let first = conv.convert(2.0, Some(&input[..256]), &mut output).unwrap();
+            assert_eq!(conv.convert(ratio, Some(&input[256..]), &mut output).unwrap_err()
+                           .ratio().map(f64::to_bits), Some(ratio.to_bits()));
+            assert_eq!(conv.set_ratio(ratio).unwrap_err().kind(), ErrorKind::BadRatio);
+            let second = conv.convert(2.0, Some(&input[256..]), &mut output[first.1..])
+                .unwrap();
+            assert_eq!(conv.frames_in(), 256);
+
+            let mut expect = vec![0.0; 1024];
+            let mut fresh = Converter::new(Interpolator::SincFastest, 2).unwrap();
+            let (_, generated) = fresh.convert(2.0, Some(&input), &mut expect).unwrap();
+            assert_eq!(first.1 + second.1, generated);
+            assert_eq!(output[..generated], expect[..generated]);
+        }
+        let mut output = [0.0; 2 * 256 * 16];
+        assert!(convert(Interpolator::Linear, 2, 256.0, &input[..32], &mut output).is_ok());
+    }
+
+    #[test]
+    fn test_with_ratio() {
+        let input = make_fixture(1000, true);
+        let mut expect = vec![0.0; 2 * 600];
+        let (_, generated) = convert(Interpolator::SincFastest, 2, 0.5, &input, &mut expect)
+            .unwrap();
+        assert!(generated >= 2 * 100);
+
+        let mut conv = Converter::with_ratio(Interpolator::SincFastest, 2, 0.5).unwrap();
+        assert_eq!(conv.initial_ratio(), Some(0.5));
+        let mut output = vec![0.0; 2 * 600];
+        for _ in 0..2 {
+            conv.convert(0.5, Some(&input), &mut output).unwrap();
+            assert_eq!(output[..2 * 100], expect[..2 * 100]);
+            assert_eq!(conv.last_applied_ratio(), Some((0.5, 0.5)));
+
+            // After a reset, another ratio ramps from the initial one rather than starting
+            // at it.
+            conv.reset().unwrap();
+            conv.convert(1.0, Some(&input[..2 * 100]), &mut output[..2 * 100]).unwrap();
+            assert_eq!(conv.last_applied_ratio().unwrap().0, 0.5);
+            conv.reset().unwrap();
+        }
+
+        assert_eq!(Converter::with_ratio(Interpolator::SincFastest, 2, f64::NAN).err()
+                       .map(|error| error.kind()), Some(ErrorKind::BadRatio));
+        assert_eq!(Converter::with_ratio(Interpolator::SincFastest, 0, 0.5).err()
+                       .map(|error| error.kind()), Some(ErrorKind::BadChannelCount));
+        assert_eq!(Converter::new(Interpolator::SincFastest, 2).unwrap().initial_ratio(), None);
+    }
+
+    #[test]
+    fn test_error_bounds() {
+        fn assert_bounds<T: Send + Sync + Clone + 'static>() {}
+        assert_bounds::<Error>();
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let mut conv = Converter::new(Interpolator::Linear, 1).unwrap();
+        let mut output = [0.0; 16];
+        let error = conv.convert(1000.0, Some(&[0.0; 4]), &mut output).unwrap_err();
+        assert_eq!((error.kind(), error.code()), (ErrorKind::BadRatio, 6));
+        let error = Converter::new(Interpolator::Linear, 0).err().unwrap();
+        assert_eq!((error.kind(), error.code()), (ErrorKind::BadChannelCount, 11));
+        assert_eq!(Error::from_code(99).kind(), ErrorKind::Unknown(99));
+        assert_eq!(Error::out_of_memory(1).code(), 0);
+
+        #[cfg(feature = "std")]
+        {
+            fn assert_error<T: std::error::Error>() {}
+            assert_error::<Error>();
+        }
+    }
+
+    #[test]
+    fn test_converter_moves_between_threads() {
+        let input = make_fixture(1000, true);
+        let mut expect = vec![0.; 4000];
+        let mut conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let gen = conv.convert(2.0, Some(&input[..1000]), &mut expect).unwrap().1;
+
+        let mut conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let mut output = vec![0.; 4000];
+        let first = conv.convert(2.0, Some(&input[..500]), &mut output).unwrap();
+        let (conv, output) = std::thread::spawn(move || {
+            let mut conv = conv;
+            let mut output = output;
+            let second = conv.convert(2.0, Some(&input[500..1000]), &mut output[first.1..])
+                .unwrap();
+            assert_eq!(first.1 + second.1, gen);
+            (conv, output)
+        }).join().unwrap();
+        assert_eq!(output, expect);
+        assert_eq!(conv.frames_in(), 500);
+    }
+
+    #[test]
+    fn test_converter_unwind_safe() {
+        fn assert_unwind_safe<T: core::panic::UnwindSafe + core::panic::RefUnwindSafe>() {}
+        assert_unwind_safe::<Converter>();
+
+        // A misaligned buffer is rejected before anything is converted.
+        let input = make_fixture(1000, true);
+        let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        let mut output = vec![0.; 4000];
+        assert!(conv.convert(2.0, Some(&input[..3]), &mut output).is_err());
+        assert_eq!(conv.frames_in(), 0);
+        let mut expect = vec![0.; 4000];
+        let mut fresh = Converter::new(Interpolator::Linear, 2).unwrap();
+        assert_eq!(conv.convert(2.0, Some(&input), &mut output).unwrap(),
+                   fresh.convert(2.0, Some(&input), &mut expect).unwrap());
+        assert_eq!(output, expect);
+    }
+
+    /// Check that the last applied ratio is the one libsamplerate stored, which is the first
+    /// field of its private state in the vendored version.
+    fn assert_stored_ratio(conv: &Converter) {
+        #[cfg(all(feature = "vendored-0_1",
+                  not(any(feature = "system", feature = "runtime-loading"))))]
+        assert_eq!(Some(unsafe { *(conv.as_raw() as *const f64) }),
+                   conv.last_applied_ratio().map(|(_, last)| last));
+        let _ = conv;
+    }
+
+    #[test]
+    fn test_last_applied_ratio() {
+        let input = make_fixture(1000, true);
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            let mut conv = Converter::new(interpolator, 2).unwrap();
+            let mut output = vec![0.; 2 * 100];
+            assert_eq!(conv.last_applied_ratio(), None);
+            conv.convert(1.0, Some(&input[..2 * 200]), &mut output).unwrap();
+            assert_eq!(conv.last_applied_ratio(), Some((1.0, 1.0)));
+            assert_stored_ratio(&conv);
+
+            // Partway through the output, the ramp is partway to the target.
+            let mut long = vec![0.; 2 * 1000];
+            let mut other = Converter::new(interpolator, 2).unwrap();
+            other.convert(1.0, Some(&input[..2 * 200]), &mut output).unwrap();
+            let (_, generated) = other.convert(1.5, Some(&input[2 * 200..2 * 300]), &mut long)
+                .unwrap();
+            assert!(generated < long.len());
+            assert_stored_ratio(&other);
+
+            // The output is full, so the last frame is one step short of the target.
+            let (_, generated) = conv.convert(2.0, Some(&input[2 * 200..]), &mut output)
+                .unwrap();
+            assert_eq!(generated, output.len());
+            assert_eq!(conv.last_applied_ratio(), Some((1.0, 1.0 + 0.99)));
+            assert_stored_ratio(&conv);
+            conv.convert(2.0, Some(&input[2 * 200..]), &mut output).unwrap();
+            assert_stored_ratio(&conv);
+            let (first, last) = conv.last_applied_ratio().unwrap();
+            assert_eq!(first, 1.99);
+            assert!((last - 1.9999).abs() < 1e-12);
+
+            // A step response reaches the target at once.
+            conv.set_ratio(0.5).unwrap();
+            conv.convert(0.5, Some(&input[2 * 300..]), &mut output).unwrap();
+            assert_eq!(conv.last_applied_ratio(), Some((0.5, 0.5)));
+            conv.reset().unwrap();
+            assert_eq!(conv.last_applied_ratio(), None);
+        }
+    }
+
+    #[test]
+    fn test_build_info() {
+        #[cfg(all(feature = "vendored-0_1", not(feature = "system")))]
+        assert_eq!(build_info().vendored_version(), Some("0.1.9"));
+        #[cfg(feature = "system")]
+        assert_eq!(build_info().vendored_version(), None);
+        // The hosts the tests run on have lrint and lrintf.
+        #[cfg(not(feature = "system"))]
+        assert_eq!(build_info().native_lrint(), Some(true));
+        assert_eq!(build_info().native_lrint().is_some(), build_info().lrint_note().is_some());
+    }
+
+    #[test]
+    fn test_convert_1ch() {
+        test_convert_ch(false)
+    }
+
+    #[test]
+    fn test_convert_2ch() {
+        test_convert_ch(true)
+    }
+
+    fn test_push_converter_ch(ch2: bool) {
+        let input = make_fixture(1000, ch2);
+        let expect = make_fixture(2000, ch2);
+        let mut output = vec![0.; expect.len()];
+        let ch = if ch2 { 2 } else { 1 };
+        let mut conv = Converter::new(Interpolator::SincBestQuality, ch).unwrap();
+        assert_eq!(conv.convert(2.0, Some(&input[..500 * ch]), &mut output[..]).unwrap(),
+                   (500 * ch, 712  * ch));
+        assert_eq!(conv.convert(2.0, Some(&input[500 * ch..]), &mut output[712 * ch..]).unwrap(),
+                   (500 * ch, 1000 * ch));
+        assert_eq!(conv.convert(2.0, None, &mut output[1712 * ch..]).unwrap(),
+                   (0,        288  * ch));
+        for (o, e) in output.iter().zip(expect.iter())
+                .skip(10).take(output.len() - 20) {
+            assert!((o - e).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_convert_frames_2ch() {
+        let input = make_fixture(1000, true);
+        let mut expect = vec![0.; 4000];
+        let samples = convert(Interpolator::SincFastest, 2, 2.0, &input, &mut expect).unwrap();
+        let mut output = vec![0.; 4000];
+        let result = convert_frames(Interpolator::SincFastest, 2, 2.0, &input, &mut output)
+            .unwrap();
+        assert_eq!(result, ConversionResult { input_frames_used: 1000,
+                                              output_frames_generated: 2000 });
+        assert_eq!((result.input_samples_used(2), result.output_samples_generated(2)), samples);
+        assert_eq!(output, expect);
+    }
+
+    #[test]
+    fn test_push_converter_frames_2ch() {
+        let input = make_fixture(1000, true);
+        let expect = make_fixture(2000, true);
+        let mut output = vec![0.; expect.len()];
+        let mut conv = Converter::new(Interpolator::SincBestQuality, 2).unwrap();
+        let (mut input_left, mut output_left) = (&input[..], &mut output[..]);
+        for (chunk_frames, frames) in [(500, (500, 712)), (500, (500, 1000)), (0, (0, 288))] {
+            let chunk = Some(&input_left[..chunk_frames * 2]).filter(|_| chunk_frames > 0);
+            let result = conv.convert_frames(2.0, chunk, output_left).unwrap();
+            assert_eq!((result.input_frames_used, result.output_frames_generated), frames);
+            input_left = &input_left[result.input_samples_used(2)..];
+            output_left = &mut output_left[result.output_samples_generated(2)..];
+        }
+        assert!(input_left.is_empty() && output_left.is_empty());
+        for (o, e) in output.iter().zip(expect.iter())
+                .skip(10).take(output.len() - 20) {
+            assert!((o - e).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_bad_buffer_length() {
+        let input = [0.0; 5];
+        let mut output = [0.0; 10];
+        let error = convert(Interpolator::Linear, 2, 2.0, &input, &mut output).unwrap_err();
+        assert_eq!(error, Error::bad_buffer_length(5, 2));
+        assert_eq!(error.to_string(),
+                   "buffer of 5 samples is not a whole number of 2-channel frames");
+        assert_eq!(convert(Interpolator::Linear, 2, 2.0, &input[..4], &mut output[..9]),
+                   Err(Error::bad_buffer_length(9, 2)));
+        // The same buffers are fine for a mono stream.
+        assert!(convert(Interpolator::Linear, 1, 2.0, &input, &mut output).is_ok());
+
+        let error = convert(Interpolator::Linear, 0, 2.0, &input, &mut output).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 5, channels: 0 });
+        assert_eq!(error.to_string(), "channel count must not be zero");
+        assert!(convert(Interpolator::Linear, 0, 2.0, &[], &mut []).is_err());
+
+        let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        assert_eq!(conv.convert(2.0, Some(&input), &mut output),
+                   Err(Error::bad_buffer_length(5, 2)));
+        assert_eq!(conv.convert(2.0, None, &mut output[..3]),
+                   Err(Error::bad_buffer_length(3, 2)));
+        // A rejected call leaves the stream untouched.
+        assert_eq!(conv.frames_in(), 0);
+        assert_eq!(conv.convert(2.0, Some(&input[..4]), &mut output).unwrap().0, 4);
+    }
+
+    #[test]
+    fn test_overlap() {
+        // The halves of one buffer are adjacent, not overlapping, in either order.
+        let mut buffer = vec![0.5; 2 * 64];
+        let (input, output) = buffer.split_at_mut(64);
+        assert!(convert(Interpolator::Linear, 1, 0.5, input, output).is_ok());
+        let (output, input) = buffer.split_at_mut(64);
+        assert!(convert(Interpolator::Linear, 1, 0.5, input, output).is_ok());
+        let mut conv = Converter::new(Interpolator::Linear, 1).unwrap();
+        assert!(conv.convert(0.5, Some(input), output).is_ok());
+
+        // Overlapping buffers cannot be borrowed safely, but shared ones can be checked.
+        let ptr = buffer.as_ptr();
+        let (first, second, empty) = unsafe {
+            (slice::from_raw_parts(ptr, 64), slice::from_raw_parts(ptr.add(63), 64),
+             slice::from_raw_parts(ptr.add(32), 0))
+        };
+        for &(input, output) in &[(first, second), (second, first), (first, first),
+                                  (&first[32..33], first)] {
+            let error = check_overlap(input, output).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::DataOverlap);
+        }
+        assert!(check_overlap(&first[..63], second).is_ok());
+        assert!(check_overlap(second, &first[..63]).is_ok());
+        assert!(check_overlap(empty, first).is_ok());
+        assert!(check_overlap(first, empty).is_ok());
+    }
+
+    #[test]
+    fn test_chunked() {
+        // Chunks of 1000 samples, a whole number of frames below the limit, stand in for
+        // the limit of a 32-bit ``long``.
+        let input = make_fixture(5000, true);
+        for interpolator in Interpolator::all() {
+            let mut expected = vec![0.0; 2 * 7600];
+            let mut output = vec![0.0; 2 * 7600];
+            let (used, generated) = convert(interpolator, 2, 1.5, &input, &mut expected)
+                .unwrap();
+            assert_eq!(convert_chunked(interpolator, 2, 1.5, &input, &mut output, 1001)
+                           .unwrap(), (used, generated));
+            assert_eq!(output[..generated], expected[..generated]);
+
+            let mut conv = Converter::new(interpolator, 2).unwrap();
+            let mut chunked = Converter::new(interpolator, 2).unwrap();
+            let (used, generated) = conv.process(1.5, &input, false, &mut expected).unwrap();
+            assert_eq!(chunked.process_chunked(1.5, &input, false, &mut output, 1001).unwrap(),
+                       (used, generated));
+            assert_eq!(output[..generated], expected[..generated]);
+            assert_eq!(chunked.frames_in(), conv.frames_in());
+        }
+    }
+
+    #[test]
+    fn test_try_clone() {
+        for &interpolator in &[Interpolator::SincMediumQuality, Interpolator::ZeroOrderHold,
+                               Interpolator::Linear] {
+            let input = make_fixture(1000, true);
+            let mut conv = Converter::new(interpolator, 2).unwrap();
+            let mut primed = vec![0.; 4000];
+            let (used, gen) = conv.convert(1.7, Some(&input[..1000]), &mut primed).unwrap();
+            assert_eq!(used, 1000);
+
+            // A system library older than 0.2.0 lacks src_clone.
+            #[cfg(feature = "system")]
+            match library_version() {
+                Some(found) if found < CLONE_LIBRARY_VERSION => {
+                    assert_eq!(conv.try_clone().err().unwrap().kind(),
+                               ErrorKind::IncompatibleLibrary {
+                                   found,
+                                   required: CLONE_LIBRARY_VERSION,
+                               });
+                    continue
+                }
+                _ => (),
+            }
+            let mut clone = conv.try_clone().unwrap();
+            assert_eq!((clone.frames_in(), clone.frames_out()), (500, gen as u64 / 2));
+            assert_eq!(clone.last_applied_ratio(), conv.last_applied_ratio());
+            let mut output = vec![0.; 4000];
+            let mut cloned = vec![0.; 4000];
+            let rest = conv.convert(1.7, Some(&input[1000..]), &mut output).unwrap();
+            assert_eq!(clone.convert(1.7, Some(&input[1000..]), &mut cloned).unwrap(), rest);
+            assert!(rest.1 > 0);
+            assert_eq!(output.iter().map(|s| s.to_bits()).collect::<Vec<_>>(),
+                       cloned.iter().map(|s| s.to_bits()).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        let input = make_fixture(1000, false);
+        let mut expect = vec![0.; 2000];
+        let mut conv = Converter::new(Interpolator::SincFastest, 1).unwrap();
+        let (_, gen) = conv.convert(2.0, Some(&input), &mut expect).unwrap();
+
+        let deleted = deleted_states();
+        let state = Converter::new(Interpolator::SincFastest, 1).unwrap().into_raw();
+        assert_eq!(deleted_states(), deleted);
+        assert_eq!(unsafe { src_get_channels(state) }, 1);
+        let mut conv = unsafe { Converter::from_raw(state) };
+        assert_eq!(conv.as_raw(), state);
+        // libsamplerate does not record the interpolator in the state.
+        assert_eq!(conv.interpolator(), None);
+        let mut output = vec![0.; 2000];
+        assert_eq!(conv.convert(2.0, Some(&input), &mut output).unwrap().1, gen);
+        assert_eq!(output, expect);
+
+        // The state is freed once, by the converter that owns it last.
+        let state = conv.into_raw();
+        assert_eq!(deleted_states(), deleted);
+        drop(unsafe { Converter::from_raw(state) });
+        assert_eq!(deleted_states(), deleted + 1);
+    }
+
+    #[test]
+    fn test_push_converter_1ch() {
+        test_push_converter_ch(false)
+    }
+
+    #[test]
+    fn test_push_converter_2ch() {
+        test_push_converter_ch(true)
+    }
+}
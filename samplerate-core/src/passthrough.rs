@@ -0,0 +1,408 @@
+//! Fast path copying the input to the output at a ratio of 1.
+//!
+//! libsamplerate keeps a history of the input and, for the sinc interpolators, a lookahead,
+//! which the fast path cannot update without converting. Instead, the fast path copies the
+//! input without calling libsamplerate, and keeps the last frames it copied in a ring of its
+//! own. When the converter leaves the fast path, libsamplerate is reset and given those
+//! frames, as much as the filter reaches back, at a ratio of exactly 1. It then holds them as
+//! if it had converted the whole stream, except that the fast path already generated them;
+//! the frames libsamplerate generates for them again are dropped, and the output carries on
+//! from the last frame copied.
+//!
+//! Once the ratio stays at 1 for as many input frames as libsamplerate holds back, the fast
+//! path is taken again. It first outputs the frames libsamplerate held back, as they were
+//! input, from the ring, and then copies the input again.
+
+use {Converter, Error, ErrorKind, Result, RATIO_RAMP_THRESHOLD};
+
+/// Number of frames given to libsamplerate beyond those its filter holds back.
+const HISTORY_MARGIN_FRAMES: usize = 2;
+
+/// Number of samples of input kept for libsamplerate; the fast path is only taken if the
+/// frames its filter holds back, and the margin, fit.
+const HISTORY_SAMPLES: usize = 2048;
+
+/// State of the passthrough fast path of a [``Converter``](struct.Converter.html).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PassthroughTracker {
+    /// Largest distance of the ratio from 1 that takes the fast path, if enabled.
+    threshold: Option<f64>,
+    /// libsamplerate was given no input since the converter was created or reset.
+    fresh: bool,
+    /// The fast path copied the input since libsamplerate was last given any.
+    copying: bool,
+    /// Last frames of input, in a ring of a whole number of frames that starts at
+    /// ``history_start`` and holds ``history_len`` samples.
+    history: [f32; HISTORY_SAMPLES],
+    history_start: usize,
+    history_len: usize,
+    /// Number of the last frames of ``history`` that libsamplerate held back when the fast
+    /// path was taken again, and that it outputs before any input.
+    backlog: usize,
+    /// Number of input frames converted since the ratio was last further than the threshold
+    /// from 1.
+    settled: usize,
+    /// Number of output frames that libsamplerate generates next but that the fast path
+    /// already generated.
+    pub(crate) discard: usize,
+}
+
+impl PassthroughTracker {
+    pub(crate) fn new() -> PassthroughTracker {
+        PassthroughTracker { threshold: None, fresh: true, copying: false,
+                             history: [0.0; HISTORY_SAMPLES], history_start: 0, history_len: 0,
+                             backlog: 0, settled: 0, discard: 0 }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = PassthroughTracker { threshold: self.threshold, ..PassthroughTracker::new() }
+    }
+
+    /// Check whether ``ratio`` is within the threshold of 1, and libsamplerate applied it,
+    /// or a ratio within the threshold too, to the last frame, ``last_ratio``.
+    fn is_settled(&self, ratio: f64, last_ratio: f64) -> bool {
+        let is_near = |ratio: f64| {
+            self.threshold.is_some_and(|threshold| (ratio - 1.0).abs() <= threshold)
+        };
+        // libsamplerate stops ramping short of the ratio by less than the ramp threshold.
+        is_near(ratio) &&
+            (is_near(last_ratio) || (last_ratio - ratio).abs() < RATIO_RAMP_THRESHOLD)
+    }
+
+    /// Append ``input`` to the history, which keeps as many of the last frames as fit.
+    fn record(&mut self, channels: usize, input: &[f32]) {
+        let capacity = HISTORY_SAMPLES / channels * channels;
+        let input = &input[input.len().saturating_sub(capacity)..];
+        let end = (self.history_start + self.history_len) % capacity;
+        let first = input.len().min(capacity - end);
+        self.history[end..end + first].copy_from_slice(&input[..first]);
+        self.history[..input.len() - first].copy_from_slice(&input[first..]);
+        let len = self.history_len + input.len();
+        if len > capacity {
+            self.history_start = (self.history_start + len - capacity) % capacity;
+        }
+        self.history_len = len.min(capacity);
+    }
+
+    /// Copy the ``len`` samples of the history that start ``back`` samples before its end into
+    /// the start of ``output``.
+    fn recall(&self, channels: usize, back: usize, len: usize, output: &mut [f32]) {
+        let capacity = HISTORY_SAMPLES / channels * channels;
+        let start = (self.history_start + self.history_len - back) % capacity;
+        let first = len.min(capacity - start);
+        output[..first].copy_from_slice(&self.history[start..start + first]);
+        output[first..len].copy_from_slice(&self.history[..len - first]);
+    }
+
+    /// Record that libsamplerate converted ``input`` at ``ratio``, ending at ``last_ratio``.
+    pub(crate) fn converted(&mut self, channels: usize, ratio: f64, last_ratio: f64,
+                            input: &[f32]) {
+        if self.threshold.is_some() {
+            self.record(channels, input);
+            self.settled = if self.is_settled(ratio, last_ratio) {
+                self.settled.saturating_add(input.len() / channels)
+            } else {
+                0
+            };
+        }
+    }
+}
+
+impl Converter {
+    /// Set how far from 1 the ratio may be for the converter to copy the input to the output
+    /// instead of converting it, or disable this fast path with ``None``, which is the
+    /// default.
+    ///
+    /// The fast path is taken while the converter runs at a ratio within ``threshold`` of 1,
+    /// and not at the end of the stream; it copies the input without calling libsamplerate.
+    /// The last frames copied are kept, as many as
+    /// [``input_latency``](#method.input_latency) reports, and given to libsamplerate when
+    /// the converter leaves the fast path for another ratio, or at the end of the stream, so
+    /// that the output carries on from the frames copied without a discontinuity. After
+    /// converting at another ratio, the fast path is taken again once the ratio stayed within
+    /// ``threshold`` of 1 for as many input frames as ``input_latency`` reports; the frames
+    /// libsamplerate held back are then output as they were input, which may put the output
+    /// up to a frame off from where the conversion would have placed it.
+    ///
+    /// Copying the input differs from converting it at a ratio of 1 in the timing of the
+    /// output: the input is not held back by the sinc interpolators, nor delayed by one frame
+    /// by ``Linear`` and ``ZeroOrderHold``. A ratio within ``threshold`` of 1 is taken to be
+    /// 1 exactly, so ``threshold`` should be well below the smallest deviation of interest.
+    /// The fast path is never taken by a converter created with
+    /// [``from_raw``](#method.from_raw), whose interpolator is unknown, nor by one whose
+    /// channels times the frames kept exceed 2048 samples, e.g. one with more than 14
+    /// channels for ``SincBestQuality``.
+    ///
+    /// Returns an error of kind [``BadThreshold``](enum.ErrorKind.html#variant.BadThreshold)
+    /// if ``threshold`` is negative or NaN, leaving the threshold as it was.
+    pub fn set_passthrough_threshold(&mut self, threshold: Option<f64>) -> Result<()> {
+        if threshold.is_some_and(|threshold| threshold < 0.0 || threshold.is_nan()) {
+            return Err(Error::new(ErrorKind::BadThreshold))
+        }
+        self.passthrough.threshold = threshold;
+        Ok(())
+    }
+
+    /// Retrieve how far from 1 the ratio may be for the passthrough fast path to be taken,
+    /// or ``None`` if it is disabled.
+    pub fn passthrough_threshold(&self) -> Option<f64> {
+        self.passthrough.threshold
+    }
+
+    /// Check whether a conversion at ``ratio`` that does not end the stream takes the
+    /// passthrough fast path.
+    pub(crate) fn passes_through(&self, ratio: f64) -> bool {
+        let tracker = &self.passthrough;
+        let latency = self.input_latency();
+        let last_ratio = if self.last_ratio == 0.0 { ratio } else { self.last_ratio };
+        tracker.is_settled(ratio, last_ratio) &&
+            (tracker.fresh || tracker.copying || tracker.settled >= latency) &&
+            (latency + HISTORY_MARGIN_FRAMES) * self.channels() <= HISTORY_SAMPLES &&
+            self.interpolator().is_some()
+    }
+
+    /// Copy as many frames of ``input`` into ``output`` as fit, after the frames
+    /// libsamplerate held back if the fast path is taken again after converting.
+    pub(crate) fn pass_through(&mut self, input: &[f32], output: &mut [f32])
+            -> Result<(usize, usize)> {
+        let channels = self.channels();
+        let latency = self.input_latency();
+        let tracker = &mut self.passthrough;
+        if !tracker.copying {
+            tracker.backlog = if tracker.fresh { 0 } else { latency };
+            tracker.fresh = false;
+            tracker.copying = true;
+        }
+        let recalled = tracker.backlog.min(output.len() / channels);
+        tracker.recall(channels, tracker.backlog * channels, recalled * channels, output);
+        tracker.backlog -= recalled;
+        let output = &mut output[recalled * channels..];
+        let frames = if tracker.backlog == 0 {
+            (input.len() / channels).min(output.len() / channels)
+        } else {
+            0
+        };
+        output[..frames * channels].copy_from_slice(&input[..frames * channels]);
+        tracker.record(channels, &input[..frames * channels]);
+        self.frames_in += frames as u64;
+        self.frames_out += (recalled + frames) as u64;
+        if let Some(ref mut silence) = self.silence {
+            silence.reset();
+        }
+        Ok((frames * channels, (recalled + frames) * channels))
+    }
+
+    /// Record that libsamplerate converts without the fast path, and if the fast path was
+    /// taken last, give libsamplerate the last frames it copied at a ratio of 1. Returns the
+    /// number of output frames to discard.
+    pub(crate) fn leave_passthrough(&mut self) -> Result<usize> {
+        self.passthrough.fresh = false;
+        if !self.passthrough.copying {
+            return Ok(self.passthrough.discard)
+        }
+        let channels = self.channels();
+        let len = ((self.input_latency() + HISTORY_MARGIN_FRAMES) * channels)
+            .min(self.passthrough.history_len);
+        let mut history = [0.0; HISTORY_SAMPLES];
+        self.passthrough.recall(channels, len, len, &mut history);
+        let error = unsafe { (self.api().src_reset)(self.state) };
+        if error != 0 {
+            return Err(Error::from_code(error))
+        }
+        // The frames were counted when they were copied.
+        let (frames_in, frames_out) = (self.frames_in, self.frames_out);
+        let mut scratch = [0.0; HISTORY_SAMPLES];
+        let capacity = HISTORY_SAMPLES / channels * channels;
+        let (mut used, mut generated) = (0, 0);
+        while used < len {
+            let (u, g) = self.call_src(1.0, &history[used..len], false,
+                                       &mut scratch[..capacity])?;
+            used += u;
+            generated += g;
+            if u == 0 && g == 0 {
+                break
+            }
+        }
+        self.frames_in = frames_in;
+        self.frames_out = frames_out;
+
+        // The output frames before libsamplerate reaches the first frame not copied, except
+        // for those held back that the fast path did not output yet.
+        let latency = self.output_latency();
+        let tracker = &mut self.passthrough;
+        tracker.discard += (latency + used / channels)
+            .saturating_sub(generated / channels + tracker.backlog);
+        tracker.backlog = 0;
+        tracker.copying = false;
+        tracker.settled = 0;
+        Ok(tracker.discard)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use Interpolator;
+    use test::make_fixture;
+    use super::*;
+
+    const INTERPOLATORS: [Interpolator; 5] =
+        [Interpolator::SincBestQuality, Interpolator::SincMediumQuality,
+         Interpolator::SincFastest, Interpolator::Linear, Interpolator::ZeroOrderHold];
+
+    /// Convert ``input`` in chunks of ``chunk`` frames, at the ratio ``ratio(n)`` for the
+    /// ``n``-th chunk, then flush the converter.
+    fn run<F: Fn(usize) -> f64>(conv: &mut Converter, input: &[f32], chunk: usize, ratio: F)
+            -> Vec<f32> {
+        run_with(conv, input, chunk, ratio, |_, _| ())
+    }
+
+    /// Convert like ``run``, calling ``before(conv, n)`` before the ``n``-th chunk.
+    fn run_with<F, G>(conv: &mut Converter, input: &[f32], chunk: usize, ratio: F, before: G)
+            -> Vec<f32>
+            where F: Fn(usize) -> f64, G: Fn(&mut Converter, usize) {
+        let channels = conv.channels();
+        let mut output = Vec::new();
+        let mut buffer = vec![0.0; (chunk * 2 + 16) * channels];
+        for (index, mut rest) in input.chunks(chunk * channels).enumerate() {
+            before(conv, index);
+            while !rest.is_empty() {
+                let (used, generated) = conv.convert(ratio(index), Some(rest), &mut buffer)
+                    .unwrap();
+                output.extend_from_slice(&buffer[..generated]);
+                rest = &rest[used..];
+            }
+        }
+        loop {
+            let (_, generated) = conv.convert(ratio(usize::MAX), None, &mut buffer).unwrap();
+            if generated == 0 {
+                break
+            }
+            output.extend_from_slice(&buffer[..generated]);
+        }
+        output
+    }
+
+    /// Retrieve the largest difference between consecutive frames of a channel.
+    fn largest_step(samples: &[f32], channels: usize) -> f32 {
+        samples.windows(channels + 1)
+            .map(|window| (window[channels] - window[0]).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn test_copies() {
+        let input = make_fixture(3000, true);
+        let mut conv = Converter::new(Interpolator::SincBestQuality, 2).unwrap();
+        conv.set_passthrough_threshold(Some(1e-6)).unwrap();
+        let mut output = vec![0.0; input.len() + 64];
+        assert_eq!(conv.convert(1.0 + 1e-7, Some(&input), &mut output).unwrap(),
+                   (input.len(), input.len()));
+        assert_eq!(output[..input.len()], input[..]);
+        assert_eq!((conv.frames_in(), conv.frames_out()), (3000, 3000));
+
+        // Flushing generates nothing, since all of the input was copied.
+        assert_eq!(conv.convert(1.0, None, &mut output).unwrap(), (0, 0));
+
+        // Not at another ratio, nor after converting at another ratio.
+        conv.reset().unwrap();
+        conv.convert(1.5, Some(&input), &mut output).unwrap();
+        conv.convert(1.0, Some(&input), &mut output).unwrap();
+        assert!(output[..1000] != input[..1000]);
+    }
+
+    #[test]
+    fn test_toggle() {
+        // 1.0, then 1.001, then 1.0 again, in chunks both longer and shorter than the filter.
+        let ratio = |index: usize| if (20..40).contains(&index) { 1.001 } else { 1.0 };
+        for &interpolator in &INTERPOLATORS {
+            for &chunk in &[256, 50] {
+                let input = make_fixture(30000 / chunk * chunk, true);
+                let mut conv = Converter::new(interpolator, 2).unwrap();
+                conv.set_passthrough_threshold(Some(0.0)).unwrap();
+                let output = run(&mut conv, &input, chunk, ratio);
+                // The end of the stream is converted against silence, unlike the rest.
+                let tail = 2 * (conv.input_latency() + 1) * 2;
+                let step = largest_step(&output[..output.len() - tail], 2);
+                // No step is larger than those of the input, which a frame skipped or
+                // repeated at a toggle would make.
+                let limit = largest_step(&input, 2) * 1.01;
+                assert!(step < limit, "{:?} in chunks of {}: step {} above {}", interpolator,
+                        chunk, step, limit);
+                let expected = (input.len() / 2) as f64 + 20.0 * chunk as f64 * 0.001;
+                assert!((output.len() as f64 / 2.0 - expected).abs() < 4.0,
+                        "{:?} in chunks of {}: {} frames", interpolator, chunk,
+                        output.len() / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_leave_at_unit_ratio() {
+        // Converting at a ratio of 1 carries on from the copied frames, with the output of
+        // the interpolators at that ratio, in chunks both longer and shorter than the filter.
+        for &interpolator in &INTERPOLATORS {
+            for &chunk in &[500, 50] {
+                let input = make_fixture(10000, true);
+                let mut conv = Converter::new(interpolator, 2).unwrap();
+                conv.set_passthrough_threshold(Some(0.0)).unwrap();
+                let output = run_with(&mut conv, &input, chunk, |_| 1.0, |conv, index| {
+                    if index == 5000 / chunk {
+                        conv.set_passthrough_threshold(None).unwrap();
+                    }
+                });
+                let tolerance = match interpolator {
+                    Interpolator::Linear | Interpolator::ZeroOrderHold => 0.0,
+                    _ => 1e-5,
+                };
+                let tail = 2 * (conv.input_latency() + 1) * 2;
+                assert!(output.len() >= input.len() - tail, "{:?}", interpolator);
+                for (index, (&o, &i)) in output.iter().zip(&input[..input.len() - tail])
+                        .enumerate() {
+                    assert!((o - i).abs() <= tolerance, "{:?} in chunks of {}: sample {}: {} \
+                            vs {}", interpolator, chunk, index, o, i);
+                }
+                assert_eq!(conv.frames_in(), 10000);
+                assert_eq!(conv.frames_out() as usize, output.len() / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rearm() {
+        // Back at a ratio of 1, the fast path is taken again once the ratio settled, and
+        // copies the input from then on.
+        for &interpolator in &INTERPOLATORS {
+            let input = make_fixture(20000, true);
+            let mut conv = Converter::new(interpolator, 2).unwrap();
+            conv.set_passthrough_threshold(Some(0.0)).unwrap();
+            // Room for the frames held back as well when the fast path is taken again.
+            let mut output = vec![0.0; 2 * 700];
+            let mut converted = Vec::new();
+            for (index, chunk) in input.chunks(2 * 500).enumerate() {
+                let ratio = if (5..10).contains(&index) { 1.001 } else { 1.0 };
+                let (used, generated) = conv.convert(ratio, Some(chunk), &mut output).unwrap();
+                assert_eq!(used, chunk.len());
+                if generated < used || output[generated - used..generated] != chunk[..] {
+                    converted.push(index);
+                }
+            }
+            // Only while the ratio differs from 1, and ramps back to it over a dozen chunks.
+            assert_eq!(converted.first(), Some(&5), "{:?}", interpolator);
+            assert!(converted.last() < Some(&25), "{:?}: {:?}", interpolator, converted);
+            assert!(conv.passes_through(1.0));
+        }
+    }
+
+    #[test]
+    fn test_bad_threshold() {
+        let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        conv.set_passthrough_threshold(Some(1e-6)).unwrap();
+        for &threshold in &[-1e-9, f64::NAN] {
+            let error = conv.set_passthrough_threshold(Some(threshold)).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::BadThreshold);
+            assert_eq!(conv.passthrough_threshold(), Some(1e-6));
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Handoff of double-buffered input, such as from DMA, to a converter.
+//!
+//! The producer, typically a DMA controller and its interrupt handler, fills the two halves
+//! of a buffer alternately, and the consumer converts each half while the other one is being
+//! filled. This only works if the consumer reads each half before the producer comes back to
+//! it; [``PingPongResampler``](struct.PingPongResampler.html) checks that it did, and reports
+//! an overrun instead of converting samples that were overwritten while they were read.
+
+use core::sync::atomic::{AtomicU32, Ordering, fence};
+
+use {Converter, Error, Result};
+
+/// Half of the buffer of a [``PingPongResampler``](struct.PingPongResampler.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Half {
+    /// The first ``N`` samples.
+    A = 0,
+    /// The last ``N`` samples.
+    B = 1,
+}
+
+/// Double buffer of ``N`` interleaved samples per half, handed off from a producer that fills
+/// it, typically from an interrupt handler, to a consumer that converts it, typically in the
+/// main loop, without locks or allocation.
+///
+/// The producer fills the halves alternately, starting with [``Half::A``](enum.Half.html),
+/// either with [``fill_half``](#method.fill_half) or by having DMA write into
+/// [``dma_buffer``](#method.dma_buffer), and calls [``commit_half``](#method.commit_half)
+/// when a half is complete. The consumer calls [``process_ready``](#method.process_ready) to
+/// convert the committed halves in order. A half is safe to read from when it is committed
+/// until the other half is committed, since the producer then starts filling it again; if
+/// the consumer falls behind further than that, the samples it missed are reported as an
+/// overrun.
+///
+/// The buffer is meant to be a ``static``, shared by the producer and a single consumer.
+/// Only ``load`` and ``store`` atomic operations are used, so it works on targets without
+/// compare-and-swap, such as the Cortex-M0+.
+pub struct PingPongResampler<const N: usize> {
+    /// Samples as the bits of their ``f32`` values, so that DMA can write them directly.
+    buffer: [[AtomicU32; N]; 2],
+    /// Number of halves committed by the producer, counting any it skipped.
+    committed: AtomicU32,
+    /// Number of halves processed or lost by the consumer.
+    consumed: AtomicU32,
+}
+
+impl<const N: usize> Default for PingPongResampler<N> {
+    fn default() -> Self {
+        PingPongResampler::new()
+    }
+}
+
+impl<const N: usize> PingPongResampler<N> {
+    // Constants, rather than values, can be repeated in an array even if they are not
+    // ``Copy``; each repetition is a new atomic.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const SILENT_SAMPLE: AtomicU32 = AtomicU32::new(0);
+    #[allow(clippy::declare_interior_mutable_const)]
+    const SILENT_HALF: [AtomicU32; N] = [Self::SILENT_SAMPLE; N];
+
+    /// Create a buffer of silence with no committed halves.
+    pub const fn new() -> Self {
+        PingPongResampler {
+            buffer: [Self::SILENT_HALF; 2],
+            committed: AtomicU32::new(0),
+            consumed: AtomicU32::new(0),
+        }
+    }
+
+    /// Retrieve the address of the buffer, for a DMA controller to write ``2 * N`` ``f32``
+    /// samples into: ``N`` of ``Half::A`` followed by ``N`` of ``Half::B``.
+    pub fn dma_buffer(&self) -> *mut f32 {
+        self.buffer.as_ptr() as *mut f32
+    }
+
+    /// Write ``samples`` into ``half``, for a producer that copies the samples itself.
+    ///
+    /// Panics if ``samples`` is longer than ``N``.
+    pub fn fill_half(&self, half: Half, samples: &[f32]) {
+        assert!(samples.len() <= N, "samples must fit in a half-buffer");
+        // Like the writer of a sequence lock: the commit of the other half, which started
+        // filling this one, comes before the samples, so that a consumer that reads any of
+        // them also sees that commit when it checks for an overrun. DMA writes are not
+        // ordered by this fence; with DMA, the consumer only sees the overrun once the
+        // interrupt handler commits the half the controller moves on to.
+        fence(Ordering::Release);
+        for (slot, sample) in self.buffer[half as usize].iter().zip(samples) {
+            slot.store(sample.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Mark ``half`` as filled, making it available to the consumer.
+    ///
+    /// If ``half`` is not the one after the last committed half, e.g. because an interrupt
+    /// was missed, the other half is counted as committed and lost, so that the consumer
+    /// stays in step with the producer and reports the overrun.
+    pub fn commit_half(&self, half: Half) {
+        let committed = self.committed.load(Ordering::Relaxed);
+        let skipped = (committed % 2 != half as u32) as u32;
+        self.committed.store(committed.wrapping_add(1 + skipped), Ordering::Release);
+    }
+
+    /// Retrieve the number of halves committed so far, counting any skipped ones, modulo
+    /// 2<sup>32</sup>.
+    pub fn committed_halves(&self) -> u32 {
+        self.committed.load(Ordering::Relaxed)
+    }
+
+    /// Copy the next committed half into ``samples``.
+    ///
+    /// Returns whether a half was ready, or an error of kind
+    /// [``Overrun``](enum.ErrorKind.html#variant.Overrun) if the producer overwrote it before
+    /// or while it was copied, in which case the consumer skips to the latest committed half.
+    fn read_ready(&self, samples: &mut [f32; N]) -> Result<bool> {
+        let next = match self.next_ready()? {
+            Some(next) => next,
+            None => return Ok(false),
+        };
+        self.copy_half(next, samples);
+        self.finish_read(next)?;
+        Ok(true)
+    }
+
+    /// Retrieve the index of the next half to read, if it is committed and intact.
+    fn next_ready(&self) -> Result<Option<u32>> {
+        let next = self.consumed.load(Ordering::Relaxed);
+        match self.committed.load(Ordering::Acquire).wrapping_sub(next) {
+            0 => Ok(None),
+            1 => Ok(Some(next)),
+            _ => Err(self.skip_lost(next)),
+        }
+    }
+
+    fn copy_half(&self, index: u32, samples: &mut [f32; N]) {
+        for (sample, slot) in samples.iter_mut().zip(&self.buffer[(index % 2) as usize]) {
+            *sample = f32::from_bits(slot.load(Ordering::Relaxed));
+        }
+    }
+
+    /// Check that the half ``index`` was not being filled again while it was copied.
+    fn finish_read(&self, index: u32) -> Result<()> {
+        // Like a sequence lock: if the other half was committed while copying, the producer
+        // may have been filling this one again.
+        fence(Ordering::Acquire);
+        if self.committed.load(Ordering::Relaxed).wrapping_sub(index) > 1 {
+            return Err(self.skip_lost(index))
+        }
+        self.consumed.store(index.wrapping_add(1), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Skip from the half ``next`` to the latest committed one, reporting the halves lost.
+    fn skip_lost(&self, next: u32) -> Error {
+        let committed = self.committed.load(Ordering::Relaxed);
+        self.consumed.store(committed.wrapping_sub(1), Ordering::Relaxed);
+        Error::overrun(committed.wrapping_sub(next) - 1)
+    }
+
+    /// Convert the next committed half into ``output`` with ``converter`` at ``ratio``.
+    ///
+    /// Returns the number of generated samples, which is zero if no half is ready. Returns an
+    /// error of kind [``Overrun``](enum.ErrorKind.html#variant.Overrun) if the producer
+    /// overwrote the half before it was read, reporting the number of halves lost; the next
+    /// call converts the latest committed half. Call it in a loop until it returns zero to
+    /// catch up with the producer.
+    ///
+    /// Panics if ``N`` is not a whole number of frames of the converter, or if ``output`` is
+    /// too short for the samples converted from a half, which are about ``N`` times ``ratio``.
+    pub fn process_ready(&self, converter: &mut Converter, ratio: f64, output: &mut [f32])
+            -> Result<usize> {
+        assert!(N % converter.channels() == 0,
+                "half-buffer must be a multiple of the channel count");
+        let mut samples = [0.0; N];
+        if !self.read_ready(&mut samples)? {
+            return Ok(0)
+        }
+        let (mut used, mut generated) = (0, 0);
+        while used < N {
+            assert!(generated < output.len(), "output is too short for a half-buffer");
+            let (u, g) = converter.process(ratio, &samples[used..], false,
+                                           &mut output[generated..])?;
+            used += u;
+            generated += g;
+        }
+        Ok(generated)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+    use super::*;
+    use {ErrorKind, Interpolator};
+
+    fn lost(result: Result<usize>) -> u32 {
+        match result.map_err(|error| error.kind()) {
+            Err(ErrorKind::Overrun { lost_halves }) => lost_halves,
+            result => panic!("expected an overrun, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_matches_one_shot() {
+        let input: Vec<f32> = (0..2 * 256 * 8).map(|n| ((n / 2) as f32 * 0.02).sin()).collect();
+        let buffer = PingPongResampler::<512>::new();
+        let mut converter = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let mut output = Vec::new();
+        let mut block = [0.0; 2 * 1024];
+        for (index, half) in input.chunks(512).enumerate() {
+            let half_id = if index % 2 == 0 { Half::A } else { Half::B };
+            buffer.fill_half(half_id, half);
+            buffer.commit_half(half_id);
+            let generated = buffer.process_ready(&mut converter, 1.5, &mut block).unwrap();
+            output.extend_from_slice(&block[..generated]);
+            assert_eq!(buffer.process_ready(&mut converter, 1.5, &mut block).unwrap(), 0);
+        }
+
+        let mut expected = [0.0; 2 * 256 * 8 * 2];
+        let mut converter = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let (_, generated) = converter.process(1.5, &input, false, &mut expected).unwrap();
+        assert_eq!(output[..], expected[..generated]);
+    }
+
+    #[test]
+    fn test_overrun() {
+        let buffer = PingPongResampler::<4>::new();
+        let mut converter = Converter::new(Interpolator::Linear, 1).unwrap();
+        let mut output = [0.0; 64];
+
+        // Lapped by two halves: only the latest one is still intact.
+        buffer.fill_half(Half::A, &[1.0; 4]);
+        buffer.commit_half(Half::A);
+        buffer.commit_half(Half::B);
+        buffer.fill_half(Half::A, &[3.0; 4]);
+        buffer.commit_half(Half::A);
+        assert_eq!(lost(buffer.process_ready(&mut converter, 1.0, &mut output)), 2);
+        assert!(buffer.process_ready(&mut converter, 1.0, &mut output).unwrap() > 0);
+        assert_eq!(buffer.process_ready(&mut converter, 1.0, &mut output).unwrap(), 0);
+
+        // A missed interrupt: B is committed twice in a row, so A was never seen.
+        buffer.commit_half(Half::B);
+        assert!(buffer.process_ready(&mut converter, 1.0, &mut output).unwrap() > 0);
+        buffer.commit_half(Half::B);
+        assert_eq!(buffer.committed_halves(), 6);
+        assert_eq!(lost(buffer.process_ready(&mut converter, 1.0, &mut output)), 1);
+        assert!(buffer.process_ready(&mut converter, 1.0, &mut output).unwrap() > 0);
+    }
+
+    #[test]
+    fn test_overwritten_while_read() {
+        let buffer = PingPongResampler::<4>::new();
+        let mut samples = [0.0; 4];
+        buffer.fill_half(Half::A, &[1.0; 4]);
+        buffer.commit_half(Half::A);
+        let next = buffer.next_ready().unwrap().unwrap();
+        buffer.copy_half(next, &mut samples);
+        // The producer gets to the end of B and starts filling A again during the copy.
+        buffer.commit_half(Half::B);
+        buffer.fill_half(Half::A, &[3.0; 2]);
+        let error = buffer.finish_read(next).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Overrun { lost_halves: 1 });
+        // B is still intact.
+        assert!(buffer.read_ready(&mut samples).unwrap());
+        assert!(!buffer.read_ready(&mut samples).unwrap());
+    }
+
+    /// On a machine with several CPUs, the producer really runs during the copies; with one,
+    /// only when it preempts the consumer.
+    #[test]
+    fn test_concurrent_producer() {
+        const HALVES: u32 = 100_000;
+        let buffer = Arc::new(PingPongResampler::<256>::new());
+        let producer = {
+            let buffer = buffer.clone();
+            thread::spawn(move || {
+                for index in 0..HALVES {
+                    let half = if index % 2 == 0 { Half::A } else { Half::B };
+                    // The whole half holds the index of the commit, so a torn read shows.
+                    buffer.fill_half(half, &[index as f32; 256]);
+                    buffer.commit_half(half);
+                }
+            })
+        };
+
+        let (mut read, mut lost) = (0, 0);
+        let mut samples = [0.0; 256];
+        loop {
+            let finished = producer.is_finished();
+            match buffer.read_ready(&mut samples) {
+                Ok(true) => {
+                    let index = buffer.consumed.load(Ordering::Relaxed) - 1;
+                    assert!(samples.iter().all(|&sample| sample == index as f32),
+                            "torn read of half {}: {:?}", index, samples);
+                    read += 1;
+                }
+                Ok(false) if finished => break,
+                Ok(false) => (),
+                Err(error) => match error.kind() {
+                    ErrorKind::Overrun { lost_halves } => lost += lost_halves,
+                    kind => panic!("unexpected error {:?}", kind),
+                },
+            }
+        }
+        producer.join().unwrap();
+        assert_eq!(read + lost, HALVES);
+        assert!(read > 0);
+    }
+}
@@ -0,0 +1,202 @@
+//! Conversion of channels kept in separate buffers.
+//!
+//! libsamplerate only converts interleaved frames, so the planes are interleaved into and
+//! deinterleaved from fixed-size chunks on the stack, like the samples of
+//! [strided](fn.convert_strided.html) buffers; no scratch buffer is needed.
+
+use {ConversionResult, Converter, Error, Interpolator, Result, SRC_ERR_BAD_CHANNEL_COUNT};
+
+/// Number of samples converted per chunk.
+const CHUNK_SAMPLES: usize = 2048;
+
+/// Retrieve the length shared by all of ``planes``, or an error of kind
+/// [``BadPlaneLength``](enum.ErrorKind.html#variant.BadPlaneLength) if they differ.
+fn plane_len<P: AsRef<[f32]>>(planes: &[P]) -> Result<usize> {
+    let expected = planes.first().map_or(0, |plane| plane.as_ref().len());
+    match planes.iter().map(|plane| plane.as_ref().len()).find(|&len| len != expected) {
+        Some(len) => Err(Error::bad_plane_length(len, expected)),
+        None => Ok(expected),
+    }
+}
+
+/// Perform a single conversion from the channels in ``inputs`` into the channels in
+/// ``outputs``, one buffer per channel, like [``convert``](fn.convert.html) does for
+/// interleaved channels.
+///
+/// Returns the number of frames used from every input and generated into every output.
+/// Returns an error of kind
+/// [``BadChannelCount``](enum.ErrorKind.html#variant.BadChannelCount) if there are not as
+/// many outputs as inputs, and of kind
+/// [``BadPlaneLength``](enum.ErrorKind.html#variant.BadPlaneLength) if the inputs, or the
+/// outputs, are not all of the same length.
+pub fn convert_planar(interpolator: Interpolator, ratio: f64, inputs: &[&[f32]],
+                      outputs: &mut [&mut [f32]]) -> Result<ConversionResult> {
+    Converter::new(interpolator, inputs.len())?
+        .process_planar(ratio, Some(inputs), true, outputs)
+}
+
+impl Converter {
+    /// Convert samples using internal state like [``convert``](#method.convert), from the
+    /// channels in ``inputs`` into the channels in ``outputs``, one buffer per channel.
+    ///
+    /// If there is no more input data, provide ``None`` as ``inputs``, and the converter will
+    /// flush its internal state. Returns the number of frames used from every input and
+    /// generated into every output. Returns an error of kind
+    /// [``BadChannelCount``](enum.ErrorKind.html#variant.BadChannelCount) if there are not as
+    /// many inputs and outputs as the converter has channels, and of kind
+    /// [``BadPlaneLength``](enum.ErrorKind.html#variant.BadPlaneLength) if the inputs, or the
+    /// outputs, are not all of the same length; either leaves the converter as it was.
+    ///
+    /// The channels are converted a few thousand samples at a time, so a change of ratio is
+    /// interpolated over that many samples rather than over all of ``outputs``.
+    pub fn convert_planar(&mut self, ratio: f64, inputs: Option<&[&[f32]]>,
+                          outputs: &mut [&mut [f32]]) -> Result<ConversionResult> {
+        self.process_planar(ratio, inputs, inputs.is_none(), outputs)
+    }
+
+    /// Convert ``inputs``, and then flush the internal state if ``end`` is set.
+    fn process_planar(&mut self, ratio: f64, inputs: Option<&[&[f32]]>, end: bool,
+                      outputs: &mut [&mut [f32]]) -> Result<ConversionResult> {
+        let channels = self.channels();
+        if channels > CHUNK_SAMPLES || outputs.len() != channels ||
+                inputs.is_some_and(|inputs| inputs.len() != channels) {
+            return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+        }
+        let input_frames = inputs.map_or(Ok(0), plane_len)?;
+        let output_frames = plane_len(outputs)?;
+
+        let chunk = CHUNK_SAMPLES / channels;
+        let mut input_f32 = [0.0; CHUNK_SAMPLES];
+        let mut output_f32 = [0.0; CHUNK_SAMPLES];
+        let (mut used, mut generated) = (0, 0);
+        loop {
+            let output_len = chunk.min(output_frames - generated) * channels;
+            let mut input_len = chunk.min(input_frames - used) * channels;
+            if output_len < (output_frames - generated) * channels {
+                // As in ``process_chunked``, stop for want of input rather than with the chunk
+                // of output full.
+                let frames = (output_len / channels).saturating_sub(1) as f64 /
+                    ratio.max(self.last_ratio);
+                input_len = input_len.min((frames as usize).max(1) * channels);
+            }
+            if let Some(inputs) = inputs {
+                for (frame, dense) in input_f32[..input_len].chunks_mut(channels).enumerate() {
+                    for (sample, plane) in dense.iter_mut().zip(inputs) {
+                        *sample = plane[used + frame];
+                    }
+                }
+            }
+            // The end of the stream comes with the last chunk, as with a single conversion.
+            let rest = used + input_len / channels == input_frames;
+            let (u, g) = self.process(ratio, &input_f32[..input_len], end && rest,
+                                      &mut output_f32[..output_len])?;
+            for (frame, dense) in output_f32[..g].chunks(channels).enumerate() {
+                for (&sample, plane) in dense.iter().zip(outputs.iter_mut()) {
+                    plane[generated + frame] = sample;
+                }
+            }
+            used += u / channels;
+            generated += g / channels;
+            if u == 0 && g == 0 || rest && g < output_len {
+                break
+            }
+        }
+        Ok(ConversionResult { input_frames_used: used, output_frames_generated: generated })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use test::make_fixture;
+    use {ErrorKind, convert};
+    use super::*;
+
+    fn deinterleave(samples: &[f32]) -> [Vec<f32>; 2] {
+        [samples.iter().step_by(2).cloned().collect(),
+         samples.iter().skip(1).step_by(2).cloned().collect()]
+    }
+
+    #[test]
+    fn test_matches_interleaved() {
+        // More frames than fit in a chunk, so that the planes are converted in several.
+        let input = make_fixture(5000, true);
+        let planes = deinterleave(&input);
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            let mut expected = vec![0.0; 2 * 7600];
+            let (used, generated) = convert(interpolator, 2, 1.5, &input, &mut expected)
+                .unwrap();
+            expected.truncate(generated);
+
+            let mut outputs = [vec![0.0; 7600], vec![0.0; 7600]];
+            let result = {
+                let [ref mut left, ref mut right] = outputs;
+                convert_planar(interpolator, 1.5, &[&planes[0], &planes[1]],
+                               &mut [left, right]).unwrap()
+            };
+            assert_eq!(result.input_samples_used(2), used);
+            assert_eq!(result.output_samples_generated(2), generated);
+            for output in &mut outputs {
+                output.truncate(result.output_frames_generated);
+            }
+            assert_eq!(outputs, deinterleave(&expected));
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_interleaved() {
+        let input = make_fixture(5000, true);
+        let planes = deinterleave(&input);
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            let mut interleaved = Converter::new(interpolator, 2).unwrap();
+            let mut planar = Converter::new(interpolator, 2).unwrap();
+            let mut expected = vec![0.0; 2 * 1500];
+            let (mut left, mut right) = (vec![0.0; 1500], vec![0.0; 1500]);
+            // Chunks of at most 1000 frames, starting where the last one stopped being used.
+            // A last chunk of a single frame is left out, as libsamplerate's linear
+            // interpolator reads before it, which is outside of the buffer for the planes.
+            let mut start = 0;
+            loop {
+                let end = (start + 1000).min(5000);
+                let chunk = [&planes[0][start..end], &planes[1][start..end]];
+                let inputs = if end - start > 1 { Some(&chunk[..]) } else { None };
+                let result = planar.convert_planar(1.5, inputs, &mut [&mut left, &mut right])
+                    .unwrap();
+                let input = inputs.map(|_| &input[2 * start..2 * end]);
+                let (used, generated) = interleaved.convert(1.5, input, &mut expected).unwrap();
+                assert_eq!(result.input_samples_used(2), used);
+                assert_eq!(result.output_samples_generated(2), generated);
+                let frames = result.output_frames_generated;
+                assert_eq!([left[..frames].to_vec(), right[..frames].to_vec()],
+                           deinterleave(&expected[..generated]));
+                if input.is_none() && generated == 0 {
+                    break
+                }
+                start += result.input_frames_used;
+            }
+        }
+    }
+
+    #[test]
+    fn test_planar_errors() {
+        let (a, b, c) = ([0.0; 16], [0.0; 16], [0.0; 15]);
+        let (mut x, mut y, mut z) = ([0.0; 32], [0.0; 32], [0.0; 31]);
+        let error = convert_planar(Interpolator::Linear, 2.0, &[&a, &c], &mut [&mut x, &mut y])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadPlaneLength { len: 15, expected: 16 });
+        let error = convert_planar(Interpolator::Linear, 2.0, &[&a, &b], &mut [&mut x, &mut z])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadPlaneLength { len: 31, expected: 32 });
+        let error = convert_planar(Interpolator::Linear, 2.0, &[&a, &b], &mut [&mut x])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadChannelCount);
+
+        let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        let error = conv.convert_planar(2.0, Some(&[&a]), &mut [&mut x]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadChannelCount);
+        let error = conv.convert_planar(2.0, None, &mut [&mut x, &mut z]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadPlaneLength { len: 31, expected: 32 });
+        assert_eq!(conv.frames_in(), 0);
+    }
+}
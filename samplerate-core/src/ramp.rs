@@ -0,0 +1,112 @@
+//! Conversion with a ratio swept between two given values.
+
+use {Converter, Result};
+
+impl Converter {
+    /// Convert samples like [``convert``](#method.convert), sweeping the ratio from
+    /// ``start_ratio`` to ``end_ratio`` whatever ratio the converter had before.
+    ///
+    /// libsamplerate interpolates the ratio linearly over the frames of ``output``, so the
+    /// sweep ends at ``end_ratio`` only if the output buffer is filled; otherwise it stops at
+    /// the ratio of the last frame generated, which
+    /// [``last_applied_ratio``](#method.last_applied_ratio) reports. Sweeping over ``input``
+    /// of ``n`` frames takes ``n * (end_ratio - start_ratio) / ln(end_ratio / start_ratio)``
+    /// frames of output, or ``n * start_ratio`` frames if the ratios are equal. Like after
+    /// ``convert``, the next call starts from the ratio reached, and a ``convert`` call at
+    /// ``end_ratio`` carries on at a constant ratio.
+    ///
+    /// If there is no more input data, provide ``None`` as ``input``, and the converter will
+    /// flush its internal state while sweeping. Returns the number of used input samples and
+    /// generated output samples, respectively, and an error of kind
+    /// [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if either ratio is not
+    /// [valid](fn.is_valid_ratio.html), or any other error that ``convert`` reports for its
+    /// arguments, such as [``StreamEnded``](enum.ErrorKind.html#variant.StreamEnded), leaving
+    /// the converter as it was.
+    pub fn convert_ramped(&mut self, start_ratio: f64, end_ratio: f64, input: Option<&[f32]>,
+                          output: &mut [f32]) -> Result<(usize, usize)> {
+        let (end, input) = (input.is_none(), input.unwrap_or(&[]));
+        // Everything that ``process`` rejects is checked before the ratio is changed.
+        self.check_process(end_ratio, input, end, output)?;
+        self.set_ratio(start_ratio)?;
+        self.process(end_ratio, input, end, output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+    use std::vec::Vec;
+    use std::vec;
+    use {ErrorKind, Interpolator};
+    use super::*;
+
+    /// Retrieve the positions at which ``samples`` cross zero upwards, interpolated linearly
+    /// between samples.
+    fn upward_crossings(samples: &[f32]) -> Vec<f64> {
+        samples.windows(2).enumerate()
+            .filter(|&(_, pair)| pair[0] < 0.0 && pair[1] >= 0.0)
+            .map(|(index, pair)| index as f64 + (pair[0] / (pair[0] - pair[1])) as f64)
+            .collect()
+    }
+
+    #[test]
+    fn test_sweep() {
+        // A period of 100 input frames, which the sweep stretches to 200 output frames.
+        let input: Vec<f32> = (0..40000).map(|n| (n as f64 * 2.0 * PI / 100.0).sin() as f32)
+            .collect();
+        let mut conv = Converter::new(Interpolator::SincMediumQuality, 1).unwrap();
+        // Start from another ratio, to check that the sweep does not ramp from it.
+        conv.set_ratio(3.0).unwrap();
+        // The sinc interpolator holds back half of its filter, which the sweep cannot reach.
+        let swept = input.len() - conv.input_latency();
+        let mut output = vec![0.0; (swept as f64 * (2.0 - 1.0) / 2f64.ln()) as usize];
+
+        let (used, generated) = conv.convert_ramped(1.0, 2.0, Some(&input), &mut output)
+            .unwrap();
+        assert_eq!(generated, output.len());
+        assert_eq!(used, input.len());
+        let (first, last) = conv.last_applied_ratio().unwrap();
+        assert_eq!(first, 1.0);
+        assert!((last - 2.0).abs() < 1e-3, "{}", last);
+
+        let crossings = upward_crossings(&output);
+        let periods: Vec<f64> = crossings.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert!((periods[1] - 100.0).abs() < 1.0, "{}", periods[1]);
+        assert!((periods[periods.len() - 2] - 200.0).abs() < 2.0,
+                "{}", periods[periods.len() - 2]);
+        // The frequency falls with every period, past the start-up of the filter.
+        for (index, pair) in periods[1..].windows(2).enumerate() {
+            assert!(pair[1] > pair[0], "period {}: {} then {}", index + 1, pair[0], pair[1]);
+        }
+
+        // The flush carries on at the ratio reached, or sweeps further.
+        let (_, flushed) = conv.convert_ramped(2.0, 2.0, None, &mut output).unwrap();
+        assert!(flushed > 0);
+        assert_eq!(conv.last_applied_ratio(), Some((2.0, 2.0)));
+    }
+
+    #[test]
+    fn test_bad_ratios() {
+        let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        let (input, mut output) = ([0.5; 64], [0.0; 256]);
+        for &(start, end) in &[(0.0, 2.0), (2.0, 1000.0), (f64::NAN, 1.0), (1.0, f64::NAN)] {
+            let error = conv.convert_ramped(start, end, Some(&input), &mut output).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::BadRatio);
+        }
+        let error = conv.convert_ramped(1.0, 2.0, Some(&input[..63]), &mut output).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 63, channels: 2 });
+        assert_eq!(conv.frames_in(), 0);
+        assert_eq!(conv.last_applied_ratio(), None);
+    }
+
+    #[test]
+    fn test_ended_stream_keeps_ratio() {
+        let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        let (input, mut output) = ([0.5; 64], [0.0; 256]);
+        conv.convert(1.5, Some(&input), &mut output).unwrap();
+        while conv.convert(1.5, None, &mut output).unwrap().1 > 0 {}
+        let error = conv.convert_ramped(3.0, 2.0, Some(&input), &mut output).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::StreamEnded);
+        assert_eq!(conv.last_ratio, 1.5);
+    }
+}
@@ -0,0 +1,207 @@
+//! Conversion between sample rates given in hertz.
+//!
+//! The ratio of two sample rates, such as 48000 / 44100, is rational but rarely representable
+//! as an ``f64``, so libsamplerate only ever converts at a rounded ratio. The rounding error
+//! is far too small to be heard, but a caller that counts output frames by multiplying input
+//! frames by the rounded ratio, chunk after chunk, sees its count wander from the output.
+//! [``Converter::frames_behind``](../struct.Converter.html#method.frames_behind) compares the
+//! output against the count computed from the rates with integer arithmetic instead.
+
+use {Converter, Error, Interpolator, Result, convert};
+
+/// Bounds of the conversion ratio, as for libsamplerate.
+const MAX_RATIO: u64 = 256;
+
+/// Retrieve the conversion ratio from ``from_hz`` to ``to_hz``.
+///
+/// Returns an error of kind
+/// [``BadSampleRates``](enum.ErrorKind.html#variant.BadSampleRates) if either rate is zero,
+/// or if they are more than 256 times apart, which is outside of the ratios libsamplerate
+/// accepts.
+pub fn ratio_for_rates(from_hz: u32, to_hz: u32) -> Result<f64> {
+    let (from, to) = (from_hz as u64, to_hz as u64);
+    if from == 0 || to == 0 || from > to * MAX_RATIO || to > from * MAX_RATIO {
+        return Err(Error::bad_sample_rates(from_hz, to_hz))
+    }
+    Ok(to_hz as f64 / from_hz as f64)
+}
+
+/// Perform a single conversion from ``from_hz`` to ``to_hz``, like
+/// [``convert``](fn.convert.html) at the ratio of the rates.
+///
+/// Returns an error of kind
+/// [``BadSampleRates``](enum.ErrorKind.html#variant.BadSampleRates) if the rates are not
+/// [valid](fn.ratio_for_rates.html).
+pub fn convert_rates(interpolator: Interpolator, channels: usize, from_hz: u32, to_hz: u32,
+                     input: &[f32], output: &mut [f32]) -> Result<(usize, usize)> {
+    convert(interpolator, channels, ratio_for_rates(from_hz, to_hz)?, input, output)
+}
+
+/// Output frames expected from the calls to
+/// [``Converter::convert_rates``](../struct.Converter.html#method.convert_rates).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateTracker {
+    from_hz: u32,
+    to_hz: u32,
+    /// Output frames expected from the input used before the rates last changed.
+    expected_frames: u64,
+    /// Input frames used since the rates last changed.
+    input_frames: u64,
+    /// Output frames generated by all of the calls.
+    output_frames: u64,
+}
+
+impl RateTracker {
+    fn new(from_hz: u32, to_hz: u32) -> RateTracker {
+        RateTracker { from_hz, to_hz, expected_frames: 0, input_frames: 0, output_frames: 0 }
+    }
+
+    fn set_rates(&mut self, from_hz: u32, to_hz: u32) {
+        if (from_hz, to_hz) != (self.from_hz, self.to_hz) {
+            self.expected_frames = self.expected();
+            self.input_frames = 0;
+            self.from_hz = from_hz;
+            self.to_hz = to_hz;
+        }
+    }
+
+    fn expected(&self) -> u64 {
+        let frames = self.input_frames as u128 * self.to_hz as u128 / self.from_hz as u128;
+        self.expected_frames + frames as u64
+    }
+
+    fn frames_behind(&self) -> i64 {
+        self.expected() as i64 - self.output_frames as i64
+    }
+}
+
+impl Converter {
+    /// Convert samples from ``from_hz`` to ``to_hz`` like [``convert``](#method.convert) at
+    /// the ratio of the rates, which may change between calls.
+    ///
+    /// Returns an error of kind
+    /// [``BadSampleRates``](enum.ErrorKind.html#variant.BadSampleRates) if the rates are not
+    /// [valid](fn.ratio_for_rates.html), leaving the converter as it was.
+    pub fn convert_rates(&mut self, from_hz: u32, to_hz: u32, input: Option<&[f32]>,
+                         output: &mut [f32]) -> Result<(usize, usize)> {
+        let ratio = ratio_for_rates(from_hz, to_hz)?;
+        let channels = self.channels();
+        let (used, generated) = self.convert(ratio, input, output)?;
+        let rates = self.rates.get_or_insert(RateTracker::new(from_hz, to_hz));
+        rates.set_rates(from_hz, to_hz);
+        rates.input_frames += (used / channels) as u64;
+        rates.output_frames += (generated / channels) as u64;
+        Ok((used, generated))
+    }
+
+    /// Retrieve how many frames the output of [``convert_rates``](#method.convert_rates) is
+    /// behind the exact count for the input it used, ``input * to_hz / from_hz`` rounded
+    /// down, or 0 if it was not called since the converter was created or
+    /// [``reset``](#method.reset).
+    ///
+    /// The count is computed with integer arithmetic, so it does not drift however many
+    /// calls there are. The output falls behind by the delay of the interpolator until the
+    /// end of the stream is flushed, and ramping to new rates moves it by a few frames;
+    /// otherwise, it stays within a frame of the count. Calls to ``convert`` are not
+    /// counted.
+    pub fn frames_behind(&self) -> i64 {
+        self.rates.map_or(0, |rates| rates.frames_behind())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use test::make_fixture;
+    use {ErrorKind, output_len_hint};
+    use super::*;
+
+    /// Convert one period of a sine wave in ``from_hz`` frames to ``to_hz`` frames, and check
+    /// it against the same period generated at ``to_hz``.
+    fn convert_period(interpolator: Interpolator, input: &[f32], from_hz: u32, to_hz: u32)
+            -> Vec<f32> {
+        let len = output_len_hint(interpolator, 1, to_hz as f64 / from_hz as f64, input.len());
+        let mut output = vec![0.0; len];
+        let (used, generated) = convert_rates(interpolator, 1, from_hz, to_hz, input,
+                                              &mut output).unwrap();
+        assert_eq!(used, input.len());
+        assert_eq!(generated, to_hz as usize / 10);
+        output.truncate(generated);
+        let expected = make_fixture(generated, false);
+        for (index, (o, e)) in output.iter().zip(&expected).enumerate().skip(10)
+                .take(generated - 20) {
+            assert!((o - e).abs() < 0.01, "{}: {} != {}", index, o, e);
+        }
+        output
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = make_fixture(4410, false);
+        let output = convert_period(Interpolator::SincBestQuality, &input, 44100, 48000);
+        convert_period(Interpolator::SincBestQuality, &output, 48000, 44100);
+
+        let input = make_fixture(4800, false);
+        let output = convert_period(Interpolator::SincBestQuality, &input, 48000, 44100);
+        convert_period(Interpolator::SincBestQuality, &output, 44100, 48000);
+    }
+
+    #[test]
+    fn test_bad_sample_rates() {
+        assert_eq!(ratio_for_rates(44100, 48000).unwrap(), 48000.0 / 44100.0);
+        assert_eq!(ratio_for_rates(1, 256).unwrap(), 256.0);
+        assert_eq!(ratio_for_rates(256, 1).unwrap(), 1.0 / 256.0);
+        let mut conv = Converter::new(Interpolator::Linear, 1).unwrap();
+        for &(from_hz, to_hz) in &[(0, 48000), (48000, 0), (0, 0), (1, 257), (257, 1),
+                                   (u32::MAX, 1), (1, u32::MAX)] {
+            let error = convert_rates(Interpolator::Linear, 1, from_hz, to_hz, &[0.0; 4],
+                                      &mut [0.0; 4]).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::BadSampleRates { from_hz, to_hz });
+            assert!(conv.convert_rates(from_hz, to_hz, Some(&[0.0; 4]), &mut [0.0; 4])
+                        .is_err());
+        }
+        assert_eq!(conv.frames_in(), 0);
+    }
+
+    /// Stream ten seconds of noise at ``from_hz`` in chunks of varying size, returning the
+    /// least and the most frames the output was behind after the first second.
+    fn stream(conv: &mut Converter, from_hz: u32, to_hz: u32) -> (i64, i64) {
+        let mut pending: Vec<f32> = Vec::new();
+        let mut output = vec![0.0; 4096];
+        let mut seed = 1u32;
+        let (mut least, mut most) = (i64::MAX, i64::MIN);
+        while conv.frames_in() < from_hz as u64 * 10 {
+            let chunk = 1 + (conv.frames_in() * 37 % 511) as usize;
+            for _ in 0..chunk {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                pending.push((seed >> 16) as f32 / 32768.0 - 1.0);
+            }
+            let (used, _) = conv.convert_rates(from_hz, to_hz, Some(&pending), &mut output)
+                .unwrap();
+            pending.drain(..used);
+            if conv.frames_in() > from_hz as u64 {
+                least = least.min(conv.frames_behind());
+                most = most.max(conv.frames_behind());
+            }
+        }
+        (least, most)
+    }
+
+    #[test]
+    fn test_no_drift() {
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            for &(from_hz, to_hz) in &[(44100, 48000), (48000, 44100), (44100, 96000)] {
+                let mut conv = Converter::new(interpolator, 1).unwrap();
+                let (least, most) = stream(&mut conv, from_hz, to_hz);
+                assert!(most - least <= 1, "{:?} {} {}", interpolator, from_hz, to_hz);
+                // Flushing the stream generates the output the interpolator held back.
+                let mut output = [0.0; 4096];
+                while conv.convert_rates(from_hz, to_hz, None, &mut output).unwrap().1 > 0 {}
+                assert!(conv.frames_behind().abs() <= 1);
+                conv.reset().unwrap();
+                assert_eq!(conv.frames_behind(), 0);
+            }
+        }
+    }
+}
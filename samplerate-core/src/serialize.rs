@@ -0,0 +1,76 @@
+//! Serialization of interpolators with [serde](https://serde.rs/).
+//!
+//! Interpolators are stored by name, such as ``"sinc_best_quality"``, rather than by the
+//! value of the libsamplerate constant, so that configuration files stay readable and do not
+//! depend on the order of the variants.
+
+use core::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, Visitor};
+use serde::ser;
+
+use {INTERPOLATORS, INTERPOLATOR_NAMES, Interpolator};
+
+impl Serialize for Interpolator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match INTERPOLATORS.iter().position(|interpolator| interpolator == self) {
+            Some(index) => serializer.serialize_str(INTERPOLATOR_NAMES[index]),
+            None => Err(ser::Error::custom("not an interpolator")),
+        }
+    }
+}
+
+struct InterpolatorVisitor;
+
+impl<'de> Visitor<'de> for InterpolatorVisitor {
+    type Value = Interpolator;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the name of an interpolator")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Interpolator, E> {
+        match INTERPOLATOR_NAMES.iter().position(|&name| name == value) {
+            Some(index) => Ok(INTERPOLATORS[index]),
+            None => Err(E::unknown_variant(value, &INTERPOLATOR_NAMES)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Interpolator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Interpolator, D::Error> {
+        deserializer.deserialize_str(InterpolatorVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::ToString;
+    use serde_json;
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for interpolator in Interpolator::all() {
+            let json = serde_json::to_string(&interpolator).unwrap();
+            assert_eq!(serde_json::from_str::<Interpolator>(&json).unwrap(), interpolator);
+        }
+        assert_eq!(serde_json::to_string(&Interpolator::SincBestQuality).unwrap(),
+                   "\"sinc_best_quality\"");
+        assert_eq!(serde_json::to_string(&Interpolator::ZeroOrderHold).unwrap(),
+                   "\"zero_order_hold\"");
+    }
+
+    #[test]
+    fn test_unknown() {
+        let error = serde_json::from_str::<Interpolator>("\"cubic\"").unwrap_err().to_string();
+        assert!(error.starts_with("unknown variant `cubic`, expected one of \
+                                   `sinc_best_quality`, `sinc_medium_quality`, \
+                                   `sinc_fastest`, `zero_order_hold`, `linear`"), "{}", error);
+        for json in &["\"__nonexhaustive\"", "\"__Nonexhaustive\"", "\"SincFastest\"", "2"] {
+            assert!(serde_json::from_str::<Interpolator>(json).is_err(), "{}", json);
+        }
+        assert!(serde_json::to_string(&Interpolator::__Nonexhaustive).is_err());
+    }
+}
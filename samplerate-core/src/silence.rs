@@ -0,0 +1,219 @@
+use Converter;
+
+/// Half length of the longest libsamplerate filter (``SincBestQuality``), in input frames,
+/// rounded up. The filter is stretched by the inverse ratio when downsampling.
+const FILTER_HALF_FRAMES: f64 = 143.0;
+
+/// Number of consecutive exactly zero output frames required, in addition to silent input,
+/// before skipping libsamplerate.
+const OUTPUT_WATERMARK_FRAMES: u64 = 64;
+
+/// Largest number of input frames in a period of the ratio; see ``period``.
+const MAX_PERIOD_FRAMES: usize = 4096;
+
+/// Shortest number of input frames ``m`` that the converter turns into exactly ``l``
+/// output frames, for an integer ``l``, returned as ``(m, l)``.
+///
+/// Skipping a whole number of periods leaves the fractional position of libsamplerate
+/// where it would have been after converting them, so the output after the silence is the
+/// same as if it had not been skipped.
+fn period(ratio: f64) -> Option<(usize, usize)> {
+    (1..MAX_PERIOD_FRAMES + 1)
+        .map(|m| (m, m as f64 * ratio))
+        // `f64::round` is not available without std.
+        .map(|(m, l)| (m, l, (l + 0.5) as usize))
+        .find(|&(_, l, rounded)| l >= 1.0 && (l - rounded as f64).abs() < 1e-9 * l)
+        .map(|(m, _, rounded)| (m, rounded))
+}
+
+fn trailing_zero_frames(samples: &[f32], channels: usize) -> u64 {
+    (samples.iter().rev().take_while(|&&sample| sample == 0.0).count() / channels) as u64
+}
+
+/// State of the silence fast path of a [``Converter``](struct.Converter.html).
+#[derive(Clone)]
+pub(crate) struct SilenceTracker {
+    /// Ratio that libsamplerate starts the next conversion from, if known.
+    ratio: Option<f64>,
+    period: Option<(usize, usize)>,
+    /// Number of trailing zero frames in the input and output so far.
+    zero_in: u64,
+    zero_out: u64,
+    /// Number of input frames not passed to libsamplerate at all.
+    skipped: u64,
+}
+
+impl SilenceTracker {
+    pub(crate) fn new() -> SilenceTracker {
+        SilenceTracker { ratio: None, period: None, zero_in: 0, zero_out: 0, skipped: 0 }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = SilenceTracker { skipped: self.skipped, ..SilenceTracker::new() }
+    }
+
+    /// Record that the next conversion starts from ``ratio`` without interpolation.
+    pub(crate) fn set_ratio(&mut self, ratio: f64) {
+        if self.ratio != Some(ratio) {
+            self.ratio = Some(ratio);
+            self.period = period(ratio);
+        }
+    }
+
+    /// Determine how many input and output frames of a conversion with ``ratio`` can skip
+    /// libsamplerate, given that ``input_frames`` frames of silence are available and
+    /// ``output_frames`` frames fit in the output.
+    pub(crate) fn skippable(&self, ratio: f64, input_frames: usize,
+                            output_frames: usize) -> (usize, usize) {
+        // The filter history, which extends a half length to each side of the current
+        // position, and the lookahead, another half length, must all be silent.
+        let history = (2.0 * FILTER_HALF_FRAMES / ratio.min(1.0)) as u64 + 3;
+        match self.period {
+            Some((m, l)) if self.ratio == Some(ratio) && self.zero_in >= history &&
+                            self.zero_out >= OUTPUT_WATERMARK_FRAMES => {
+                let periods = (input_frames / m).min(output_frames / l);
+                (periods * m, periods * l)
+            }
+            _ => (0, 0),
+        }
+    }
+
+    /// Record a conversion that skipped libsamplerate.
+    pub(crate) fn skipped(&mut self, input_frames: usize, output_frames: usize) {
+        self.zero_in += input_frames as u64;
+        self.zero_out += output_frames as u64;
+        self.skipped += input_frames as u64;
+    }
+
+    /// Record a conversion with ``ratio`` performed by libsamplerate.
+    pub(crate) fn processed(&mut self, ratio: f64, channels: usize, input: &[f32],
+                            output: &[f32]) {
+        self.set_ratio(ratio);
+        let zero_in = trailing_zero_frames(input, channels);
+        self.zero_in = if zero_in as usize * channels == input.len() {
+            self.zero_in + zero_in
+        } else {
+            zero_in
+        };
+        let zero_out = trailing_zero_frames(output, channels);
+        self.zero_out = if zero_out as usize * channels == output.len() {
+            self.zero_out + zero_out
+        } else {
+            zero_out
+        };
+    }
+}
+
+impl Converter {
+    /// Enable or disable the silence fast path, which is disabled by default.
+    ///
+    /// With the fast path enabled, input that is entirely zero is not passed to
+    /// libsamplerate once everything the converter holds has decayed to silence, i.e. after
+    /// a few hundred frames of zero input and zero output; zero output frames are generated
+    /// directly instead. This saves nearly all of the conversion time for streams that are
+    /// mostly silent, such as voice with discontinuous transmission.
+    ///
+    /// Silence is only skipped in whole periods of the ratio, i.e. runs of input frames that
+    /// convert to an exact integer number of frames, which keeps the output identical to that
+    /// of the normal path, including after the silence ends, up to floating point rounding
+    /// in the position of libsamplerate. Consequently, the fast
+    /// path is only taken for ratios that are close to a fraction with a denominator of at
+    /// most 4096, such as 48000/44100, and only when the ratio was not changed since the
+    /// previous call.
+    pub fn set_silence_fast_path(&mut self, enabled: bool) {
+        match (enabled, self.silence.is_some()) {
+            (true, false) => self.silence = Some(SilenceTracker::new()),
+            (false, true) => self.silence = None,
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use Interpolator;
+    use super::*;
+
+    /// Bursts of a tone, separated by long stretches of digital silence.
+    fn make_dtx(frames: usize, channels: usize) -> Vec<f32> {
+        (0..frames * channels)
+            .map(|i| {
+                let frame = i / channels;
+                if (frame / 3000) % 3 == 0 {
+                    ((frame as f32) * 0.07 + (i % channels) as f32).sin() * 0.5
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    fn run(converter: &mut Converter, ratio: f64, input: &[f32], chunk: usize) -> Vec<f32> {
+        let channels = converter.channels();
+        let mut output = Vec::new();
+        let mut buffer = std::vec![0.0; ((chunk as f64 * ratio) as usize + 2) * channels];
+        for chunk in input.chunks(chunk * channels) {
+            let mut rest = chunk;
+            while !rest.is_empty() {
+                let (used, generated) = converter.convert(ratio, Some(rest), &mut buffer)
+                                                 .unwrap();
+                output.extend_from_slice(&buffer[..generated]);
+                rest = &rest[used..];
+            }
+        }
+        output
+    }
+
+    fn skipped(converter: &Converter) -> u64 {
+        converter.silence.as_ref().unwrap().skipped
+    }
+
+    #[test]
+    fn test_period() {
+        assert_eq!(period(2.0), Some((1, 2)));
+        assert_eq!(period(0.5), Some((2, 1)));
+        assert_eq!(period(48000.0 / 44100.0), Some((147, 160)));
+        assert_eq!(period(44100.0 / 48000.0), Some((160, 147)));
+        assert_eq!(period(2f64.sqrt()), None);
+    }
+
+    #[test]
+    fn test_matches_normal_path() {
+        for &(interpolator, channels, ratio) in
+                &[(Interpolator::SincBestQuality, 1, 48000.0 / 44100.0),
+                  (Interpolator::SincFastest, 2, 16000.0 / 48000.0),
+                  (Interpolator::SincMediumQuality, 2, 3.0),
+                  (Interpolator::Linear, 1, 0.5),
+                  (Interpolator::ZeroOrderHold, 1, 1.5)] {
+            let input = make_dtx(30000, channels);
+            let mut normal = Converter::new(interpolator, channels).unwrap();
+            let expected = run(&mut normal, ratio, &input, 480);
+
+            let mut fast = Converter::new(interpolator, channels).unwrap();
+            fast.set_silence_fast_path(true);
+            let actual = run(&mut fast, ratio, &input, 480);
+            assert!(skipped(&fast) > 10000, "{:?}: skipped {}", interpolator, skipped(&fast));
+            assert_eq!(fast.frames_in(), normal.frames_in());
+            assert_eq!(fast.frames_out(), normal.frames_out());
+            assert!(actual == expected, "{:?}: output differs", interpolator);
+        }
+    }
+
+    #[test]
+    fn test_not_taken() {
+        let input = make_dtx(30000, 1);
+        // An irrational ratio has no period.
+        let mut converter = Converter::new(Interpolator::SincFastest, 1).unwrap();
+        converter.set_silence_fast_path(true);
+        run(&mut converter, 2f64.sqrt(), &input, 480);
+        assert_eq!(skipped(&converter), 0);
+
+        // Quiet input is not silent input.
+        let quiet: Vec<f32> = input.iter().map(|&s| s + 1e-9).collect();
+        let mut converter = Converter::new(Interpolator::SincFastest, 1).unwrap();
+        converter.set_silence_fast_path(true);
+        run(&mut converter, 2.0, &quiet, 480);
+        assert_eq!(skipped(&converter), 0);
+    }
+}
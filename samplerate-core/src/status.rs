@@ -0,0 +1,122 @@
+//! Conversion that reports whether the end of the stream has been reached.
+//!
+//! When flushing with [``Converter::convert``](struct.Converter.html#method.convert), an
+//! output buffer that comes back full may or may not hold the last of the stream, and one
+//! that comes back empty may only be too short to make any progress. The status returned by
+//! [``Converter::convert_with_status``](struct.Converter.html#method.convert_with_status)
+//! tells the two apart, so that a flushing loop stops exactly when it should.
+
+use {ConversionResult, Converter, Error, ErrorKind, Result};
+
+/// Outcome of a conversion with
+/// [``Converter::convert_with_status``](struct.Converter.html#method.convert_with_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StreamStatus {
+    /// Number of frames used and generated.
+    pub result: ConversionResult,
+    /// Whether the end of the stream was signalled and everything the converter held back
+    /// has been generated, so that further flushing generates nothing.
+    pub done: bool,
+}
+
+impl Converter {
+    /// Convert frames like [``convert_frames``](#method.convert_frames), also reporting
+    /// whether the stream is done.
+    ///
+    /// libsamplerate stops generating output when it either runs out of input or of output
+    /// space, so after the end of the stream is signalled with ``None`` as ``input``, the
+    /// stream is done once a call leaves room in ``output``. If the tail fits ``output``
+    /// exactly, the stream is done after one more call, which generates nothing. The status
+    /// is cleared by [``reset``](#method.reset).
+    ///
+    /// Returns an error of kind [``EmptyOutput``](enum.ErrorKind.html#variant.EmptyOutput)
+    /// if ``input`` is ``None``, ``output`` is empty, and the stream is not done, since the
+    /// call cannot make progress; or any other error of ``convert``.
+    pub fn convert_with_status(&mut self, ratio: f64, input: Option<&[f32]>,
+                               output: &mut [f32]) -> Result<StreamStatus> {
+        if input.is_none() && output.is_empty() && !self.done {
+            return Err(Error::new(ErrorKind::EmptyOutput))
+        }
+        let result = self.convert_frames(ratio, input, output)?;
+        Ok(StreamStatus { result, done: self.done })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use Interpolator;
+    use super::*;
+
+    /// Create a stereo converter that has converted some input, and retrieve the tail that
+    /// flushing it generates.
+    fn converted() -> (Converter, Vec<f32>) {
+        let input: Vec<f32> = (0..2000).map(|n| (n as f32 * 0.05).sin() * 0.5).collect();
+        let mut conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let mut output = vec![0.0; 4000];
+        conv.convert(1.5, Some(&input), &mut output).unwrap();
+
+        let mut clone = conv.try_clone().unwrap();
+        let mut tail = vec![0.0; 4000];
+        let status = clone.convert_with_status(1.5, None, &mut tail).unwrap();
+        assert!(status.done);
+        tail.truncate(status.result.output_samples_generated(2));
+        assert!(!tail.is_empty());
+        (conv, tail)
+    }
+
+    #[test]
+    fn test_exact_tail() {
+        let (mut conv, tail) = converted();
+        let mut output = vec![0.0; tail.len()];
+        let status = conv.convert_with_status(1.5, None, &mut output).unwrap();
+        assert_eq!(status.result.output_samples_generated(2), tail.len());
+        assert!(!status.done);
+        assert_eq!(output, tail);
+
+        let status = conv.convert_with_status(1.5, None, &mut output).unwrap();
+        assert_eq!(status.result, ConversionResult::default());
+        assert!(status.done);
+    }
+
+    #[test]
+    fn test_short_output() {
+        let (mut conv, tail) = converted();
+        let (mut flushed, mut output, mut calls) = (Vec::new(), [0.0; 14], 0);
+        loop {
+            let status = conv.convert_with_status(1.5, None, &mut output).unwrap();
+            flushed.extend_from_slice(&output[..status.result.output_samples_generated(2)]);
+            calls += 1;
+            assert!(calls <= tail.len() / 14 + 1);
+            if status.done {
+                break
+            }
+        }
+        assert_eq!(flushed, tail);
+        assert!(calls > 1);
+    }
+
+    #[test]
+    fn test_empty_output() {
+        let (mut conv, _) = converted();
+        let error = conv.convert_with_status(1.5, None, &mut []).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::EmptyOutput);
+        assert!(!conv.convert_with_status(1.5, Some(&[]), &mut []).unwrap().done);
+
+        conv.convert_with_status(1.5, None, &mut [0.0; 4000]).unwrap();
+        assert!(conv.convert_with_status(1.5, None, &mut []).unwrap().done);
+    }
+
+    #[test]
+    fn test_input_after_end() {
+        let (mut conv, _) = converted();
+        conv.convert_with_status(1.5, None, &mut [0.0; 4000]).unwrap();
+        let error = conv.convert(1.5, Some(&[0.5; 64]), &mut [0.0; 256]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::StreamEnded);
+
+        conv.reset().unwrap();
+        let status = conv.convert_with_status(1.5, Some(&[0.5; 64]), &mut [0.0; 256]).unwrap();
+        assert!(!status.done);
+    }
+}
@@ -0,0 +1,142 @@
+//! Conversion of samples embedded in a larger structure.
+//!
+//! The frames are gathered into and scattered from fixed-size chunks on the stack, like the
+//! samples of other formats, so the buffers never need to be densified as a whole.
+
+use {Converter, Error, Interpolator, Result, SRC_ERR_BAD_CHANNEL_COUNT};
+
+/// Number of samples converted per chunk.
+const CHUNK_SAMPLES: usize = 2048;
+
+/// Number of frames of ``channels`` samples, starting every ``stride`` samples, that fit
+/// in ``len`` samples. The last frame does not need to be followed by padding.
+fn frame_count(len: usize, channels: usize, stride: usize) -> usize {
+    if len < channels { 0 } else { (len - channels) / stride + 1 }
+}
+
+/// Perform a single conversion from ``input`` into ``output``, in which each frame of
+/// ``channels`` interleaved samples starts ``in_stride`` and ``out_stride`` samples after
+/// the previous one, respectively.
+///
+/// The samples between the frames are not read from ``input`` and are left unchanged in
+/// ``output``. Otherwise, this function behaves like [``convert``](fn.convert.html).
+///
+/// Returns an error if either stride is smaller than ``channels``. Returns the number of
+/// used input frames and generated output frames, respectively.
+pub fn convert_strided(interpolator: Interpolator, channels: usize, ratio: f64,
+                       input: &[f32], in_stride: usize, output: &mut [f32], out_stride: usize)
+        -> Result<(usize, usize)> {
+    let mut converter = Converter::new(interpolator, channels)?;
+    if channels > CHUNK_SAMPLES || in_stride < channels || out_stride < channels {
+        return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+    }
+
+    let chunk = CHUNK_SAMPLES / channels;
+    let input_frames = frame_count(input.len(), channels, in_stride);
+    let output_frames = frame_count(output.len(), channels, out_stride);
+    let mut input_f32 = [0.0; CHUNK_SAMPLES];
+    let mut output_f32 = [0.0; CHUNK_SAMPLES];
+
+    let (mut used, mut generated) = (0, 0);
+    loop {
+        let output_len = chunk.min(output_frames - generated) * channels;
+        let (u, g) = if used < input_frames {
+            let input_len = chunk.min(input_frames - used) * channels;
+            for (frame, dense) in input_f32[..input_len].chunks_mut(channels).enumerate() {
+                let start = (used + frame) * in_stride;
+                dense.copy_from_slice(&input[start..start + channels]);
+            }
+            converter.convert(ratio, Some(&input_f32[..input_len]),
+                              &mut output_f32[..output_len])?
+        } else {
+            converter.convert(ratio, None, &mut output_f32[..output_len])?
+        };
+        for (frame, dense) in output_f32[..g].chunks(channels).enumerate() {
+            let start = (generated + frame) * out_stride;
+            output[start..start + channels].copy_from_slice(dense);
+        }
+        used += u / channels;
+        generated += g / channels;
+        if u == 0 && g == 0 {
+            break
+        }
+    }
+    Ok((used, generated))
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use convert;
+    use super::*;
+
+    const PADDING: f32 = 7.0;
+
+    fn make_dense(frames: usize, channels: usize) -> Vec<f32> {
+        (0..frames * channels)
+            .map(|i| ((i / channels) as f32 * 0.03 * (1 + i % channels) as f32).sin() * 0.5)
+            .collect()
+    }
+
+    fn spread(dense: &[f32], channels: usize, stride: usize) -> Vec<f32> {
+        let mut strided = std::vec![PADDING; dense.len() / channels * stride];
+        for (frame, samples) in dense.chunks(channels).enumerate() {
+            strided[frame * stride..frame * stride + channels].copy_from_slice(samples);
+        }
+        strided
+    }
+
+    fn assert_matches_dense(interpolator: Interpolator, channels: usize, ratio: f64,
+                            frames: usize, in_stride: usize, out_stride: usize) {
+        let dense = make_dense(frames, channels);
+        let out_frames = (frames as f64 * ratio) as usize;
+        let mut expected = std::vec![0.0; out_frames * channels];
+        let (used, generated) =
+            convert(interpolator, channels, ratio, &dense, &mut expected).unwrap();
+        expected.truncate(generated);
+
+        let mut output = std::vec![PADDING; out_frames * out_stride];
+        let (used_frames, generated_frames) =
+            convert_strided(interpolator, channels, ratio, &spread(&dense, channels, in_stride),
+                            in_stride, &mut output, out_stride).unwrap();
+        assert_eq!((used_frames * channels, generated_frames * channels), (used, generated));
+
+        let mut reference = spread(&expected, channels, out_stride);
+        reference.resize(output.len(), PADDING);
+        assert_eq!(output, reference);
+    }
+
+    #[test]
+    fn test_strided_matches_dense() {
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            // A stereo stream inside frames of three samples, written into frames of five.
+            assert_matches_dense(interpolator, 2, 1.5, 3000, 3, 5);
+            // One row of a column-major matrix with four columns, written densely.
+            assert_matches_dense(interpolator, 1, 0.75, 5000, 4, 1);
+            assert_matches_dense(interpolator, 3, 2.0, 700, 3, 3);
+        }
+    }
+
+    #[test]
+    fn test_strided_unpadded_tail() {
+        // The last frame only needs to hold ``channels`` samples.
+        let dense = make_dense(100, 2);
+        let mut input = spread(&dense, 2, 4);
+        input.truncate(input.len() - 2);
+        let mut output = std::vec![0.0; 500 * 3 - 1];
+        let (used, _) = convert_strided(Interpolator::Linear, 2, 2.0, &input, 4,
+                                        &mut output, 3).unwrap();
+        assert_eq!(used, 100);
+    }
+
+    #[test]
+    fn test_strided_errors() {
+        let input = [0.0; 64];
+        let mut output = [0.0; 64];
+        for &(in_stride, out_stride) in &[(1, 2), (2, 1), (0, 2)] {
+            assert_eq!(convert_strided(Interpolator::Linear, 2, 1.0, &input, in_stride,
+                                       &mut output, out_stride),
+                       Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT)));
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Conversion between frame counts and durations.
+//!
+//! Durations are converted to frames rounding down, so that a duration never covers more
+//! frames than have fully elapsed in it, and frames are converted to durations rounding up
+//! to the next nanosecond, so that converting the result back gives the same number of
+//! frames. Rounding each chunk of a stream separately would accumulate an error of up to
+//! a frame per chunk; [``FrameClock``](struct.FrameClock.html) and
+//! [``Converter::output_frames_for``](../struct.Converter.html#method.output_frames_for)
+//! round the running totals instead, so the per-chunk counts always add up to the count for
+//! the whole stream.
+
+use core::time::Duration;
+
+use Converter;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Retrieve the number of frames at ``rate`` that have fully elapsed in ``duration``.
+pub fn frames_for_duration(rate: u32, duration: Duration) -> u64 {
+    (duration.as_nanos() * rate as u128 / NANOS_PER_SEC) as u64
+}
+
+/// Retrieve the duration of ``frames`` at ``rate``, rounded up to the next nanosecond.
+///
+/// Panics if ``rate`` is zero.
+pub fn duration_for_frames(rate: u32, frames: u64) -> Duration {
+    let rate = rate as u64;
+    let nanos = ((frames % rate) as u128 * NANOS_PER_SEC).div_ceil(rate as u128);
+    Duration::new(frames / rate, 0) + Duration::from_nanos(nanos as u64)
+}
+
+/// Retrieve the number of output frames that ``input_frames`` correspond to at ``ratio``,
+/// rounded down.
+///
+/// This is the number of frames a conversion converges to; a conversion of a finite buffer
+/// may differ from it by a frame, depending on the interpolator.
+pub fn output_frames(ratio: f64, input_frames: u64) -> u64 {
+    (input_frames as f64 * ratio) as u64
+}
+
+/// Counter of the frames at a sample rate that correspond to a sequence of durations, e.g.
+/// the periods of a timer driving the conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameClock {
+    rate: u32,
+    elapsed: Duration,
+    frames: u64,
+}
+
+impl FrameClock {
+    /// Create a counter starting at zero.
+    pub fn new(rate: u32) -> FrameClock {
+        FrameClock { rate, elapsed: Duration::from_secs(0), frames: 0 }
+    }
+
+    /// Retrieve the sample rate.
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Retrieve the total duration passed to [``advance``](#method.advance).
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Retrieve the total number of frames returned by [``advance``](#method.advance).
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Advance the counter by ``duration``, and retrieve the number of frames that have
+    /// fully elapsed since the last call.
+    pub fn advance(&mut self, duration: Duration) -> u64 {
+        self.elapsed += duration;
+        let frames = frames_for_duration(self.rate, self.elapsed);
+        let advanced = frames - self.frames;
+        self.frames = frames;
+        advanced
+    }
+}
+
+impl Converter {
+    /// Retrieve the number of output frames that the next ``input_frames`` input frames
+    /// correspond to at ``ratio``, given the input frames used so far.
+    ///
+    /// As long as the ratio does not change, the results for consecutive chunks add up to
+    /// [``output_frames``](time/fn.output_frames.html) for all of the input together.
+    pub fn output_frames_for(&self, ratio: f64, input_frames: u64) -> u64 {
+        let used = self.frames_in();
+        output_frames(ratio, used + input_frames) - output_frames(ratio, used)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use Interpolator;
+    use super::*;
+
+    /// Deterministic source of test cases.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, limit: u64) -> u64 {
+            self.next() % limit
+        }
+    }
+
+    const RATES: [u32; 6] = [8000, 11025, 22050, 44100, 48000, 192000];
+
+    #[test]
+    fn test_rounding() {
+        assert_eq!(frames_for_duration(48000, Duration::from_millis(10)), 480);
+        assert_eq!(frames_for_duration(44100, Duration::from_nanos(22675)), 0);
+        assert_eq!(frames_for_duration(44100, Duration::from_nanos(22676)), 1);
+        assert_eq!(duration_for_frames(44100, 1), Duration::from_nanos(22676));
+        assert_eq!(duration_for_frames(48000, 96000), Duration::from_secs(2));
+        assert_eq!(duration_for_frames(48000, 0), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut random = XorShift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..10000 {
+            let rate = RATES[random.below(RATES.len() as u64) as usize];
+            let frames = random.below(1 << 40);
+            let duration = duration_for_frames(rate, frames);
+            assert_eq!(frames_for_duration(rate, duration), frames);
+            // One nanosecond less no longer covers the last frame.
+            if frames > 0 {
+                assert_eq!(frames_for_duration(rate, duration - Duration::from_nanos(1)),
+                           frames - 1);
+            }
+
+            let duration = Duration::from_nanos(random.below(1 << 50));
+            let frames = frames_for_duration(rate, duration);
+            assert!(duration_for_frames(rate, frames) <= duration);
+            assert!(duration_for_frames(rate, frames + 1) > duration);
+        }
+    }
+
+    #[test]
+    fn test_frame_clock_no_drift() {
+        let mut random = XorShift(0x9e37_79b9_7f4a_7c15);
+        for &rate in &RATES {
+            let mut clock = FrameClock::new(rate);
+            let mut total = 0;
+            for _ in 0..1000 {
+                total += clock.advance(Duration::from_nanos(random.below(10_000_000)));
+            }
+            assert_eq!(total, clock.frames());
+            assert_eq!(total, frames_for_duration(rate, clock.elapsed()));
+        }
+    }
+
+    #[test]
+    fn test_output_frames_no_drift() {
+        let mut random = XorShift(0xd1b5_4a32_d192_ed03);
+        for &ratio in &[48000.0 / 44100.0, 44100.0 / 48000.0, 1.0 / 3.0, 2.0, 0.999] {
+            let mut converter = Converter::new(Interpolator::Linear, 1).unwrap();
+            let mut input = std::vec![0.0; 1000];
+            let mut output = std::vec![0.0; 3000];
+            let (mut total_in, mut total_out) = (0, 0);
+            for _ in 0..100 {
+                let chunk = random.below(1000) + 1;
+                for sample in &mut input[..chunk as usize] {
+                    *sample = (random.below(2000) as f32 - 1000.0) / 1000.0;
+                }
+                total_out += converter.output_frames_for(ratio, chunk);
+                let (used, _) = converter.convert(ratio, Some(&input[..chunk as usize]),
+                                                  &mut output).unwrap();
+                assert_eq!(used as u64, chunk);
+                total_in += chunk;
+            }
+            assert_eq!(converter.frames_in(), total_in);
+            assert_eq!(total_out, output_frames(ratio, total_in));
+            // What the converter generated lags behind by at most its delay.
+            assert!(converter.frames_out() <= total_out + 1);
+            assert!(converter.frames_out() + 2 >= total_out);
+        }
+    }
+}
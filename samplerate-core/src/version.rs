@@ -0,0 +1,206 @@
+//! Runtime check of the version of the libsamplerate in use.
+//!
+//! With the ``system`` feature, the library loaded at runtime may be older than the one the
+//! bindings were built against, which otherwise shows up as missing symbols or misbehaving
+//! conversions. The version reported by ``src_get_version`` is parsed once and compared to
+//! what each piece of functionality requires: ``MINIMUM_LIBRARY_VERSION`` for the bindings
+//! as a whole, checked when converters are created, and the ``*_LIBRARY_VERSION`` constants
+//! for functions added later, checked where they are called.
+
+use core::ffi::CStr;
+use core::fmt;
+#[cfg(feature = "runtime-loading")]
+use core::ptr;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use backend::{self, Api};
+use {Error, ErrorKind, Result};
+
+/// Version of libsamplerate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LibraryVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+}
+
+impl LibraryVersion {
+    /// Create a version from its components.
+    pub const fn new(major: u8, minor: u8, patch: u8) -> LibraryVersion {
+        LibraryVersion { major, minor, patch }
+    }
+
+    /// Parse a version string in the format returned by ``src_get_version``, e.g.
+    /// ``libsamplerate-0.1.9 (c) 2002-2008 Erik de Castro Lopo``. Anything after the numeric
+    /// version, such as a distribution suffix in ``0.2.2-7ubuntu1``, is ignored, and a
+    /// missing patch number is taken to be zero.
+    ///
+    /// Returns ``None`` if the string does not contain a version.
+    pub fn parse(version: &str) -> Option<LibraryVersion> {
+        let version = version.trim_start();
+        let version = version.strip_prefix("libsamplerate-").unwrap_or(version);
+        let mut components = [0u8; 3];
+        let mut rest = version;
+        for (index, component) in components.iter_mut().enumerate() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            if digits == 0 {
+                // Only the patch number may be missing.
+                if index < 2 { return None } else { break }
+            }
+            *component = rest[..digits].parse().ok()?;
+            rest = &rest[digits..];
+            match rest.strip_prefix('.') {
+                Some(after) if index < 2 => rest = after,
+                _ if index < 1 => return None,
+                _ => break,
+            }
+        }
+        Some(LibraryVersion::new(components[0], components[1], components[2]))
+    }
+
+    fn pack(self) -> u32 {
+        (self.major as u32) << 16 | (self.minor as u32) << 8 | self.patch as u32
+    }
+
+    fn unpack(packed: u32) -> LibraryVersion {
+        LibraryVersion::new((packed >> 16) as u8, (packed >> 8) as u8, packed as u8)
+    }
+}
+
+impl fmt::Display for LibraryVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Oldest libsamplerate that the bindings work with, which is the version of the header
+/// that they were generated from.
+pub const MINIMUM_LIBRARY_VERSION: LibraryVersion = LibraryVersion::new(0, 1, 9);
+
+/// Oldest libsamplerate with ``src_clone``, which
+/// [``Converter::try_clone``](struct.Converter.html#method.try_clone) needs. The vendored
+/// sources carry a backport of it, so this only applies to other libraries.
+pub const CLONE_LIBRARY_VERSION: LibraryVersion = LibraryVersion::new(0, 2, 0);
+
+/// The parsed version of the linked libsamplerate, packed and offset by one; zero if the
+/// version was not parsed yet, and ``u32::MAX`` if the version string could not be parsed.
+static LIBRARY_VERSION: AtomicU32 = AtomicU32::new(0);
+
+const UNPARSEABLE: u32 = u32::MAX;
+
+/// Parse the version reported by the library behind ``api``.
+pub(crate) fn parse_library_version(api: &Api) -> Option<LibraryVersion> {
+    let version = unsafe { CStr::from_ptr((api.src_get_version)()) };
+    version.to_str().ok().and_then(LibraryVersion::parse)
+}
+
+/// Retrieve the version of the library behind ``api``, which is either the linked library
+/// or the one loaded at runtime.
+pub(crate) fn version_in(api: &Api) -> Option<LibraryVersion> {
+    #[cfg(feature = "runtime-loading")]
+    if !ptr::eq(api, &backend::LINKED) {
+        return backend::loaded_version().flatten()
+    }
+    // Parsing is idempotent, so threads racing to cache the result is harmless, and only
+    // loads and stores are needed, which every target supports.
+    let mut packed = LIBRARY_VERSION.load(Ordering::Relaxed);
+    if packed == 0 {
+        packed = parse_library_version(api).map_or(UNPARSEABLE, |version| version.pack() + 1);
+        LIBRARY_VERSION.store(packed, Ordering::Relaxed);
+    }
+    if packed == UNPARSEABLE { None } else { Some(LibraryVersion::unpack(packed - 1)) }
+}
+
+/// Retrieve the version of the libsamplerate that new converters use (see
+/// [``backend``](fn.backend.html)), or ``None`` if it reports a version that cannot be
+/// parsed.
+pub fn library_version() -> Option<LibraryVersion> {
+    version_in(backend::current())
+}
+
+/// Check that the library behind ``api`` is at least ``required``, the version that added
+/// the functionality about to be used.
+///
+/// A library whose version cannot be parsed is assumed to be compatible, since it is most
+/// likely a modified build rather than an old one.
+pub(crate) fn require_in(api: &Api, required: LibraryVersion) -> Result<()> {
+    match version_in(api) {
+        Some(found) if found < required =>
+            Err(Error::new(ErrorKind::IncompatibleLibrary { found, required })),
+        _ => Ok(())
+    }
+}
+
+/// Check that the libsamplerate that new converters use is at least ``required``.
+pub(crate) fn require(required: LibraryVersion) -> Result<()> {
+    require_in(backend::current(), required)
+}
+
+/// Check that the libsamplerate in use is new enough for the bindings, e.g. at startup of
+/// an application linked with the ``system`` feature, to report an old library before
+/// trying to convert anything. The converters perform the same check when they are
+/// created.
+///
+/// Returns the version of the library if it is known, or an error of kind
+/// [``IncompatibleLibrary``](enum.ErrorKind.html#variant.IncompatibleLibrary) if it is too
+/// old.
+pub fn check_library_compatibility() -> Result<Option<LibraryVersion>> {
+    require(MINIMUM_LIBRARY_VERSION)?;
+    Ok(library_version())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for &(version, expected) in &[
+            ("libsamplerate-0.1.9 (c) 2002-2008 Erik de Castro Lopo", Some((0, 1, 9))),
+            ("libsamplerate-0.2.2 (c) 2002-2021 Erik de Castro Lopo", Some((0, 2, 2))),
+            ("libsamplerate-0.1.8", Some((0, 1, 8))),
+            // Distribution packages that patch the version.
+            ("libsamplerate-0.2.2-7ubuntu1 (c) 2002-2021 Erik de Castro Lopo", Some((0, 2, 2))),
+            ("libsamplerate-0.1.9+dfsg-1", Some((0, 1, 9))),
+            ("libsamplerate-0.2.1.r12.g1e5e111", Some((0, 2, 1))),
+            ("libsamplerate-0.2 (c) 2002-2020 Erik de Castro Lopo", Some((0, 2, 0))),
+            ("0.2.2", Some((0, 2, 2))),
+            ("libsamplerate-10.20.30", Some((10, 20, 30))),
+            ("libsamplerate", None),
+            ("libsamplerate-1", None),
+            ("libsamplerate-x.y.z", None),
+            ("libsamplerate-1.300.0", None),
+            ("", None),
+        ] {
+            assert_eq!(LibraryVersion::parse(version),
+                       expected.map(|(major, minor, patch)| {
+                           LibraryVersion::new(major, minor, patch)
+                       }),
+                       "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        assert!(LibraryVersion::new(0, 1, 9) < LibraryVersion::new(0, 2, 0));
+        assert!(LibraryVersion::new(0, 10, 0) > LibraryVersion::new(0, 9, 9));
+        assert!(LibraryVersion::new(1, 0, 0) > LibraryVersion::new(0, 255, 255));
+        let version = LibraryVersion::new(1, 2, 255);
+        assert_eq!(LibraryVersion::unpack(version.pack()), version);
+    }
+
+    #[test]
+    fn test_check() {
+        #[cfg(all(feature = "vendored-0_1", not(feature = "system")))]
+        assert_eq!(check_library_compatibility(), Ok(Some(LibraryVersion::new(0, 1, 9))));
+
+        let found = library_version().unwrap();
+        let required = LibraryVersion::new(found.major + 1, 0, 0);
+        let error = require(required).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::IncompatibleLibrary { found, required });
+        assert_eq!(std::format!("{}", error),
+                   std::format!("libsamplerate {} is too old; {} or newer is required",
+                                found, required));
+        assert_eq!(require(found), Ok(()));
+    }
+}
@@ -0,0 +1,61 @@
+//! SIMD128 versions of the conversions between ``i16`` and ``f32`` samples, used when
+//! building for WebAssembly with the ``simd128`` target feature. They give exactly the same
+//! results as the scalar [``Sample``](trait.Sample.html) implementation for ``i16``.
+
+use core::arch::wasm32::*;
+
+use Sample;
+
+/// Number of ``i16`` samples in a vector.
+const LANES: usize = 8;
+
+/// Convert ``input`` to ``f32``, like ``Sample::to_f32`` for every sample.
+pub(crate) fn i16_to_f32(input: &[i16], output: &mut [f32]) {
+    let scale = f32x4_splat(1.0 / 32768.0);
+    let mut input = input.chunks_exact(LANES);
+    let mut output = output.chunks_exact_mut(LANES);
+    for (i, o) in (&mut input).zip(&mut output) {
+        // The loads and stores have an alignment of 1.
+        unsafe {
+            let samples = v128_load(i.as_ptr() as *const v128);
+            let low = f32x4_convert_i32x4(i32x4_extend_low_i16x8(samples));
+            let high = f32x4_convert_i32x4(i32x4_extend_high_i16x8(samples));
+            v128_store(o.as_mut_ptr() as *mut v128, f32x4_mul(low, scale));
+            v128_store(o.as_mut_ptr().add(4) as *mut v128, f32x4_mul(high, scale));
+        }
+    }
+    for (o, &i) in output.into_remainder().iter_mut().zip(input.remainder()) {
+        *o = i.to_f32();
+    }
+}
+
+/// Round four scaled samples half away from zero, like ``f32_to_int``.
+fn round(scaled: v128) -> v128 {
+    // Adding 0.5 before truncating could round up in the addition; the fraction is exact.
+    let truncated = f32x4_trunc(scaled);
+    let half_or_more = f32x4_ge(f32x4_abs(f32x4_sub(scaled, truncated)), f32x4_splat(0.5));
+    let sign = v128_and(scaled, f32x4_splat(-0.0));
+    let step = v128_and(half_or_more, v128_or(sign, f32x4_splat(1.0)));
+    f32x4_add(truncated, step)
+}
+
+/// Convert ``input`` to ``i16``, like ``Sample::from_f32`` for every sample.
+pub(crate) fn f32_to_i16(input: &[f32], output: &mut [i16]) {
+    let scale = f32x4_splat(32768.0);
+    let mut input = input.chunks_exact(LANES);
+    let mut output = output.chunks_exact_mut(LANES);
+    for (i, o) in (&mut input).zip(&mut output) {
+        unsafe {
+            let low = v128_load(i.as_ptr() as *const v128);
+            let high = v128_load(i.as_ptr().add(4) as *const v128);
+            // Truncation maps NaN to zero and saturates at the range of ``i32``, and
+            // narrowing saturates at the range of ``i16``.
+            let low = i32x4_trunc_sat_f32x4(round(f32x4_mul(low, scale)));
+            let high = i32x4_trunc_sat_f32x4(round(f32x4_mul(high, scale)));
+            v128_store(o.as_mut_ptr() as *mut v128, i16x8_narrow_i32x4(low, high));
+        }
+    }
+    for (o, &i) in output.into_remainder().iter_mut().zip(input.remainder()) {
+        *o = i16::from_f32(i);
+    }
+}
@@ -0,0 +1,114 @@
+// Loads stand-ins for libsamplerate and a shared build of the vendored sources at runtime.
+// The shared objects are compiled with the C compiler in `CC` (or `cc`), so this only runs
+// on Unix-like hosts.
+
+#![cfg(all(feature = "runtime-loading", unix))]
+
+extern crate samplerate_core;
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use samplerate_core::*;
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Compile ``sources`` into the shared object ``name``, and retrieve its path.
+fn compile(name: &str, sources: &[PathBuf], args: &[&str]) -> PathBuf {
+    let output = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let status = Command::new(&compiler)
+        .args(["-shared", "-fPIC", "-O2", "-o"])
+        .arg(&output)
+        .args(args)
+        .args(sources)
+        .arg("-lm")
+        .status()
+        .unwrap_or_else(|error| panic!("cannot run {}: {}", compiler, error));
+    assert!(status.success(), "cannot compile {}", name);
+    output
+}
+
+fn stub(name: &str, args: &[&str]) -> PathBuf {
+    compile(name, &[manifest_dir().join("tests/stub/samplerate_stub.c")], args)
+}
+
+/// Build the vendored sources the way samplerate-sys does, but with a different version.
+fn vendored(name: &str, version: &str) -> PathBuf {
+    let sys = manifest_dir().join("../samplerate-sys");
+    let sources: Vec<_> = ["samplerate.c", "src_sinc.c", "src_linear.c", "src_zoh.c"].iter()
+        .map(|file| sys.join("vendor").join(file))
+        .collect();
+    let include = format!("-I{}", sys.join("src").display());
+    let version = format!("-DVERSION=\"{}\"", version);
+    compile(name, &sources, &[&include, "-DHAVE_STDINT_H=1", "-DHAVE_LRINT=1",
+                              "-DHAVE_LRINTF=1", "-DCPU_CLIPS_NEGATIVE=0",
+                              "-DCPU_CLIPS_POSITIVE=0", "-DPACKAGE=\"libsamplerate\"",
+                              &version])
+}
+
+fn convert_with(converter: &mut Converter, input: &[f32]) -> Vec<f32> {
+    let mut output = vec![0.0; input.len() * 2 + 2];
+    let (_, generated) = converter.convert(1.5, Some(input), &mut output).unwrap();
+    output.truncate(generated);
+    output
+}
+
+// Only one library can ever be loaded into the process, so the failures have to be checked
+// before the library is loaded successfully, all in one test.
+#[test]
+fn test_load_library() {
+    let input: Vec<f32> = (0..2000).map(|i| (i as f32 * 0.01).sin()).collect();
+    assert_eq!(backend(), Backend::Linked);
+    let linked_version = library_version();
+    let mut linked = Converter::new(Interpolator::SincFastest, 1).unwrap();
+
+    let missing = Path::new(env!("CARGO_TARGET_TMPDIR")).join("libsamplerate-missing.so");
+    assert_eq!(unsafe { load_library(Some(&missing)) }.unwrap_err().kind(),
+               ErrorKind::LibraryNotFound);
+
+    let incomplete = stub("libsamplerate-incomplete.so",
+                          &["-DSTUB_VERSION=\"0.2.2\"", "-DSTUB_OMIT_PROCESS"]);
+    assert_eq!(unsafe { load_library(Some(&incomplete)) }.unwrap_err().kind(),
+               ErrorKind::SymbolNotFound { symbol: "src_process" });
+
+    let old = stub("libsamplerate-old.so", &["-DSTUB_VERSION=\"0.1.2-3debian\""]);
+    assert_eq!(unsafe { load_library(Some(&old)) }.unwrap_err().kind(),
+               ErrorKind::IncompatibleLibrary { found: LibraryVersion::new(0, 1, 2),
+                                                required: MINIMUM_LIBRARY_VERSION });
+
+    // Nothing was loaded by the failed attempts.
+    assert_eq!(backend(), Backend::Linked);
+    assert_eq!(library_version(), linked_version);
+
+    let shared = vendored("libsamplerate-shared.so", "0.2.2-test");
+    unsafe { load_library(Some(&shared)) }.unwrap();
+    assert_eq!(backend(), Backend::Loaded);
+    assert_eq!(library_version(), Some(LibraryVersion::new(0, 2, 2)));
+    assert_eq!(check_library_compatibility(), Ok(Some(LibraryVersion::new(0, 2, 2))));
+
+    // Converters created before keep using the linked library, and both libraries produce
+    // the same output from the same sources.
+    let mut loaded = Converter::new(Interpolator::SincFastest, 1).unwrap();
+    assert_eq!(convert_with(&mut loaded, &input), convert_with(&mut linked, &input));
+    let mut output = [0.0; 4000];
+    let (used, generated) = convert(Interpolator::Linear, 1, 2.0, &input, &mut output).unwrap();
+    assert_eq!((used, generated), (2000, 4000));
+
+    // The vendored sources as released lack src_clone, which the reported version has;
+    // the build of samplerate-sys backports it.
+    assert_eq!(loaded.try_clone().err().unwrap().kind(),
+               ErrorKind::SymbolNotFound { symbol: "src_clone" });
+    assert!(linked.try_clone().is_ok());
+
+    // Loading the same library again does nothing, and loading another one is refused.
+    unsafe { load_library(Some(&shared)) }.unwrap();
+    assert_eq!(unsafe { load_library(Some(&old)) }.unwrap_err().kind(),
+               ErrorKind::LibraryAlreadyLoaded);
+    assert_eq!(unsafe { load_library(None) }.unwrap_err().kind(),
+               ErrorKind::LibraryAlreadyLoaded);
+    assert_eq!(library_version(), Some(LibraryVersion::new(0, 2, 2)));
+}
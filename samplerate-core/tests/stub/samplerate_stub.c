@@ -0,0 +1,40 @@
+/* Stand-in for libsamplerate for tests/runtime_loading.rs, which reports STUB_VERSION and
+ * fails every conversion. Define STUB_OMIT_PROCESS to leave out src_process. */
+
+#include <stddef.h>
+
+typedef struct SRC_STATE_tag SRC_STATE;
+
+const char *src_get_version(void) { return "libsamplerate-" STUB_VERSION " (stub)"; }
+const char *src_strerror(int error) { (void)error; return "stub"; }
+const char *src_get_name(int converter_type) { (void)converter_type; return "stub"; }
+const char *src_get_description(int converter_type) { (void)converter_type; return "stub"; }
+
+SRC_STATE *src_new(int converter_type, int channels, int *error) {
+	(void)converter_type; (void)channels;
+	*error = 1;
+	return NULL;
+}
+SRC_STATE *src_delete(SRC_STATE *state) { (void)state; return NULL; }
+#ifndef STUB_OMIT_PROCESS
+int src_process(SRC_STATE *state, void *data) { (void)state; (void)data; return 1; }
+#endif
+int src_simple(void *data, int converter_type, int channels) {
+	(void)data; (void)converter_type; (void)channels;
+	return 1;
+}
+int src_set_ratio(SRC_STATE *state, double ratio) { (void)state; (void)ratio; return 1; }
+int src_get_channels(SRC_STATE *state) { (void)state; return 1; }
+int src_reset(SRC_STATE *state) { (void)state; return 1; }
+void src_short_to_float_array(const short *in, float *out, int len) {
+	(void)in; (void)out; (void)len;
+}
+void src_float_to_short_array(const float *in, short *out, int len) {
+	(void)in; (void)out; (void)len;
+}
+void src_int_to_float_array(const int *in, float *out, int len) {
+	(void)in; (void)out; (void)len;
+}
+void src_float_to_int_array(const float *in, int *out, int len) {
+	(void)in; (void)out; (void)len;
+}
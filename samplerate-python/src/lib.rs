@@ -0,0 +1,294 @@
+//! Python bindings to [``samplerate``](https://docs.rs/samplerate/), exposed as the
+//! ``samplerate_rs`` module, for converting NumPy arrays of ``float32`` samples.
+//!
+//! Arrays have the shape ``(frames,)`` for mono audio or ``(frames, channels)`` for
+//! interleaved audio, and the output has the same number of dimensions as the input. A
+//! C-contiguous input is converted in place without copying it, and the output is allocated
+//! once and handed to NumPy without copying it either. The GIL is released while converting.
+
+use std::borrow::Cow;
+use std::sync::{Mutex, PoisonError};
+
+use numpy::ndarray::{ArrayD, IxDyn};
+use numpy::{IntoPyArray, PyArrayDyn, PyReadonlyArrayDyn, PyUntypedArrayMethods};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+
+use samplerate::{Converter, Interpolator};
+
+create_exception!(samplerate_rs, SamplerateError, PyException,
+                  "Error reported by libsamplerate, e.g. for a ratio out of range.");
+
+fn to_py_err(error: samplerate::Error) -> PyErr {
+    SamplerateError::new_err(error.to_string())
+}
+
+fn parse_quality(quality: &str) -> PyResult<Interpolator> {
+    quality.parse().map_err(|error: samplerate::Error| PyValueError::new_err(error.to_string()))
+}
+
+/// Retrieve the interleaved samples of ``array`` and its channel count.
+fn samples<'a>(array: &'a PyReadonlyArrayDyn<'_, f32>) -> PyResult<(Cow<'a, [f32]>, usize)> {
+    let channels = match *array.shape() {
+        [_] => 1,
+        [_, channels] if channels > 0 => channels,
+        ref shape => return Err(PyValueError::new_err(format!(
+            "expected an array of shape (frames,) or (frames, channels), not {:?}", shape)))
+    };
+    let samples = match array.as_slice() {
+        Ok(samples) => Cow::Borrowed(samples),
+        // E.g. every other frame of a larger array, or a transposed one.
+        Err(_) => Cow::Owned(array.as_array().iter().copied().collect()),
+    };
+    Ok((samples, channels))
+}
+
+/// Wrap ``samples`` in an array with ``ndim`` dimensions, without copying them.
+fn to_array(py: Python<'_>, samples: Vec<f32>, channels: usize, ndim: usize)
+        -> Bound<'_, PyArrayDyn<f32>> {
+    let shape = if ndim == 1 {
+        vec![samples.len()]
+    } else {
+        vec![samples.len() / channels, channels]
+    };
+    ArrayD::from_shape_vec(IxDyn(&shape), samples).unwrap().into_pyarray(py)
+}
+
+/// Convert all of ``input`` into a newly allocated buffer, flushing the state at the end.
+fn convert_all(interpolator: Interpolator, channels: usize, ratio: f64, input: &[f32])
+        -> samplerate::Result<Vec<f32>> {
+    // libsamplerate generates at most one frame more than the exact ratio, rounded up.
+    let frames = (input.len() / channels) as f64 * ratio;
+    let mut output = vec![0.0; (frames as usize + 2) * channels];
+    let (_, generated) = samplerate::convert(interpolator, channels, ratio, input, &mut output)?;
+    output.truncate(generated);
+    Ok(output)
+}
+
+/// Convert a complete ``float32`` array of shape ``(frames,)`` or ``(frames, channels)`` at
+/// ``ratio`` (output rate divided by input rate). ``quality`` is one of ``"best"``,
+/// ``"medium"``, ``"fastest"``, ``"zoh"`` or ``"linear"``.
+#[pyfunction]
+#[pyo3(signature = (array, ratio, quality = "best"))]
+fn convert<'py>(py: Python<'py>, array: PyReadonlyArrayDyn<'py, f32>, ratio: f64,
+                quality: &str) -> PyResult<Bound<'py, PyArrayDyn<f32>>> {
+    let interpolator = parse_quality(quality)?;
+    let (input, channels) = samples(&array)?;
+    let output = py.allow_threads(|| convert_all(interpolator, channels, ratio, &input))
+        .map_err(to_py_err)?;
+    Ok(to_array(py, output, channels, array.ndim()))
+}
+
+/// Streaming converter, like ``Converter`` in Rust, for audio that arrives in chunks.
+#[pyclass(name = "Converter", module = "samplerate_rs")]
+struct PyConverter {
+    // Python objects may be shared between threads, which ``Converter`` may not be; only
+    // ``&mut self`` methods use it, so the lock is never contended.
+    converter: Mutex<Converter>,
+    channels: usize,
+    ratio: f64,
+    /// Number of dimensions of the last chunk, which the output of ``flush`` also has.
+    ndim: usize,
+}
+
+impl PyConverter {
+    fn converter(&mut self) -> &mut Converter {
+        self.converter.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Convert all of ``input``, or flush the state if it is ``None``.
+    fn run(&mut self, input: Option<&[f32]>) -> samplerate::Result<Vec<f32>> {
+        let channels = self.channels;
+        let frames = input.map_or(0, |input| input.len() / channels);
+        let mut output = vec![0.0; ((frames as f64 * self.ratio).ceil() as usize + 64) * channels];
+
+        let (mut used, mut generated) = (0, 0);
+        loop {
+            let rest = input.map(|input| &input[used..]);
+            let ratio = self.ratio;
+            let (u, g) = self.converter().convert(ratio, rest, &mut output[generated..])?;
+            used += u;
+            generated += g;
+            if generated == output.len() {
+                // Out of space; there may be more to come.
+                let len = output.len();
+                output.resize(len * 2, 0.0);
+            } else if g == 0 && (input.is_none() || u == 0) ||
+                    input.is_some_and(|input| used == input.len()) {
+                break
+            }
+        }
+        output.truncate(generated);
+        Ok(output)
+    }
+}
+
+#[pymethods]
+impl PyConverter {
+    /// Create a converter for ``channels`` interleaved channels; ``quality`` is as for
+    /// ``convert``.
+    #[new]
+    #[pyo3(signature = (channels, quality = "best"))]
+    fn new(channels: usize, quality: &str) -> PyResult<PyConverter> {
+        let converter = Converter::new(parse_quality(quality)?, channels).map_err(to_py_err)?;
+        Ok(PyConverter {
+            converter: Mutex::new(converter),
+            channels,
+            ratio: 1.0,
+            ndim: if channels == 1 { 1 } else { 2 },
+        })
+    }
+
+    /// Number of channels.
+    #[getter]
+    fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Convert a chunk, smoothly interpolating towards ``ratio``, and return the output it
+    /// produced. Some of it comes out only with later chunks, or with ``flush``.
+    fn process<'py>(&mut self, py: Python<'py>, chunk: PyReadonlyArrayDyn<'py, f32>,
+                    ratio: f64) -> PyResult<Bound<'py, PyArrayDyn<f32>>> {
+        let (input, channels) = samples(&chunk)?;
+        if channels != self.channels {
+            return Err(PyValueError::new_err(format!(
+                "expected {} channels, not {}", self.channels, channels)))
+        }
+        self.ratio = ratio;
+        self.ndim = chunk.ndim();
+        let output = py.allow_threads(|| self.run(Some(&input))).map_err(to_py_err)?;
+        Ok(to_array(py, output, channels, self.ndim))
+    }
+
+    /// Flush the internal state at the end of the stream, returning the rest of the output.
+    fn flush<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyArrayDyn<f32>>> {
+        let output = py.allow_threads(|| self.run(None)).map_err(to_py_err)?;
+        Ok(to_array(py, output, self.channels, self.ndim))
+    }
+
+    /// Reset the internal state, to start a new stream.
+    fn reset(&mut self) -> PyResult<()> {
+        self.converter().reset().map_err(to_py_err)
+    }
+}
+
+#[pymodule]
+fn samplerate_rs(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(convert, module)?)?;
+    module.add_class::<PyConverter>()?;
+    module.add("SamplerateError", module.py().get_type::<SamplerateError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use numpy::{PyArray1, PyArray2, PyArrayMethods};
+    use pyo3::exceptions::PyTypeError;
+    use pyo3::ffi::c_str;
+    use pyo3::types::PyDict;
+
+    use super::*;
+
+    fn with_module<F: FnOnce(Python<'_>, &Bound<'_, PyModule>)>(body: F) {
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "samplerate_rs").unwrap();
+            samplerate_rs(&module).unwrap();
+            body(py, &module)
+        })
+    }
+
+    fn make_input(frames: usize, channels: usize) -> Vec<f32> {
+        (0..frames * channels).map(|n| ((n / channels) as f32 * 0.01 * (n % channels + 1) as f32)
+            .sin() * 0.5).collect()
+    }
+
+    fn output_of(result: Bound<'_, PyAny>) -> (Vec<usize>, Vec<f32>) {
+        let array = result.downcast_into::<PyArrayDyn<f32>>().unwrap();
+        let shape = array.shape().to_vec();
+        (shape, array.readonly().as_slice().unwrap().to_vec())
+    }
+
+    #[test]
+    fn test_convert_shapes_and_parity() {
+        with_module(|py, module| {
+            let input = make_input(1000, 2);
+            let expected = convert_all(Interpolator::SincFastest, 2, 1.5, &input).unwrap();
+            let rows: Vec<Vec<f32>> = input.chunks(2).map(|frame| frame.to_vec()).collect();
+            let array = PyArray2::from_vec2(py, &rows).unwrap();
+            let result = module.getattr("convert").unwrap()
+                .call1((array, 1.5, "fastest")).unwrap();
+            assert_eq!(output_of(result), (vec![expected.len() / 2, 2], expected));
+
+            let input = make_input(1000, 1);
+            let expected = convert_all(Interpolator::SincBestQuality, 1, 0.5, &input).unwrap();
+            let array = PyArray1::from_vec(py, input);
+            let result = module.getattr("convert").unwrap().call1((array, 0.5)).unwrap();
+            assert_eq!(output_of(result), (vec![expected.len()], expected));
+        })
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        with_module(|py, module| {
+            let convert = module.getattr("convert").unwrap();
+            let doubles = PyArray1::from_vec(py, vec![0.0f64; 100]);
+            assert!(convert.call1((doubles, 2.0)).unwrap_err().is_instance_of::<PyTypeError>(py));
+
+            let cube = PyArray1::from_vec(py, vec![0.0f32; 8]).reshape([2, 2, 2]).unwrap();
+            assert!(convert.call1((cube, 2.0)).unwrap_err().is_instance_of::<PyValueError>(py));
+
+            let array = PyArray1::from_vec(py, vec![0.0f32; 100]);
+            assert!(convert.call1((&array, 2.0, "cubic")).unwrap_err()
+                .is_instance_of::<PyValueError>(py));
+            assert!(convert.call1((&array, 1000.0)).unwrap_err()
+                .is_instance_of::<SamplerateError>(py));
+        })
+    }
+
+    #[test]
+    fn test_converter_matches_convert() {
+        with_module(|py, module| {
+            let input = make_input(10000, 2);
+            let expected = convert_all(Interpolator::SincFastest, 2, 2.0, &input).unwrap();
+            let converter = module.getattr("Converter").unwrap().call1((2, "fastest")).unwrap();
+            assert_eq!(converter.getattr("channels").unwrap().extract::<usize>().unwrap(), 2);
+
+            let mut output = Vec::new();
+            for chunk in input.chunks(2 * 777) {
+                let rows: Vec<Vec<f32>> = chunk.chunks(2).map(|frame| frame.to_vec()).collect();
+                let chunk = PyArray2::from_vec2(py, &rows).unwrap();
+                let (shape, samples) = output_of(converter.call_method1("process", (chunk, 2.0))
+                    .unwrap());
+                assert_eq!(shape, [samples.len() / 2, 2]);
+                output.extend(samples);
+            }
+            let (shape, samples) = output_of(converter.call_method0("flush").unwrap());
+            assert_eq!(shape, [samples.len() / 2, 2]);
+            output.extend(samples);
+            assert_eq!(output, expected);
+
+            // A mono chunk does not fit a stereo converter.
+            let mono = PyArray1::from_vec(py, vec![0.0f32; 10]);
+            assert!(converter.call_method1("process", (mono, 2.0)).unwrap_err()
+                .is_instance_of::<PyValueError>(py));
+            converter.call_method0("reset").unwrap();
+        })
+    }
+
+    #[test]
+    fn test_non_contiguous_input() {
+        with_module(|py, module| {
+            let globals = PyDict::new(py);
+            globals.set_item("convert", module.getattr("convert").unwrap()).unwrap();
+            py.run(c_str!("import numpy as np\n\
+                x = np.sin(np.arange(4000, dtype=np.float32) * 0.01).reshape(-1, 2)\n\
+                strided = convert(x[::2], 2.0, 'linear')\n\
+                copied = convert(np.ascontiguousarray(x[::2]), 2.0, 'linear')\n\
+                assert strided.shape == copied.shape == (2000, 2), strided.shape\n\
+                assert (strided == copied).all()\n\
+                assert convert(x.T.copy().T, 2.0, 'linear').shape == (4000, 2)\n"),
+                   Some(&globals), None).unwrap();
+        })
+    }
+}
@@ -1,32 +1,440 @@
 extern crate cc;
 
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[path = "build/clip.rs"]
+mod clip;
+#[path = "build/lrint.rs"]
+mod lrint;
+#[path = "build/lto.rs"]
+mod lto;
+
+/// Vendored libsamplerate source trees, as (cargo feature, source directory, version).
+/// Exactly one of these features must be enabled unless the `system` feature is, which takes
+/// precedence over them.
+const VENDORED: &[(&str, &str, &str)] = &[
+    ("vendored-0_1", "vendor", "0.1.9"),
+];
+
+fn feature_enabled(feature: &str) -> bool {
+    let name = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+    env::var_os(name).is_some()
+}
+
+/// Environment variable that disables the layout probes of tests/abi.rs when set to ``0``, or
+/// makes a failure to compile them an error when set to anything else.
+const ABI_CHECK_VAR: &str = "SAMPLERATE_SYS_ABI_CHECK";
+
+/// Default installation prefix of wasi-sdk.
+const WASI_SDK_DEFAULT_PATH: &str = "/opt/wasi-sdk";
+
+/// Point ``build`` at wasi-sdk when building for a WASI target, which needs both its clang
+/// and its sysroot with wasi-libc. ``WASI_SYSROOT`` selects the sysroot, and ``WASI_SDK_PATH``
+/// the installation prefix it is otherwise found in; the compiler can still be overridden
+/// with ``CC`` as usual.
+fn configure_wasi(build: &mut cc::Build) {
+    println!("cargo:rerun-if-env-changed=WASI_SYSROOT");
+    println!("cargo:rerun-if-env-changed=WASI_SDK_PATH");
+    let target = env::var("TARGET").unwrap();
+    let sdk = env::var_os("WASI_SDK_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(WASI_SDK_DEFAULT_PATH));
+    let sysroot = env::var_os("WASI_SYSROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| sdk.join("share").join("wasi-sysroot"));
+    if !sysroot.join("include").is_dir() {
+        panic!("samplerate-sys: building for {} needs the wasi-sdk sysroot, which was not \
+                found at {}; install wasi-sdk from https://github.com/WebAssembly/wasi-sdk \
+                and set WASI_SDK_PATH to its location (default: {}), or set WASI_SYSROOT \
+                to the sysroot",
+               target, sysroot.display(), WASI_SDK_DEFAULT_PATH);
+    }
+    let clang = sdk.join("bin").join("clang");
+    let cc_vars = [format!("CC_{}", target), format!("CC_{}", target.replace('-', "_")),
+                   "TARGET_CC".to_owned(), "CC".to_owned()];
+    let cc_overridden = cc_vars.iter().any(|name| env::var_os(name).is_some());
+    if !cc_overridden && clang.is_file() {
+        build.compiler(clang);
+    }
+    build.flag(format!("--sysroot={}", sysroot.display()));
+}
+
+/// Create a build with the configuration needed for the target.
+fn new_build() -> cc::Build {
+    let mut build = cc::Build::new();
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("wasi") {
+        configure_wasi(&mut build);
+    }
+    build
+}
+
+/// Run ``program`` with ``args``, and retrieve what it printed if it succeeded.
+fn version_output(program: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if output.status.success() { String::from_utf8(output.stdout).ok() } else { None }
+}
+
+/// Emit LLVM bitcode instead of machine code from ``build`` if the ``lto`` feature is enabled
+/// and the rustflags ask for cross-language LTO (see build/lto.rs). Otherwise, the library is
+/// built as usual, with a warning if the rustflags ask for it but the C compiler cannot
+/// produce bitcode the linker plugin reads.
+fn configure_lto(build: &mut cc::Build) {
+    if !feature_enabled("lto") {
+        return
+    }
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    let cc_version = version_output(build.get_compiler().path(), &["--version"]);
+    let rustc = env::var_os("RUSTC").map(PathBuf::from).unwrap_or_else(|| "rustc".into());
+    let rustc_version = version_output(&rustc, &["-vV"]);
+    match lto::decide(&rustflags, cc_version.as_deref(), rustc_version.as_deref()) {
+        Ok(false) => {}
+        Ok(true) => {
+            build.flag("-flto=thin");
+            // Lets dependents that link their own C code check for it.
+            println!("cargo:lto=thin");
+        }
+        Err(reason) => {
+            println!("cargo:warning=samplerate-sys: not using cross-language LTO: {}", reason);
+        }
+    }
+}
+
+/// Let the C compiler vectorize with SIMD128 if the ``wasm-simd`` feature is enabled and the
+/// target is WebAssembly with the ``simd128`` target feature, which the Rust code then uses
+/// too. Other targets are unaffected by the feature.
+fn configure_wasm_simd(build: &mut cc::Build) {
+    let wasm = env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32");
+    if !feature_enabled("wasm-simd") || !wasm {
+        return
+    }
+    let target_features = env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default();
+    if target_features.split(',').any(|feature| feature == "simd128") {
+        build.flag("-msimd128");
+    } else {
+        println!("cargo:warning=samplerate-sys: not using SIMD128: the target lacks the \
+                  simd128 feature; build with RUSTFLAGS=\"-C target-feature=+simd128\"");
+    }
+}
+
+/// Functions of ``src_sinc.c`` that the NEON build takes from ``src/sinc_neon.c`` instead.
+const NEON_FUNCTIONS: &[&str] = &["calc_output_single", "calc_output_stereo"];
+
+/// Check whether the sinc interpolators are built with NEON, which they are if the ``neon``
+/// feature is enabled and the target is aarch64, where NEON is part of the baseline. 32-bit
+/// ARM targets are left scalar, since not every ARMv7 core has NEON and the library cannot
+/// choose its code at runtime; other targets are unaffected by the feature.
+fn neon_enabled() -> bool {
+    feature_enabled("neon") && env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("aarch64")
+}
+
+/// Replace ``NEON_FUNCTIONS`` in the text of ``src_sinc.c`` with an include of
+/// ``src/sinc_neon.c``.
+fn patch_sinc_neon(text: &mut String) {
+    println!("cargo:rerun-if-changed=src/sinc_neon.c");
+    for (index, name) in NEON_FUNCTIONS.iter().enumerate() {
+        let signature = format!("\n{} (SINC_FILTER *filter,", name);
+        let end_marker = format!("}} /* {} */\n", name);
+        let (start, end) = match (text.find(&signature), text.find(&end_marker)) {
+            // The definition starts on the line before its name, with its return type.
+            (Some(name_at), Some(end_at)) => (text[..name_at].rfind('\n').unwrap() + 1,
+                                               end_at + end_marker.len()),
+            _ => panic!("cannot patch src_sinc.c: no definition of {} found", name),
+        };
+        let replacement = if index == 0 { "#include \"sinc_neon.c\"\n" } else { "" };
+        text.replace_range(start..end, replacement);
+    }
+}
+
+/// Check whether the C compiler for the target can compile ``lrint::PROBE_SOURCE``.
+fn probe_lrint() -> bool {
+    let out_dir = Path::new(&env::var("OUT_DIR").unwrap()).join("probe");
+    fs::create_dir_all(&out_dir).unwrap();
+    let source = out_dir.join("lrint.c");
+    fs::write(&source, lrint::PROBE_SOURCE).unwrap();
+    let compiler = new_build().get_compiler();
+    let mut command = compiler.to_command();
+    command.args(lrint::probe_args(compiler.is_like_msvc(), &source, &out_dir.join("lrint.o")));
+    command.output().is_ok_and(|output| output.status.success())
+}
+
+/// Decide whether the vendored sources use lrint and lrintf, which round to nearest; the
+/// replacement libsamplerate falls back to rounds differently, which biases the output.
+fn detect_lrint() -> lrint::Lrint {
+    println!("cargo:rerun-if-env-changed={}", lrint::OVERRIDE_VAR);
+    let override_value = env::var(lrint::OVERRIDE_VAR).ok();
+    let lrint = lrint::decide(override_value.as_deref(), probe_lrint)
+        .unwrap_or_else(|reason| panic!("samplerate-sys: {}", reason));
+    if !lrint.available {
+        println!("cargo:warning=samplerate-sys: the target has no lrint or lrintf; \
+                  libsamplerate {}", lrint.note());
+    }
+    lrint
+}
+
+/// Decide whether lrint saturates for the target (see build/clip.rs).
+fn detect_clipping(lrint: lrint::Lrint) -> clip::Clipping {
+    println!("cargo:rerun-if-env-changed={}", clip::POSITIVE_VAR);
+    println!("cargo:rerun-if-env-changed={}", clip::NEGATIVE_VAR);
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let positive = env::var(clip::POSITIVE_VAR).ok();
+    let negative = env::var(clip::NEGATIVE_VAR).ok();
+    clip::decide(&target_arch, lrint.available, positive.as_deref(), negative.as_deref())
+        .unwrap_or_else(|reason| panic!("samplerate-sys: {}", reason))
+}
+
+/// Compiler configuration shared by every build of the vendored sources.
+fn configure(version: &str, lrint: lrint::Lrint, clipping: clip::Clipping) -> cc::Build {
+    let flag = |value: bool| if value { "1" } else { "0" };
+    let mut build = new_build();
+    configure_lto(&mut build);
+    configure_wasm_simd(&mut build);
+    build
+        // First, do what autoconf would do, but only for feature flags that are
+        // actually used somewhere.
+        .include("src")
+        // We can safely assume we have C99.
+        .define("HAVE_STDINT_H", "1")
+        .define("HAVE_LRINT", flag(lrint.available))
+        .define("HAVE_LRINTF", flag(lrint.available))
+        .define("CPU_CLIPS_NEGATIVE", flag(clipping.negative))
+        .define("CPU_CLIPS_POSITIVE", flag(clipping.positive))
+        // Package name and version.
+        .define("PACKAGE", "\"libsamplerate\"")
+        .define("VERSION", &format!("\"{}\"", version)[..])
+        .flag_if_supported("-Wno-implicit-fallthrough");
+    build
+}
+
+/// Files of the vendored sources that are patched, and thus compiled from copies in
+/// ``OUT_DIR``; the coefficient tables and the other headers are used from the source tree.
+const PATCHED_FILES: &[&str] = &["samplerate.h", "common.h", "samplerate.c", "src_sinc.c",
+                                 "src_linear.c", "src_zoh.c"];
+
+/// Backport of ``src_clone`` from libsamplerate 0.2.0 to the vendored 0.1.9, as (file,
+/// anchor, text inserted after the anchor). It adds a copy hook to each converter and
+/// ``src_clone`` itself, and does not change the output. The vendored files stay as released
+/// upstream; longer insertions are kept in ``src/backport``.
+const CLONE_BACKPORT: &[(&str, &str, &str)] = &[
+    ("common.h", "\tvoid\t(*reset) (struct SRC_PRIVATE_tag *psrc) ;\n",
+     "\n\t/* State clone. */\n\
+      \tint\t\t(*copy) (struct SRC_PRIVATE_tag *from, struct SRC_PRIVATE_tag *to) ;\n"),
+    ("samplerate.h", "SRC_STATE* src_new (int converter_type, int channels, int *error) ;\n",
+     "\n/*\n\
+      ** Clone a handle : return an anonymous pointer to a new converter\n\
+      ** containing the same internal state as orig. Error returned in *error.\n\
+      */\n\
+      SRC_STATE* src_clone (SRC_STATE* orig, int *error) ;\n"),
+    ("samplerate.c", "} /* src_new */\n",
+     concat!("\n", include_str!("src/backport/src_clone.c"))),
+    ("src_linear.c", "static void linear_reset (SRC_PRIVATE *psrc) ;\n",
+     "static int linear_copy (SRC_PRIVATE *from, SRC_PRIVATE *to) ;\n"),
+    ("src_linear.c", "\tpsrc->reset = linear_reset ;\n", "\tpsrc->copy = linear_copy ;\n"),
+    ("src_linear.c", "} /* linear_reset */\n",
+     concat!("\n", include_str!("src/backport/linear_copy.c"))),
+    ("src_sinc.c", "static void sinc_reset (SRC_PRIVATE *psrc) ;\n",
+     "static int sinc_copy (SRC_PRIVATE *from, SRC_PRIVATE *to) ;\n"),
+    ("src_sinc.c", "\tpsrc->reset = sinc_reset ;\n", "\tpsrc->copy = sinc_copy ;\n"),
+    ("src_sinc.c", "} /* sinc_reset */\n",
+     concat!("\n", include_str!("src/backport/sinc_copy.c"))),
+    ("src_zoh.c", "static void zoh_reset (SRC_PRIVATE *psrc) ;\n",
+     "static int zoh_copy (SRC_PRIVATE *from, SRC_PRIVATE *to) ;\n"),
+    ("src_zoh.c", "\tpsrc->reset = zoh_reset ;\n", "\tpsrc->copy = zoh_copy ;\n"),
+    ("src_zoh.c", "} /* zoh_reset */\n",
+     concat!("\n", include_str!("src/backport/zoh_copy.c"))),
+];
+
+/// Read ``file`` of the vendored sources in ``dir``, with ``CLONE_BACKPORT`` applied.
+fn read_patched(dir: &str, file: &str) -> String {
+    let path = Path::new(dir).join(file);
+    println!("cargo:rerun-if-changed={}", path.display());
+    let mut text = fs::read_to_string(&path).unwrap();
+    for &(_, anchor, insertion) in CLONE_BACKPORT.iter().filter(|&&(name, _, _)| name == file) {
+        let at = text.find(anchor)
+            .unwrap_or_else(|| panic!("cannot patch {}: {:?} not found", file, anchor));
+        text.insert_str(at + anchor.len(), insertion);
+    }
+    text
+}
+
+fn build_vendored(dir: &str, version: &str, lrint: lrint::Lrint, clipping: clip::Clipping) {
+    // Second, actually build the library.
+    println!("cargo:rerun-if-changed={}", dir);
+    let out_dir = Path::new(&env::var("OUT_DIR").unwrap()).join("vendored");
+    fs::create_dir_all(&out_dir).unwrap();
+    for file in PATCHED_FILES {
+        let mut text = read_patched(dir, file);
+        if *file == "src_sinc.c" && neon_enabled() {
+            patch_sinc_neon(&mut text);
+        }
+        fs::write(out_dir.join(file), text).unwrap();
+    }
+
+    configure(version, lrint, clipping)
+        // The patched sources come first; the coefficient tables are used unmodified.
+        .include(&out_dir)
+        .include(dir)
+        .file(out_dir.join("src_linear.c"))
+        .file(out_dir.join("src_sinc.c"))
+        .file(out_dir.join("src_zoh.c"))
+        .file(out_dir.join("samplerate.c"))
+        .compile("samplerate");
+}
+
+/// Functions with external linkage in the vendored sources, which are renamed in the
+/// double-precision copy so that it can be linked alongside the normal build.
+const EXTERNAL_SYMBOLS: &[&str] = &[
+    "src_new", "src_clone", "src_callback_new", "src_delete", "src_process",
+    "src_callback_read", "src_set_ratio", "src_get_channels", "src_reset", "src_get_name",
+    "src_get_description", "src_get_version", "src_is_valid_ratio", "src_error",
+    "src_strerror", "src_simple", "src_short_to_float_array", "src_float_to_short_array",
+    "src_int_to_float_array", "src_float_to_int_array", "sinc_get_name",
+    "sinc_get_description", "sinc_set_converter", "linear_get_name",
+    "linear_get_description", "linear_set_converter", "zoh_get_name", "zoh_get_description",
+    "zoh_set_converter",
+];
+
+/// Replacements in ``src_sinc.c`` of the double-precision copy.
+const SINC_POSITION_PATCH: &[(&str, &str)] = &[
+    ("#define\tSHIFT_BITS\t\t\t\t12", "#define\tSHIFT_BITS\t\t\t\t32"),
+    ("typedef int32_t increment_t ;", "typedef int64_t increment_t ;"),
+    ("lrint ((x) * FP_ONE)", "llrint ((x) * FP_ONE)"),
+];
+
+/// Replace every occurrence of the identifier ``from`` in C source ``text`` with ``to``.
+fn replace_identifier(text: &str, from: &str, to: &str) -> String {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(from) {
+        let before = rest[..index].chars().next_back();
+        let after = rest[index + from.len()..].chars().next();
+        result.push_str(&rest[..index]);
+        if before.is_some_and(is_ident) || after.is_some_and(is_ident) {
+            result.push_str(from);
+        } else {
+            result.push_str(to);
+        }
+        rest = &rest[index + from.len()..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Build a copy of the vendored library that processes ``double`` samples, with its symbols
+/// prefixed by ``srs_f64_``.
+fn build_double(dir: &str, version: &str, lrint: lrint::Lrint, clipping: clip::Clipping) {
+    let out_dir = Path::new(&env::var("OUT_DIR").unwrap()).join("double");
+    fs::create_dir_all(&out_dir).unwrap();
+    for file in PATCHED_FILES {
+        let mut text = replace_identifier(&read_patched(dir, file), "float", "double");
+        for symbol in EXTERNAL_SYMBOLS {
+            text = replace_identifier(&text, symbol, &format!("srs_f64_{}", symbol));
+        }
+        if *file == "src_sinc.c" {
+            // The filter position has a 12-bit fraction, which adds timing jitter well above
+            // the double-precision noise floor; use a 64-bit position with a 32-bit fraction.
+            for &(from, to) in SINC_POSITION_PATCH {
+                assert!(text.contains(from), "cannot patch src_sinc.c: {:?} not found", from);
+                text = text.replace(from, to);
+            }
+        }
+        fs::write(out_dir.join(file), text).unwrap();
+    }
+
+    configure(version, lrint, clipping)
+        // The patched sources come first; the coefficient tables are used unmodified.
+        .include(&out_dir)
+        .include(dir)
+        .file(out_dir.join("src_linear.c"))
+        .file(out_dir.join("src_sinc.c"))
+        .file(out_dir.join("src_zoh.c"))
+        .file(out_dir.join("samplerate.c"))
+        .compile("samplerate_f64");
+}
+
 fn main() {
-    if cfg!(feature = "system") {
+    // Only the vendored sources are configured here.
+    let lrint = if !cfg!(feature = "system") || feature_enabled("double-precision") {
+        Some(detect_lrint())
+    } else {
+        None
+    };
+    let clipping = lrint.map(detect_clipping);
+
+    // The `system` feature wins over the vendored-* features, one of which is a default.
+    let vendored_version = if cfg!(feature = "system") {
         println!("cargo:rustc-link-lib=samplerate");
+        None
     } else {
-        let version = env!("CARGO_PKG_VERSION").split("+").next().unwrap();
-        cc::Build::new()
-            // First, do what autoconf would do, but only for feature flags that are
-            // actually used somewhere.
-            .include("src")
-            // We can safely assume we have C99.
-            .define("HAVE_STDINT_H", "1")
-            .define("HAVE_LRINT", "1")
-            .define("HAVE_LRINTF", "1")
-            // These are safe defaults.
-            .define("CPU_CLIPS_NEGATIVE", "0")
-            .define("CPU_CLIPS_POSITIVE", "0")
-            // Package name and version.
-            .define("PACKAGE", "\"libsamplerate\"")
-            .define("VERSION", &format!("\"{}\"", version)[..])
-
-            // Second, actually build the library.
-            .flag_if_supported("-Wno-implicit-fallthrough")
+        let features: Vec<_> = VENDORED.iter().map(|&(feature, _, _)| feature).collect();
+        let selected: Vec<_> = VENDORED.iter()
+            .filter(|&&(feature, _, _)| feature_enabled(feature))
+            .collect();
+        match selected.len() {
+            0 => panic!("samplerate-sys: no libsamplerate selected; enable the `system` \
+                         feature or one of the features: {}", features.join(", ")),
+            1 => (),
+            _ => panic!("samplerate-sys: the features {} are mutually exclusive; \
+                         enable only one of them",
+                        selected.iter().map(|&&(feature, _, _)| feature)
+                                .collect::<Vec<_>>().join(", ")),
+        }
+        let (_, dir, version) = *selected[0];
+        build_vendored(dir, version, lrint.unwrap(), clipping.unwrap());
+        Some(version)
+    };
+
+    if feature_enabled("double-precision") {
+        // The double-precision copy is always built from the vendored sources, even if the
+        // system library is used otherwise.
+        let &(_, dir, version) = VENDORED.iter()
+            .find(|&&(feature, _, _)| feature_enabled(feature))
+            .unwrap_or(&VENDORED[0]);
+        build_double(dir, version, lrint.unwrap(), clipping.unwrap());
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("build_info.rs"), format!(
+        "/// Version of the vendored libsamplerate compiled into this crate, or `None` if the\n\
+         /// system libsamplerate is linked instead.\n\
+         pub const VENDORED_VERSION: Option<&str> = {:?};\n\
+         /// Whether the vendored sources, including the double-precision copy, round with\n\
+         /// lrint and lrintf, or `None` if none are compiled in.\n\
+         pub const NATIVE_LRINT: Option<bool> = {:?};\n\
+         /// How the vendored sources round and how that was decided, for bug reports.\n\
+         pub const LRINT_NOTE: Option<&str> = {:?};\n\
+         /// Whether the vendored sources rely on lrint saturating positive and negative\n\
+         /// values out of range (`CPU_CLIPS_POSITIVE` and `CPU_CLIPS_NEGATIVE`) rather than\n\
+         /// clipping them first, or `None` if none are compiled in.\n\
+         pub const CPU_CLIPS: Option<(bool, bool)> = {:?};\n",
+        vendored_version, lrint.map(|lrint| lrint.available),
+        lrint.map(|lrint| lrint.note()),
+        clipping.map(|clipping| (clipping.positive, clipping.negative)))).unwrap();
+
+    // Layout probes for tests/abi.rs, always checked against the vendored header that
+    // src/lib.rs was generated from. Linking the system library does not otherwise need a C
+    // compiler, so without one the probes are skipped with a warning, unless they were asked
+    // for explicitly.
+    println!("cargo:rerun-if-env-changed={}", ABI_CHECK_VAR);
+    println!("cargo:rustc-check-cfg=cfg(samplerate_abi_check)");
+    let abi_check = env::var_os(ABI_CHECK_VAR);
+    if abi_check.as_deref() != Some(OsStr::new("0")) {
+        let probes = new_build()
             .include("vendor")
-            .file("vendor/src_linear.c")
-            .file("vendor/src_sinc.c")
-            .file("vendor/src_zoh.c")
-            .file("vendor/samplerate.c")
-            .compile("samplerate");
+            .file("src/abi.c")
+            .try_compile("samplerate_abi");
+        match probes {
+            Ok(()) => println!("cargo:rustc-cfg=samplerate_abi_check"),
+            Err(error) if abi_check.is_some() =>
+                panic!("cannot compile the ABI probes requested by {}: {}", ABI_CHECK_VAR,
+                       error),
+            Err(error) =>
+                println!("cargo:warning=skipping the ABI probes, which cannot be compiled: {}; \
+                          set {}=0 to silence this", error, ABI_CHECK_VAR),
+        }
     }
 }
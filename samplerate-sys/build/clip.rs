@@ -0,0 +1,64 @@
+// Decides the CPU_CLIPS_POSITIVE and CPU_CLIPS_NEGATIVE defines, which tell libsamplerate
+// whether converting an out of range double to an integer with lrint already saturates, so
+// that src_float_to_short_array and src_float_to_int_array can skip clipping in software.
+// This file is included both by build.rs and by tests/clip.rs, so it only looks at the values
+// it is given and never at the build environment.
+
+/// Environment variables that override the table, e.g. for an unusual target: ``1`` if lrint
+/// saturates in that direction, ``0`` if not.
+pub const POSITIVE_VAR: &str = "SAMPLERATE_SYS_CPU_CLIPS_POSITIVE";
+pub const NEGATIVE_VAR: &str = "SAMPLERATE_SYS_CPU_CLIPS_NEGATIVE";
+
+/// Known clipping behavior of lrint, as (``CARGO_CFG_TARGET_ARCH``, positive, negative).
+///
+/// The converters shift the result of lrint as a ``long``, so hardware saturation only helps
+/// where ``long`` has 32 bits; on 64-bit targets, values just out of range fit in a ``long``
+/// and would wrap around when narrowed. WebAssembly traps on out of range conversions.
+pub const TABLE: &[(&str, bool, bool)] = &[
+    ("x86_64", false, false),
+    ("aarch64", false, false),
+    ("wasm32", false, false),
+    // VFP and the soft-float helpers both saturate.
+    ("arm", true, true),
+    // fcvt.w.d saturates.
+    ("riscv32", true, true),
+    ("riscv64", false, false),
+];
+
+/// Clipping behavior the vendored sources are compiled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clipping {
+    pub positive: bool,
+    pub negative: bool,
+}
+
+fn parse_override(name: &str, value: &str) -> Result<bool, String> {
+    match value.trim() {
+        "1" => Ok(true),
+        "0" => Ok(false),
+        value => Err(format!("{} must be 1 or 0, not {:?}", name, value)),
+    }
+}
+
+/// Decide the clipping behavior for ``target_arch``, given whether the target has a native
+/// lrint and the values of ``POSITIVE_VAR`` and ``NEGATIVE_VAR`` if they are set.
+///
+/// Targets that are not in the table, and targets that use the lrint replacement from
+/// ``float_cast.h``, clip in software, which is correct everywhere.
+pub fn decide(target_arch: &str, native_lrint: bool, positive_override: Option<&str>,
+              negative_override: Option<&str>) -> Result<Clipping, String> {
+    let (positive, negative) = TABLE.iter()
+        .find(|&&(arch, _, _)| arch == target_arch)
+        .filter(|_| native_lrint)
+        .map_or((false, false), |&(_, positive, negative)| (positive, negative));
+    Ok(Clipping {
+        positive: match positive_override {
+            Some(value) => parse_override(POSITIVE_VAR, value)?,
+            None => positive,
+        },
+        negative: match negative_override {
+            Some(value) => parse_override(NEGATIVE_VAR, value)?,
+            None => negative,
+        },
+    })
+}
@@ -0,0 +1,87 @@
+// Decides whether the vendored sources may use the C99 lrint and lrintf functions. This file
+// is included both by build.rs and by tests/lrint.rs, so it only looks at the values it is
+// given and never at the build environment.
+
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Environment variable that skips the probe, e.g. when cross-compiling with a toolchain
+/// whose headers are known: ``1`` if the functions exist for the target, ``0`` if not.
+pub const OVERRIDE_VAR: &str = "SAMPLERATE_SYS_HAVE_LRINT";
+
+/// C source that only compiles if ``math.h`` declares both functions. The feature macros are
+/// the ones ``float_cast.h`` defines before including ``math.h``.
+pub const PROBE_SOURCE: &str = "\
+#define _ISOC9X_SOURCE 1
+#define _ISOC99_SOURCE 1
+#include <math.h>
+
+long samplerate_probe_lrint(double x) { return lrint(x); }
+long samplerate_probe_lrintf(float x) { return lrintf(x); }
+";
+
+/// How the availability of lrint and lrintf was decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// By compiling ``PROBE_SOURCE`` for the target.
+    Probe,
+    /// By ``OVERRIDE_VAR``.
+    Override,
+}
+
+/// Availability of lrint and lrintf for the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lrint {
+    pub available: bool,
+    pub source: Source,
+}
+
+impl Lrint {
+    /// Describe the rounding path libsamplerate uses, for ``build_info()``.
+    pub fn note(&self) -> String {
+        let path = if self.available {
+            "lrint and lrintf"
+        } else {
+            "the replacement in float_cast.h, which truncates toward zero on most targets"
+        };
+        let source = match self.source {
+            Source::Probe => "detected by compiling a probe".to_owned(),
+            Source::Override => format!("set by {}", OVERRIDE_VAR),
+        };
+        format!("rounds with {} ({})", path, source)
+    }
+}
+
+/// Decide whether lrint and lrintf are available, given the value of ``OVERRIDE_VAR`` if it
+/// is set. ``probe`` is only called without an override, and returns whether
+/// ``PROBE_SOURCE`` compiled.
+pub fn decide<F: FnOnce() -> bool>(override_value: Option<&str>, probe: F)
+        -> Result<Lrint, String> {
+    match override_value.map(str::trim) {
+        None => Ok(Lrint { available: probe(), source: Source::Probe }),
+        Some("1") => Ok(Lrint { available: true, source: Source::Override }),
+        Some("0") => Ok(Lrint { available: false, source: Source::Override }),
+        Some(value) => Err(format!("{} must be 1 or 0, not {:?}", OVERRIDE_VAR, value)),
+    }
+}
+
+/// Arguments that make the C compiler compile ``source`` to ``object``, failing if a
+/// function is used without a declaration. ``msvc`` selects the MSVC command line syntax.
+pub fn probe_args(msvc: bool, source: &Path, object: &Path) -> Vec<OsString> {
+    let mut args: Vec<OsString> = Vec::new();
+    if msvc {
+        // C4013: 'function' undefined; assuming extern returning int.
+        args.push("/we4013".into());
+        args.push("/c".into());
+        let mut output = OsString::from("/Fo");
+        output.push(object);
+        args.push(output);
+    } else {
+        args.push("-Werror=implicit-function-declaration".into());
+        args.push("-c".into());
+        args.push("-o".into());
+        args.push(object.into());
+    }
+    args.push(source.into());
+    args
+}
@@ -0,0 +1,80 @@
+// Decides whether the vendored sources can be compiled to LLVM bitcode for cross-language
+// LTO. This file is included both by build.rs and by tests/lto.rs, so it only looks at the
+// strings it is given and never at the build environment.
+
+/// Retrieve the major LLVM version from the output of ``rustc -vV``.
+pub fn rustc_llvm_major(verbose_version: &str) -> Option<u32> {
+    verbose_version.lines()
+        .find_map(|line| line.strip_prefix("LLVM version: "))
+        .and_then(parse_major)
+}
+
+/// Retrieve the major LLVM version from the output of ``cc --version``, if the compiler is
+/// clang built from upstream LLVM, possibly with a vendor prefix (e.g. ``Ubuntu clang version
+/// 18.1.3 (1ubuntu1)``). Apple clang numbers its releases independently of LLVM, so the
+/// version of its bitcode is unknown.
+pub fn clang_llvm_major(version: &str) -> Option<u32> {
+    let first_line = version.lines().next()?;
+    if first_line.starts_with("Apple ") {
+        return None
+    }
+    let index = first_line.find("clang version ")?;
+    parse_major(&first_line[index + "clang version ".len()..])
+}
+
+fn parse_major(version: &str) -> Option<u32> {
+    let digits = version.find(|c: char| !c.is_ascii_digit()).unwrap_or(version.len());
+    version[..digits].parse().ok()
+}
+
+/// Check whether ``CARGO_ENCODED_RUSTFLAGS`` enable ``-C linker-plugin-lto``. Like rustc,
+/// the last occurrence of the option wins; a path to a linker plugin also enables it.
+pub fn linker_plugin_lto_enabled(encoded_rustflags: &str) -> bool {
+    let mut enabled = false;
+    let mut flags = encoded_rustflags.split('\x1f');
+    while let Some(flag) = flags.next() {
+        let option = match flag {
+            "-C" | "--codegen" => match flags.next() {
+                Some(option) => option,
+                None => break,
+            },
+            _ => match flag.strip_prefix("-C").or_else(|| flag.strip_prefix("--codegen=")) {
+                Some(option) => option,
+                None => continue,
+            },
+        };
+        if option == "linker-plugin-lto" {
+            enabled = true;
+        } else if let Some(value) = option.strip_prefix("linker-plugin-lto=") {
+            enabled = !matches!(value, "n" | "no" | "off" | "false");
+        }
+    }
+    enabled
+}
+
+/// Decide whether to compile the vendored sources with ``-flto=thin``, given the encoded
+/// rustflags and the version output of the C compiler and of rustc, if they could be run.
+///
+/// Bitcode objects can only be linked when rustc hands them to the linker plugin, so without
+/// ``-C linker-plugin-lto`` the answer is ``Ok(false)``. With it, the plugin (which comes with
+/// the clang toolchain) has to understand the bitcode rustc produces as well; if the C
+/// compiler contradicts the rustflags, the reason is returned.
+pub fn decide(encoded_rustflags: &str, cc_version: Option<&str>,
+              rustc_version: Option<&str>) -> Result<bool, String> {
+    if !linker_plugin_lto_enabled(encoded_rustflags) {
+        return Ok(false)
+    }
+    let clang = match cc_version.and_then(clang_llvm_major) {
+        Some(clang) => clang,
+        None => return Err("the C compiler is not clang built from upstream LLVM".to_owned()),
+    };
+    let rustc = match rustc_version.and_then(rustc_llvm_major) {
+        Some(rustc) => rustc,
+        None => return Err("the LLVM version of rustc is unknown".to_owned()),
+    };
+    if clang < rustc {
+        return Err(format!("clang uses LLVM {}, which cannot read the bitcode of rustc, \
+                            which uses LLVM {}", clang, rustc))
+    }
+    Ok(true)
+}
@@ -0,0 +1,36 @@
+// Layout probes for the ABI verification tests in tests/abi.rs. These are compiled against the
+// same header the Rust declarations were generated from, and are not used otherwise.
+
+#include <stddef.h>
+#include "samplerate.h"
+
+struct srs_abi_align_src_data { char pad; SRC_DATA data; };
+
+size_t srs_abi_sizeof_long(void) { return sizeof(long); }
+size_t srs_abi_sizeof_src_data(void) { return sizeof(SRC_DATA); }
+size_t srs_abi_alignof_src_data(void) { return offsetof(struct srs_abi_align_src_data, data); }
+
+size_t srs_abi_offsetof_src_data(int field) {
+    switch (field) {
+        case 0: return offsetof(SRC_DATA, data_in);
+        case 1: return offsetof(SRC_DATA, data_out);
+        case 2: return offsetof(SRC_DATA, input_frames);
+        case 3: return offsetof(SRC_DATA, output_frames);
+        case 4: return offsetof(SRC_DATA, input_frames_used);
+        case 5: return offsetof(SRC_DATA, output_frames_gen);
+        case 6: return offsetof(SRC_DATA, end_of_input);
+        case 7: return offsetof(SRC_DATA, src_ratio);
+        default: return (size_t)-1;
+    }
+}
+
+int srs_abi_converter_type(int index) {
+    switch (index) {
+        case 0: return SRC_SINC_BEST_QUALITY;
+        case 1: return SRC_SINC_MEDIUM_QUALITY;
+        case 2: return SRC_SINC_FASTEST;
+        case 3: return SRC_ZERO_ORDER_HOLD;
+        case 4: return SRC_LINEAR;
+        default: return -1;
+    }
+}
@@ -0,0 +1,20 @@
+/* Backported from libsamplerate 0.2.0; inserted into the vendored 0.1.9 by build.rs. */
+static int
+sinc_copy (SRC_PRIVATE *from, SRC_PRIVATE *to)
+{	SINC_FILTER *to_filter, *from_filter ;
+	size_t private_length ;
+
+	if (from->private_data == NULL)
+		return SRC_ERR_NO_PRIVATE ;
+
+	from_filter = (SINC_FILTER*) from->private_data ;
+	private_length = sizeof (SINC_FILTER) + sizeof (from_filter->buffer [0]) * (from_filter->b_len + from_filter->channels) ;
+
+	if ((to_filter = calloc (1, private_length)) == NULL)
+		return SRC_ERR_MALLOC_FAILED ;
+
+	memcpy (to_filter, from_filter, private_length) ;
+	to->private_data = to_filter ;
+
+	return SRC_ERR_NO_ERROR ;
+} /* sinc_copy */
@@ -0,0 +1,20 @@
+/* Backported from libsamplerate 0.2.0; inserted into the vendored 0.1.9 by build.rs. */
+static int
+zoh_copy (SRC_PRIVATE *from, SRC_PRIVATE *to)
+{	ZOH_DATA *to_priv, *from_priv ;
+	size_t private_size ;
+
+	if (from->private_data == NULL)
+		return SRC_ERR_NO_PRIVATE ;
+
+	from_priv = (ZOH_DATA*) from->private_data ;
+	private_size = sizeof (*to_priv) + from_priv->channels * sizeof (float) ;
+
+	if ((to_priv = calloc (1, private_size)) == NULL)
+		return SRC_ERR_MALLOC_FAILED ;
+
+	memcpy (to_priv, from_priv, private_size) ;
+	to->private_data = to_priv ;
+
+	return SRC_ERR_NO_ERROR ;
+} /* zoh_copy */
@@ -0,0 +1,55 @@
+//! Bindings to a copy of the vendored libsamplerate that processes `double` samples.
+//!
+//! The copy is generated from the vendored sources by `build.rs`, replacing `float` with
+//! `double` throughout (the coefficient tables are stored with double precision in the
+//! sources already) and prefixing every external symbol with `srs_f64_`, so that it can be
+//! linked alongside the normal build. Converter type constants and error codes are the same
+//! as in the normal build, but states of one build must not be passed to the other, which is
+//! why this module has its own `SRC_STATE` type.
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SRC_STATE_tag {
+    _unused: [u8; 0],
+}
+pub type SRC_STATE = SRC_STATE_tag;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SRC_DATA {
+    pub data_in: *const ::core::ffi::c_double,
+    pub data_out: *mut ::core::ffi::c_double,
+    pub input_frames: ::core::ffi::c_long,
+    pub output_frames: ::core::ffi::c_long,
+    pub input_frames_used: ::core::ffi::c_long,
+    pub output_frames_gen: ::core::ffi::c_long,
+    pub end_of_input: ::core::ffi::c_int,
+    pub src_ratio: ::core::ffi::c_double,
+}
+extern "C" {
+    #[link_name = "srs_f64_src_new"]
+    pub fn src_new(
+        converter_type: ::core::ffi::c_int,
+        channels: ::core::ffi::c_int,
+        error: *mut ::core::ffi::c_int,
+    ) -> *mut SRC_STATE;
+    #[link_name = "srs_f64_src_delete"]
+    pub fn src_delete(state: *mut SRC_STATE) -> *mut SRC_STATE;
+    #[link_name = "srs_f64_src_process"]
+    pub fn src_process(state: *mut SRC_STATE, data: *mut SRC_DATA) -> ::core::ffi::c_int;
+    #[link_name = "srs_f64_src_simple"]
+    pub fn src_simple(
+        data: *mut SRC_DATA,
+        converter_type: ::core::ffi::c_int,
+        channels: ::core::ffi::c_int,
+    ) -> ::core::ffi::c_int;
+    #[link_name = "srs_f64_src_set_ratio"]
+    pub fn src_set_ratio(state: *mut SRC_STATE, new_ratio: ::core::ffi::c_double) -> ::core::ffi::c_int;
+    #[link_name = "srs_f64_src_get_channels"]
+    pub fn src_get_channels(state: *mut SRC_STATE) -> ::core::ffi::c_int;
+    #[link_name = "srs_f64_src_reset"]
+    pub fn src_reset(state: *mut SRC_STATE) -> ::core::ffi::c_int;
+    #[link_name = "srs_f64_src_error"]
+    pub fn src_error(state: *mut SRC_STATE) -> ::core::ffi::c_int;
+    #[link_name = "srs_f64_src_strerror"]
+    pub fn src_strerror(error: ::core::ffi::c_int) -> *const ::core::ffi::c_char;
+}
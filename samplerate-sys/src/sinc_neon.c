@@ -0,0 +1,145 @@
+/*
+** NEON versions of calc_output_single and calc_output_stereo from src_sinc.c, used on
+** aarch64 with the `neon` feature. build.rs includes this file into a copy of src_sinc.c in
+** place of the scalar functions; the vendored sources are left as they are.
+**
+** The coefficients of two taps are interpolated at once. The stereo filter sums each
+** channel over the taps in the same order as the scalar code; the mono filter sums even and
+** odd taps separately, so its output differs from that of the scalar code in rounding.
+*/
+
+#include <arm_neon.h>
+
+/* Interpolate the coefficients at filter_a and filter_b, in the two lanes of the result.
+** Like the scalar code, the difference of neighbouring coefficients is taken in float. */
+static inline float64x2_t
+neon_coeffs (const coeff_t *coeffs, increment_t filter_a, increment_t filter_b)
+{	float32x2_t a = vld1_f32 (coeffs + fp_to_int (filter_a)) ;
+	float32x2_t b = vld1_f32 (coeffs + fp_to_int (filter_b)) ;
+	float32x2_t low = vzip1_f32 (a, b) ;
+	float32x2_t high = vzip2_f32 (a, b) ;
+	double fraction [2] = { fp_to_double (filter_a), fp_to_double (filter_b) } ;
+
+	return vfmaq_f64 (vcvt_f64_f32 (low), vld1q_f64 (fraction), vcvt_f64_f32 (vsub_f32 (high, low))) ;
+} /* neon_coeffs */
+
+/* Interpolate the coefficient at filter_index like the scalar code. */
+static inline double
+scalar_coeff (const coeff_t *coeffs, increment_t filter_index)
+{	int indx = fp_to_int (filter_index) ;
+
+	return coeffs [indx] + fp_to_double (filter_index) * (coeffs [indx + 1] - coeffs [indx]) ;
+} /* scalar_coeff */
+
+/* Apply count taps of one half of the filter to a mono buffer, starting at filter_index and
+** data_index, and stepping by -increment and by direction, which is 1 or -1. */
+static inline double
+neon_half_single (const SINC_FILTER *filter, increment_t filter_index, increment_t increment,
+				int data_index, int direction, int count)
+{	float64x2_t sum = vdupq_n_f64 (0.0) ;
+	float32x2_t data ;
+	double result ;
+	int k ;
+
+	for (k = 0 ; k + 1 < count ; k += 2)
+	{	if (direction > 0)
+			data = vld1_f32 (filter->buffer + data_index) ;
+		else
+			data = vrev64_f32 (vld1_f32 (filter->buffer + data_index - 1)) ;
+
+		sum = vfmaq_f64 (sum, neon_coeffs (filter->coeffs, filter_index, filter_index - increment), vcvt_f64_f32 (data)) ;
+
+		filter_index -= 2 * increment ;
+		data_index += 2 * direction ;
+		} ;
+
+	result = vaddvq_f64 (sum) ;
+	if (k < count)
+		result += scalar_coeff (filter->coeffs, filter_index) * filter->buffer [data_index] ;
+
+	return result ;
+} /* neon_half_single */
+
+/* Apply count taps of one half of the filter to a stereo buffer, like neon_half_single. */
+static inline float64x2_t
+neon_half_stereo (const SINC_FILTER *filter, increment_t filter_index, increment_t increment,
+				int data_index, int direction, int count)
+{	float64x2_t sum = vdupq_n_f64 (0.0), icoeff ;
+	int k ;
+
+	for (k = 0 ; k + 1 < count ; k += 2)
+	{	icoeff = neon_coeffs (filter->coeffs, filter_index, filter_index - increment) ;
+		sum = vfmaq_laneq_f64 (sum, vcvt_f64_f32 (vld1_f32 (filter->buffer + data_index)), icoeff, 0) ;
+		sum = vfmaq_laneq_f64 (sum, vcvt_f64_f32 (vld1_f32 (filter->buffer + data_index + 2 * direction)), icoeff, 1) ;
+
+		filter_index -= 2 * increment ;
+		data_index += 4 * direction ;
+		} ;
+
+	if (k < count)
+	{	icoeff = vdupq_n_f64 (scalar_coeff (filter->coeffs, filter_index)) ;
+		sum = vfmaq_f64 (sum, vcvt_f64_f32 (vld1_f32 (filter->buffer + data_index)), icoeff) ;
+		} ;
+
+	return sum ;
+} /* neon_half_stereo */
+
+/* Number of taps of the left half of the filter, which stops before filter_index goes below
+** zero, and of the right half, which also stops at zero; each applies at least one tap. */
+static inline int
+left_tap_count (increment_t filter_index, increment_t increment)
+{	return filter_index / increment + 1 ;
+} /* left_tap_count */
+
+static inline int
+right_tap_count (increment_t filter_index, increment_t increment)
+{	int count = (filter_index + increment - 1) / increment ;
+
+	return count > 1 ? count : 1 ;
+} /* right_tap_count */
+
+static inline double
+calc_output_single (SINC_FILTER *filter, increment_t increment, increment_t start_filter_index)
+{	double		left, right ;
+	increment_t	filter_index, max_filter_index ;
+	int			coeff_count ;
+
+	max_filter_index = int_to_fp (filter->coeff_half_len) ;
+
+	filter_index = start_filter_index ;
+	coeff_count = (max_filter_index - filter_index) / increment ;
+	filter_index = filter_index + coeff_count * increment ;
+	left = neon_half_single (filter, filter_index, increment, filter->b_current - coeff_count,
+				1, left_tap_count (filter_index, increment)) ;
+
+	filter_index = increment - start_filter_index ;
+	coeff_count = (max_filter_index - filter_index) / increment ;
+	filter_index = filter_index + coeff_count * increment ;
+	right = neon_half_single (filter, filter_index, increment, filter->b_current + 1 + coeff_count,
+				-1, right_tap_count (filter_index, increment)) ;
+
+	return (left + right) ;
+} /* calc_output_single */
+
+static inline void
+calc_output_stereo (SINC_FILTER *filter, increment_t increment, increment_t start_filter_index, double scale, float * output)
+{	float64x2_t	left, right ;
+	increment_t	filter_index, max_filter_index ;
+	int			coeff_count ;
+
+	max_filter_index = int_to_fp (filter->coeff_half_len) ;
+
+	filter_index = start_filter_index ;
+	coeff_count = (max_filter_index - filter_index) / increment ;
+	filter_index = filter_index + coeff_count * increment ;
+	left = neon_half_stereo (filter, filter_index, increment,
+				filter->b_current - filter->channels * coeff_count, 1, left_tap_count (filter_index, increment)) ;
+
+	filter_index = increment - start_filter_index ;
+	coeff_count = (max_filter_index - filter_index) / increment ;
+	filter_index = filter_index + coeff_count * increment ;
+	right = neon_half_stereo (filter, filter_index, increment,
+				filter->b_current + filter->channels * (1 + coeff_count), -1, right_tap_count (filter_index, increment)) ;
+
+	vst1_f32 (output, vcvt_f32_f64 (vmulq_n_f64 (vaddq_f64 (left, right), scale))) ;
+} /* calc_output_stereo */
@@ -0,0 +1,109 @@
+//! Declarations that are not generated from the vendored ``samplerate.h`` into ``lib.rs``, so
+//! that regenerating it with the command at its top loses nothing.
+
+use super::SRC_STATE;
+#[cfg(test)]
+use super::SRC_DATA;
+
+extern "C" {
+    /// Backported from libsamplerate 0.2.0 to the vendored sources by the build script, which
+    /// adds it to copies of them; the header is kept as released.
+    pub fn src_clone(orig: *mut SRC_STATE, error: *mut ::core::ffi::c_int) -> *mut SRC_STATE;
+}
+
+// The layout test bindgen generates reads fields through a null pointer, which rustc rejects
+// (``deref_nullptr``), so ``lib.rs`` is generated without it and this copy uses ``offset_of!``
+// instead. tests/abi.rs checks the layout against the C compiler on every target.
+#[test]
+fn bindgen_test_layout_SRC_DATA() {
+    assert_eq!(
+        ::core::mem::size_of::<SRC_DATA>(),
+        64usize,
+        concat!("Size of: ", stringify!(SRC_DATA))
+    );
+    assert_eq!(
+        ::core::mem::align_of::<SRC_DATA>(),
+        8usize,
+        concat!("Alignment of ", stringify!(SRC_DATA))
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, data_in),
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(data_in)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, data_out),
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(data_out)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, input_frames),
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(input_frames)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, output_frames),
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(output_frames)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, input_frames_used),
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(input_frames_used)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, output_frames_gen),
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(output_frames_gen)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, end_of_input),
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(end_of_input)
+        )
+    );
+    assert_eq!(
+        ::core::mem::offset_of!(SRC_DATA, src_ratio),
+        56usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(SRC_DATA),
+            "::",
+            stringify!(src_ratio)
+        )
+    );
+}
@@ -0,0 +1,73 @@
+// Checks that the Rust declarations in src/lib.rs match the C header on the current target.
+// A mismatch here (e.g. in the width of `long`) would silently corrupt conversions. The C
+// probes are built by default; these tests are skipped if `SAMPLERATE_SYS_ABI_CHECK=0`
+// disables them, or if there is no C compiler and the variable is not set at all.
+
+extern crate libc;
+extern crate samplerate_sys;
+
+#[cfg(samplerate_abi_check)]
+use std::mem::{align_of, offset_of, size_of};
+
+#[cfg(samplerate_abi_check)]
+use samplerate_sys::*;
+
+#[cfg(not(samplerate_abi_check))]
+#[test]
+fn test_abi() {
+    eprintln!("skipping the ABI checks; the probes were disabled or could not be compiled");
+}
+
+#[cfg(samplerate_abi_check)]
+extern "C" {
+    fn srs_abi_sizeof_long() -> libc::size_t;
+    fn srs_abi_sizeof_src_data() -> libc::size_t;
+    fn srs_abi_alignof_src_data() -> libc::size_t;
+    fn srs_abi_offsetof_src_data(field: libc::c_int) -> libc::size_t;
+    fn srs_abi_converter_type(index: libc::c_int) -> libc::c_int;
+}
+
+#[cfg(samplerate_abi_check)]
+#[test]
+fn test_long_width() {
+    assert_eq!(unsafe { srs_abi_sizeof_long() }, size_of::<libc::c_long>());
+}
+
+#[cfg(samplerate_abi_check)]
+#[test]
+fn test_src_data_layout() {
+    unsafe {
+        assert_eq!(srs_abi_sizeof_src_data(), size_of::<SRC_DATA>());
+        assert_eq!(srs_abi_alignof_src_data(), align_of::<SRC_DATA>());
+    }
+
+    let offsets = [
+        offset_of!(SRC_DATA, data_in),
+        offset_of!(SRC_DATA, data_out),
+        offset_of!(SRC_DATA, input_frames),
+        offset_of!(SRC_DATA, output_frames),
+        offset_of!(SRC_DATA, input_frames_used),
+        offset_of!(SRC_DATA, output_frames_gen),
+        offset_of!(SRC_DATA, end_of_input),
+        offset_of!(SRC_DATA, src_ratio),
+    ];
+    for (field, &offset) in offsets.iter().enumerate() {
+        assert_eq!(unsafe { srs_abi_offsetof_src_data(field as libc::c_int) }, offset,
+                   "offset of SRC_DATA field #{}", field);
+    }
+}
+
+#[cfg(samplerate_abi_check)]
+#[test]
+fn test_converter_types() {
+    let types = [
+        SRC_SINC_BEST_QUALITY,
+        SRC_SINC_MEDIUM_QUALITY,
+        SRC_SINC_FASTEST,
+        SRC_ZERO_ORDER_HOLD,
+        SRC_LINEAR,
+    ];
+    for (index, &value) in types.iter().enumerate() {
+        assert_eq!(unsafe { srs_abi_converter_type(index as libc::c_int) }, value as libc::c_int);
+    }
+}
@@ -0,0 +1,61 @@
+// Checks how build.rs sets CPU_CLIPS_POSITIVE and CPU_CLIPS_NEGATIVE, and that the vendored
+// conversions to integers saturate with the values it chose.
+
+extern crate samplerate_sys;
+
+#[path = "../build/clip.rs"]
+#[allow(dead_code)]
+mod clip;
+
+use clip::*;
+
+#[test]
+fn test_decide() {
+    let software = Clipping { positive: false, negative: false };
+    let hardware = Clipping { positive: true, negative: true };
+    assert_eq!(decide("x86_64", true, None, None), Ok(software));
+    assert_eq!(decide("aarch64", true, None, None), Ok(software));
+    assert_eq!(decide("wasm32", true, None, None), Ok(software));
+    assert_eq!(decide("arm", true, None, None), Ok(hardware));
+    assert_eq!(decide("riscv32", true, None, None), Ok(hardware));
+    assert_eq!(decide("riscv64", true, None, None), Ok(software));
+    // The table describes lrint itself, not the replacement for it.
+    assert_eq!(decide("arm", false, None, None), Ok(software));
+    assert_eq!(decide("m68k", true, None, None), Ok(software));
+
+    assert_eq!(decide("m68k", true, Some("1"), None),
+               Ok(Clipping { positive: true, negative: false }));
+    assert_eq!(decide("arm", true, None, Some("0")),
+               Ok(Clipping { positive: true, negative: false }));
+    assert!(decide("arm", true, Some("true"), None).is_err());
+    assert!(decide("arm", true, None, Some("")).is_err());
+}
+
+#[test]
+#[cfg(not(feature = "system"))]
+fn test_conversions_saturate() {
+    let (positive, negative) = samplerate_sys::CPU_CLIPS.unwrap();
+    let context = format!("CPU_CLIPS_POSITIVE={} CPU_CLIPS_NEGATIVE={}",
+                          positive as u8, negative as u8);
+    let input = [1.0, 1.0 + f32::EPSILON, 1.5, 2.0, 1e3, 1e30, f32::INFINITY,
+                 -1.0, -1.0 - f32::EPSILON, -1.5, -2.0, -1e3, -1e30, f32::NEG_INFINITY,
+                 0.5, -0.5, 0.0];
+
+    let mut output = [0i16; 17];
+    unsafe {
+        samplerate_sys::src_float_to_short_array(input.as_ptr(), output.as_mut_ptr(),
+                                                  input.len() as _);
+    }
+    assert_eq!(output, [32767, 32767, 32767, 32767, 32767, 32767, 32767,
+                        -32768, -32768, -32768, -32768, -32768, -32768, -32768,
+                        16384, -16384, 0], "{}", context);
+
+    let mut output = [0i32; 17];
+    unsafe {
+        samplerate_sys::src_float_to_int_array(input.as_ptr(), output.as_mut_ptr(),
+                                                input.len() as _);
+    }
+    assert_eq!(output[..7], [i32::MAX; 7], "{}", context);
+    assert_eq!(output[7..14], [i32::MIN; 7], "{}", context);
+    assert_eq!(output[14..], [1 << 30, -(1 << 30), 0], "{}", context);
+}
@@ -0,0 +1,70 @@
+// Golden output vectors for each vendored libsamplerate. These lock the output of each pinned
+// version: if one of them changes, so does the audio produced by every user of that pin.
+#![cfg(not(feature = "system"))]
+
+extern crate samplerate_sys;
+
+use samplerate_sys::*;
+
+type Golden = (u32, f64, usize, f64, [(usize, f32); 5]);
+
+#[cfg(feature = "vendored-0_1")]
+#[allow(clippy::excessive_precision)]
+const GOLDEN: &[Golden] = &[
+    (0, 1.5,  384, 6.745860383380e-1, [(0, 1.2319114e-3), (17, 2.3567256e-1), (100, 7.470344e-2),
+                                       (192, 5.7274293e-2), (383, 1.5231137e-1)]),
+    (0, 0.75, 192, 3.035986125469e-1, [(0, 1.0586988e-2), (17, 5.9858185e-1), (100, 1.4624947e-1),
+                                       (96, 5.7269894e-2), (191, 1.7058864e-1)]),
+    (1, 1.5,  384, 6.719633338507e-1, [(0, 2.9263918e-3), (17, 2.3516491e-1), (100, 7.4730136e-2),
+                                       (192, 5.7274215e-2), (383, 1.4913054e-1)]),
+    (1, 0.75, 192, 3.019794635475e-1, [(0, 1.2174306e-2), (17, 5.993744e-1), (100, 1.462502e-1),
+                                       (96, 5.7274267e-2), (191, 1.6556086e-1)]),
+    (2, 1.5,  384, 6.663349629380e-1, [(0, 6.244251e-3), (17, 2.3599991e-1), (100, 7.4730955e-2),
+                                       (192, 5.7274353e-2), (383, 1.4311995e-1)]),
+    (2, 0.75, 192, 2.987418095581e-1, [(0, 1.5485579e-2), (17, 5.9911174e-1), (100, 1.4625092e-1),
+                                       (96, 5.7274457e-2), (191, 1.551769e-1)]),
+    (3, 1.5,  385, 3.670014962554e-1, [(0, 0e0), (17, 2.8827643e-1), (100, -1.1325149e-1),
+                                       (192, 1.3752082e-1), (384, 6.589718e-2)]),
+    (3, 0.75, 193, 1.989420168102e-1, [(0, 0e0), (17, 6.8072355e-1), (100, 4.8671573e-2),
+                                       (96, 1.3752082e-1), (192, 6.589718e-2)]),
+    (4, 1.5,  384, 4.142388282344e-1, [(0, 0e0), (17, 2.7399662e-1), (100, -4.288843e-2),
+                                       (192, 9.998804e-2), (383, 1.12277925e-1)]),
+    (4, 0.75, 192, 1.677990769967e-1, [(0, 0e0), (17, 6.563024e-1), (100, 7.1531154e-2),
+                                       (96, 9.998804e-2), (191, 2.2060603e-2)]),
+];
+
+#[test]
+fn test_golden_vectors() {
+    let input: Vec<f32> = (0..256)
+        .map(|i| (i as f32 * 0.1).sin() * 0.5 + (i as f32 * 0.37).sin() * 0.25)
+        .collect();
+    for &(converter, ratio, frames, sum, samples) in GOLDEN {
+        let mut output = vec![0.0f32; 512];
+        let mut data = SRC_DATA {
+            data_in: input.as_ptr(),
+            data_out: output.as_mut_ptr(),
+            input_frames: input.len() as _,
+            output_frames: output.len() as _,
+            input_frames_used: 0,
+            output_frames_gen: 0,
+            end_of_input: 1,
+            src_ratio: ratio,
+        };
+        assert_eq!(unsafe { src_simple(&mut data, converter as _, 1) }, 0);
+        assert_eq!(data.output_frames_gen as usize, frames, "converter {} @ {}", converter, ratio);
+
+        let actual_sum: f64 = output[..frames].iter().map(|&x| x as f64).sum();
+        assert!((actual_sum - sum).abs() < 1e-6, "converter {} @ {}: sum {} != {}",
+                converter, ratio, actual_sum, sum);
+        for &(index, value) in samples.iter() {
+            assert!((output[index] - value).abs() < 1e-6, "converter {} @ {}: [{}] {} != {}",
+                    converter, ratio, index, output[index], value);
+        }
+    }
+}
+
+#[test]
+fn test_vendored_version() {
+    #[cfg(feature = "vendored-0_1")]
+    assert_eq!(VENDORED_VERSION, Some("0.1.9"));
+}
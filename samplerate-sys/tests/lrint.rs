@@ -0,0 +1,73 @@
+// Checks how build.rs decides whether the vendored sources use lrint and lrintf.
+
+#[path = "../build/lrint.rs"]
+#[allow(dead_code)]
+mod lrint;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use lrint::*;
+
+#[test]
+fn test_decide() {
+    let found = Lrint { available: true, source: Source::Probe };
+    assert_eq!(decide(None, || true), Ok(found));
+    assert_eq!(decide(None, || false), Ok(Lrint { available: false, ..found }));
+    // The override skips the probe, which may not work when cross-compiling.
+    let unreachable = || -> bool { panic!("probed despite the override") };
+    assert_eq!(decide(Some("1"), unreachable),
+               Ok(Lrint { available: true, source: Source::Override }));
+    assert_eq!(decide(Some("0\n"), unreachable),
+               Ok(Lrint { available: false, source: Source::Override }));
+    assert!(decide(Some("yes"), unreachable).is_err());
+    assert!(decide(Some(""), unreachable).is_err());
+}
+
+#[test]
+fn test_note() {
+    assert_eq!(Lrint { available: true, source: Source::Probe }.note(),
+               "rounds with lrint and lrintf (detected by compiling a probe)");
+    let note = Lrint { available: false, source: Source::Override }.note();
+    assert!(note.contains("replacement") && note.ends_with("(set by SAMPLERATE_SYS_HAVE_LRINT)"),
+            "{}", note);
+}
+
+#[test]
+fn test_probe_args() {
+    let args = probe_args(true, Path::new("probe.c"), Path::new("out/probe.obj"));
+    assert_eq!(args, ["/we4013", "/c", "/Foout/probe.obj", "probe.c"]);
+    let args = probe_args(false, Path::new("probe.c"), Path::new("out/probe.o"));
+    assert_eq!(args, ["-Werror=implicit-function-declaration", "-c", "-o", "out/probe.o",
+                      "probe.c"]);
+}
+
+/// Compile ``source`` with the host C compiler like build.rs does, or retrieve ``None`` if
+/// there is no compiler to run.
+fn compiles(name: &str, source: &str) -> Option<bool> {
+    let dir = env::temp_dir().join(format!("samplerate-sys-lrint-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.c", name));
+    fs::write(&path, source).unwrap();
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let output = Command::new(compiler)
+        .args(probe_args(false, &path, &dir.join(format!("{}.o", name))))
+        .output().ok()?;
+    Some(output.status.success())
+}
+
+#[test]
+fn test_probe() {
+    if cfg!(target_env = "msvc") {
+        return
+    }
+    if let Some(compiled) = compiles("probe", PROBE_SOURCE) {
+        assert!(compiled);
+        // Without the declaration from math.h, the probe fails rather than assuming that
+        // lrint returns int.
+        let undeclared = PROBE_SOURCE.replace("#include <math.h>", "");
+        assert_eq!(compiles("undeclared", &undeclared), Some(false));
+    }
+}
@@ -0,0 +1,61 @@
+// Checks when build.rs compiles the vendored sources for cross-language LTO.
+
+#[path = "../build/lto.rs"]
+#[allow(dead_code)]
+mod lto;
+
+use lto::*;
+
+const RUSTC: &str = "rustc 1.95.0 (59807616e 2026-04-14)\n\
+                     binary: rustc\n\
+                     host: x86_64-unknown-linux-gnu\n\
+                     release: 1.95.0\n\
+                     LLVM version: 22.1.2\n";
+
+const CLANG_22: &str = "clang version 22.1.0 (https://github.com/llvm/llvm-project 4c6ae2f)\n\
+                        Target: x86_64-unknown-linux-gnu\n\
+                        Thread model: posix\n";
+const CLANG_18_UBUNTU: &str = "Ubuntu clang version 18.1.3 (1ubuntu1)\n\
+                               Target: x86_64-pc-linux-gnu\n";
+const APPLE_CLANG: &str = "Apple clang version 16.0.0 (clang-1600.0.26.6)\n\
+                           Target: arm64-apple-darwin24.1.0\n";
+const GCC: &str = "cc (Debian 14.2.0-19) 14.2.0\n\
+                   Copyright (C) 2024 Free Software Foundation, Inc.\n";
+
+#[test]
+fn test_versions() {
+    assert_eq!(rustc_llvm_major(RUSTC), Some(22));
+    assert_eq!(rustc_llvm_major("rustc 1.95.0\n"), None);
+    assert_eq!(clang_llvm_major(CLANG_22), Some(22));
+    assert_eq!(clang_llvm_major(CLANG_18_UBUNTU), Some(18));
+    assert_eq!(clang_llvm_major("Homebrew clang version 20.1.8\n"), Some(20));
+    assert_eq!(clang_llvm_major(APPLE_CLANG), None);
+    assert_eq!(clang_llvm_major(GCC), None);
+}
+
+#[test]
+fn test_rustflags() {
+    assert!(linker_plugin_lto_enabled("-Clinker-plugin-lto"));
+    assert!(linker_plugin_lto_enabled("-Ctarget-cpu=native\x1f-C\x1flinker-plugin-lto"));
+    assert!(linker_plugin_lto_enabled("--codegen=linker-plugin-lto=/usr/lib/LLVMgold.so"));
+    assert!(linker_plugin_lto_enabled("-Clinker-plugin-lto=no\x1f-Clinker-plugin-lto=yes"));
+    assert!(!linker_plugin_lto_enabled(""));
+    assert!(!linker_plugin_lto_enabled("-Clinker-plugin-lto\x1f-Clinker-plugin-lto=off"));
+    assert!(!linker_plugin_lto_enabled("-Clto=fat\x1f-Clinker=clang"));
+    assert!(!linker_plugin_lto_enabled("-C"));
+}
+
+#[test]
+fn test_decide() {
+    let flags = "-Clinker=clang\x1f-Clink-arg=-fuse-ld=lld\x1f-Clinker-plugin-lto";
+    assert_eq!(decide(flags, Some(CLANG_22), Some(RUSTC)), Ok(true));
+    // Without the linker plugin, the bitcode would end up in front of a linker that
+    // cannot read it; that is the usual build, not a conflict.
+    assert_eq!(decide("", Some(CLANG_22), Some(RUSTC)), Ok(false));
+    assert_eq!(decide("", Some(GCC), None), Ok(false));
+    assert!(decide(flags, Some(GCC), Some(RUSTC)).is_err());
+    assert!(decide(flags, Some(APPLE_CLANG), Some(RUSTC)).is_err());
+    assert!(decide(flags, Some(CLANG_18_UBUNTU), Some(RUSTC)).is_err());
+    assert!(decide(flags, None, Some(RUSTC)).is_err());
+    assert!(decide(flags, Some(CLANG_22), None).is_err());
+}
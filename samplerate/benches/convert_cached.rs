@@ -0,0 +1,34 @@
+//! Compares `convert` with `convert_cached` on many small buffers.
+//!
+//! Run with `cargo bench --features std --bench convert_cached`.
+
+extern crate samplerate;
+
+use std::time::Instant;
+
+use samplerate::{convert, convert_cached, Interpolator};
+
+const ITERATIONS: usize = 2000;
+
+fn main() {
+    let input: Vec<f32> = (0..64).map(|i| (i as f32 * 0.05).sin()).collect();
+    let mut output = vec![0.0; 1024];
+
+    for &interpolator in &[Interpolator::SincBestQuality, Interpolator::SincFastest,
+                           Interpolator::Linear] {
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            convert(interpolator, 1, 2.0, &input, &mut output).unwrap();
+        }
+        let uncached = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            convert_cached(interpolator, 1, 2.0, &input, &mut output).unwrap();
+        }
+        let cached = start.elapsed();
+
+        println!("{:?}: convert {:?}/call, convert_cached {:?}/call",
+                 interpolator, uncached / ITERATIONS as u32, cached / ITERATIONS as u32);
+    }
+}
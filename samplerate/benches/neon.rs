@@ -0,0 +1,50 @@
+//! Measures the throughput of the sinc interpolators, to compare the NEON build of the
+//! vendored libsamplerate on aarch64 with the scalar one.
+//!
+//! Run with `cargo bench --bench neon` and with `cargo bench --bench neon --features neon`
+//! on an aarch64 host; elsewhere, the two builds are the same.
+
+extern crate samplerate;
+
+use std::time::{Duration, Instant};
+
+use samplerate::{Converter, Interpolator};
+
+const FRAMES: usize = 441_000;
+const CHUNK_FRAMES: usize = 1024;
+const RUNS: usize = 5;
+
+/// Convert ``input`` in chunks, returning the fastest of a few runs.
+fn run(interpolator: Interpolator, channels: usize, ratio: f64, input: &[f32]) -> Duration {
+    let mut output = vec![0.0; ((CHUNK_FRAMES as f64 * ratio) as usize + 16) * channels];
+    (0..RUNS).map(|_| {
+        let mut converter = Converter::new(interpolator, channels).unwrap();
+        let start = Instant::now();
+        for chunk in input.chunks(CHUNK_FRAMES * channels) {
+            let mut rest = chunk;
+            while !rest.is_empty() {
+                let (used, _) = converter.convert(ratio, Some(rest), &mut output).unwrap();
+                rest = &rest[used..];
+            }
+        }
+        start.elapsed()
+    }).min().unwrap()
+}
+
+fn main() {
+    println!("NEON build: {}", cfg!(all(feature = "neon", target_arch = "aarch64")));
+    for &channels in &[1, 2] {
+        // 10 seconds at 44.1 kHz.
+        let input: Vec<f32> = (0..FRAMES * channels)
+            .map(|i| ((i / channels) as f32 * 0.03 + (i % channels) as f32).sin() * 0.5)
+            .collect();
+        for &interpolator in &[Interpolator::SincBestQuality, Interpolator::SincMediumQuality,
+                               Interpolator::SincFastest] {
+            for &ratio in &[48000.0 / 44100.0, 0.5] {
+                let elapsed = run(interpolator, channels, ratio, &input);
+                println!("{:?}, {} channels at {:.4}: {:?} ({:.1}x realtime)", interpolator,
+                         channels, ratio, elapsed, 10.0 / elapsed.as_secs_f64());
+            }
+        }
+    }
+}
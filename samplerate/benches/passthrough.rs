@@ -0,0 +1,42 @@
+//! Compares converting at a ratio of 1 with and without the passthrough fast path, for a
+//! stream that leaves the ratio of 1 only briefly.
+//!
+//! Run with `cargo bench --bench passthrough`.
+
+extern crate samplerate;
+
+use std::time::{Duration, Instant};
+
+use samplerate::{Converter, Interpolator};
+
+const FRAMES: usize = 480_000;
+const CHUNK_FRAMES: usize = 512;
+
+fn run(interpolator: Interpolator, input: &[f32], ratio: f64, fast_path: bool) -> Duration {
+    let mut converter = Converter::new(interpolator, 2).unwrap();
+    if fast_path {
+        converter.set_passthrough_threshold(Some(1e-9)).unwrap();
+    }
+    let mut output = vec![0.0; (CHUNK_FRAMES * 2 + 16) * 2];
+    let start = Instant::now();
+    for (index, chunk) in input.chunks(CHUNK_FRAMES * 2).enumerate() {
+        let ratio = if index < 900 { 1.0 } else { ratio };
+        let mut rest = chunk;
+        while !rest.is_empty() {
+            let (used, _) = converter.convert(ratio, Some(rest), &mut output).unwrap();
+            rest = &rest[used..];
+        }
+    }
+    start.elapsed()
+}
+
+fn main() {
+    // 10 seconds of stereo at 48 kHz, of which the last half second is rate-matched.
+    let input: Vec<f32> = (0..FRAMES * 2).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+
+    for &interpolator in &[Interpolator::SincBestQuality, Interpolator::SincMediumQuality,
+                           Interpolator::SincFastest, Interpolator::Linear] {
+        println!("{:?}: normal {:?}, fast path {:?}", interpolator,
+                 run(interpolator, &input, 1.001, false), run(interpolator, &input, 1.001, true));
+    }
+}
@@ -0,0 +1,34 @@
+//! Measures converting in very short chunks, where the cost of calling into libsamplerate
+//! rather than the conversion itself dominates. This is what cross-language LTO (the `lto`
+//! feature) can speed up.
+//!
+//! Run with `cargo bench --bench short_calls`, and compare to a run with LTO as described
+//! in the README.
+
+extern crate samplerate;
+
+use std::time::{Duration, Instant};
+
+use samplerate::{Converter, Interpolator};
+
+const FRAMES: usize = 4_800_000;
+
+fn run(interpolator: Interpolator, chunk_frames: usize) -> Duration {
+    let input: Vec<f32> = (0..FRAMES * 2).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+    let mut converter = Converter::new(interpolator, 2).unwrap();
+    let mut output = vec![0.0; (chunk_frames + 2) * 2];
+    let start = Instant::now();
+    for chunk in input.chunks(chunk_frames * 2) {
+        converter.convert(1.0, Some(chunk), &mut output).unwrap();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    for &interpolator in &[Interpolator::Linear, Interpolator::ZeroOrderHold] {
+        for &chunk_frames in &[1, 4, 32, 480] {
+            println!("{:?}, {} frames per call: {:?}", interpolator, chunk_frames,
+                     run(interpolator, chunk_frames));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Compares converting mostly silent input with and without the silence fast path.
+//!
+//! Run with `cargo bench --bench silence_fast_path`.
+
+extern crate samplerate;
+
+use std::time::{Duration, Instant};
+
+use samplerate::{Converter, Interpolator};
+
+const FRAMES: usize = 480_000;
+const CHUNK_FRAMES: usize = 480;
+
+fn run(interpolator: Interpolator, input: &[f32], fast_path: bool) -> Duration {
+    let mut converter = Converter::new(interpolator, 1).unwrap();
+    converter.set_silence_fast_path(fast_path);
+    let mut output = vec![0.0; CHUNK_FRAMES * 3 + 2];
+    let start = Instant::now();
+    for chunk in input.chunks(CHUNK_FRAMES) {
+        converter.convert(3.0, Some(chunk), &mut output).unwrap();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    // 30 seconds at 16 kHz, converted to 48 kHz, with 1.5 seconds of speech every 15 seconds.
+    let speech: Vec<f32> = (0..FRAMES)
+        .map(|i| if i % 240_000 < 24_000 { (i as f32 * 0.05).sin() * 0.5 } else { 0.0 })
+        .collect();
+    let silence = vec![0.0; FRAMES];
+
+    for &interpolator in &[Interpolator::SincBestQuality, Interpolator::SincFastest,
+                           Interpolator::Linear] {
+        for &(name, input) in &[("mostly silent", &speech), ("silent", &silence)] {
+            println!("{:?}, {} input: normal {:?}, fast path {:?}", interpolator, name,
+                     run(interpolator, input, false), run(interpolator, input, true));
+        }
+    }
+}
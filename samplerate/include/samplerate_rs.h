@@ -0,0 +1,256 @@
+#ifndef SAMPLERATE_RS_H
+#define SAMPLERATE_RS_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stddef.h>
+
+/**
+ * The call succeeded.
+ */
+#define SRS_OK 0
+
+/**
+ * A pointer argument was null.
+ */
+#define SRS_ERR_NULL_POINTER -1
+
+/**
+ * The call panicked; the queue or pull converter should only be freed.
+ */
+#define SRS_ERR_PANIC -2
+
+/**
+ * A buffer could not be allocated.
+ */
+#define SRS_ERR_OUT_OF_MEMORY -3
+
+/**
+ * Input was pushed after the stream was flushed.
+ */
+#define SRS_ERR_FLUSHED -4
+
+/**
+ * The interpolator is unknown.
+ */
+#define SRS_ERR_BAD_INTERPOLATOR -5
+
+/**
+ * libsamplerate reported an error other than the ones with codes of their own.
+ */
+#define SRS_ERR_LIBRARY -6
+
+/**
+ * The ratio is out of range.
+ */
+#define SRS_ERR_BAD_RATIO -7
+
+/**
+ * A bounded queue has no room for the input; nothing was queued.
+ */
+#define SRS_ERR_QUEUE_FULL -8
+
+/**
+ * A number of frames is zero where it must not be, or too large to address.
+ */
+#define SRS_ERR_BAD_FRAME_COUNT -9
+
+/**
+ * Fixed-output converter pulling its input from a callback; see ``PullConverter`` in the
+ * Rust documentation.
+ */
+typedef struct SrsPull SrsPull;
+
+/**
+ * Push-queue converter; see ``QueueConverter`` in the Rust documentation.
+ */
+typedef struct SrsQueue SrsQueue;
+
+/**
+ * Callback writing up to ``frames`` interleaved frames of input to ``buffer``, and returning
+ * the number of frames written, or 0 at the end of the stream. ``user_data`` is the pointer
+ * given to ``srs_pull_read``.
+ */
+typedef size_t (*SrsFill)(void *user_data, float *buffer, size_t frames);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a queue converting ``channels`` interleaved channels at ``ratio``, and store it in
+ * ``*queue``. ``interpolator`` is one of libsamplerate's ``SRC_SINC_BEST_QUALITY`` (0) to
+ * ``SRC_LINEAR`` (4). On failure, ``*queue`` is left unchanged.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or valid for writing a pointer.
+ */
+int srs_queue_new(int interpolator, size_t channels, double ratio, SrsQueue **queue);
+
+/**
+ * Create a queue like ``srs_queue_new`` that queues at most ``max_input_frames`` frames of
+ * input, allocated up front. Pushing more than fits fails with ``SRS_ERR_QUEUE_FULL``.
+ *
+ * Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``max_input_frames`` is zero.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or valid for writing a pointer.
+ */
+int srs_queue_new_bounded(int interpolator, size_t channels, double ratio, size_t max_input_frames, SrsQueue **queue);
+
+/**
+ * Queue ``frames`` interleaved frames from ``input`` for conversion. Nothing is queued if
+ * this fails.
+ *
+ * Returns ``SRS_ERR_QUEUE_FULL`` if a bounded queue has no room for all of the input, and
+ * ``SRS_ERR_BAD_FRAME_COUNT`` if ``frames`` is too large to address.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed, and ``input`` must
+ * be valid for reading ``frames`` frames, or may be null if ``frames`` is zero.
+ */
+int srs_queue_push(SrsQueue *queue, const float *input, size_t frames);
+
+/**
+ * Convert queued input into up to ``frames`` interleaved frames at ``output``, storing the
+ * number of frames generated in ``*generated``. Fewer frames are generated only if more
+ * input is needed, or if the stream was flushed and has no more output.
+ *
+ * Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``frames`` is too large to address.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed, ``output`` must be
+ * valid for writing ``frames`` frames, or may be null if ``frames`` is zero, and
+ * ``generated`` must be null or valid for writing.
+ */
+int srs_queue_pop(SrsQueue *queue, float *output, size_t frames, size_t *generated);
+
+/**
+ * Mark the end of the stream, so that the following pops return the rest of the output.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+ */
+int srs_queue_flush(SrsQueue *queue);
+
+/**
+ * Change the conversion ratio, which is interpolated smoothly over the next pop.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+ */
+int srs_queue_set_ratio(SrsQueue *queue, double ratio);
+
+/**
+ * Discard the queued input and reset the internal state, to start a new stream.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+ */
+int srs_queue_reset(SrsQueue *queue);
+
+/**
+ * Retrieve the number of queued input frames not yet used, or 0 if ``queue`` is null.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+ */
+size_t srs_queue_buffered_frames(const SrsQueue *queue);
+
+/**
+ * Check whether the stream was flushed and all of its output was popped, or return 0 if
+ * ``queue`` is null.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+ */
+int srs_queue_is_finished(const SrsQueue *queue);
+
+/**
+ * Free a queue; does nothing if ``queue`` is null.
+ *
+ * # Safety
+ *
+ * ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+ */
+void srs_queue_free(SrsQueue *queue);
+
+/**
+ * Create a fixed-output converter for ``channels`` interleaved channels that pulls at most
+ * ``scratch_frames`` frames of input at once from the callback given to ``srs_pull_read``,
+ * and store it in ``*pull``. ``interpolator`` is as for ``srs_queue_new``. On failure,
+ * ``*pull`` is left unchanged.
+ *
+ * Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``scratch_frames`` is zero.
+ *
+ * # Safety
+ *
+ * ``pull`` must be null or valid for writing a pointer.
+ */
+int srs_pull_new(int interpolator, size_t channels, size_t scratch_frames, SrsPull **pull);
+
+/**
+ * Convert at ``ratio`` into ``frames`` interleaved frames at ``output``, calling
+ * ``fill(user_data, buffer, frames)`` whenever more input is needed, and store the number of
+ * frames generated in ``*generated``. Fewer frames are generated only if the stream has no
+ * more output. Once ``fill`` returns 0, it is not called again until the converter is reset.
+ *
+ * Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``frames`` is too large to address, and
+ * ``SRS_ERR_PANIC`` if ``fill`` returns more frames than it was asked for.
+ *
+ * # Safety
+ *
+ * ``pull`` must be null or returned by ``srs_pull_new`` and not freed, ``output`` must be
+ * valid for writing ``frames`` frames, or may be null if ``frames`` is zero, ``generated``
+ * must be null or valid for writing, and ``fill`` must be null or safe to call with
+ * ``user_data``. ``fill`` must not unwind.
+ */
+int srs_pull_read(SrsPull *pull, double ratio, float *output, size_t frames, SrsFill fill, void *user_data, size_t *generated);
+
+/**
+ * Discard the input pulled so far and reset the internal state, to start a new stream.
+ *
+ * # Safety
+ *
+ * ``pull`` must be null or returned by ``srs_pull_new`` and not freed.
+ */
+int srs_pull_reset(SrsPull *pull);
+
+/**
+ * Check whether ``fill`` returned the end of the stream and all of its output was read, or
+ * return 0 if ``pull`` is null.
+ *
+ * # Safety
+ *
+ * ``pull`` must be null or returned by ``srs_pull_new`` and not freed.
+ */
+int srs_pull_is_finished(const SrsPull *pull);
+
+/**
+ * Free a fixed-output converter; does nothing if ``pull`` is null.
+ *
+ * # Safety
+ *
+ * ``pull`` must be null or returned by ``srs_pull_new`` and not freed.
+ */
+void srs_pull_free(SrsPull *pull);
+
+/**
+ * Describe an error code returned by one of the functions, as a static string.
+ */
+const char *srs_strerror(int code);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SAMPLERATE_RS_H */
@@ -0,0 +1,382 @@
+//! Converter that trades quality for speed to stay within a processing time budget.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::vec::Vec;
+
+use {Clock, Converter, Interpolator, Result, SystemClock, check_frames, try_reserve,
+     try_reserve_deque, try_vec};
+
+/// Interpolators used by [``AdaptiveConverter::new``](struct.AdaptiveConverter.html#method.new),
+/// best first.
+pub const DEFAULT_LEVELS: [Interpolator; 3] =
+    [Interpolator::SincMediumQuality, Interpolator::SincFastest, Interpolator::Linear];
+
+/// Fraction of the budget above which a call makes the converter switch to a faster level.
+pub const DOWNGRADE_LOAD: f64 = 0.9;
+/// Fraction of the budget below which calls count towards switching to a better level.
+pub const UPGRADE_LOAD: f64 = 0.5;
+/// Number of consecutive calls below ``UPGRADE_LOAD`` after which the converter switches to
+/// a better level.
+pub const UPGRADE_CALLS: u32 = 16;
+
+/// Number of output frames over which the output of two levels is crossfaded.
+pub const CROSSFADE_FRAMES: u64 = 256;
+/// Number of recent input frames fed to a new level before it is faded in, so that its
+/// filter state matches the stream.
+const HISTORY_FRAMES: usize = 2048;
+/// Number of output frames generated per call to the underlying converters.
+const SCRATCH_FRAMES: usize = 1024;
+
+/// Record of a switch between levels of an
+/// [``AdaptiveConverter``](struct.AdaptiveConverter.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwitchEvent {
+    /// Index of the output frame at which the crossfade to the new level starts.
+    pub frame: u64,
+    pub from: Interpolator,
+    pub to: Interpolator,
+    /// Processing time of the call that caused the switch, as a fraction of the budget.
+    pub load: f64,
+}
+
+struct Stage {
+    level: usize,
+    converter: Converter,
+    /// Generated samples that have not been emitted yet.
+    pending: VecDeque<f32>,
+    /// Index of the output frame at the front of ``pending``.
+    start: u64,
+}
+
+impl Stage {
+    fn end(&self, channels: usize) -> u64 {
+        self.start + (self.pending.len() / channels) as u64
+    }
+
+    /// Convert all of ``input`` (or flush the converter, if ``None``) into ``pending``.
+    fn run(&mut self, ratio: f64, input: Option<&[f32]>, scratch: &mut [f32]) -> Result<()> {
+        match input {
+            Some(mut input) => loop {
+                // Reserve before converting, so that no generated samples are lost if the
+                // allocation fails.
+                try_reserve_deque(&mut self.pending, scratch.len())?;
+                let (used, generated) = self.converter.convert(ratio, Some(input), scratch)?;
+                self.pending.extend(&scratch[..generated]);
+                input = &input[used..];
+                if input.is_empty() || (used == 0 && generated == 0) {
+                    return Ok(())
+                }
+            },
+            None => loop {
+                try_reserve_deque(&mut self.pending, scratch.len())?;
+                let (_, generated) = self.converter.convert(ratio, None, scratch)?;
+                self.pending.extend(&scratch[..generated]);
+                if generated == 0 {
+                    return Ok(())
+                }
+            },
+        }
+    }
+}
+
+/// Converter that switches between interpolators to keep the processing time of each call
+/// within a budget.
+///
+/// The converter starts at the first (best) of its levels. Whenever a call takes more than
+/// [``DOWNGRADE_LOAD``](constant.DOWNGRADE_LOAD.html) of the budget, it moves to the next,
+/// faster level; after [``UPGRADE_CALLS``](constant.UPGRADE_CALLS.html) consecutive calls
+/// taking less than [``UPGRADE_LOAD``](constant.UPGRADE_LOAD.html) of the budget, it moves
+/// back to the previous level. The gap between the thresholds keeps it from oscillating.
+///
+/// A switch does not cause a discontinuity: the new level is primed with the recent input,
+/// run alongside the old one, and crossfaded in over
+/// [``CROSSFADE_FRAMES``](constant.CROSSFADE_FRAMES.html) output frames. While a crossfade is
+/// in progress, both levels run, so processing time is not evaluated. Since interpolators
+/// have different latencies, the number of frames returned by a call around a switch
+/// differs slightly from the usual; the output stream itself stays continuous.
+pub struct AdaptiveConverter<C: Clock = SystemClock> {
+    levels: Vec<Interpolator>,
+    channels: usize,
+    budget: Duration,
+    clock: C,
+    current: Stage,
+    /// Level being faded in, and the output frame where the crossfade starts.
+    incoming: Option<(Stage, u64)>,
+    history: VecDeque<f32>,
+    /// Number of output frames an ideal converter would have generated so far.
+    ideal_frames: f64,
+    emitted: u64,
+    fast_calls: u32,
+    events: Vec<SwitchEvent>,
+    scratch: Vec<f32>,
+}
+
+impl AdaptiveConverter<SystemClock> {
+    /// Create a converter using [``DEFAULT_LEVELS``](constant.DEFAULT_LEVELS.html) that
+    /// keeps each call within ``budget`` of real time.
+    pub fn new(channels: usize, budget: Duration) -> Result<AdaptiveConverter<SystemClock>> {
+        AdaptiveConverter::with_clock(&DEFAULT_LEVELS, channels, budget, SystemClock::new())
+    }
+}
+
+impl<C: Clock> AdaptiveConverter<C> {
+    /// Create a converter switching between ``levels``, best first, with time measured
+    /// by ``clock``.
+    ///
+    /// Returns an error of kind [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory)
+    /// if the buffers cannot be allocated, or any error of
+    /// [``Converter::new``](struct.Converter.html#method.new).
+    pub fn with_clock(levels: &[Interpolator], channels: usize, budget: Duration, clock: C)
+            -> Result<AdaptiveConverter<C>> {
+        assert!(!levels.is_empty(), "need at least one level");
+        let converter = Converter::new(levels[0], channels)?;
+        let mut owned_levels = Vec::new();
+        try_reserve(&mut owned_levels, levels.len())?;
+        owned_levels.extend_from_slice(levels);
+        Ok(AdaptiveConverter {
+            levels: owned_levels,
+            channels,
+            budget,
+            clock,
+            current: Stage { level: 0, converter, pending: VecDeque::new(), start: 0 },
+            incoming: None,
+            history: VecDeque::new(),
+            ideal_frames: 0.0,
+            emitted: 0,
+            fast_calls: 0,
+            events: Vec::new(),
+            scratch: try_vec(0.0, SCRATCH_FRAMES * channels)?,
+        })
+    }
+
+    /// Retrieve the interpolator in use, or being switched to.
+    pub fn interpolator(&self) -> Interpolator {
+        let stage = self.incoming.as_ref().map(|(stage, _)| stage).unwrap_or(&self.current);
+        self.levels[stage.level]
+    }
+
+    /// Retrieve the switches that happened since the last call to this method.
+    pub fn take_events(&mut self) -> Vec<SwitchEvent> {
+        core::mem::take(&mut self.events)
+    }
+
+    /// Convert all of ``input``, appending the output to ``output``.
+    ///
+    /// The clock is read before and after the conversion. If it panics afterwards, the
+    /// output has already been appended and the converter stays usable; only the outcome
+    /// of that call is not evaluated.
+    ///
+    /// Returns the number of appended samples, or an error of kind
+    /// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) if a buffer cannot grow,
+    /// in which case ``output`` is left unchanged. Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``input`` is not
+    /// a whole number of frames.
+    pub fn convert(&mut self, ratio: f64, input: &[f32], output: &mut Vec<f32>)
+            -> Result<usize> {
+        check_frames(self.channels, input.len(), 0)?;
+        let started = self.clock.now();
+        let len = output.len();
+        self.process(ratio, Some(input), output)?;
+        let elapsed = self.clock.now() - started;
+        self.govern(ratio, elapsed)?;
+        Ok(output.len() - len)
+    }
+
+    /// Flush the internal state, appending the output to ``output``.
+    ///
+    /// Returns the number of appended samples.
+    pub fn flush(&mut self, ratio: f64, output: &mut Vec<f32>) -> Result<usize> {
+        let len = output.len();
+        self.process(ratio, None, output)?;
+        Ok(output.len() - len)
+    }
+
+    fn process(&mut self, ratio: f64, input: Option<&[f32]>, output: &mut Vec<f32>)
+            -> Result<()> {
+        if let Some(input) = input {
+            try_reserve_deque(&mut self.history, input.len())?;
+        }
+        self.current.run(ratio, input, &mut self.scratch)?;
+        if let Some((ref mut stage, _)) = self.incoming {
+            stage.run(ratio, input, &mut self.scratch)?;
+        }
+        if let Some(input) = input {
+            self.history.extend(input);
+            let excess = self.history.len().saturating_sub(HISTORY_FRAMES * self.channels);
+            self.history.drain(..excess);
+            self.ideal_frames += (input.len() / self.channels) as f64 * ratio;
+        }
+        // Every emitted frame is before the end of one of the stages.
+        let channels = self.channels;
+        let end = self.incoming.as_ref().map_or(0, |(stage, _)| stage.end(channels))
+            .max(self.current.end(channels));
+        try_reserve(output, end.saturating_sub(self.emitted) as usize * channels)?;
+        self.emit(input.is_none(), output);
+        Ok(())
+    }
+
+    fn emit(&mut self, end: bool, output: &mut Vec<f32>) {
+        let channels = self.channels;
+        if let Some((mut incoming, fade_start)) = self.incoming.take() {
+            // Output the new level emits for frames that were already emitted is discarded.
+            let stale = self.emitted.saturating_sub(incoming.start)
+                .min(incoming.end(channels) - incoming.start);
+            incoming.pending.drain(..stale as usize * channels);
+            incoming.start += stale;
+
+            let (old_end, new_end) = (self.current.end(channels), incoming.end(channels));
+            let end_frame = if end { old_end.max(new_end) } else { old_end.min(new_end) };
+            let mut complete = false;
+            while self.emitted < end_frame {
+                let frame = self.emitted;
+                let gain = frame.saturating_sub(fade_start) as f32 / CROSSFADE_FRAMES as f32;
+                let has_old = frame < old_end;
+                let has_new = frame >= incoming.start && frame < new_end;
+                for _ in 0..channels {
+                    let old = if has_old { self.current.pending.pop_front() } else { None };
+                    let new = if has_new { incoming.pending.pop_front() } else { None };
+                    output.push(match (old, new) {
+                        (Some(old), Some(new)) => old + (new - old) * gain.min(1.0),
+                        (Some(sample), None) | (None, Some(sample)) => sample,
+                        (None, None) => 0.0,
+                    });
+                }
+                if has_old {
+                    self.current.start += 1;
+                }
+                if has_new {
+                    incoming.start += 1;
+                }
+                self.emitted += 1;
+                if gain >= 1.0 && has_new {
+                    complete = true;
+                    break
+                }
+            }
+            if complete || end {
+                self.current = incoming;
+            } else {
+                self.incoming = Some((incoming, fade_start));
+                return
+            }
+        }
+
+        // Without a crossfade, the current level always starts at the emitted frame.
+        let stale = self.emitted.saturating_sub(self.current.start)
+            .min(self.current.end(channels) - self.current.start);
+        self.current.pending.drain(..stale as usize * channels);
+        self.current.start += stale;
+        self.emitted = self.current.end(channels);
+        self.current.start = self.emitted;
+        output.extend(self.current.pending.drain(..));
+    }
+
+    fn govern(&mut self, ratio: f64, elapsed: Duration) -> Result<()> {
+        if self.incoming.is_some() {
+            self.fast_calls = 0;
+            return Ok(())
+        }
+        let load = elapsed.as_secs_f64() / self.budget.as_secs_f64();
+        let level = self.current.level;
+        if load > DOWNGRADE_LOAD && level + 1 < self.levels.len() {
+            self.fast_calls = 0;
+            self.switch(level + 1, ratio, load)
+        } else if load < UPGRADE_LOAD && level > 0 {
+            self.fast_calls += 1;
+            if self.fast_calls >= UPGRADE_CALLS {
+                self.fast_calls = 0;
+                self.switch(level - 1, ratio, load)
+            } else {
+                Ok(())
+            }
+        } else {
+            self.fast_calls = 0;
+            Ok(())
+        }
+    }
+
+    fn switch(&mut self, level: usize, ratio: f64, load: f64) -> Result<()> {
+        try_reserve(&mut self.events, 1)?;
+        let converter = Converter::new(self.levels[level], self.channels)?;
+        let history_frames = self.history.len() / self.channels;
+        let start = (self.ideal_frames - history_frames as f64 * ratio).round().max(0.0);
+        let mut stage = Stage { level, converter, pending: VecDeque::new(), start: start as u64 };
+        let mut history = Vec::new();
+        try_reserve(&mut history, self.history.len())?;
+        history.extend(self.history.iter());
+        stage.run(ratio, Some(&history), &mut self.scratch)?;
+        self.events.push(SwitchEvent {
+            frame: self.emitted,
+            from: self.levels[self.current.level],
+            to: self.levels[level],
+            load,
+        });
+        self.incoming = Some((stage, self.emitted));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+    use detect_clicks;
+    use super::*;
+
+    /// Clock that advances by a configurable amount whenever it is read.
+    struct ThrottledClock {
+        now: Duration,
+        cost: Rc<Cell<Duration>>,
+    }
+
+    impl Clock for ThrottledClock {
+        fn now(&mut self) -> Duration {
+            self.now += self.cost.get();
+            self.now
+        }
+    }
+
+    #[test]
+    fn test_downgrade_and_recover() {
+        let channels = 2;
+        let ratio = 48000.0 / 44100.0;
+        let input: Vec<f32> = (0..channels * 60000)
+            .map(|i| {
+                let frame = (i / channels) as f32;
+                let fade = (frame.min(59999.0 - frame) / 500.0).min(1.0);
+                (frame * 0.02 + (i % channels) as f32).sin() * 0.7 * fade
+            })
+            .collect();
+
+        let cost = Rc::new(Cell::new(Duration::from_micros(100)));
+        let clock = ThrottledClock { now: Duration::from_secs(0), cost: cost.clone() };
+        let mut conv = AdaptiveConverter::with_clock(&DEFAULT_LEVELS, channels,
+                                                     Duration::from_millis(1), clock).unwrap();
+        let mut output = Vec::new();
+        let mut events = Vec::new();
+        for (index, chunk) in input.chunks(channels * 300).enumerate() {
+            // Calls 20 to 49 take longer than the budget.
+            cost.set(Duration::from_micros(if (20..50).contains(&index) { 1500 } else { 100 }));
+            conv.convert(ratio, chunk, &mut output).unwrap();
+            events.extend(conv.take_events());
+        }
+        conv.flush(ratio, &mut output).unwrap();
+
+        let switches: Vec<_> = events.iter().map(|event| (event.from, event.to)).collect();
+        assert_eq!(switches, [
+            (Interpolator::SincMediumQuality, Interpolator::SincFastest),
+            (Interpolator::SincFastest, Interpolator::Linear),
+            (Interpolator::Linear, Interpolator::SincFastest),
+            (Interpolator::SincFastest, Interpolator::SincMediumQuality),
+        ]);
+        assert!(events[0].load > DOWNGRADE_LOAD);
+        assert!(events[2].load < UPGRADE_LOAD);
+        assert_eq!(conv.interpolator(), Interpolator::SincMediumQuality);
+
+        let expected = (60000.0 * ratio) as i64;
+        assert!(((output.len() / channels) as i64 - expected).abs() <= 4);
+        assert_eq!(detect_clicks(&output, channels).unwrap(), [0usize; 0]);
+    }
+}
@@ -0,0 +1,196 @@
+use alloc::vec::Vec;
+
+use {Converter, Interpolator, Result, try_reserve};
+
+struct Entry {
+    source_rate: u32,
+    channels: usize,
+    converter: Converter,
+    /// Value of the use counter when the converter was last retrieved.
+    last_use: u64,
+}
+
+/// Set of converters to one target sample rate, one for every distinct source sample rate
+/// and channel count, created on first use, e.g. for mixing streams that arrive at arbitrary
+/// sample rates.
+///
+/// All of the streams with the same source sample rate and channel count share a converter,
+/// so a bank is meant for a mixer in which each such configuration carries one stream at a
+/// time. A converter that has not been retrieved in a configurable number of calls is
+/// considered idle and is freed, so configurations that stop arriving do not hold on to
+/// their filter state.
+pub struct RateConverterBank {
+    target_rate: u32,
+    interpolator: Interpolator,
+    idle_limit: Option<u64>,
+    /// Number of converters retrieved so far.
+    uses: u64,
+    entries: Vec<Entry>,
+}
+
+impl RateConverterBank {
+    /// Create an empty bank converting to ``target_rate`` with ``interpolator``, which never
+    /// frees idle converters.
+    pub fn new(target_rate: u32, interpolator: Interpolator) -> RateConverterBank {
+        RateConverterBank { target_rate, interpolator, idle_limit: None, uses: 0,
+                            entries: Vec::new() }
+    }
+
+    /// Retrieve the target sample rate.
+    pub fn target_rate(&self) -> u32 {
+        self.target_rate
+    }
+
+    /// Free converters once they have not been retrieved for ``limit`` calls to
+    /// [``get``](#method.get) or [``convert_from``](#method.convert_from) for other
+    /// configurations, or never if ``limit`` is ``None``.
+    ///
+    /// A freed converter loses its state; if its configuration arrives again, it starts
+    /// over with a new converter.
+    pub fn set_idle_limit(&mut self, limit: Option<u64>) {
+        self.idle_limit = limit;
+    }
+
+    /// Retrieve the number of converters in the bank.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the bank has no converters.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Retrieve the ratio at which streams at ``source_rate`` are converted.
+    pub fn ratio(&self, source_rate: u32) -> f64 {
+        self.target_rate as f64 / source_rate as f64
+    }
+
+    /// Retrieve the converter for streams of ``channels`` channels at ``source_rate``,
+    /// creating it if there is none, and free the converters that have become idle.
+    pub fn get(&mut self, source_rate: u32, channels: usize) -> Result<&mut Converter> {
+        self.uses += 1;
+        if let Some(limit) = self.idle_limit {
+            let uses = self.uses;
+            self.entries.retain(|entry| {
+                (entry.source_rate == source_rate && entry.channels == channels) ||
+                    uses - entry.last_use <= limit
+            });
+        }
+        let index = match self.entries.iter().position(|entry| {
+            entry.source_rate == source_rate && entry.channels == channels
+        }) {
+            Some(index) => index,
+            None => {
+                try_reserve(&mut self.entries, 1)?;
+                let converter = Converter::new(self.interpolator, channels)?;
+                self.entries.push(Entry { source_rate, channels, converter, last_use: 0 });
+                self.entries.len() - 1
+            }
+        };
+        let entry = &mut self.entries[index];
+        entry.last_use = self.uses;
+        Ok(&mut entry.converter)
+    }
+
+    /// Remove the converter for streams of ``channels`` channels at ``source_rate``, e.g.
+    /// once the stream has been flushed, and return it if there was one.
+    pub fn remove(&mut self, source_rate: u32, channels: usize) -> Option<Converter> {
+        let index = self.entries.iter().position(|entry| {
+            entry.source_rate == source_rate && entry.channels == channels
+        })?;
+        Some(self.entries.remove(index).converter)
+    }
+
+    /// Convert the next part of a stream of ``channels`` channels at ``source_rate``, like
+    /// [``Converter::convert``](struct.Converter.html#method.convert) with the converter
+    /// retrieved by [``get``](#method.get).
+    ///
+    /// Returns the number of used input samples and generated output samples, respectively.
+    pub fn convert_from(&mut self, source_rate: u32, channels: usize, input: &[f32],
+                        output: &mut [f32]) -> Result<(usize, usize)> {
+        let ratio = self.ratio(source_rate);
+        self.get(source_rate, channels)?.convert(ratio, Some(input), output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use test::sine;
+    use super::*;
+
+    /// Feed ``input`` through ``convert`` in chunks of ``chunk`` frames, collecting the output.
+    fn stream<F>(input: &[f32], channels: usize, chunk: usize, mut convert: F) -> Vec<f32>
+            where F: FnMut(&[f32], &mut [f32]) -> (usize, usize) {
+        let mut output = Vec::new();
+        let mut scratch = vec![0.0; chunk * channels * 4];
+        for chunk in input.chunks(chunk * channels) {
+            let (used, generated) = convert(chunk, &mut scratch);
+            assert_eq!(used, chunk.len());
+            output.extend_from_slice(&scratch[..generated]);
+        }
+        output
+    }
+
+    #[test]
+    fn test_matches_individual_converters() {
+        let voice = sine(16000, 1);
+        let music = sine(44100, 2);
+        let mut bank = RateConverterBank::new(48000, Interpolator::SincFastest);
+
+        // Both streams arrive interleaved, as a mixer would see them.
+        let (mut voice_out, mut music_out) = (Vec::new(), Vec::new());
+        let mut scratch = vec![0.0; 4096];
+        for (voice, music) in voice.chunks(160).zip(music.chunks(441 * 2)) {
+            let (_, generated) = bank.convert_from(16000, 1, voice, &mut scratch).unwrap();
+            voice_out.extend_from_slice(&scratch[..generated]);
+            let (_, generated) = bank.convert_from(44100, 2, music, &mut scratch).unwrap();
+            music_out.extend_from_slice(&scratch[..generated]);
+        }
+        assert_eq!(bank.len(), 2);
+
+        for &(input, channels, rate, chunk, output) in &[(&voice, 1, 16000, 160, &voice_out),
+                                                         (&music, 2, 44100, 441, &music_out)] {
+            let mut converter = Converter::new(Interpolator::SincFastest, channels).unwrap();
+            let ratio = 48000.0 / rate as f64;
+            let expected = stream(input, channels, chunk, |input, output| {
+                converter.convert(ratio, Some(input), output).unwrap()
+            });
+            assert_eq!(**output, expected);
+        }
+    }
+
+    #[test]
+    fn test_idle_eviction() {
+        let mut bank = RateConverterBank::new(48000, Interpolator::Linear);
+        bank.set_idle_limit(Some(2));
+        let input = [0.5; 64];
+        let mut output = [0.0; 256];
+        bank.convert_from(8000, 1, &input, &mut output).unwrap();
+        bank.convert_from(16000, 1, &input, &mut output).unwrap();
+        bank.convert_from(16000, 1, &input, &mut output).unwrap();
+        assert_eq!(bank.len(), 2);
+        // The 8 kHz converter has now been idle for three calls.
+        bank.convert_from(16000, 2, &input, &mut output).unwrap();
+        assert_eq!(bank.len(), 2);
+        assert!(bank.remove(8000, 1).is_none());
+
+        // A converter that keeps being used survives, and one that comes back starts over.
+        for _ in 0..10 {
+            bank.convert_from(16000, 1, &input, &mut output).unwrap();
+        }
+        assert_eq!(bank.len(), 1);
+        assert_eq!(bank.get(16000, 1).unwrap().frames_in(), 12 * 64);
+        assert_eq!(bank.get(16000, 2).unwrap().frames_in(), 0);
+        assert!(bank.remove(16000, 2).is_some());
+        assert_eq!(bank.len(), 1);
+    }
+
+    #[test]
+    fn test_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<RateConverterBank>();
+    }
+}
@@ -0,0 +1,224 @@
+use std::borrow::ToOwned;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+use std::format;
+
+use hound::{WavReader, WavSpec, WavWriter};
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+
+use Interpolator;
+use wav::{WavError, convert_stream};
+
+/// What [``resample_dir``](fn.resample_dir.html) does with the channels of the input files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelMode {
+    /// Keep the channels of each file.
+    Keep,
+    /// Average the channels of each frame into a single one.
+    Downmix,
+}
+
+/// What [``resample_dir``](fn.resample_dir.html) does when an output file already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingOutput {
+    /// Record the input file as failed, and leave the output file as it is.
+    Fail,
+    /// Record the input file as skipped, and leave the output file as it is.
+    Skip,
+    /// Replace the output file.
+    Overwrite,
+}
+
+/// Options for [``resample_dir``](fn.resample_dir.html).
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Sample rate of the output files.
+    pub sample_rate: u32,
+    pub interpolator: Interpolator,
+    pub channels: ChannelMode,
+    /// Name of each output file, in which ``{stem}`` is replaced with the name of the input
+    /// file without its extension, and ``{rate}`` with the output sample rate.
+    pub file_name: String,
+    pub existing_output: ExistingOutput,
+    /// Largest number of files converted at once, or 0 for as many as there are CPUs.
+    pub max_parallel: usize,
+}
+
+impl BatchOptions {
+    /// Create options converting to ``sample_rate`` with ``SincMediumQuality``, keeping the
+    /// channels and the file names, and failing files whose output already exists.
+    pub fn new(sample_rate: u32) -> BatchOptions {
+        BatchOptions {
+            sample_rate,
+            interpolator: Interpolator::SincMediumQuality,
+            channels: ChannelMode::Keep,
+            file_name: String::from("{stem}.wav"),
+            existing_output: ExistingOutput::Fail,
+            max_parallel: 0,
+        }
+    }
+
+    fn output_name(&self, stem: &str) -> String {
+        self.file_name.replace("{stem}", stem).replace("{rate}", &self.sample_rate.to_string())
+    }
+}
+
+/// File converted by [``resample_dir``](fn.resample_dir.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertedFile {
+    pub input: PathBuf,
+    pub output: PathBuf,
+    pub frames_in: u64,
+    pub frames_out: u64,
+}
+
+/// File that [``resample_dir``](fn.resample_dir.html) could not convert, and why.
+#[derive(Debug)]
+pub struct FailedFile {
+    pub input: PathBuf,
+    pub error: WavError,
+}
+
+/// Outcome of [``resample_dir``](fn.resample_dir.html). Each list is sorted by the path of
+/// the input file.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub converted: Vec<ConvertedFile>,
+    /// Input files skipped because their output already exists.
+    pub skipped: Vec<PathBuf>,
+    pub failed: Vec<FailedFile>,
+    /// Number of frames read from the converted files.
+    pub total_frames: u64,
+}
+
+enum Outcome {
+    Converted(ConvertedFile),
+    Skipped(PathBuf),
+    Failed(FailedFile),
+}
+
+/// Resample every WAV file in ``input_dir`` into ``output_dir`` according to ``options``,
+/// converting several files at once.
+///
+/// Input files are those directly in ``input_dir`` with a ``.wav`` extension, in any case.
+/// ``output_dir`` is created if it does not exist, and may be the same as ``input_dir``.
+/// Each file is converted like with [``resample_wav_file``](fn.resample_wav_file.html), in
+/// chunks and without its loop points, and written under a temporary name that is renamed to
+/// the output name once it is complete; a file that fails leaves no output behind. A file
+/// failing does not stop the others from being converted.
+///
+/// Returns an error only if ``input_dir`` cannot be listed, ``output_dir`` cannot be
+/// created, or the worker threads cannot be started; the outcome for each file is listed in
+/// the report.
+pub fn resample_dir<P: AsRef<Path>, Q: AsRef<Path>>(input_dir: P, output_dir: Q,
+                                                    options: &BatchOptions)
+        -> Result<BatchReport, WavError> {
+    let (input_dir, output_dir) = (input_dir.as_ref(), output_dir.as_ref());
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(input_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let is_wav = path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"));
+        if is_wav && entry.file_type()?.is_file() {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+    fs::create_dir_all(output_dir)?;
+
+    // Resolve the output names up front, so that files whose names collide fail instead of
+    // overwriting each other.
+    let mut claimed: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let jobs: Vec<(PathBuf, Result<PathBuf, io::Error>)> = inputs.into_iter()
+        .map(|input| {
+            let stem = input.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            let output = output_dir.join(options.output_name(&stem));
+            let output = match claimed.get(&output) {
+                Some(first) => Err(io::Error::new(io::ErrorKind::AlreadyExists, format!(
+                    "output {} is also the output of {}", output.display(),
+                    first.display()))),
+                None => {
+                    claimed.insert(output.clone(), input.clone());
+                    Ok(output)
+                }
+            };
+            (input, output)
+        })
+        .collect();
+
+    let pool = ThreadPoolBuilder::new().num_threads(options.max_parallel).build()
+        .map_err(io::Error::other)?;
+    let outcomes: Vec<Outcome> = pool.install(|| {
+        jobs.into_par_iter()
+            .map(|(input, output)| match output {
+                Ok(output) => convert_file(input, output, options),
+                Err(error) => Outcome::Failed(FailedFile { input, error: error.into() }),
+            })
+            .collect()
+    });
+
+    let mut report = BatchReport::default();
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Converted(file) => {
+                report.total_frames += file.frames_in;
+                report.converted.push(file);
+            }
+            Outcome::Skipped(input) => report.skipped.push(input),
+            Outcome::Failed(file) => report.failed.push(file),
+        }
+    }
+    Ok(report)
+}
+
+fn convert_file(input: PathBuf, output: PathBuf, options: &BatchOptions) -> Outcome {
+    if output.exists() {
+        match options.existing_output {
+            ExistingOutput::Fail => {
+                let error = io::Error::new(io::ErrorKind::AlreadyExists,
+                                           format!("output {} exists", output.display()));
+                return Outcome::Failed(FailedFile { input, error: error.into() })
+            }
+            ExistingOutput::Skip => return Outcome::Skipped(input),
+            ExistingOutput::Overwrite => (),
+        }
+    }
+
+    let mut partial_name = output.file_name().unwrap_or_default().to_owned();
+    partial_name.push(".partial");
+    let partial = output.with_file_name(partial_name);
+    let result = write_file(&input, &partial, options)
+        .and_then(|frames| fs::rename(&partial, &output).map(|()| frames).map_err(Into::into));
+    match result {
+        Ok((frames_in, frames_out)) =>
+            Outcome::Converted(ConvertedFile { input, output, frames_in, frames_out }),
+        Err(error) => {
+            let _ = fs::remove_file(&partial);
+            Outcome::Failed(FailedFile { input, error })
+        }
+    }
+}
+
+fn write_file(input: &Path, output: &Path, options: &BatchOptions)
+        -> Result<(u64, u64), WavError> {
+    let mut reader = WavReader::open(input)?;
+    let spec = reader.spec();
+    let downmix = options.channels == ChannelMode::Downmix && spec.channels > 1;
+    let out_spec = WavSpec {
+        sample_rate: options.sample_rate,
+        channels: if downmix { 1 } else { spec.channels },
+        ..spec
+    };
+    let ratio = options.sample_rate as f64 / spec.sample_rate as f64;
+    let mut writer = WavWriter::create(output, out_spec)?;
+    let frames = convert_stream(&mut reader, &mut writer, options.interpolator, ratio,
+                                downmix)?;
+    writer.finalize()?;
+    Ok(frames)
+}
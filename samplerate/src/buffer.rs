@@ -0,0 +1,156 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use time::duration_for_frames;
+use {Interpolator, Result, check_frames, convert_to_vec};
+
+/// Interleaved audio that knows its own sample rate and channel count.
+///
+/// This is a convenience for small tools, so that resampling audio is a single call instead
+/// of threading the rate and the channel count through every function; it is deliberately
+/// minimal, and not meant as the basis for an audio framework. Everything it does can be
+/// done with [``convert``](fn.convert.html) directly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioBuffer {
+    data: Vec<f32>,
+    channels: usize,
+    rate: u32,
+}
+
+impl AudioBuffer {
+    /// Create a buffer from interleaved ``data`` with ``channels`` channels at ``rate`` Hz.
+    ///
+    /// Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``channels`` is
+    /// zero or ``data`` is not a whole number of frames.
+    ///
+    /// Panics if ``rate`` is zero.
+    pub fn from_interleaved(data: Vec<f32>, channels: usize, rate: u32)
+            -> Result<AudioBuffer> {
+        assert!(rate > 0, "sample rate must be non-zero");
+        check_frames(channels, data.len(), 0)?;
+        Ok(AudioBuffer { data, channels, rate })
+    }
+
+    /// Create a buffer by interleaving ``planes``, one per channel, at ``rate`` Hz.
+    ///
+    /// Panics if there are no planes, if they differ in length, or if ``rate`` is zero.
+    pub fn from_planar<P: AsRef<[f32]>>(planes: &[P], rate: u32) -> AudioBuffer {
+        assert!(!planes.is_empty(), "channel count must be non-zero");
+        let frames = planes[0].as_ref().len();
+        assert!(planes.iter().all(|plane| plane.as_ref().len() == frames),
+                "planes must have the same length");
+        assert!(rate > 0, "sample rate must be non-zero");
+        let data = (0..frames)
+            .flat_map(|frame| planes.iter().map(move |plane| plane.as_ref()[frame]))
+            .collect();
+        AudioBuffer { data, channels: planes.len(), rate }
+    }
+
+    /// Retrieve the interleaved samples.
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Unwrap the interleaved samples.
+    pub fn into_data(self) -> Vec<f32> {
+        self.data
+    }
+
+    /// Retrieve the samples of each channel.
+    pub fn to_planar(&self) -> Vec<Vec<f32>> {
+        (0..self.channels)
+            .map(|channel| self.data.iter().skip(channel).step_by(self.channels).copied()
+                           .collect())
+            .collect()
+    }
+
+    /// Retrieve the number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Retrieve the sample rate, in Hz.
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Retrieve the number of frames.
+    pub fn frames(&self) -> usize {
+        self.data.len() / self.channels
+    }
+
+    /// Retrieve the duration, rounded up to the next nanosecond.
+    pub fn duration(&self) -> Duration {
+        duration_for_frames(self.rate, self.frames() as u64)
+    }
+
+    /// Convert the buffer to ``target_rate`` Hz with ``interpolator``, flushing the
+    /// converter at the end, like [``convert``](fn.convert.html).
+    ///
+    /// Returns an error of kind [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory)
+    /// if the output cannot be allocated, and an error from libsamplerate if the rates are
+    /// more than 256 times apart.
+    pub fn resample(&self, target_rate: u32, interpolator: Interpolator)
+            -> Result<AudioBuffer> {
+        let ratio = target_rate as f64 / self.rate as f64;
+        let data = convert_to_vec(interpolator, self.channels, ratio, &self.data)?;
+        Ok(AudioBuffer { data, channels: self.channels, rate: target_rate })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec;
+    use super::*;
+    use {ErrorKind, convert};
+
+    fn make_planes(frames: usize) -> Vec<Vec<f32>> {
+        vec![(0..frames).map(|n| (n as f32 * 0.01).sin() * 0.5).collect(),
+             (0..frames).map(|n| (n as f32 * 0.03).cos() * 0.25).collect()]
+    }
+
+    #[test]
+    fn test_metadata() {
+        let planes = make_planes(44100);
+        let buffer = AudioBuffer::from_planar(&planes, 44100);
+        assert_eq!((buffer.channels(), buffer.rate(), buffer.frames()), (2, 44100, 44100));
+        assert_eq!(buffer.duration(), Duration::from_secs(1));
+        assert_eq!(buffer.data()[..4], [planes[0][0], planes[1][0], planes[0][1], planes[1][1]]);
+        assert_eq!(buffer.to_planar(), planes);
+
+        let resampled = buffer.resample(48000, Interpolator::Linear).unwrap();
+        assert_eq!((resampled.channels(), resampled.rate()), (2, 48000));
+        assert!(resampled.duration() >= Duration::from_millis(999) &&
+                resampled.duration() <= Duration::from_millis(1001));
+        assert!(buffer.resample(1, Interpolator::Linear).is_err());
+
+        let empty = AudioBuffer::from_interleaved(Vec::new(), 1, 8000).unwrap();
+        assert_eq!(empty.duration(), Duration::from_secs(0));
+        assert_eq!(empty.to_planar(), [Vec::<f32>::new()]);
+
+        let error = AudioBuffer::from_interleaved(vec![0.0; 3], 2, 8000).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 2 });
+        let error = AudioBuffer::from_interleaved(vec![0.0; 3], 0, 8000).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 0 });
+    }
+
+    #[test]
+    fn test_resample_matches_convert() {
+        let planes = make_planes(5000);
+        let buffer = AudioBuffer::from_planar(&planes, 48000);
+        let resampled = buffer.resample(44100, Interpolator::SincFastest).unwrap();
+
+        let mut expected = vec![0.0; 2 * 5000];
+        let (_, generated) = convert(Interpolator::SincFastest, 2, 44100.0 / 48000.0,
+                                     buffer.data(), &mut expected).unwrap();
+        expected.truncate(generated);
+        assert_eq!(resampled.into_data(), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "planes must have the same length")]
+    fn test_unequal_planes() {
+        AudioBuffer::from_planar(&[vec![0.0; 10], vec![0.0; 9]], 48000);
+    }
+}
@@ -0,0 +1,211 @@
+use alloc::vec::Vec;
+
+use {Converter, ConverterInternals, Error, ErrorInternals, Interpolator, Result, check_ratio,
+     try_reserve};
+
+/// A [``Converter``](struct.Converter.html) that takes all of the input it is given, keeping
+/// whatever libsamplerate does not use yet for the next call.
+///
+/// Unlike with [``QueueConverter``](struct.QueueConverter.html), input is converted as it is
+/// pushed, into an output buffer supplied with it. If that buffer is too small for all of the
+/// output, the rest of the input stays pending; see
+/// [``pending_input_frames``](#method.pending_input_frames).
+pub struct BufferedConverter {
+    converter: Converter,
+    ratio: f64,
+    pending: Vec<f32>,
+}
+
+impl BufferedConverter {
+    /// Create a converter for ``channels`` interleaved channels at ``ratio``.
+    pub fn new(interpolator: Interpolator, channels: usize, ratio: f64)
+            -> Result<BufferedConverter> {
+        check_ratio(ratio)?;
+        Ok(BufferedConverter {
+            converter: Converter::new(interpolator, channels)?,
+            ratio,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        self.converter.channels()
+    }
+
+    /// Retrieve the conversion ratio.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Change the conversion ratio, which is interpolated smoothly over the next push, like
+    /// with [``Converter::convert``](struct.Converter.html#method.convert).
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        check_ratio(ratio)?;
+        self.ratio = ratio;
+        Ok(())
+    }
+
+    /// Retrieve the number of input frames pushed and not yet used by libsamplerate.
+    ///
+    /// A handful of frames is always held back by the interpolator; more than that means
+    /// that the last output buffer was too small, and the next push or flush converts them.
+    pub fn pending_input_frames(&self) -> usize {
+        self.pending.len() / self.channels()
+    }
+
+    /// Take all of ``input`` and convert as much of it as fits into ``output``, together
+    /// with the input pending from the previous pushes.
+    ///
+    /// Returns the number of generated samples. Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if either buffer
+    /// is not a whole number of frames, or of kind
+    /// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) if the input cannot be
+    /// buffered; in both cases none of ``input`` is taken.
+    pub fn push(&mut self, input: &[f32], output: &mut [f32]) -> Result<usize> {
+        let channels = self.channels();
+        for len in [input.len(), output.len()] {
+            if len % channels != 0 {
+                return Err(Error::bad_buffer_length(len, channels))
+            }
+        }
+        try_reserve(&mut self.pending, input.len())?;
+        self.pending.extend_from_slice(input);
+        self.convert(false, output)
+    }
+
+    /// Convert the pending input into ``output`` and then flush the internal state, at the
+    /// end of the stream.
+    ///
+    /// Returns the number of generated samples, which is less than the length of ``output``
+    /// only if the stream has no more output. Call it again until then, and
+    /// [``reset``](#method.reset) the converter before pushing a new stream.
+    pub fn flush(&mut self, output: &mut [f32]) -> Result<usize> {
+        self.convert(true, output)
+    }
+
+    /// Discard the pending input and reset the internal state, to start a new stream.
+    pub fn reset(&mut self) -> Result<()> {
+        self.converter.reset()?;
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn convert(&mut self, end: bool, output: &mut [f32]) -> Result<usize> {
+        let mut generated = 0;
+        while generated < output.len() {
+            let (used, g) = self.converter.process(self.ratio, &self.pending, end,
+                                                   &mut output[generated..])?;
+            self.pending.drain(..used);
+            generated += g;
+            if used == 0 && g == 0 {
+                break
+            }
+        }
+        Ok(generated)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use {ErrorKind, convert};
+    use test::sine;
+    use super::*;
+
+    /// Push ``input`` in chunks of ``chunk`` samples and flush, converting into ``block``.
+    fn stream(conv: &mut BufferedConverter, input: &[f32], chunk: usize, block: &mut [f32])
+            -> Vec<f32> {
+        let mut output = Vec::new();
+        for chunk in input.chunks(chunk) {
+            let generated = conv.push(chunk, block).unwrap();
+            output.extend_from_slice(&block[..generated]);
+        }
+        loop {
+            let generated = conv.flush(block).unwrap();
+            output.extend_from_slice(&block[..generated]);
+            if generated < block.len() {
+                return output
+            }
+        }
+    }
+
+    /// Drop the frame that the flush converts from the last input frame, which a one-shot
+    /// conversion with the linear interpolator reports as unused and never converts.
+    fn trim_last_frame(interpolator: Interpolator, output: &mut Vec<f32>, channels: usize) {
+        if interpolator == Interpolator::Linear {
+            let len = output.len();
+            output.truncate(len - channels);
+        }
+    }
+
+    #[test]
+    fn test_matches_one_shot() {
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            let input = sine(5000, 2);
+            let mut expected = vec![0.0; 2 * 7600];
+            let (_, generated) = convert(interpolator, 2, 1.5, &input, &mut expected).unwrap();
+            expected.truncate(generated);
+
+            // Chunks of an odd number of samples are not whole frames, so use one channel.
+            let mono: Vec<f32> = input.iter().step_by(2).cloned().collect();
+            let mut mono_expected = vec![0.0; 7600];
+            let (_, generated) = convert(interpolator, 1, 1.5, &mono, &mut mono_expected)
+                .unwrap();
+            mono_expected.truncate(generated);
+
+            for &chunk in &[7, 13, 501] {
+                let mut conv = BufferedConverter::new(interpolator, 1, 1.5).unwrap();
+                let mut output = stream(&mut conv, &mono, chunk, &mut [0.0; 1024]);
+                trim_last_frame(interpolator, &mut output, 1);
+                assert_audio_eq!(output, mono_expected, 1e-6, channels = 1);
+                assert_eq!(conv.pending_input_frames(), 0);
+
+                let mut conv = BufferedConverter::new(interpolator, 2, 1.5).unwrap();
+                let mut output = stream(&mut conv, &input, 2 * chunk, &mut [0.0; 1024]);
+                trim_last_frame(interpolator, &mut output, 2);
+                assert_audio_eq!(output, expected, 1e-6, channels = 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_output() {
+        let input = sine(5000, 2);
+        let mut expected = vec![0.0; 2 * 7600];
+        let (_, generated) = convert(Interpolator::SincFastest, 2, 1.5, &input, &mut expected)
+            .unwrap();
+        expected.truncate(generated);
+
+        // The output holds fewer frames than each push generates, so input piles up.
+        let mut conv = BufferedConverter::new(Interpolator::SincFastest, 2, 1.5).unwrap();
+        let mut block = [0.0; 2 * 100];
+        let mut output = Vec::new();
+        for chunk in input.chunks(2 * 200) {
+            let generated = conv.push(chunk, &mut block).unwrap();
+            assert_eq!(generated, block.len());
+            output.extend_from_slice(&block[..generated]);
+        }
+        assert!(conv.pending_input_frames() > 1000, "{}", conv.pending_input_frames());
+        output.extend(stream(&mut conv, &[], 2, &mut block));
+        assert_audio_eq!(output, expected, 1e-6, channels = 2);
+
+        // Resetting discards the pending input.
+        conv.push(&input[..2000], &mut []).unwrap();
+        assert_eq!(conv.pending_input_frames(), 1000);
+        conv.reset().unwrap();
+        assert_eq!(conv.pending_input_frames(), 0);
+        assert_eq!(stream(&mut conv, &input, 2 * 333, &mut block), output);
+    }
+
+    #[test]
+    fn test_bad_buffer_length() {
+        let mut conv = BufferedConverter::new(Interpolator::Linear, 2, 1.5).unwrap();
+        let error = conv.push(&[0.0; 3], &mut [0.0; 8]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 2 });
+        let error = conv.push(&[0.0; 4], &mut [0.0; 7]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 7, channels: 2 });
+        assert_eq!(conv.pending_input_frames(), 0);
+    }
+}
@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+use std::vec::Vec;
+
+use {Converter, ConverterInternals, Interpolator, Result};
+
+/// Maximum number of converter states kept by [``convert_cached``](fn.convert_cached.html)
+/// in each thread. When the limit is reached, the least recently used state is freed.
+pub const MAX_CACHED_CONVERTERS: usize = 4;
+
+std::thread_local! {
+    static CONVERTERS: RefCell<Vec<(Interpolator, usize, Converter)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Perform a single conversion from input buffer to output buffer with a fixed conversion
+/// ratio, reusing converter state between calls.
+///
+/// This function produces exactly the same output as [``convert``](fn.convert.html), but
+/// instead of creating and destroying a converter on every call, it keeps up to
+/// [``MAX_CACHED_CONVERTERS``](constant.MAX_CACHED_CONVERTERS.html) converter states per
+/// thread, keyed by interpolator and channel count, and resets them before use. This saves
+/// allocating the filter state, which is noticeable when converting many small buffers
+/// (see ``benches/convert_cached.rs``); resetting still clears the sinc filter history, so
+/// the saving shrinks as buffers grow.
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_cached(interpolator: Interpolator, channels: usize, ratio: f64,
+                      input: &[f32], output: &mut [f32]) -> Result<(usize, usize)> {
+    let cached = CONVERTERS.with(|converters| {
+        let mut converters = converters.borrow_mut();
+        converters.iter()
+            .position(|&(i, c, _)| i == interpolator && c == channels)
+            .map(|index| converters.remove(index).2)
+    });
+    let mut converter = match cached {
+        Some(mut converter) => {
+            converter.reset()?;
+            converter
+        }
+        None => Converter::new(interpolator, channels)?
+    };
+
+    let result = converter.process(ratio, input, /*end=*/true, output);
+
+    CONVERTERS.with(|converters| {
+        let mut converters = converters.borrow_mut();
+        if converters.len() == MAX_CACHED_CONVERTERS {
+            converters.remove(0);
+        }
+        converters.push((interpolator, channels, converter));
+    });
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use convert;
+    use super::*;
+
+    fn cached_count() -> usize {
+        CONVERTERS.with(|converters| converters.borrow().len())
+    }
+
+    #[test]
+    fn test_convert_cached_matches_convert() {
+        let input: Vec<f32> = (0..600).map(|i| (i as f32 * 0.05).sin()).collect();
+        for _ in 0..3 {
+            for &(interpolator, channels, ratio) in &[
+                (Interpolator::SincFastest, 1, 2.0),
+                (Interpolator::SincBestQuality, 2, 0.5),
+                (Interpolator::Linear, 3, 1.5),
+                (Interpolator::SincFastest, 2, 44100.0 / 48000.0),
+            ] {
+                let mut expected = vec![0.; 1500];
+                let expected_result = convert(interpolator, channels, ratio,
+                                              &input, &mut expected).unwrap();
+                let mut output = vec![0.; 1500];
+                assert_eq!(convert_cached(interpolator, channels, ratio,
+                                          &input, &mut output).unwrap(),
+                           expected_result);
+                assert_eq!(output, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_cached_limit() {
+        let input = [0.0; 60];
+        let mut output = [0.0; 120];
+        for channels in 1..=(MAX_CACHED_CONVERTERS + 2) {
+            convert_cached(Interpolator::Linear, channels, 1.0,
+                           &input[..channels * 10], &mut output).unwrap();
+        }
+        assert_eq!(cached_count(), MAX_CACHED_CONVERTERS);
+    }
+
+    #[test]
+    fn test_convert_cached_error() {
+        assert!(convert_cached(Interpolator::Linear, 1, 1000.0, &[0.0], &mut [0.0]).is_err());
+        assert!(convert_cached(Interpolator::Linear, 1, 1.0, &[0.0], &mut [0.0]).is_ok());
+    }
+}
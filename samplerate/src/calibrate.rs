@@ -0,0 +1,195 @@
+//! Selection of an interpolator by timing each of them on the device at hand.
+
+use std::time::Duration;
+use std::vec::Vec;
+
+use {Clock, Converter, Interpolator, Result, SystemClock};
+
+/// Interpolators timed by [``Calibration::measure``](struct.Calibration.html#method.measure),
+/// best first.
+pub const CALIBRATION_LEVELS: [Interpolator; 5] = [
+    Interpolator::SincBestQuality,
+    Interpolator::SincMediumQuality,
+    Interpolator::SincFastest,
+    Interpolator::Linear,
+    Interpolator::ZeroOrderHold,
+];
+
+/// Number of blocks each interpolator converts while it is timed, after one untimed block
+/// that warms up the caches.
+pub const CALIBRATION_BLOCKS: usize = 16;
+
+/// Fraction of the budget that [``calibrate``](fn.calibrate.html) leaves unused, to absorb
+/// the jitter of a real audio callback.
+pub const DEFAULT_SAFETY_MARGIN: f64 = 0.25;
+
+/// Time an interpolator took to convert a block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterpolatorTiming {
+    pub interpolator: Interpolator,
+    /// Median time per block.
+    pub median: Duration,
+    /// Longest time per block.
+    pub max: Duration,
+}
+
+/// Timings of every interpolator for one configuration, e.g. measured at startup and cached
+/// by the application across runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    timings: Vec<InterpolatorTiming>,
+}
+
+/// Fill ``buffer`` with white noise, so that no converter takes a fast path for silence.
+fn make_noise(buffer: &mut [f32]) {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for sample in buffer {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        *sample = (state >> 40) as f32 / (1u64 << 23) as f32 - 1.0;
+    }
+}
+
+impl Calibration {
+    /// Time every interpolator in [``CALIBRATION_LEVELS``](constant.CALIBRATION_LEVELS.html)
+    /// converting blocks of ``block_frames`` output frames of ``channels`` channels at
+    /// ``ratio``.
+    pub fn measure(channels: usize, ratio: f64, block_frames: usize) -> Result<Calibration> {
+        Calibration::measure_with_clock(channels, ratio, block_frames, &mut SystemClock::new())
+    }
+
+    /// Like [``measure``](#method.measure), with time measured by ``clock``.
+    ///
+    /// The clock is read right before and right after each timed block, for
+    /// [``CALIBRATION_BLOCKS``](constant.CALIBRATION_BLOCKS.html) blocks of each
+    /// interpolator in turn.
+    pub fn measure_with_clock<C: Clock>(channels: usize, ratio: f64, block_frames: usize,
+                                        clock: &mut C) -> Result<Calibration> {
+        let input_frames = (block_frames as f64 / ratio) as usize + 2;
+        let mut input = std::vec![0.0; input_frames * channels];
+        make_noise(&mut input);
+        let mut output = std::vec![0.0; block_frames * channels];
+
+        let mut timings = Vec::with_capacity(CALIBRATION_LEVELS.len());
+        for &interpolator in &CALIBRATION_LEVELS {
+            let mut converter = Converter::new(interpolator, channels)?;
+            converter.convert(ratio, Some(&input), &mut output)?;
+            let mut times = [Duration::ZERO; CALIBRATION_BLOCKS];
+            for time in &mut times {
+                let start = clock.now();
+                converter.convert(ratio, Some(&input), &mut output)?;
+                *time = clock.now().saturating_sub(start);
+            }
+            times.sort();
+            timings.push(InterpolatorTiming {
+                interpolator,
+                median: times[CALIBRATION_BLOCKS / 2],
+                max: times[CALIBRATION_BLOCKS - 1],
+            });
+        }
+        Ok(Calibration { timings })
+    }
+
+    /// Create a calibration from timings measured earlier, best interpolator first.
+    pub fn from_timings(timings: Vec<InterpolatorTiming>) -> Calibration {
+        Calibration { timings }
+    }
+
+    /// Retrieve the timings, best interpolator first.
+    pub fn timings(&self) -> &[InterpolatorTiming] {
+        &self.timings
+    }
+
+    /// Retrieve the best interpolator whose median time per block fits in ``budget`` with
+    /// ``safety_margin`` of it left unused, or the fastest one if none does.
+    pub fn select(&self, budget: Duration, safety_margin: f64) -> Interpolator {
+        let limit = budget.mul_f64((1.0 - safety_margin).max(0.0));
+        self.timings.iter()
+            .find(|timing| timing.median <= limit)
+            .or(self.timings.last())
+            .map_or(Interpolator::ZeroOrderHold, |timing| timing.interpolator)
+    }
+}
+
+/// Retrieve the best interpolator that converts blocks of ``block_frames`` output frames of
+/// ``channels`` channels at ``ratio`` within ``realtime_budget`` per block on this device,
+/// leaving [``DEFAULT_SAFETY_MARGIN``](constant.DEFAULT_SAFETY_MARGIN.html) of it unused.
+///
+/// Every interpolator is timed on a few blocks of synthetic audio, which takes about
+/// [``CALIBRATION_BLOCKS``](constant.CALIBRATION_BLOCKS.html) blocks worth of processing
+/// time per interpolator. Use [``Calibration``](struct.Calibration.html) to keep the
+/// timings, e.g. to cache them or to choose a different margin.
+pub fn calibrate(channels: usize, ratio: f64, realtime_budget: Duration,
+                 block_frames: usize) -> Result<Interpolator> {
+    Ok(Calibration::measure(channels, ratio, block_frames)?
+        .select(realtime_budget, DEFAULT_SAFETY_MARGIN))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Clock charging a fixed time per timed block, which depends on the interpolator.
+    struct ScriptedClock {
+        now: Duration,
+        reads: usize,
+        costs: [Duration; 5],
+    }
+
+    impl Clock for ScriptedClock {
+        fn now(&mut self) -> Duration {
+            // Each interpolator reads the clock twice per block.
+            let level = self.reads / (2 * CALIBRATION_BLOCKS);
+            if self.reads % 2 == 1 {
+                self.now += self.costs[level];
+            }
+            self.reads += 1;
+            self.now
+        }
+    }
+
+    fn scripted() -> Calibration {
+        let costs = [8, 4, 2, 1, 0].map(|ms| Duration::from_micros(ms * 1000 + 100));
+        let mut clock = ScriptedClock { now: Duration::ZERO, reads: 0, costs };
+        let calibration = Calibration::measure_with_clock(2, 48000.0 / 44100.0, 256,
+                                                          &mut clock).unwrap();
+        assert_eq!(clock.reads, 2 * CALIBRATION_BLOCKS * CALIBRATION_LEVELS.len());
+        calibration
+    }
+
+    #[test]
+    fn test_select() {
+        let calibration = scripted();
+        let levels: Vec<_> = calibration.timings().iter().map(|t| t.interpolator).collect();
+        assert_eq!(levels, CALIBRATION_LEVELS);
+        assert_eq!(calibration.timings()[1].median, Duration::from_micros(4100));
+        assert_eq!(calibration.timings()[1].max, Duration::from_micros(4100));
+
+        let select = |ms, margin| calibration.select(Duration::from_millis(ms), margin);
+        assert_eq!(select(100, DEFAULT_SAFETY_MARGIN), Interpolator::SincBestQuality);
+        assert_eq!(select(5, 0.0), Interpolator::SincMediumQuality);
+        // A quarter of 5 ms is kept free, which leaves less than 4.1 ms.
+        assert_eq!(select(5, DEFAULT_SAFETY_MARGIN), Interpolator::SincFastest);
+        assert_eq!(select(2, 0.0), Interpolator::Linear);
+        // Nothing fits, so the fastest interpolator is the best that can be done.
+        assert_eq!(select(0, 0.0), Interpolator::ZeroOrderHold);
+
+        let cached = Calibration::from_timings(calibration.timings().to_vec());
+        assert_eq!(cached, calibration);
+    }
+
+    #[test]
+    fn test_measure() {
+        let calibration = Calibration::measure(2, 48000.0 / 44100.0, 64).unwrap();
+        for timing in calibration.timings() {
+            assert!(timing.median <= timing.max, "{:?}", timing);
+        }
+        let best = &calibration.timings()[0];
+        let fastest = &calibration.timings()[CALIBRATION_LEVELS.len() - 1];
+        assert!(best.median > fastest.median, "{:?}", calibration);
+        assert_eq!(calibrate(2, 48000.0 / 44100.0, Duration::from_secs(1), 64).unwrap(),
+                   Interpolator::SincBestQuality);
+        assert!(calibrate(2, 1000.0, Duration::from_secs(1), 64).is_err());
+    }
+}
@@ -0,0 +1,558 @@
+//! C interface to [``QueueConverter``](../struct.QueueConverter.html) and
+//! [``PullConverter``](../struct.PullConverter.html), for programs that mix C or C++ with
+//! Rust.
+//!
+//! The declarations are in ``include/samplerate_rs.h``, generated from this module with
+//! ``cbindgen --config cbindgen.toml --output include/samplerate_rs.h src/capi.rs`` in the
+//! ``samplerate`` directory. To link from C, build a static or dynamic library, e.g. with
+//! ``cargo rustc -p samplerate --release --features capi --crate-type staticlib``.
+//!
+//! Every function returns ``SRS_OK`` or one of the negative ``SRS_ERR_*`` codes, and
+//! describes the code with ``srs_strerror``. Panics do not unwind into the caller: they are
+//! caught and reported as ``SRS_ERR_PANIC``, after which the queue or pull converter should
+//! only be freed.
+//! This requires the default ``panic = "unwind"``; with ``panic = "abort"``, a panic aborts
+//! the process.
+//!
+//! A queue or pull converter must not be used from several threads at once, but may be moved
+//! between threads.
+
+use std::boxed::Box;
+use std::ffi::{c_char, c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use {ErrorKind, Interpolator, PullConverter, QueueConverter, Result};
+
+/// The call succeeded.
+pub const SRS_OK: c_int = 0;
+/// A pointer argument was null.
+pub const SRS_ERR_NULL_POINTER: c_int = -1;
+/// The call panicked; the queue or pull converter should only be freed.
+pub const SRS_ERR_PANIC: c_int = -2;
+/// A buffer could not be allocated.
+pub const SRS_ERR_OUT_OF_MEMORY: c_int = -3;
+/// Input was pushed after the stream was flushed.
+pub const SRS_ERR_FLUSHED: c_int = -4;
+/// The interpolator is unknown.
+pub const SRS_ERR_BAD_INTERPOLATOR: c_int = -5;
+/// libsamplerate reported an error other than the ones with codes of their own.
+pub const SRS_ERR_LIBRARY: c_int = -6;
+/// The ratio is out of range.
+pub const SRS_ERR_BAD_RATIO: c_int = -7;
+/// A bounded queue has no room for the input; nothing was queued.
+pub const SRS_ERR_QUEUE_FULL: c_int = -8;
+/// A number of frames is zero where it must not be, or too large to address.
+pub const SRS_ERR_BAD_FRAME_COUNT: c_int = -9;
+
+/// Push-queue converter; see ``QueueConverter`` in the Rust documentation.
+pub struct SrsQueue(QueueConverter);
+
+/// Fixed-output converter pulling its input from a callback; see ``PullConverter`` in the
+/// Rust documentation.
+pub struct SrsPull(PullConverter);
+
+/// Callback writing up to ``frames`` interleaved frames of input to ``buffer``, and returning
+/// the number of frames written, or 0 at the end of the stream. ``user_data`` is the pointer
+/// given to ``srs_pull_read``.
+pub type SrsFill = Option<unsafe extern "C" fn(user_data: *mut c_void, buffer: *mut f32,
+                                               frames: usize) -> usize>;
+
+fn error_code(result: Result<()>) -> c_int {
+    match result {
+        Ok(()) => SRS_OK,
+        Err(error) => match error.kind() {
+            ErrorKind::OutOfMemory { .. } => SRS_ERR_OUT_OF_MEMORY,
+            ErrorKind::BadRatio => SRS_ERR_BAD_RATIO,
+            ErrorKind::QueueFull { .. } => SRS_ERR_QUEUE_FULL,
+            ErrorKind::BadBufferLength { .. } => SRS_ERR_BAD_FRAME_COUNT,
+            _ => SRS_ERR_LIBRARY,
+        },
+    }
+}
+
+/// Run ``body``, converting a panic into ``SRS_ERR_PANIC``.
+fn guard<F: FnOnce() -> c_int>(body: F) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(SRS_ERR_PANIC)
+}
+
+fn interpolator(value: c_int) -> Option<Interpolator> {
+    match value {
+        0 => Some(Interpolator::SincBestQuality),
+        1 => Some(Interpolator::SincMediumQuality),
+        2 => Some(Interpolator::SincFastest),
+        3 => Some(Interpolator::ZeroOrderHold),
+        4 => Some(Interpolator::Linear),
+        _ => None,
+    }
+}
+
+/// Create a queue converting ``channels`` interleaved channels at ``ratio``, and store it in
+/// ``*queue``. ``interpolator`` is one of libsamplerate's ``SRC_SINC_BEST_QUALITY`` (0) to
+/// ``SRC_LINEAR`` (4). On failure, ``*queue`` is left unchanged.
+///
+/// # Safety
+///
+/// ``queue`` must be null or valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_new(interpolator: c_int, channels: usize, ratio: f64,
+                                       queue: *mut *mut SrsQueue) -> c_int {
+    guard(|| new_queue(interpolator, queue, |interpolator| {
+        QueueConverter::new(interpolator, channels, ratio)
+    }))
+}
+
+/// Create a queue like ``srs_queue_new`` that queues at most ``max_input_frames`` frames of
+/// input, allocated up front. Pushing more than fits fails with ``SRS_ERR_QUEUE_FULL``.
+///
+/// Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``max_input_frames`` is zero.
+///
+/// # Safety
+///
+/// ``queue`` must be null or valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_new_bounded(interpolator: c_int, channels: usize,
+                                               ratio: f64, max_input_frames: usize,
+                                               queue: *mut *mut SrsQueue) -> c_int {
+    guard(|| {
+        if max_input_frames == 0 {
+            return SRS_ERR_BAD_FRAME_COUNT
+        }
+        new_queue(interpolator, queue, |interpolator| {
+            QueueConverter::with_max_buffered_frames(interpolator, channels, ratio,
+                                                     max_input_frames, 0)
+        })
+    })
+}
+
+unsafe fn new_queue<F>(interpolator: c_int, queue: *mut *mut SrsQueue, new: F) -> c_int
+        where F: FnOnce(Interpolator) -> Result<QueueConverter> {
+    if queue.is_null() {
+        return SRS_ERR_NULL_POINTER
+    }
+    let interpolator = match self::interpolator(interpolator) {
+        Some(interpolator) => interpolator,
+        None => return SRS_ERR_BAD_INTERPOLATOR,
+    };
+    match new(interpolator) {
+        Ok(converter) => {
+            *queue = Box::into_raw(Box::new(SrsQueue(converter)));
+            SRS_OK
+        }
+        Err(error) => error_code(Err(error)),
+    }
+}
+
+/// Queue ``frames`` interleaved frames from ``input`` for conversion. Nothing is queued if
+/// this fails.
+///
+/// Returns ``SRS_ERR_QUEUE_FULL`` if a bounded queue has no room for all of the input, and
+/// ``SRS_ERR_BAD_FRAME_COUNT`` if ``frames`` is too large to address.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed, and ``input`` must
+/// be valid for reading ``frames`` frames, or may be null if ``frames`` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_push(queue: *mut SrsQueue, input: *const f32,
+                                        frames: usize) -> c_int {
+    guard(|| {
+        let queue = match queue.as_mut() {
+            Some(queue) => &mut queue.0,
+            None => return SRS_ERR_NULL_POINTER,
+        };
+        if queue.is_flushed() {
+            return SRS_ERR_FLUSHED
+        }
+        if frames == 0 {
+            return SRS_OK
+        }
+        if input.is_null() {
+            return SRS_ERR_NULL_POINTER
+        }
+        let len = match frames.checked_mul(queue.channels()) {
+            Some(len) => len,
+            None => return SRS_ERR_BAD_FRAME_COUNT,
+        };
+        error_code(queue.push(slice::from_raw_parts(input, len)))
+    })
+}
+
+/// Convert queued input into up to ``frames`` interleaved frames at ``output``, storing the
+/// number of frames generated in ``*generated``. Fewer frames are generated only if more
+/// input is needed, or if the stream was flushed and has no more output.
+///
+/// Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``frames`` is too large to address.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed, ``output`` must be
+/// valid for writing ``frames`` frames, or may be null if ``frames`` is zero, and
+/// ``generated`` must be null or valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_pop(queue: *mut SrsQueue, output: *mut f32, frames: usize,
+                                       generated: *mut usize) -> c_int {
+    guard(|| {
+        let queue = match queue.as_mut() {
+            Some(queue) => &mut queue.0,
+            None => return SRS_ERR_NULL_POINTER,
+        };
+        if generated.is_null() || (output.is_null() && frames != 0) {
+            return SRS_ERR_NULL_POINTER
+        }
+        *generated = 0;
+        if frames == 0 {
+            return SRS_OK
+        }
+        let len = match frames.checked_mul(queue.channels()) {
+            Some(len) => len,
+            None => return SRS_ERR_BAD_FRAME_COUNT,
+        };
+        let output = slice::from_raw_parts_mut(output, len);
+        match queue.pop(output) {
+            Ok(samples) => {
+                *generated = samples / queue.channels();
+                SRS_OK
+            }
+            Err(error) => error_code(Err(error)),
+        }
+    })
+}
+
+/// Mark the end of the stream, so that the following pops return the rest of the output.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_flush(queue: *mut SrsQueue) -> c_int {
+    guard(|| match queue.as_mut() {
+        Some(queue) => {
+            queue.0.flush();
+            SRS_OK
+        }
+        None => SRS_ERR_NULL_POINTER,
+    })
+}
+
+/// Change the conversion ratio, which is interpolated smoothly over the next pop.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_set_ratio(queue: *mut SrsQueue, ratio: f64) -> c_int {
+    guard(|| match queue.as_mut() {
+        Some(queue) => error_code(queue.0.set_ratio(ratio)),
+        None => SRS_ERR_NULL_POINTER,
+    })
+}
+
+/// Discard the queued input and reset the internal state, to start a new stream.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_reset(queue: *mut SrsQueue) -> c_int {
+    guard(|| match queue.as_mut() {
+        Some(queue) => error_code(queue.0.reset()),
+        None => SRS_ERR_NULL_POINTER,
+    })
+}
+
+/// Retrieve the number of queued input frames not yet used, or 0 if ``queue`` is null.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_buffered_frames(queue: *const SrsQueue) -> usize {
+    queue.as_ref().map_or(0, |queue| queue.0.buffered_frames())
+}
+
+/// Check whether the stream was flushed and all of its output was popped, or return 0 if
+/// ``queue`` is null.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_is_finished(queue: *const SrsQueue) -> c_int {
+    queue.as_ref().is_some_and(|queue| queue.0.is_finished()) as c_int
+}
+
+/// Free a queue; does nothing if ``queue`` is null.
+///
+/// # Safety
+///
+/// ``queue`` must be null or returned by ``srs_queue_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_queue_free(queue: *mut SrsQueue) {
+    if !queue.is_null() {
+        // Dropping the queue only frees memory, which does not panic.
+        drop(Box::from_raw(queue));
+    }
+}
+
+/// Create a fixed-output converter for ``channels`` interleaved channels that pulls at most
+/// ``scratch_frames`` frames of input at once from the callback given to ``srs_pull_read``,
+/// and store it in ``*pull``. ``interpolator`` is as for ``srs_queue_new``. On failure,
+/// ``*pull`` is left unchanged.
+///
+/// Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``scratch_frames`` is zero.
+///
+/// # Safety
+///
+/// ``pull`` must be null or valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn srs_pull_new(interpolator: c_int, channels: usize, scratch_frames: usize,
+                                      pull: *mut *mut SrsPull) -> c_int {
+    guard(|| {
+        if pull.is_null() {
+            return SRS_ERR_NULL_POINTER
+        }
+        let interpolator = match self::interpolator(interpolator) {
+            Some(interpolator) => interpolator,
+            None => return SRS_ERR_BAD_INTERPOLATOR,
+        };
+        if scratch_frames == 0 {
+            return SRS_ERR_BAD_FRAME_COUNT
+        }
+        match PullConverter::new(interpolator, channels, scratch_frames) {
+            Ok(converter) => {
+                *pull = Box::into_raw(Box::new(SrsPull(converter)));
+                SRS_OK
+            }
+            Err(error) => error_code(Err(error)),
+        }
+    })
+}
+
+/// Convert at ``ratio`` into ``frames`` interleaved frames at ``output``, calling
+/// ``fill(user_data, buffer, frames)`` whenever more input is needed, and store the number of
+/// frames generated in ``*generated``. Fewer frames are generated only if the stream has no
+/// more output. Once ``fill`` returns 0, it is not called again until the converter is reset.
+///
+/// Returns ``SRS_ERR_BAD_FRAME_COUNT`` if ``frames`` is too large to address, and
+/// ``SRS_ERR_PANIC`` if ``fill`` returns more frames than it was asked for.
+///
+/// # Safety
+///
+/// ``pull`` must be null or returned by ``srs_pull_new`` and not freed, ``output`` must be
+/// valid for writing ``frames`` frames, or may be null if ``frames`` is zero, ``generated``
+/// must be null or valid for writing, and ``fill`` must be null or safe to call with
+/// ``user_data``. ``fill`` must not unwind.
+#[no_mangle]
+pub unsafe extern "C" fn srs_pull_read(pull: *mut SrsPull, ratio: f64, output: *mut f32,
+                                       frames: usize, fill: SrsFill, user_data: *mut c_void,
+                                       generated: *mut usize) -> c_int {
+    guard(|| {
+        let pull = match pull.as_mut() {
+            Some(pull) => &mut pull.0,
+            None => return SRS_ERR_NULL_POINTER,
+        };
+        let fill = match fill {
+            Some(fill) => fill,
+            None => return SRS_ERR_NULL_POINTER,
+        };
+        if generated.is_null() || (output.is_null() && frames != 0) {
+            return SRS_ERR_NULL_POINTER
+        }
+        *generated = 0;
+        if frames == 0 {
+            return SRS_OK
+        }
+        let channels = pull.channels();
+        let len = match frames.checked_mul(channels) {
+            Some(len) => len,
+            None => return SRS_ERR_BAD_FRAME_COUNT,
+        };
+        let output = slice::from_raw_parts_mut(output, len);
+        let result = pull.read_with(ratio, output, |buffer| {
+            let requested = buffer.len() / channels;
+            let written = fill(user_data, buffer.as_mut_ptr(), requested);
+            assert!(written <= requested, "fill returned more frames than it was asked for");
+            written * channels
+        });
+        match result {
+            Ok(samples) => {
+                *generated = samples / channels;
+                SRS_OK
+            }
+            Err(error) => error_code(Err(error)),
+        }
+    })
+}
+
+/// Discard the input pulled so far and reset the internal state, to start a new stream.
+///
+/// # Safety
+///
+/// ``pull`` must be null or returned by ``srs_pull_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_pull_reset(pull: *mut SrsPull) -> c_int {
+    guard(|| match pull.as_mut() {
+        Some(pull) => error_code(pull.0.reset()),
+        None => SRS_ERR_NULL_POINTER,
+    })
+}
+
+/// Check whether ``fill`` returned the end of the stream and all of its output was read, or
+/// return 0 if ``pull`` is null.
+///
+/// # Safety
+///
+/// ``pull`` must be null or returned by ``srs_pull_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_pull_is_finished(pull: *const SrsPull) -> c_int {
+    pull.as_ref().is_some_and(|pull| pull.0.is_finished()) as c_int
+}
+
+/// Free a fixed-output converter; does nothing if ``pull`` is null.
+///
+/// # Safety
+///
+/// ``pull`` must be null or returned by ``srs_pull_new`` and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn srs_pull_free(pull: *mut SrsPull) {
+    if !pull.is_null() {
+        drop(Box::from_raw(pull));
+    }
+}
+
+/// Describe an error code returned by one of the functions, as a static string.
+#[no_mangle]
+pub extern "C" fn srs_strerror(code: c_int) -> *const c_char {
+    let message: &'static [u8] = match code {
+        SRS_OK => b"no error\0",
+        SRS_ERR_NULL_POINTER => b"a pointer argument is null\0",
+        SRS_ERR_PANIC => b"the call panicked; free the queue or pull converter\0",
+        SRS_ERR_OUT_OF_MEMORY => b"cannot allocate memory\0",
+        SRS_ERR_FLUSHED => b"cannot push after flushing; reset the queue first\0",
+        SRS_ERR_BAD_INTERPOLATOR => b"unknown interpolator\0",
+        SRS_ERR_LIBRARY => b"libsamplerate reported an error\0",
+        SRS_ERR_BAD_RATIO => b"the ratio is out of range\0",
+        SRS_ERR_QUEUE_FULL => b"the queue has no room for the input\0",
+        SRS_ERR_BAD_FRAME_COUNT => b"the number of frames is zero or too large\0",
+        _ => b"unknown error code\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CStr;
+    use std::ptr;
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        unsafe {
+            let mut queue = ptr::null_mut();
+            assert_eq!(srs_queue_new(4, 2, 2.0, &mut queue), SRS_OK);
+            let input = [0.5f32; 2 * 100];
+            assert_eq!(srs_queue_push(queue, input.as_ptr(), 100), SRS_OK);
+            assert_eq!(srs_queue_flush(queue), SRS_OK);
+            assert_eq!(srs_queue_push(queue, input.as_ptr(), 100), SRS_ERR_FLUSHED);
+
+            let mut output = [0.0f32; 2 * 300];
+            let mut generated = 0;
+            assert_eq!(srs_queue_pop(queue, output.as_mut_ptr(), 300, &mut generated), SRS_OK);
+            assert_eq!(generated, 200);
+            assert_eq!(srs_queue_is_finished(queue), 1);
+            assert_eq!(output[100..400], [0.5; 300]);
+            srs_queue_free(queue);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            let mut queue = ptr::null_mut();
+            assert_eq!(srs_queue_new(5, 2, 2.0, &mut queue), SRS_ERR_BAD_INTERPOLATOR);
+            assert_eq!(srs_queue_new(4, 2, 1000.0, &mut queue), SRS_ERR_BAD_RATIO);
+            assert_eq!(srs_queue_new(4, 0, 1.0, &mut queue), SRS_ERR_LIBRARY);
+            assert_eq!(srs_queue_new_bounded(4, 2, 1.0, 0, &mut queue), SRS_ERR_BAD_FRAME_COUNT);
+            assert_eq!(srs_queue_new(4, 2, 1.0, ptr::null_mut()), SRS_ERR_NULL_POINTER);
+            assert!(queue.is_null());
+            assert_eq!(srs_queue_push(ptr::null_mut(), ptr::null(), 0), SRS_ERR_NULL_POINTER);
+            assert_eq!(srs_queue_buffered_frames(ptr::null()), 0);
+            srs_queue_free(ptr::null_mut());
+
+            assert_eq!(srs_queue_new(4, 1, 1.0, &mut queue), SRS_OK);
+            assert_eq!(srs_queue_push(queue, ptr::null(), 1), SRS_ERR_NULL_POINTER);
+            assert_eq!(srs_queue_set_ratio(queue, 0.0), SRS_ERR_BAD_RATIO);
+            srs_queue_free(queue);
+
+            // Frame counts overflowing the number of samples are rejected before use.
+            let mut samples = [0.0f32; 4];
+            assert_eq!(srs_queue_new(4, 2, 1.0, &mut queue), SRS_OK);
+            assert_eq!(srs_queue_push(queue, samples.as_ptr(), usize::MAX),
+                       SRS_ERR_BAD_FRAME_COUNT);
+            let mut generated = 1;
+            assert_eq!(srs_queue_pop(queue, samples.as_mut_ptr(), usize::MAX, &mut generated),
+                       SRS_ERR_BAD_FRAME_COUNT);
+            assert_eq!(generated, 0);
+            srs_queue_free(queue);
+
+            assert_eq!(srs_queue_new_bounded(4, 2, 1.0, 3, &mut queue), SRS_OK);
+            assert_eq!(srs_queue_push(queue, samples.as_ptr(), 2), SRS_OK);
+            assert_eq!(srs_queue_push(queue, samples.as_ptr(), 2), SRS_ERR_QUEUE_FULL);
+            assert_eq!(srs_queue_buffered_frames(queue), 2);
+            srs_queue_free(queue);
+
+            let message = CStr::from_ptr(srs_strerror(SRS_ERR_FLUSHED));
+            assert!(message.to_str().unwrap().starts_with("cannot push"));
+            assert_eq!(CStr::from_ptr(srs_strerror(42)).to_str(), Ok("unknown error code"));
+        }
+    }
+
+    /// Hand out 10 frames of stereo input at a time from the ``usize`` counting the frames
+    /// left at ``user_data``.
+    unsafe extern "C" fn fill(user_data: *mut c_void, buffer: *mut f32, frames: usize)
+            -> usize {
+        let left = &mut *(user_data as *mut usize);
+        let written = frames.min(*left).min(10);
+        slice::from_raw_parts_mut(buffer, 2 * written).fill(0.5);
+        *left -= written;
+        written
+    }
+
+    unsafe extern "C" fn overfill(_: *mut c_void, _: *mut f32, frames: usize) -> usize {
+        frames + 1
+    }
+
+    #[test]
+    fn test_pull_round_trip() {
+        unsafe {
+            let mut pull = ptr::null_mut();
+            assert_eq!(srs_pull_new(4, 2, 0, &mut pull), SRS_ERR_BAD_FRAME_COUNT);
+            assert_eq!(srs_pull_new(4, 2, 16, &mut pull), SRS_OK);
+            let mut left = 100usize;
+            let user_data = &mut left as *mut usize as *mut c_void;
+            let mut output = [0.0f32; 2 * 300];
+            let mut generated = 0;
+            assert_eq!(srs_pull_read(pull, 2.0, output.as_mut_ptr(), 300, Some(fill), user_data,
+                                     &mut generated), SRS_OK);
+            assert_eq!(generated, 200);
+            assert_eq!(srs_pull_is_finished(pull), 1);
+            assert_eq!(output[100..300], [0.5; 200]);
+
+            assert_eq!(srs_pull_reset(pull), SRS_OK);
+            assert_eq!(srs_pull_read(pull, 2.0, output.as_mut_ptr(), 1, None, user_data,
+                                     &mut generated), SRS_ERR_NULL_POINTER);
+            assert_eq!(srs_pull_read(pull, 2.0, output.as_mut_ptr(), usize::MAX, Some(fill),
+                                     user_data, &mut generated), SRS_ERR_BAD_FRAME_COUNT);
+            assert_eq!(srs_pull_read(pull, 2.0, output.as_mut_ptr(), 300, Some(overfill),
+                                     ptr::null_mut(), &mut generated), SRS_ERR_PANIC);
+            srs_pull_free(pull);
+            srs_pull_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_panic_is_caught() {
+        assert_eq!(guard(|| panic!("from a test")), SRS_ERR_PANIC);
+    }
+}
@@ -0,0 +1,227 @@
+//! Detection of discontinuities in converted audio, for debugging.
+//!
+//! A click is a sample whose first difference (the step from the previous sample in the same
+//! channel) is much larger than the RMS of the first differences over the preceding
+//! [``CLICK_WINDOW``](constant.CLICK_WINDOW.html) frames. Smooth signals, including ones with
+//! a lot of energy at high frequencies, have a bounded ratio of peak to RMS difference;
+//! a dropped, repeated or misplaced chunk usually does not.
+
+use std::vec::Vec;
+
+use {Converter, Result, check_frames};
+
+/// Number of preceding frames whose first differences form the local RMS.
+///
+/// No clicks are reported within the first ``CLICK_WINDOW`` frames of a stream.
+pub const CLICK_WINDOW: usize = 64;
+
+/// Default ratio of a first difference to the local RMS above which a sample is reported.
+pub const DEFAULT_CLICK_THRESHOLD: f32 = 8.0;
+
+/// First differences smaller than this are never reported, so that the detector is not
+/// triggered by noise in near-silence.
+const MIN_CLICK: f32 = 1e-3;
+
+struct ChannelState {
+    last: f32,
+    squares: [f32; CLICK_WINDOW],
+    sum: f64,
+}
+
+struct Analyzer {
+    threshold: f32,
+    channels: Vec<ChannelState>,
+    // Not ``usize``, which a stream outgrows within a day on 32-bit targets.
+    frames: u64,
+}
+
+impl Analyzer {
+    fn new(channels: usize, threshold: f32) -> Analyzer {
+        let channels = (0..channels)
+            .map(|_| ChannelState { last: 0.0, squares: [0.0; CLICK_WINDOW], sum: 0.0 })
+            .collect();
+        Analyzer { threshold, channels, frames: 0 }
+    }
+
+    fn analyze(&mut self, samples: &[f32], clicks: &mut Vec<u64>) {
+        let threshold = self.threshold;
+        for frame in samples.chunks(self.channels.len()) {
+            let index = self.frames;
+            let mut click = false;
+            for (state, &sample) in self.channels.iter_mut().zip(frame) {
+                let diff = sample - state.last;
+                state.last = sample;
+                if index == 0 {
+                    continue
+                }
+                let square = diff * diff;
+                let mean = (state.sum / CLICK_WINDOW as f64) as f32;
+                if index > CLICK_WINDOW as u64 && diff.abs() > MIN_CLICK &&
+                        square > threshold * threshold * mean {
+                    // Keep the click out of the history so that it does not mask clicks
+                    // following shortly after.
+                    click = true;
+                    continue
+                }
+                let slot = &mut state.squares[(index % CLICK_WINDOW as u64) as usize];
+                state.sum += square as f64 - *slot as f64;
+                *slot = square;
+            }
+            if click {
+                clicks.push(index);
+            }
+            self.frames += 1;
+        }
+    }
+}
+
+/// Find discontinuities in interleaved ``samples``.
+///
+/// Returns the indices of frames in which the first difference of any channel exceeds
+/// [``DEFAULT_CLICK_THRESHOLD``](constant.DEFAULT_CLICK_THRESHOLD.html) times the local RMS
+/// of first differences in that channel, or an error of kind
+/// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``channels`` is zero
+/// or ``samples`` is not a whole number of frames.
+pub fn detect_clicks(samples: &[f32], channels: usize) -> Result<Vec<usize>> {
+    check_frames(channels, samples.len(), 0)?;
+    let mut clicks = Vec::new();
+    Analyzer::new(channels, DEFAULT_CLICK_THRESHOLD).analyze(samples, &mut clicks);
+    // Every index is less than the length of ``samples``.
+    Ok(clicks.into_iter().map(|index| index as usize).collect())
+}
+
+/// Wrapper around [``Converter``](struct.Converter.html) that looks for discontinuities in
+/// its output.
+///
+/// The output of every call to [``convert``](#method.convert) is analyzed as one continuous
+/// stream, like with [``detect_clicks``](fn.detect_clicks.html), so clicks introduced at chunk
+/// boundaries are found as well.
+pub struct ClickDetector {
+    converter: Converter,
+    analyzer: Analyzer,
+    clicks: Vec<u64>,
+}
+
+impl ClickDetector {
+    /// Wrap a converter using the default threshold.
+    pub fn new(converter: Converter) -> ClickDetector {
+        ClickDetector::with_threshold(converter, DEFAULT_CLICK_THRESHOLD)
+    }
+
+    /// Wrap a converter, reporting first differences above ``threshold`` times the local RMS.
+    pub fn with_threshold(converter: Converter, threshold: f32) -> ClickDetector {
+        let analyzer = Analyzer::new(converter.channels(), threshold);
+        ClickDetector { converter, analyzer, clicks: Vec::new() }
+    }
+
+    /// Convert samples like [``Converter::convert``](struct.Converter.html#method.convert),
+    /// and analyze the generated output.
+    pub fn convert(&mut self, ratio: f64, input: Option<&[f32]>, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        let (used, generated) = self.converter.convert(ratio, input, output)?;
+        self.analyzer.analyze(&output[..generated], &mut self.clicks);
+        Ok((used, generated))
+    }
+
+    /// Retrieve the output frame indices of suspected discontinuities found so far.
+    pub fn clicks(&self) -> &[u64] {
+        &self.clicks
+    }
+
+    /// Retrieve the number of output frames analyzed so far.
+    pub fn frames(&self) -> u64 {
+        self.analyzer.frames
+    }
+
+    /// Retrieve the wrapped converter.
+    pub fn converter(&mut self) -> &mut Converter {
+        &mut self.converter
+    }
+
+    /// Unwrap the converter, discarding the analysis.
+    pub fn into_inner(self) -> Converter {
+        self.converter
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use {ErrorKind, Interpolator, convert};
+    use test::sine;
+    use super::*;
+
+    /// The sine fixture, faded in and out so that the start and end of the buffer do not
+    /// themselves introduce a discontinuity.
+    fn faded_sine(frames: usize, channels: usize) -> Vec<f32> {
+        let mut samples = sine(frames, channels);
+        for (frame, samples) in samples.chunks_mut(channels).enumerate() {
+            let fade = (frame.min(frames - 1 - frame) as f32 / 200.0).min(1.0);
+            samples.iter_mut().for_each(|sample| *sample *= fade);
+        }
+        samples
+    }
+
+    #[test]
+    fn test_detect_glitched_sine() {
+        for &channels in &[1, 2] {
+            let mut samples = faded_sine(4000, channels);
+            let glitches = [500, 1234, 1300, 3700];
+            // Toggle a DC offset in the last channel at each glitch.
+            for frame in 0..4000 {
+                let toggles = glitches.iter().filter(|&&glitch| frame >= glitch).count();
+                if toggles % 2 == 1 {
+                    samples[frame * channels + channels - 1] += 1.0;
+                }
+            }
+            assert_eq!(detect_clicks(&samples, channels).unwrap(), glitches);
+        }
+
+        let error = detect_clicks(&[0.0; 3], 2).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 2 });
+        let error = detect_clicks(&[0.0; 3], 0).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 0 });
+    }
+
+    #[test]
+    fn test_detect_clean_conversion() {
+        for &(interpolator, channels, ratio) in &[
+            (Interpolator::SincBestQuality, 1, 2.0),
+            (Interpolator::SincMediumQuality, 2, 44100.0 / 48000.0),
+            (Interpolator::Linear, 2, 1.5),
+        ] {
+            let input = faded_sine(3000, channels);
+            let mut output = vec![0.; 7000 * channels];
+            let (_, generated) = convert(interpolator, channels, ratio,
+                                         &input, &mut output).unwrap();
+            assert_eq!(detect_clicks(&output[..generated], channels).unwrap(), [0usize; 0]);
+        }
+    }
+
+    #[test]
+    fn test_click_detector_chunk_boundaries() {
+        let input = faded_sine(3000, 2);
+        let mut output = vec![0.; 7000 * 2];
+        let mut detector =
+            ClickDetector::new(Converter::new(Interpolator::SincFastest, 2).unwrap());
+        let mut generated = 0;
+        for chunk in input.chunks(2 * 250) {
+            generated += detector.convert(2.0, Some(chunk), &mut output[generated..])
+                .unwrap().1;
+        }
+        generated += detector.convert(2.0, None, &mut output[generated..]).unwrap().1;
+        assert_eq!(detector.frames(), generated as u64 / 2);
+        assert_eq!(detector.clicks(), [0u64; 0]);
+
+        // Skip part of the output, as a chunking bug would.
+        let mut detector =
+            ClickDetector::new(Converter::new(Interpolator::SincFastest, 2).unwrap());
+        let (_, first) = detector.convert(2.0, Some(&input[..2 * 1000]), &mut output).unwrap();
+        let mut scratch = vec![0.; 2 * 40];
+        detector.converter().convert(2.0, Some(&input[2 * 1000..2 * 1020]), &mut scratch)
+            .unwrap();
+        detector.convert(2.0, Some(&input[2 * 1020..]), &mut output).unwrap();
+        assert_eq!(detector.clicks(), [first as u64 / 2]);
+    }
+}
@@ -0,0 +1,333 @@
+//! Resampling of framed PCM streams with [tokio-util](https://crates.io/crates/tokio-util).
+//!
+//! [``ResampleCodec``](struct.ResampleCodec.html) decodes PCM bytes at the source sample rate
+//! into interleaved ``f32`` frames at the target sample rate, and encodes frames at the
+//! target sample rate back into PCM bytes at the source sample rate, so the same codec works
+//! on both ends of a connection. Each direction has its own converter, which carries its
+//! state from one buffer to the next.
+
+use std::io;
+use std::vec::Vec;
+use std::{fmt, error};
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use {Converter, Error, Interpolator, Sample, check_frames, try_reserve, try_vec};
+
+/// Number of output frames generated per call to the converters.
+const CHUNK_FRAMES: usize = 1024;
+
+/// Encoding of the PCM samples in the byte stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Signed 16-bit little-endian integers.
+    S16Le,
+    /// 32-bit little-endian floats.
+    F32Le,
+}
+
+impl Encoding {
+    /// Retrieve the size of one sample, in bytes.
+    pub fn sample_len(self) -> usize {
+        match self {
+            Encoding::S16Le => 2,
+            Encoding::F32Le => 4,
+        }
+    }
+
+    fn read(self, bytes: &mut BytesMut) -> f32 {
+        match self {
+            Encoding::S16Le => bytes.get_i16_le().to_f32(),
+            Encoding::F32Le => bytes.get_f32_le(),
+        }
+    }
+
+    fn write(self, sample: f32, bytes: &mut BytesMut) {
+        match self {
+            Encoding::S16Le => bytes.put_i16_le(i16::from_f32(sample)),
+            Encoding::F32Le => bytes.put_f32_le(sample),
+        }
+    }
+}
+
+/// Codec error.
+#[derive(Debug)]
+pub enum CodecError {
+    /// The stream could not be read or written, or ended in the middle of a frame.
+    Io(io::Error),
+    /// The samples could not be converted.
+    Convert(Error),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodecError::Io(ref error) => write!(f, "{}", error),
+            CodecError::Convert(ref error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            CodecError::Io(ref error) => Some(error),
+            CodecError::Convert(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for CodecError {
+    fn from(error: io::Error) -> CodecError {
+        CodecError::Io(error)
+    }
+}
+
+impl From<Error> for CodecError {
+    fn from(error: Error) -> CodecError {
+        CodecError::Convert(error)
+    }
+}
+
+/// Convert all of ``input``, or flush the converter if it is ``None``, appending the output
+/// to ``output``.
+fn run(converter: &mut Converter, ratio: f64, input: Option<&[f32]>,
+       output: &mut Vec<f32>) -> Result<(), Error> {
+    let chunk = CHUNK_FRAMES * converter.channels();
+    let mut scratch = try_vec(0.0, chunk)?;
+    let mut used = 0;
+    loop {
+        let (u, g) = converter.convert(ratio, input.map(|input| &input[used..]),
+                                       &mut scratch[..chunk])?;
+        try_reserve(output, g)?;
+        output.extend_from_slice(&scratch[..g]);
+        used += u;
+        if u == 0 && g == 0 || input.is_some_and(|input| used == input.len() && g < chunk) {
+            return Ok(())
+        }
+    }
+}
+
+/// tokio-util codec that resamples PCM streams; see the [module documentation](index.html).
+pub struct ResampleCodec {
+    encoding: Encoding,
+    channels: usize,
+    ratio: f64,
+    decoder: Converter,
+    encoder: Converter,
+    decoder_flushed: bool,
+}
+
+impl ResampleCodec {
+    /// Create a codec for streams of ``channels`` interleaved samples in ``encoding`` at
+    /// ``source_rate``, decoded to and encoded from ``f32`` samples at ``target_rate``.
+    pub fn new(interpolator: Interpolator, channels: usize, encoding: Encoding,
+               source_rate: u32, target_rate: u32) -> Result<ResampleCodec, Error> {
+        Ok(ResampleCodec {
+            encoding,
+            channels,
+            ratio: target_rate as f64 / source_rate as f64,
+            decoder: Converter::new(interpolator, channels)?,
+            encoder: Converter::new(interpolator, channels)?,
+            decoder_flushed: false,
+        })
+    }
+
+    /// Retrieve the ratio of the target to the source sample rate.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    fn frame_len(&self) -> usize {
+        self.channels * self.encoding.sample_len()
+    }
+
+    fn decode_frames(&mut self, src: &mut BytesMut) -> Result<Vec<f32>, CodecError> {
+        let frames = src.len() / self.frame_len();
+        let mut input = Vec::new();
+        try_reserve(&mut input, frames * self.channels)?;
+        input.extend((0..frames * self.channels).map(|_| self.encoding.read(src)));
+        let mut output = Vec::new();
+        try_reserve(&mut output, ((frames as f64 * self.ratio) as usize + 2) * self.channels)?;
+        run(&mut self.decoder, self.ratio, Some(&input), &mut output)?;
+        Ok(output)
+    }
+
+    fn encode_frames(&mut self, input: Option<&[f32]>,
+                     dst: &mut BytesMut) -> Result<(), CodecError> {
+        let mut output = Vec::new();
+        run(&mut self.encoder, 1.0 / self.ratio, input, &mut output)?;
+        // ``BytesMut`` has no fallible reservation, so this is the one allocation that aborts
+        // if it fails; it is no larger than ``output``, which was reserved fallibly.
+        dst.reserve(output.len() * self.encoding.sample_len());
+        for &sample in &output {
+            self.encoding.write(sample, dst);
+        }
+        Ok(())
+    }
+
+    /// Flush the tail of the encoded stream into ``dst``, after the last frames have been
+    /// encoded. tokio-util does not notify encoders when a stream ends, so this has to be
+    /// called explicitly, e.g. through ``FramedWrite::encoder_mut``.
+    pub fn encode_end(&mut self, dst: &mut BytesMut) -> Result<(), CodecError> {
+        self.encode_frames(None, dst)
+    }
+}
+
+impl Decoder for ResampleCodec {
+    type Item = Vec<f32>;
+    type Error = CodecError;
+
+    /// Decode all of the whole frames in ``src``, leaving a partial frame at the end for the
+    /// next call.
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Vec<f32>>, CodecError> {
+        if src.len() < self.frame_len() {
+            return Ok(None)
+        }
+        let output = self.decode_frames(src)?;
+        Ok(if output.is_empty() { None } else { Some(output) })
+    }
+
+    /// Decode the rest of the frames and flush the converter.
+    ///
+    /// Returns an error if the stream ends in the middle of a frame.
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Vec<f32>>, CodecError> {
+        if src.len() % self.frame_len() != 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                      "stream ends in the middle of a frame").into())
+        }
+        if self.decoder_flushed {
+            return Ok(None)
+        }
+        let mut output = self.decode_frames(src)?;
+        run(&mut self.decoder, self.ratio, None, &mut output)?;
+        self.decoder_flushed = true;
+        Ok(if output.is_empty() { None } else { Some(output) })
+    }
+}
+
+impl<'a> Encoder<&'a [f32]> for ResampleCodec {
+    type Error = CodecError;
+
+    /// Encode interleaved frames at the target sample rate.
+    ///
+    /// Returns an error of kind
+    /// [``BadBufferLength``](../enum.ErrorKind.html#variant.BadBufferLength) if ``item`` is not
+    /// a whole number of frames.
+    fn encode(&mut self, item: &'a [f32], dst: &mut BytesMut) -> Result<(), CodecError> {
+        check_frames(self.channels, item.len(), 0)?;
+        self.encode_frames(Some(item), dst)
+    }
+}
+
+impl Encoder<Vec<f32>> for ResampleCodec {
+    type Error = CodecError;
+
+    /// Encode interleaved frames at the target sample rate, like the implementation for
+    /// ``&[f32]``.
+    fn encode(&mut self, item: Vec<f32>, dst: &mut BytesMut) -> Result<(), CodecError> {
+        self.encode(&item[..], dst)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::vec::Vec;
+    use futures_core::Stream;
+    use tokio_util::codec::FramedRead;
+    use tokio_util::codec::Decoder;
+    use ErrorKind;
+    use test::sine;
+    use super::*;
+
+    /// Convert ``input`` all at once, flushing the converter at the end.
+    fn offline(channels: usize, ratio: f64, input: &[f32]) -> Vec<f32> {
+        let mut converter = Converter::new(Interpolator::SincFastest, channels).unwrap();
+        let mut output = Vec::new();
+        run(&mut converter, ratio, Some(input), &mut output).unwrap();
+        run(&mut converter, ratio, None, &mut output).unwrap();
+        output
+    }
+
+    fn encode_pcm(encoding: Encoding, samples: &[f32]) -> Vec<u8> {
+        let mut bytes = BytesMut::new();
+        for &sample in samples {
+            encoding.write(sample, &mut bytes);
+        }
+        bytes.to_vec()
+    }
+
+    /// Waker for a stream that is always ready, which never wakes it.
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn test_framed_read() {
+        // Three channels of 16-bit samples do not divide the read buffer size evenly, so
+        // some reads end in the middle of a frame.
+        for &(encoding, channels) in &[(Encoding::S16Le, 3), (Encoding::F32Le, 2)] {
+            let input = sine(20000, channels);
+            let bytes = encode_pcm(encoding, &input);
+            let decoded: Vec<f32> = bytes.chunks(encoding.sample_len())
+                .map(|sample| encoding.read(&mut BytesMut::from(sample)))
+                .collect();
+            let expected = offline(channels, 48000.0 / 44100.0, &decoded);
+
+            let codec = ResampleCodec::new(Interpolator::SincFastest, channels, encoding,
+                                           44100, 48000).unwrap();
+            // Reading from a slice never blocks, so the stream is always ready.
+            let mut framed = FramedRead::new(&bytes[..], codec);
+            let waker = Waker::from(Arc::new(NoopWaker));
+            let mut context = Context::from_waker(&waker);
+            let (mut output, mut items) = (Vec::new(), 0);
+            while let Poll::Ready(Some(item)) = Pin::new(&mut framed).poll_next(&mut context) {
+                let item = item.unwrap();
+                assert!(item.len() % channels == 0);
+                output.extend_from_slice(&item);
+                items += 1;
+            }
+            assert!(items > 1);
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn test_partial_frame_at_eof() {
+        let mut codec = ResampleCodec::new(Interpolator::Linear, 2, Encoding::S16Le,
+                                           48000, 44100).unwrap();
+        let mut src = BytesMut::from(&encode_pcm(Encoding::S16Le, &sine(100, 2))[..]);
+        src.put_u8(0);
+        assert!(codec.decode(&mut src).unwrap().is_some());
+        assert_eq!(src.len(), 1);
+        match codec.decode_eof(&mut src) {
+            Err(CodecError::Io(ref error)) if error.kind() == io::ErrorKind::UnexpectedEof => (),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let input = sine(10000, 2);
+        let mut codec = ResampleCodec::new(Interpolator::SincFastest, 2, Encoding::F32Le,
+                                           22050, 44100).unwrap();
+        let mut dst = BytesMut::new();
+        for chunk in input.chunks(734) {
+            codec.encode(chunk, &mut dst).unwrap();
+        }
+        codec.encode_end(&mut dst).unwrap();
+        assert_eq!(dst.to_vec(), encode_pcm(Encoding::F32Le, &offline(2, 0.5, &input)));
+
+        match codec.encode(&input[..3], &mut dst) {
+            Err(CodecError::Convert(ref error))
+                if error.kind() == ErrorKind::BadBufferLength { len: 3, channels: 2 } => (),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Reduction of audio to per-pixel peaks for drawing waveform overviews.
+//!
+//! This is not sample rate conversion. A resampler low-pass filters its input to avoid
+//! aliasing, which removes exactly the short peaks that a waveform display should show, and
+//! it is much slower than needed to reduce millions of frames to a few thousand points.
+//! Instead, each bucket of consecutive frames is summarized by the smallest and the largest
+//! sample in it, separately for every channel.
+//!
+//! The results are interleaved like the input: the summary of every channel for the first
+//! bucket, then for the second bucket, and so on.
+
+use alloc::vec::Vec;
+
+use {Result, check_frames, try_reserve, try_vec};
+
+/// Smallest and largest sample in a bucket of one channel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MinMax {
+    /// Smallest sample.
+    pub min: f32,
+    /// Largest sample.
+    pub max: f32,
+}
+
+impl MinMax {
+    /// Summary of no samples, which any sample replaces.
+    const EMPTY: MinMax = MinMax { min: f32::INFINITY, max: f32::NEG_INFINITY };
+
+    fn add(&mut self, sample: f32) {
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+    }
+}
+
+/// Retrieve the range of frames in bucket ``bucket`` out of ``buckets`` over ``frames``
+/// frames. Each bucket covers at least one frame, so when there are fewer frames than
+/// buckets, some frames are in several buckets.
+fn bucket_range(bucket: usize, buckets: usize, frames: usize) -> (usize, usize) {
+    let start = (bucket as u64 * frames as u64 / buckets as u64) as usize;
+    let end = ((bucket + 1) as u64 * frames as u64 / buckets as u64) as usize;
+    (start.min(frames - 1), end.max(start + 1).min(frames))
+}
+
+/// Retrieve the number of frames in ``input``, checking that it is a whole number of them.
+fn frame_count(input: &[f32], channels: usize) -> Result<usize> {
+    check_frames(channels, input.len(), 0)?;
+    Ok(input.len() / channels)
+}
+
+/// Summarize ``input`` as ``buckets`` buckets of about the same number of frames.
+///
+/// Returns ``buckets * channels`` summaries, or none if ``input`` is empty. Returns an error
+/// of kind [``BadBufferLength``](../enum.ErrorKind.html#variant.BadBufferLength) if
+/// ``channels`` is zero or ``input`` is not a whole number of frames, and of kind
+/// [``OutOfMemory``](../enum.ErrorKind.html#variant.OutOfMemory) if the summaries cannot be
+/// allocated.
+pub fn peaks(input: &[f32], channels: usize, buckets: usize) -> Result<Vec<MinMax>> {
+    let frames = frame_count(input, channels)?;
+    if frames == 0 {
+        return Ok(Vec::new())
+    }
+    let mut output = try_vec(MinMax::EMPTY, buckets.saturating_mul(channels))?;
+    for (bucket, summaries) in output.chunks_mut(channels).enumerate() {
+        let (start, end) = bucket_range(bucket, buckets, frames);
+        for frame in input[start * channels..end * channels].chunks(channels) {
+            for (summary, &sample) in summaries.iter_mut().zip(frame) {
+                summary.add(sample);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Retrieve the root mean square of the samples in each of the buckets that
+/// [``peaks``](fn.peaks.html) would produce, e.g. to draw the body of the waveform inside
+/// its peaks.
+#[cfg(feature = "std")]
+pub fn rms(input: &[f32], channels: usize, buckets: usize) -> Result<Vec<f32>> {
+    let frames = frame_count(input, channels)?;
+    if frames == 0 {
+        return Ok(Vec::new())
+    }
+    let mut output = try_vec(0.0, buckets.saturating_mul(channels))?;
+    for (bucket, sums) in output.chunks_mut(channels).enumerate() {
+        let (start, end) = bucket_range(bucket, buckets, frames);
+        for frame in input[start * channels..end * channels].chunks(channels) {
+            for (sum, &sample) in sums.iter_mut().zip(frame) {
+                *sum += sample * sample;
+            }
+        }
+        for sum in sums {
+            *sum = (*sum / (end - start) as f32).sqrt();
+        }
+    }
+    Ok(output)
+}
+
+/// Incremental version of [``peaks``](fn.peaks.html) for a stream, e.g. a recording being
+/// drawn while it is captured, with a fixed number of frames per bucket.
+///
+/// The number of frames per bucket may be fractional; bucket boundaries are rounded from
+/// the start of the stream, so they do not drift.
+pub struct PeakDecimator {
+    channels: usize,
+    frames_per_bucket: f64,
+    /// Index of the bucket being accumulated.
+    bucket: u64,
+    /// Number of frames summarized so far.
+    frames: u64,
+    /// Frames at which the current bucket starts and ends.
+    bucket_start: u64,
+    bucket_end: u64,
+    current: Vec<MinMax>,
+}
+
+impl PeakDecimator {
+    /// Create a decimator for ``channels`` interleaved channels.
+    ///
+    /// Returns an error of kind [``OutOfMemory``](../enum.ErrorKind.html#variant.OutOfMemory)
+    /// if the summary of the current bucket cannot be allocated.
+    ///
+    /// Panics if ``channels`` is zero, or if ``frames_per_bucket`` is less than one.
+    pub fn new(channels: usize, frames_per_bucket: f64) -> Result<PeakDecimator> {
+        assert!(channels > 0, "channel count must be at least one");
+        assert!(frames_per_bucket >= 1.0, "a bucket must have at least one frame");
+        Ok(PeakDecimator {
+            channels,
+            frames_per_bucket,
+            bucket: 0,
+            frames: 0,
+            bucket_start: 0,
+            bucket_end: frames_per_bucket as u64,
+            current: try_vec(MinMax::EMPTY, channels)?,
+        })
+    }
+
+    /// Retrieve the number of complete buckets produced so far.
+    pub fn buckets(&self) -> u64 {
+        self.bucket
+    }
+
+    /// Summarize ``input``, appending the summaries of all of the buckets it completes to
+    /// ``output``.
+    ///
+    /// Returns an error of kind
+    /// [``BadBufferLength``](../enum.ErrorKind.html#variant.BadBufferLength) if ``input`` is
+    /// not a whole number of frames, or of kind
+    /// [``OutOfMemory``](../enum.ErrorKind.html#variant.OutOfMemory) if ``output`` cannot
+    /// grow, in which case none of it is summarized.
+    pub fn push(&mut self, input: &[f32], output: &mut Vec<MinMax>) -> Result<()> {
+        check_frames(self.channels, input.len(), 0)?;
+        // Every bucket has at least one frame, and only the first one started before ``input``.
+        let frames = input.len() / self.channels;
+        let buckets = (frames as f64 / self.frames_per_bucket) as usize + 1;
+        try_reserve(output, buckets.saturating_mul(self.channels))?;
+        for frame in input.chunks(self.channels) {
+            for (summary, &sample) in self.current.iter_mut().zip(frame) {
+                summary.add(sample);
+            }
+            self.frames += 1;
+            if self.frames == self.bucket_end {
+                self.complete(output);
+            }
+        }
+        Ok(())
+    }
+
+    /// Append the summary of the partial bucket at the end of the stream to ``output``, if
+    /// there is one.
+    ///
+    /// Returns an error of kind [``OutOfMemory``](../enum.ErrorKind.html#variant.OutOfMemory)
+    /// if ``output`` cannot grow, in which case the bucket is kept.
+    pub fn finish(&mut self, output: &mut Vec<MinMax>) -> Result<()> {
+        if self.frames > self.bucket_start {
+            try_reserve(output, self.channels)?;
+            self.complete(output);
+        }
+        Ok(())
+    }
+
+    fn complete(&mut self, output: &mut Vec<MinMax>) {
+        output.extend_from_slice(&self.current);
+        for summary in &mut self.current {
+            *summary = MinMax::EMPTY;
+        }
+        self.bucket += 1;
+        self.bucket_start = self.frames;
+        self.bucket_end = ((self.bucket + 1) as f64 * self.frames_per_bucket) as u64;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::PI;
+    use ErrorKind;
+    use super::*;
+
+    fn make_sine(frames: usize, period: f32) -> Vec<f32> {
+        (0..frames).map(|n| (2.0 * PI * n as f32 / period).sin()).collect()
+    }
+
+    #[test]
+    fn test_full_scale_sine() {
+        // A period of 100.5 frames puts the peaks between samples, but never further than
+        // a quarter of a frame from one.
+        let input = make_sine(1_000_000, 100.5);
+        let summary = peaks(&input, 1, 2000).unwrap();
+        assert_eq!(summary.len(), 2000);
+        for bucket in &summary {
+            assert!(bucket.max > 0.999 && bucket.max <= 1.0, "{:?}", bucket);
+            assert!(bucket.min < -0.999 && bucket.min >= -1.0, "{:?}", bucket);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_rms() {
+        let input = make_sine(1_000_000, 100.5);
+        for &value in &rms(&input, 1, 2000).unwrap() {
+            assert!((value - 0.5f32.sqrt()).abs() < 0.01, "{}", value);
+        }
+        assert_eq!(rms(&[3.0, 1.0, -4.0, 1.0], 2, 1).unwrap(), [12.5f32.sqrt(), 1.0]);
+    }
+
+    #[test]
+    fn test_boundaries_keep_peak() {
+        let frames = 10007;
+        for &buckets in &[1, 7, 100, 9999, 10007, 20000] {
+            for &position in &[0, 1, frames / 3, frames / 2, frames - 1] {
+                let mut input = std::vec![0.0; frames * 2];
+                input[position * 2] = 1.0;
+                input[position * 2 + 1] = -1.0;
+                let summary = peaks(&input, 2, buckets).unwrap();
+                assert_eq!(summary.len(), buckets * 2);
+                assert!(summary.iter().step_by(2).any(|bucket| bucket.max == 1.0),
+                        "{} buckets: peak at {} dropped", buckets, position);
+                assert!(summary.iter().skip(1).step_by(2).any(|bucket| bucket.min == -1.0),
+                        "{} buckets: trough at {} dropped", buckets, position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decimator_matches_peaks() {
+        let input: Vec<f32> = make_sine(30000, 77.7).iter().enumerate()
+            .map(|(n, sample)| sample * (n % 13) as f32 / 13.0)
+            .collect();
+        // 15000 frames per channel in 100 buckets.
+        let expected = peaks(&input, 2, 100).unwrap();
+        let mut decimator = PeakDecimator::new(2, 150.0).unwrap();
+        let mut output = Vec::new();
+        for chunk in input.chunks(2 * 97) {
+            decimator.push(chunk, &mut output).unwrap();
+        }
+        decimator.finish(&mut output).unwrap();
+        assert_eq!(decimator.buckets(), 100);
+        assert_eq!(output, expected);
+
+        // A partial bucket at the end is flushed by ``finish``.
+        let mut decimator = PeakDecimator::new(1, 2.5).unwrap();
+        let mut output = Vec::new();
+        decimator.push(&[1.0, 2.0, 3.0, -1.0, -2.0, 5.0], &mut output).unwrap();
+        assert_eq!(output, [MinMax { min: 1.0, max: 2.0 }, MinMax { min: -2.0, max: 3.0 }]);
+        decimator.finish(&mut output).unwrap();
+        assert_eq!(output[2..], [MinMax { min: 5.0, max: 5.0 }]);
+        decimator.finish(&mut output).unwrap();
+        assert_eq!(output.len(), 3);
+    }
+
+    #[test]
+    fn test_bad_buffer_length() {
+        let bad_length = ErrorKind::BadBufferLength { len: 3, channels: 2 };
+        assert_eq!(peaks(&[0.0; 3], 2, 10).unwrap_err().kind(), bad_length);
+        assert_eq!(peaks(&[0.0; 3], 0, 10).unwrap_err().kind(),
+                   ErrorKind::BadBufferLength { len: 3, channels: 0 });
+        let mut decimator = PeakDecimator::new(2, 1.0).unwrap();
+        let mut output = Vec::new();
+        assert_eq!(decimator.push(&[0.0; 3], &mut output).unwrap_err().kind(), bad_length);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let error = peaks(&[0.0; 4], 2, usize::MAX / 2).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::OutOfMemory { .. }), "{:?}", error);
+    }
+}
@@ -0,0 +1,275 @@
+//! Conversion of ``f64`` samples with double-precision processing.
+
+use core::ffi::{c_int, c_long};
+use samplerate_sys::double::*;
+
+use {Error, Interpolator, Result, check_frames, check_ratio};
+
+/// Largest number of samples passed to libsamplerate in a single call. It counts them in a
+/// ``long``, which is only 32 bits wide on Windows and on 32-bit targets; longer buffers are
+/// converted in several calls, like by [``convert``](fn.convert.html).
+const MAX_CALL_SAMPLES: usize = c_long::MAX as usize;
+
+fn make_data(channels: usize, ratio: f64, end: bool,
+             input: &[f64], output: &mut [f64]) -> SRC_DATA {
+    SRC_DATA {
+        data_in:            input.as_ptr(),
+        data_out:           output.as_mut_ptr(),
+        input_frames:       (input.len() / channels) as c_long,
+        output_frames:      (output.len() / channels) as c_long,
+        input_frames_used:  0,
+        output_frames_gen:  0,
+        end_of_input:       end as c_int,
+        src_ratio:          ratio,
+    }
+}
+
+/// Perform a single conversion from input buffer to output buffer with a fixed conversion
+/// ratio, processing samples in double precision.
+///
+/// Unlike with [``convert_generic``](fn.convert_generic.html), the samples are not rounded to
+/// ``f32`` at any point: this uses a copy of libsamplerate that stores and filters the
+/// samples as ``f64``, and tracks the filter position with a 32-bit rather than 12-bit
+/// fraction, so the noise floor is set by the filter design rather than by the sample
+/// precision. Otherwise, this function behaves like [``convert``](fn.convert.html).
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_f64_precise(interpolator: Interpolator, channels: usize, ratio: f64,
+                           input: &[f64], output: &mut [f64]) -> Result<(usize, usize)> {
+    convert_chunked(interpolator, channels, ratio, input, output, MAX_CALL_SAMPLES)
+}
+
+/// Convert like [``convert_f64_precise``](fn.convert_f64_precise.html), passing at most
+/// ``max_samples`` samples of either buffer to libsamplerate at once.
+fn convert_chunked(interpolator: Interpolator, channels: usize, ratio: f64, input: &[f64],
+                   output: &mut [f64], max_samples: usize) -> Result<(usize, usize)> {
+    check_frames(channels, input.len(), output.len())?;
+    check_ratio(ratio)?;
+    if input.len() > max_samples || output.len() > max_samples {
+        // src_simple converts in a single call, but a state carries over between calls.
+        return ConverterF64::new(interpolator, channels)?
+            .process(ratio, input, true, output, max_samples)
+    }
+    let mut data = make_data(channels, ratio, /*end=*/true, input, output);
+    let error = unsafe { src_simple(&mut data as *mut _, interpolator as c_int,
+                                    channels as c_int) };
+    if error != 0 {
+        return Err(Error::from_code(error))
+    }
+    Ok((data.input_frames_used as usize * channels,
+        data.output_frames_gen as usize * channels))
+}
+
+/// Interface for performing a continuous conversion of ``f64`` samples with double-precision
+/// processing, like [``Converter``](struct.Converter.html).
+pub struct ConverterF64 {
+    state: *mut SRC_STATE
+}
+
+impl ConverterF64 {
+    /// Create a converter.
+    pub fn new(interpolator: Interpolator, channels: usize) -> Result<ConverterF64> {
+        let mut error: c_int = 0;
+        let state = unsafe { src_new(interpolator as c_int, channels as c_int,
+                                     &mut error as *mut _) };
+        if state.is_null() {
+            return Err(Error::from_code(error))
+        }
+        Ok(ConverterF64 { state })
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        unsafe { src_get_channels(self.state) as usize }
+    }
+
+    /// Reset the internal state to the same state it had after [``new``](#method.new).
+    pub fn reset(&mut self) -> Result<()> {
+        let error = unsafe { src_reset(self.state) };
+        if error != 0 {
+            return Err(Error::from_code(error))
+        }
+        Ok(())
+    }
+
+    /// Set the starting conversion ratio for the next call to [``convert``](#method.convert),
+    /// like [``Converter::set_ratio``](struct.Converter.html#method.set_ratio).
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        check_ratio(ratio)?;
+        let error = unsafe { src_set_ratio(self.state, ratio) };
+        if error != 0 {
+            return Err(Error::from_code(error))
+        }
+        Ok(())
+    }
+
+    /// Convert samples using internal state, smoothly interpolating ratio, like
+    /// [``Converter::convert``](struct.Converter.html#method.convert).
+    pub fn convert(&mut self, ratio: f64, input: Option<&[f64]>, output: &mut [f64])
+            -> Result<(usize, usize)> {
+        self.process(ratio, input.unwrap_or(&[]), input.is_none(), output, MAX_CALL_SAMPLES)
+    }
+
+    /// Convert ``input``, passing at most ``max_samples`` samples of either buffer to
+    /// libsamplerate at once, and ending the stream with the last chunk of input if ``end``
+    /// is set.
+    fn process(&mut self, ratio: f64, input: &[f64], end: bool, output: &mut [f64],
+               max_samples: usize) -> Result<(usize, usize)> {
+        let channels = self.channels();
+        check_frames(channels, input.len(), output.len())?;
+        check_ratio(ratio)?;
+        let chunk = max_samples / channels * channels;
+        let (mut used, mut generated) = (0, 0);
+        loop {
+            let input_len = chunk.min(input.len() - used);
+            let output_len = chunk.min(output.len() - generated);
+            let last = end && used + input_len == input.len();
+            let mut data = make_data(channels, ratio, last, &input[used..used + input_len],
+                                     &mut output[generated..generated + output_len]);
+            let error = unsafe { src_process(self.state, &mut data as *mut _) };
+            if error != 0 {
+                return Err(Error::from_code(error))
+            }
+            let (u, g) = (data.input_frames_used as usize * channels,
+                          data.output_frames_gen as usize * channels);
+            used += u;
+            generated += g;
+            if u == 0 && g == 0 || input.len() <= chunk && output.len() <= chunk {
+                break
+            }
+        }
+        Ok((used, generated))
+    }
+}
+
+impl Drop for ConverterF64 {
+    fn drop(&mut self) {
+        unsafe { src_delete(self.state); }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+    use std::vec::Vec;
+    use std::vec;
+    use {convert, Converter, ErrorKind};
+    use super::*;
+
+    const FREQ: f64 = 0.031;
+    const FRAMES: usize = 20000;
+
+    fn ratio() -> f64 {
+        2f64.sqrt()
+    }
+
+    /// Residual of ``output`` relative to the ideal resampled sine, after removing the gain
+    /// and phase error (i.e. the passband ripple of the filter), in dB.
+    fn residual_db(output: &[f64]) -> f64 {
+        let omega = 2.0 * PI * FREQ / ratio();
+        let range = 1000..output.len() - 1000;
+        let (mut ss, mut sc, mut cc, mut ys, mut yc) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for k in range.clone() {
+            let (s, c) = (omega * k as f64).sin_cos();
+            ss += s * s; sc += s * c; cc += c * c;
+            ys += output[k] * s; yc += output[k] * c;
+        }
+        let det = ss * cc - sc * sc;
+        let (a, b) = ((ys * cc - yc * sc) / det, (yc * ss - ys * sc) / det);
+        let (mut signal, mut noise) = (0.0, 0.0);
+        for k in range {
+            let (s, c) = (omega * k as f64).sin_cos();
+            let fit = a * s + b * c;
+            signal += fit * fit;
+            noise += (output[k] - fit) * (output[k] - fit);
+        }
+        10.0 * (noise / signal).log10()
+    }
+
+    #[test]
+    fn test_precise_residual() {
+        let input: Vec<f64> = (0..FRAMES).map(|n| (2.0 * PI * FREQ * n as f64).sin() * 0.5)
+                                         .collect();
+        let len = (FRAMES as f64 * ratio()) as usize + 16;
+
+        let input_f32: Vec<f32> = input.iter().map(|&s| s as f32).collect();
+        let mut output_f32 = vec![0.0f32; len];
+        let (_, generated) = convert(Interpolator::SincBestQuality, 1, ratio(),
+                                     &input_f32, &mut output_f32).unwrap();
+        let output_f32: Vec<f64> = output_f32[..generated].iter().map(|&s| s as f64).collect();
+
+        let mut output = vec![0.0; len];
+        let (_, generated) = convert_f64_precise(Interpolator::SincBestQuality, 1, ratio(),
+                                                 &input, &mut output).unwrap();
+        let output = &output[..generated];
+
+        let (single, double) = (residual_db(&output_f32), residual_db(output));
+        assert!(double < single - 20.0, "f32: {:.1} dB, f64: {:.1} dB", single, double);
+    }
+
+    #[test]
+    fn test_converter_f64_streaming() {
+        let input: Vec<f64> = (0..FRAMES).map(|n| (n as f64 * 0.01).sin()).collect();
+        let mut expected = vec![0.0; 2 * FRAMES];
+        let (_, gen) = convert_f64_precise(Interpolator::SincFastest, 2, 0.5,
+                                           &input, &mut expected).unwrap();
+
+        let mut conv = ConverterF64::new(Interpolator::SincFastest, 2).unwrap();
+        assert_eq!(conv.channels(), 2);
+        let mut output = vec![0.0; 2 * FRAMES];
+        let mut generated = 0;
+        for chunk in input.chunks(2 * 777) {
+            generated += conv.convert(0.5, Some(chunk), &mut output[generated..]).unwrap().1;
+        }
+        generated += conv.convert(0.5, None, &mut output[generated..]).unwrap().1;
+        assert_eq!(generated, gen);
+        assert_eq!(&output[..gen], &expected[..gen]);
+
+        // Both builds coexist, and agree to single precision.
+        let input_f32: Vec<f32> = input.iter().map(|&s| s as f32).collect();
+        let mut output_f32 = vec![0.0f32; 2 * FRAMES];
+        let mut conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let (_, g) = conv.convert(0.5, Some(&input_f32), &mut output_f32).unwrap();
+        for (&o, &e) in output_f32[..g].iter().zip(expected.iter()) {
+            assert!((o as f64 - e).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_chunked() {
+        // Chunks of 1000 samples stand in for the limit of a 32-bit ``long``.
+        let input: Vec<f64> = (0..FRAMES).map(|n| (n as f64 * 0.01).sin()).collect();
+        let mut expected = vec![0.0; 2 * FRAMES];
+        let mut output = vec![0.0; 2 * FRAMES];
+        let (used, generated) = convert_f64_precise(Interpolator::SincFastest, 2, 1.5, &input,
+                                                    &mut expected).unwrap();
+        assert_eq!(convert_chunked(Interpolator::SincFastest, 2, 1.5, &input, &mut output,
+                                   1001).unwrap(), (used, generated));
+        assert_eq!(output[..generated], expected[..generated]);
+
+        let mut conv = ConverterF64::new(Interpolator::SincFastest, 2).unwrap();
+        let mut chunked = ConverterF64::new(Interpolator::SincFastest, 2).unwrap();
+        let (used, generated) = conv.convert(0.5, Some(&input), &mut expected).unwrap();
+        assert_eq!(chunked.process(0.5, &input, false, &mut output, 1001).unwrap(),
+                   (used, generated));
+        assert_eq!(output[..generated], expected[..generated]);
+    }
+
+    #[test]
+    fn test_converter_f64_error() {
+        assert!(ConverterF64::new(Interpolator::SincFastest, 0).is_err());
+        assert!(convert_f64_precise(Interpolator::Linear, 1, 1000.0, &[0.0], &mut [0.0])
+                    .is_err());
+
+        // Like ``convert``, buffers of partial frames are rejected rather than panicking.
+        let error = convert_f64_precise(Interpolator::Linear, 2, 1.5, &[0.0; 5], &mut [0.0; 8])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 5, channels: 2 });
+        let error = convert_f64_precise(Interpolator::Linear, 0, 1.5, &[0.0; 4], &mut [0.0; 8])
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 4, channels: 0 });
+        let mut conv = ConverterF64::new(Interpolator::Linear, 2).unwrap();
+        let error = conv.convert(1.5, Some(&[0.0; 4]), &mut [0.0; 7]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 7, channels: 2 });
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+use {Converter, Error, Result, SRC_ERR_BAD_CHANNEL_COUNT, try_reserve};
+
+/// Number of samples flushed at once.
+const DRAIN_CHUNK_SAMPLES: usize = 4096;
+
+/// Flushing of a [``Converter``](struct.Converter.html) at the end of a stream into a
+/// growable buffer, without looping over [``convert``](struct.Converter.html#method.convert)
+/// by hand.
+pub trait Drain {
+    /// Flush the filter tail of the stream, like calling ``convert`` with ``None`` as input
+    /// until it generates nothing more, and return the flushed samples.
+    ///
+    /// Draining a stream that was already drained returns nothing, until the converter is
+    /// [``reset``](struct.Converter.html#method.reset). Returns an error of kind
+    /// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) if the output cannot be
+    /// allocated.
+    fn drain(&mut self, ratio: f64) -> Result<Vec<f32>> {
+        let mut output = Vec::new();
+        self.drain_into(ratio, &mut output)?;
+        Ok(output)
+    }
+
+    /// Flush the filter tail like [``drain``](#method.drain), appending the samples to
+    /// ``output`` and returning how many were appended.
+    ///
+    /// On error, ``output`` keeps the samples flushed before it occurred.
+    fn drain_into(&mut self, ratio: f64, output: &mut Vec<f32>) -> Result<usize>;
+}
+
+impl Drain for Converter {
+    fn drain_into(&mut self, ratio: f64, output: &mut Vec<f32>) -> Result<usize> {
+        let channels = self.channels();
+        if channels > DRAIN_CHUNK_SAMPLES {
+            return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+        }
+        let mut chunk = [0.0; DRAIN_CHUNK_SAMPLES];
+        let chunk = &mut chunk[..DRAIN_CHUNK_SAMPLES / channels * channels];
+        let start = output.len();
+        loop {
+            let (_, generated) = self.convert(ratio, None, chunk)?;
+            if generated == 0 {
+                return Ok(output.len() - start)
+            }
+            try_reserve(output, generated)?;
+            output.extend_from_slice(&chunk[..generated]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use {Interpolator, convert};
+    use test::sine;
+    use super::*;
+
+    /// Convert ``input`` in chunks of ``chunk`` frames, then drain the converter, returning
+    /// the output and the number of drained samples.
+    fn stream(conv: &mut Converter, ratio: f64, input: &[f32], chunk: usize)
+            -> (Vec<f32>, usize) {
+        let channels = conv.channels();
+        let mut output = Vec::new();
+        let mut buffer = vec![0.0; 4 * chunk * channels];
+        let mut pending = Vec::new();
+        for chunk in input.chunks(chunk * channels) {
+            // Input that is not used yet is passed again with the next chunk.
+            pending.extend_from_slice(chunk);
+            let (used, generated) = conv.convert(ratio, Some(&pending), &mut buffer).unwrap();
+            pending.drain(..used);
+            output.extend_from_slice(&buffer[..generated]);
+        }
+        let drained = conv.drain_into(ratio, &mut output).unwrap();
+        (output, drained)
+    }
+
+    #[test]
+    fn test_drain_matches_convert() {
+        for &interpolator in &[Interpolator::SincBestQuality, Interpolator::SincFastest,
+                               Interpolator::Linear] {
+            let input = sine(5000, 2);
+            let mut expected = vec![0.0; 2 * 7600];
+            let (_, generated) = convert(interpolator, 2, 1.5, &input, &mut expected).unwrap();
+            expected.truncate(generated);
+
+            let mut conv = Converter::new(interpolator, 2).unwrap();
+            let (output, drained) = stream(&mut conv, 1.5, &input, 300);
+            assert_audio_eq!(output, expected, 1e-6, channels = 2);
+            // Only the sinc interpolators delay the signal, and so have a tail to flush.
+            assert_eq!(drained > 0, interpolator != Interpolator::Linear);
+
+            // A drained stream stays drained, until it is reset.
+            assert!(conv.drain(1.5).unwrap().is_empty());
+            conv.reset().unwrap();
+            assert_eq!(stream(&mut conv, 1.5, &input, 300), (output, drained));
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::boxed::Box;
+use std::vec::Vec;
+use core::mem;
+
+use fundsp::MAX_BUFFER_SIZE;
+use fundsp::audiounit::AudioUnit;
+use fundsp::buffer::{BufferMut, BufferRef, BufferVec};
+use fundsp::math::AttoHash;
+use fundsp::signal::{Signal, SignalFrame};
+
+use {Converter, Error, Interpolator, Result, SRC_ERR_BAD_CHANNEL_COUNT};
+
+/// Number of source frames generated in one go; the largest block fundsp processes at once.
+const CHUNK_FRAMES: usize = MAX_BUFFER_SIZE;
+
+/// fundsp generator that plays another generator, running at its own sample rate, converted
+/// to the sample rate of the graph.
+///
+/// The source is rendered in blocks of up to 64 frames, which are converted and then handed
+/// out to the graph one sample or one block at a time, so the source never sees the
+/// per-sample pulls of the graph. The sample rate of the graph is set with
+/// ``set_sample_rate`` as usual; the sample rate of the source is fixed when creating the
+/// node.
+///
+/// The output is aligned with the source for the sinc interpolators. ``Linear`` and
+/// ``ZeroOrderHold`` delay it by one source sample, which the node reports as its latency.
+///
+/// The source is only run between calls into libsamplerate. If it panics, the block it was
+/// rendering is discarded and the node stays consistent; the next sample is taken from a
+/// new block, for however well the source itself copes with the panic.
+pub struct ResampleNode {
+    source: Box<dyn AudioUnit>,
+    interpolator: Interpolator,
+    channels: usize,
+    converter: Converter,
+    source_rate: f64,
+    ratio: f64,
+    block: BufferVec,
+    /// Interleaved frames rendered by the source and not yet used by the converter.
+    input: Vec<f32>,
+    input_pos: usize,
+    /// Interleaved frames generated by the converter and not yet output.
+    output: Vec<f32>,
+    output_pos: usize,
+    output_len: usize,
+}
+
+// The converter is only ever accessed through `&mut self`.
+unsafe impl Sync for ResampleNode {}
+
+impl ResampleNode {
+    /// Unique ID of the node type, as returned by ``get_id``.
+    pub const ID: u64 = 0x5352_4300;
+
+    fn new(channels: usize, source: Box<dyn AudioUnit>, source_rate: f64,
+           interpolator: Interpolator) -> Result<ResampleNode> {
+        if source.inputs() != 0 || source.outputs() != channels {
+            return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+        }
+        let mut node = ResampleNode {
+            source,
+            interpolator,
+            channels,
+            converter: Converter::new(interpolator, channels)?,
+            source_rate,
+            ratio: 0.0,
+            block: BufferVec::new(channels),
+            input: Vec::with_capacity(CHUNK_FRAMES * channels),
+            input_pos: 0,
+            output: Vec::new(),
+            output_pos: 0,
+            output_len: 0,
+        };
+        node.source.set_sample_rate(source_rate);
+        node.update_ratio(fundsp::DEFAULT_SR)?;
+        Ok(node)
+    }
+
+    /// Create a node playing the mono generator ``source``, which runs at ``source_rate``.
+    ///
+    /// Returns an error if ``source`` has any inputs or does not have exactly one output.
+    pub fn mono(source: Box<dyn AudioUnit>, source_rate: f64,
+                interpolator: Interpolator) -> Result<ResampleNode> {
+        ResampleNode::new(1, source, source_rate, interpolator)
+    }
+
+    /// Create a node playing the stereo generator ``source``, which runs at ``source_rate``.
+    ///
+    /// Returns an error if ``source`` has any inputs or does not have exactly two outputs.
+    pub fn stereo(source: Box<dyn AudioUnit>, source_rate: f64,
+                  interpolator: Interpolator) -> Result<ResampleNode> {
+        ResampleNode::new(2, source, source_rate, interpolator)
+    }
+
+    /// Retrieve the conversion ratio, i.e. the sample rate of the graph divided by the sample
+    /// rate of the source.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Retrieve the delay of the output relative to the source, in output samples.
+    pub fn latency_samples(&self) -> f64 {
+        match self.interpolator {
+            Interpolator::Linear | Interpolator::ZeroOrderHold => self.ratio,
+            _ => 0.0,
+        }
+    }
+
+    fn update_ratio(&mut self, sample_rate: f64) -> Result<()> {
+        let ratio = sample_rate / self.source_rate;
+        self.converter.set_ratio(ratio)?;
+        self.ratio = ratio;
+        // One block of source frames never generates more than this.
+        let frames = (CHUNK_FRAMES as f64 * ratio) as usize + 2;
+        self.output.resize(frames * self.channels, 0.0);
+        Ok(())
+    }
+
+    fn render(&mut self) {
+        self.source.process(CHUNK_FRAMES, &BufferRef::empty(), &mut self.block.buffer_mut());
+        self.input.clear();
+        for frame in 0..CHUNK_FRAMES {
+            for channel in 0..self.channels {
+                self.input.push(self.block.at_f32(channel, frame));
+            }
+        }
+        self.input_pos = 0;
+    }
+
+    fn next_frame(&mut self) -> &[f32] {
+        while self.output_pos == self.output_len {
+            if self.input_pos == self.input.len() {
+                self.render();
+            }
+            let (used, generated) = self.converter
+                .convert(self.ratio, Some(&self.input[self.input_pos..]), &mut self.output)
+                .expect("conversion with a valid ratio failed");
+            self.input_pos += used;
+            self.output_pos = 0;
+            self.output_len = generated;
+        }
+        let frame = &self.output[self.output_pos..self.output_pos + self.channels];
+        self.output_pos += self.channels;
+        frame
+    }
+}
+
+impl Clone for ResampleNode {
+    /// Clone the node. The source is cloned along with its state, but the clone starts
+    /// converting from an empty converter state.
+    fn clone(&self) -> ResampleNode {
+        let mut node = ResampleNode::new(self.channels, self.source.clone(), self.source_rate,
+                                         self.interpolator)
+            .expect("creating a converter with known good parameters failed");
+        node.update_ratio(self.ratio * self.source_rate)
+            .expect("setting a known good ratio failed");
+        node
+    }
+}
+
+impl AudioUnit for ResampleNode {
+    fn reset(&mut self) {
+        self.source.reset();
+        self.converter.reset().expect("resetting the converter failed");
+        self.input.clear();
+        self.input_pos = 0;
+        self.output_pos = 0;
+        self.output_len = 0;
+    }
+
+    /// Set the sample rate of the graph. This changes the conversion ratio immediately,
+    /// without interpolating it or resetting the source.
+    ///
+    /// Panics if the ratio to the sample rate of the source is not supported by libsamplerate.
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        if let Err(error) = self.update_ratio(sample_rate) {
+            panic!("cannot convert from {} Hz to {} Hz: {}", self.source_rate, sample_rate,
+                   error)
+        }
+    }
+
+    fn tick(&mut self, _input: &[f32], output: &mut [f32]) {
+        output.copy_from_slice(self.next_frame());
+    }
+
+    fn process(&mut self, size: usize, _input: &BufferRef, output: &mut BufferMut) {
+        for index in 0..size {
+            for (channel, &sample) in self.next_frame().iter().enumerate() {
+                output.set_f32(channel, index, sample);
+            }
+        }
+    }
+
+    fn inputs(&self) -> usize {
+        0
+    }
+
+    fn outputs(&self) -> usize {
+        self.channels
+    }
+
+    fn route(&mut self, _input: &SignalFrame, _frequency: f64) -> SignalFrame {
+        let mut output = SignalFrame::new(self.channels);
+        for channel in 0..self.channels {
+            output.set(channel, Signal::Latency(self.latency_samples()));
+        }
+        output
+    }
+
+    fn get_id(&self) -> u64 {
+        ResampleNode::ID
+    }
+
+    fn ping(&mut self, probe: bool, hash: AttoHash) -> AttoHash {
+        self.source.ping(probe, hash.hash(ResampleNode::ID))
+    }
+
+    fn footprint(&self) -> usize {
+        mem::size_of::<ResampleNode>()
+    }
+
+    fn allocate(&mut self) {
+        self.source.allocate();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+    use std::vec::Vec;
+    use fundsp::net::Net;
+    use fundsp::oscillator::Sine;
+    use fundsp::prelude::{An, dc, mul, sine_hz};
+    use super::*;
+
+    const SOURCE_RATE: f64 = 32000.0;
+    const GRAPH_RATE: f64 = 48000.0;
+
+    /// Fit a sine of ``freq`` Hz at the graph rate to ``output``, skipping the start, and
+    /// return its amplitude, its delay in samples, and the relative residual in dB.
+    fn fit_sine(output: &[f32], freq: f64) -> (f64, f64, f64) {
+        let omega = 2.0 * PI * freq / GRAPH_RATE;
+        let range = 1000..output.len();
+        let (mut ys, mut yc) = (0.0, 0.0);
+        for k in range.clone() {
+            let (s, c) = (omega * k as f64).sin_cos();
+            ys += output[k] as f64 * s;
+            yc += output[k] as f64 * c;
+        }
+        let (a, b) = (ys * 2.0 / range.len() as f64, yc * 2.0 / range.len() as f64);
+        let (mut signal, mut noise) = (0.0, 0.0);
+        for k in range {
+            let (s, c) = (omega * k as f64).sin_cos();
+            let fit = a * s + b * c;
+            signal += fit * fit;
+            noise += (output[k] as f64 - fit).powi(2);
+        }
+        ((a * a + b * b).sqrt(), -b.atan2(a) / omega, 10.0 * (noise / signal).log10())
+    }
+
+    fn make_graph(interpolator: Interpolator, freq: f32) -> Net {
+        // Unlike `sine_hz`, this starts at a known phase.
+        let sine = dc(freq) >> An(Sine::<f32>::with_phase(0.0));
+        let node = ResampleNode::mono(Box::new(sine), SOURCE_RATE, interpolator).unwrap();
+        let mut graph = Net::wrap(Box::new(node)) >> mul(0.5);
+        graph.set_sample_rate(GRAPH_RATE);
+        graph.allocate();
+        graph
+    }
+
+    #[test]
+    fn test_sine_frequency() {
+        let mut graph = make_graph(Interpolator::SincMediumQuality, 1000.0);
+        let output: Vec<f32> = (0..9600).map(|_| graph.get_mono()).collect();
+        let (amplitude, delay, residual) = fit_sine(&output, 1000.0);
+        assert!((amplitude - 0.5).abs() < 1e-3, "amplitude {}", amplitude);
+        assert!(delay.abs() < 0.05, "delay {}", delay);
+        assert!(residual < -60.0, "residual {} dB", residual);
+
+        // A slightly different frequency leaves a large residual.
+        assert!(fit_sine(&output, 1010.0).2 > -10.0);
+    }
+
+    #[test]
+    fn test_latency() {
+        for &(interpolator, latency) in &[(Interpolator::SincFastest, 0.0),
+                                          (Interpolator::Linear, GRAPH_RATE / SOURCE_RATE)] {
+            let mut graph = make_graph(interpolator, 300.0);
+            assert_eq!(graph.latency(), Some(latency));
+            let output: Vec<f32> = (0..9600).map(|_| graph.get_mono()).collect();
+            let (_, delay, _) = fit_sine(&output, 300.0);
+            assert!((delay - latency).abs() < 0.2, "{:?}: delay {}", interpolator, delay);
+        }
+    }
+
+    #[test]
+    fn test_stereo_blocks_match_ticks() {
+        let make_node = || {
+            let mut node = ResampleNode::stereo(Box::new(sine_hz::<f32>(500.0) |
+                                                         sine_hz::<f32>(1500.0)),
+                                                SOURCE_RATE, Interpolator::SincFastest)
+                .unwrap();
+            node.set_sample_rate(GRAPH_RATE);
+            node
+        };
+
+        // Block sizes that do not line up with the 64-frame source blocks.
+        let sizes: Vec<usize> = [37, 64, 1, 50].iter().cloned().cycle().take(26).collect();
+
+        let mut ticked = make_node();
+        let mut expected = Vec::new();
+        for _ in 0..sizes.iter().sum() {
+            let mut frame = [0.0; 2];
+            ticked.tick(&[], &mut frame);
+            expected.extend_from_slice(&frame);
+        }
+
+        let mut blocked = make_node();
+        let mut block = BufferVec::new(2);
+        let mut actual = Vec::new();
+        for &size in &sizes {
+            blocked.process(size, &BufferRef::empty(), &mut block.buffer_mut());
+            for index in 0..size {
+                actual.extend_from_slice(&[block.at_f32(0, index), block.at_f32(1, index)]);
+            }
+        }
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_channel_mismatch() {
+        let stereo = Box::new(sine_hz::<f32>(500.0) | sine_hz::<f32>(1500.0));
+        assert!(ResampleNode::mono(stereo, SOURCE_RATE, Interpolator::Linear).is_err());
+        let mono = Box::new(sine_hz::<f32>(500.0));
+        assert!(ResampleNode::stereo(mono, SOURCE_RATE, Interpolator::Linear).is_err());
+    }
+}
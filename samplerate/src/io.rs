@@ -0,0 +1,363 @@
+//! Resampling of raw ``f32`` PCM through ``std::io``.
+//!
+//! [``ResamplingReader``](struct.ResamplingReader.html) and
+//! [``ResamplingWriter``](struct.ResamplingWriter.html) convert streams of little-endian
+//! ``f32`` interleaved samples as they are read or written. The bytes may be split
+//! anywhere, even in the middle of a sample; whole frames are converted as they arrive, and
+//! the rest is kept for the next call.
+
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use {Converter, ConverterInternals, Error, ErrorKind, Interpolator, Result, try_reserve,
+     try_vec};
+
+/// Number of output frames generated per call to the converter.
+const CHUNK_FRAMES: usize = 1024;
+
+/// Number of bytes read from the inner reader at once.
+const READ_LEN: usize = 4096;
+
+fn convert_error(error: Error) -> io::Error {
+    let kind = match error.kind() {
+        ErrorKind::OutOfMemory { .. } => io::ErrorKind::OutOfMemory,
+        _ => io::ErrorKind::InvalidData,
+    };
+    io::Error::new(kind, error)
+}
+
+/// Conversion state shared by the reader and the writer.
+struct Stream {
+    converter: Converter,
+    ratio: f64,
+    /// Bytes of a sample that is not complete yet.
+    partial: Vec<u8>,
+    /// Samples that the converter has not used yet, including those of an incomplete frame.
+    input: Vec<f32>,
+    scratch: Vec<f32>,
+}
+
+impl Stream {
+    fn new(interpolator: Interpolator, channels: usize, ratio: f64) -> Result<Stream> {
+        let mut converter = Converter::new(interpolator, channels)?;
+        converter.set_ratio(ratio)?;
+        Ok(Stream {
+            converter,
+            ratio,
+            partial: Vec::new(),
+            input: Vec::new(),
+            scratch: try_vec(0.0, CHUNK_FRAMES * channels)?,
+        })
+    }
+
+    /// Take ``bytes`` as input. Returns an error of kind
+    /// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) if the buffers cannot grow,
+    /// in which case none of ``bytes`` is taken.
+    fn push(&mut self, mut bytes: &[u8]) -> Result<()> {
+        try_reserve(&mut self.input, (self.partial.len() + bytes.len()) / 4)?;
+        try_reserve(&mut self.partial, 4)?;
+        if !self.partial.is_empty() {
+            let len = bytes.len().min(4 - self.partial.len());
+            self.partial.extend_from_slice(&bytes[..len]);
+            bytes = &bytes[len..];
+            if self.partial.len() < 4 {
+                return Ok(())
+            }
+            let sample = [self.partial[0], self.partial[1], self.partial[2], self.partial[3]];
+            self.input.push(f32::from_le_bytes(sample));
+            self.partial.clear();
+        }
+        let mut samples = bytes.chunks_exact(4);
+        self.input.extend(samples.by_ref()
+            .map(|sample| f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]])));
+        self.partial.extend_from_slice(samples.remainder());
+        Ok(())
+    }
+
+    /// Convert the whole frames of input, appending the output to ``output``. If ``end`` is
+    /// set, the input must be whole frames, and the converter is flushed afterwards.
+    fn convert(&mut self, end: bool, output: &mut Vec<u8>) -> io::Result<()> {
+        let channels = self.converter.channels();
+        if end && (!self.partial.is_empty() || self.input.len() % channels != 0) {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                      "stream ends in the middle of a frame"))
+        }
+        let mut used = 0;
+        loop {
+            let whole = (self.input.len() - used) / channels * channels;
+            let (u, g) = self.converter
+                .process(self.ratio, &self.input[used..used + whole], end, &mut self.scratch)
+                .map_err(convert_error)?;
+            used += u;
+            try_reserve(output, g * 4).map_err(convert_error)?;
+            for sample in &self.scratch[..g] {
+                output.extend_from_slice(&sample.to_le_bytes());
+            }
+            if u == 0 && g == 0 || !end && g < self.scratch.len() {
+                break
+            }
+        }
+        self.input.drain(..used);
+        Ok(())
+    }
+}
+
+/// Reader of little-endian ``f32`` interleaved samples that resamples those of an inner
+/// reader. The inner reader may return any number of bytes at once, even part of a sample;
+/// whole frames are converted as they arrive, and the rest is kept for the next read.
+///
+/// When the inner reader reaches the end of the stream, the tail of the converter is
+/// flushed and read before this reader reaches the end as well. The output is the same as
+/// that of a single [``convert``](fn.convert.html) call with all of the input, except that
+/// [``Linear``](enum.Interpolator.html#variant.Linear) and
+/// [``ZeroOrderHold``](enum.Interpolator.html#variant.ZeroOrderHold) may generate one more
+/// frame from the input that arrived last.
+pub struct ResamplingReader<R> {
+    inner: R,
+    stream: Stream,
+    /// Converted bytes not read yet, from ``position`` on.
+    output: Vec<u8>,
+    position: usize,
+    eof: bool,
+}
+
+impl<R: Read> ResamplingReader<R> {
+    /// Create a reader that converts the ``channels`` interleaved channels read from
+    /// ``inner`` at ``ratio``.
+    ///
+    /// Returns an error of kind [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if
+    /// ``ratio`` is not [valid](fn.is_valid_ratio.html), or any error of
+    /// [``Converter::new``](struct.Converter.html#method.new).
+    pub fn new(inner: R, interpolator: Interpolator, channels: usize,
+               ratio: f64) -> Result<ResamplingReader<R>> {
+        Ok(ResamplingReader {
+            inner,
+            stream: Stream::new(interpolator, channels, ratio)?,
+            output: Vec::new(),
+            position: 0,
+            eof: false,
+        })
+    }
+
+    /// Retrieve a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Retrieve a mutable reference to the inner reader. Reading from it directly skips
+    /// over samples that will then not be converted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Retrieve the inner reader, discarding the samples that were not converted or not
+    /// read yet.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read and convert more input, until there is output or the stream ends.
+    fn fill(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.position = 0;
+        let mut bytes = [0; READ_LEN];
+        while self.output.is_empty() && !self.eof {
+            let len = self.inner.read(&mut bytes)?;
+            self.eof = len == 0;
+            self.stream.push(&bytes[..len]).map_err(convert_error)?;
+            self.stream.convert(self.eof, &mut self.output)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ResamplingReader<R> {
+    /// Read converted samples, reading and converting more from the inner reader if none
+    /// are left.
+    ///
+    /// Returns errors of the inner reader as they are. Returns an error of kind
+    /// ``InvalidData`` if the samples could not be converted, or of kind ``OutOfMemory`` if
+    /// a buffer cannot grow, with the [``Error``](struct.Error.html) as its source, and of
+    /// kind ``UnexpectedEof`` if the inner reader ends in the middle of a frame.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.output.len() {
+            self.fill()?;
+        }
+        let len = buf.len().min(self.output.len() - self.position);
+        buf[..len].copy_from_slice(&self.output[self.position..self.position + len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+/// Writer of little-endian ``f32`` interleaved samples that resamples them into an inner
+/// writer. The samples may be written in pieces of any length, like those of a
+/// [``ResamplingReader``](struct.ResamplingReader.html) read.
+///
+/// The end of the stream has to be marked by calling [``finish``](#method.finish), which
+/// flushes the tail of the converter; dropping the writer discards it. The output is the
+/// same as that of a [``ResamplingReader``](struct.ResamplingReader.html).
+pub struct ResamplingWriter<W: Write> {
+    inner: W,
+    stream: Stream,
+    output: Vec<u8>,
+}
+
+impl<W: Write> ResamplingWriter<W> {
+    /// Create a writer that converts the ``channels`` interleaved channels written to it at
+    /// ``ratio`` into ``inner``.
+    ///
+    /// Returns the same errors as
+    /// [``ResamplingReader::new``](struct.ResamplingReader.html#method.new).
+    pub fn new(inner: W, interpolator: Interpolator, channels: usize,
+               ratio: f64) -> Result<ResamplingWriter<W>> {
+        Ok(ResamplingWriter {
+            inner,
+            stream: Stream::new(interpolator, channels, ratio)?,
+            output: Vec::new(),
+        })
+    }
+
+    /// Retrieve a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Retrieve a mutable reference to the inner writer. Writing to it directly puts the
+    /// bytes ahead of the output of the samples that were not converted yet.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// End the stream, writing the tail of the converter into the inner writer, and flush
+    /// and retrieve the inner writer.
+    ///
+    /// Returns an error of kind ``UnexpectedEof`` if the samples written end in the middle
+    /// of a frame, and otherwise the same errors as [``write``](#method.write).
+    pub fn finish(mut self) -> io::Result<W> {
+        self.output.clear();
+        self.stream.convert(true, &mut self.output)?;
+        self.inner.write_all(&self.output)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for ResamplingWriter<W> {
+    /// Convert the whole frames written so far, and write the output into the inner
+    /// writer. All of ``buf`` is taken; the bytes of an incomplete frame are kept for the
+    /// next call.
+    ///
+    /// Returns errors of the inner writer as they are, and an error of kind
+    /// ``InvalidData`` if the samples could not be converted, or of kind ``OutOfMemory`` if
+    /// a buffer cannot grow, with the [``Error``](struct.Error.html) as its source. When a
+    /// buffer cannot grow, none of ``buf`` is taken.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.push(buf).map_err(convert_error)?;
+        self.output.clear();
+        self.stream.convert(false, &mut self.output)?;
+        self.inner.write_all(&self.output)?;
+        Ok(buf.len())
+    }
+
+    /// Flush the inner writer. This does not end the stream; the converter still holds
+    /// back the samples its filter needs, which only [``finish``](#method.finish) writes.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+    use std::vec;
+    use {ErrorKind, convert};
+    use test::sine;
+    use super::*;
+
+    fn to_bytes(samples: &[f32]) -> Vec<u8> {
+        samples.iter().flat_map(|sample| sample.to_le_bytes()).collect()
+    }
+
+    /// Reader that returns at most ``limit`` bytes at once, which splits samples.
+    struct Trickle<R> {
+        inner: R,
+        limit: usize,
+    }
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.limit);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    fn expected(interpolator: Interpolator, input: &[f32], ratio: f64) -> Vec<u8> {
+        let mut output = vec![0.0; input.len() * 2 + 64];
+        let (_, generated) = convert(interpolator, 2, ratio, input, &mut output).unwrap();
+        to_bytes(&output[..generated])
+    }
+
+    #[test]
+    fn test_reader() {
+        let input = sine(10000, 2);
+        for &(interpolator, ratio) in &[(Interpolator::SincFastest, 48000.0 / 44100.0),
+                                        (Interpolator::SincMediumQuality, 0.5)] {
+            for &limit in &[READ_LEN, 7, 4093] {
+                let inner = Trickle { inner: Cursor::new(to_bytes(&input)), limit };
+                let mut reader = ResamplingReader::new(inner, interpolator, 2, ratio).unwrap();
+                // Odd lengths split the output samples too.
+                let (mut output, mut buf) = (Vec::new(), [0; 333]);
+                loop {
+                    let len = reader.read(&mut buf).unwrap();
+                    if len == 0 {
+                        break
+                    }
+                    output.extend_from_slice(&buf[..len]);
+                }
+                assert_eq!(output, expected(interpolator, &input, ratio), "{}", limit);
+                assert_eq!(reader.read(&mut buf).unwrap(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reader_errors() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+        }
+        let mut reader = ResamplingReader::new(Failing, Interpolator::Linear, 2, 2.0).unwrap();
+        assert_eq!(reader.read(&mut [0; 16]).unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+
+        let bytes = to_bytes(&sine(10, 2))[..78].to_vec();
+        let mut reader = ResamplingReader::new(Cursor::new(bytes), Interpolator::Linear, 2,
+                                               2.0).unwrap();
+        let error = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        let error = ResamplingReader::new(Cursor::new(Vec::new()), Interpolator::Linear, 2,
+                                          1000.0).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::BadRatio);
+    }
+
+    #[test]
+    fn test_writer() {
+        let input = sine(10000, 2);
+        let bytes = to_bytes(&input);
+        let mut writer = ResamplingWriter::new(Cursor::new(Vec::new()),
+                                               Interpolator::SincFastest, 2, 0.75).unwrap();
+        for chunk in bytes.chunks(1001) {
+            writer.write_all(chunk).unwrap();
+        }
+        let output = writer.finish().unwrap().into_inner();
+        assert_eq!(output, expected(Interpolator::SincFastest, &input, 0.75));
+
+        let mut writer = ResamplingWriter::new(Vec::new(), Interpolator::Linear, 2, 0.75)
+            .unwrap();
+        writer.write_all(&bytes[..6]).unwrap();
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
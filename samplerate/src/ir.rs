@@ -0,0 +1,124 @@
+use std::vec::Vec;
+
+use {Interpolator, Result, check_frames, convert_to_vec};
+
+/// Resample an impulse response, e.g. for convolution reverb, from ``from_rate`` to
+/// ``to_rate``.
+///
+/// The whole response is converted at once, with the end of input signalled so that the tail
+/// is not held back by the converter. The result is scaled by ``from_rate / to_rate``:
+/// convolution sums over samples, so a response with more samples per second must have a
+/// proportionally smaller amplitude to produce the same output level.
+///
+/// Returns the converted response, interleaved like ``ir``, or an error of kind
+/// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) if it cannot be allocated.
+pub fn resample_ir(ir: &[f32], channels: usize, from_rate: u32, to_rate: u32,
+                   interpolator: Interpolator) -> Result<Vec<f32>> {
+    let ratio = to_rate as f64 / from_rate as f64;
+    let mut output = convert_to_vec(interpolator, channels, ratio, ir)?;
+    let gain = (1.0 / ratio) as f32;
+    for sample in &mut output {
+        *sample *= gain;
+    }
+    Ok(output)
+}
+
+/// Remove the trailing frames of ``ir`` in which every sample is smaller than ``threshold``
+/// in magnitude, e.g. the ringing left after [``resample_ir``](fn.resample_ir.html).
+///
+/// Returns an error of kind
+/// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``channels`` is zero
+/// or ``ir`` is not a whole number of frames, in which case ``ir`` is left unchanged.
+pub fn trim_trailing_silence(ir: &mut Vec<f32>, channels: usize, threshold: f32)
+        -> Result<()> {
+    check_frames(channels, ir.len(), 0)?;
+    let frames = ir.chunks(channels)
+        .rposition(|frame| frame.iter().any(|sample| sample.abs() >= threshold))
+        .map_or(0, |index| index + 1);
+    ir.truncate(frames * channels);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::PI;
+    use std::vec::Vec;
+    use ErrorKind;
+    use super::*;
+    use test_util::check_audio_eq;
+
+    fn convolve(signal: &[f32], ir: &[f32]) -> Vec<f32> {
+        let mut result = std::vec![0.0; signal.len() + ir.len() - 1];
+        for (i, &x) in signal.iter().enumerate() {
+            for (j, &h) in ir.iter().enumerate() {
+                result[i + j] += x * h;
+            }
+        }
+        result
+    }
+
+    /// A decaying resonance with a smooth onset, so that its content is well below the band
+    /// limit of either rate.
+    fn make_ir(rate: f32) -> Vec<f32> {
+        (0..(rate * 0.04) as usize)
+            .map(|n| n as f32 / rate)
+            .map(|t| (2.0 * PI * 1500.0 * t).sin() * t * (-t * 300.0).exp() * 1e8 / rate)
+            .collect()
+    }
+
+    /// Two tones, faded in and out, since a step would not be band limited either.
+    fn make_signal(rate: f32) -> Vec<f32> {
+        (0..(rate * 0.05) as usize)
+            .map(|n| n as f32 / rate)
+            .map(|t| {
+                let fade = (PI * t.min(0.05 - t).min(0.005) / 0.01).sin().powi(2);
+                ((2.0 * PI * 440.0 * t).sin() * 0.5 + (2.0 * PI * 2900.0 * t).sin() * 0.25) * fade
+            })
+            .collect()
+    }
+
+    fn assert_matches(from_rate: u32, to_rate: u32) {
+        let (from, to) = (from_rate as f32, to_rate as f32);
+        let ir = make_ir(from);
+        let resampled_ir = resample_ir(&ir, 1, from_rate, to_rate,
+                                       Interpolator::SincBestQuality).unwrap();
+        assert!((resampled_ir.len() as f32 - ir.len() as f32 * to / from).abs() <= 1.0);
+
+        // The response of the original rate, brought to the new rate, against the response
+        // computed entirely at the new rate.
+        let expected = convert_to_vec(Interpolator::SincBestQuality, 1, (to / from) as f64,
+                                   &convolve(&make_signal(from), &ir)).unwrap();
+        let actual = convolve(&make_signal(to), &resampled_ir);
+        let peak = expected.iter().fold(0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak > 0.1);
+        // Skip the edges, where the converter sees the signal truncated.
+        let range = (to * 0.005) as usize..(to * 0.045) as usize;
+        if let Err(report) = check_audio_eq(&actual[range.clone()], &expected[range], peak * 1e-3,
+                                            1, to_rate) {
+            panic!("{} -> {}: {}", from_rate, to_rate, report)
+        }
+    }
+
+    #[test]
+    fn test_convolution_matches() {
+        assert_matches(48000, 96000);
+        assert_matches(44100, 48000);
+        assert_matches(96000, 44100);
+    }
+
+    #[test]
+    fn test_trim_trailing_silence() {
+        let mut ir = std::vec![0.5, -0.5, 0.1, 0.0, 0.0, 1e-6, 0.0, 0.0];
+        trim_trailing_silence(&mut ir, 2, 1e-4).unwrap();
+        assert_eq!(ir, [0.5, -0.5, 0.1, 0.0]);
+
+        let mut ir = std::vec![1e-5; 6];
+        trim_trailing_silence(&mut ir, 3, 1e-4).unwrap();
+        assert!(ir.is_empty());
+
+        let mut ir = std::vec![1e-5; 5];
+        let error = trim_trailing_silence(&mut ir, 2, 1e-4).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 5, channels: 2 });
+        assert_eq!(ir.len(), 5);
+    }
+}
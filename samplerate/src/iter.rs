@@ -0,0 +1,269 @@
+use alloc::vec::Vec;
+
+use {Converter, Error, ErrorInternals, Result, try_vec};
+
+/// Number of frames taken from the inner iterator, and generated, at once by the iterators
+/// of [``into_iter_resampler``](trait.IntoResampler.html#method.into_iter_resampler).
+pub const RESAMPLER_BLOCK_FRAMES: usize = 1024;
+
+/// Conversion of an iterator of interleaved samples by a
+/// [``Converter``](struct.Converter.html), without collecting the samples into buffers by
+/// hand.
+pub trait IntoResampler {
+    /// Create an iterator yielding the samples of ``input`` converted at ``ratio``, in
+    /// blocks of [``RESAMPLER_BLOCK_FRAMES``](constant.RESAMPLER_BLOCK_FRAMES.html) frames.
+    fn into_iter_resampler<I>(self, ratio: f64, input: I) -> Resampled<I>
+            where I: Iterator<Item = f32>, Self: Sized {
+        self.into_iter_resampler_with(ratio, input, RESAMPLER_BLOCK_FRAMES)
+    }
+
+    /// Create an iterator like [``into_iter_resampler``](#method.into_iter_resampler) that
+    /// takes ``block_frames`` frames from ``input``, and generates as many, at once.
+    ///
+    /// Panics if ``block_frames`` is zero.
+    fn into_iter_resampler_with<I>(self, ratio: f64, input: I, block_frames: usize)
+                                   -> Resampled<I>
+            where I: Iterator<Item = f32>;
+}
+
+impl IntoResampler for Converter {
+    fn into_iter_resampler_with<I>(self, ratio: f64, input: I, block_frames: usize)
+                                   -> Resampled<I>
+            where I: Iterator<Item = f32> {
+        assert!(block_frames > 0, "blocks must hold at least one frame");
+        Resampled {
+            converter: self,
+            ratio,
+            inner: input,
+            block_frames,
+            input: Vec::new(),
+            start: 0,
+            end: 0,
+            seen: 0,
+            exhausted: false,
+            output: Vec::new(),
+            output_pos: 0,
+            output_len: 0,
+            finished: false,
+        }
+    }
+}
+
+/// Iterator yielding the samples of another iterator converted by a
+/// [``Converter``](struct.Converter.html); see [``IntoResampler``](trait.IntoResampler.html).
+///
+/// Samples are taken from the inner iterator a block at a time, converted into another
+/// block, and then yielded one by one. Once the inner iterator ends, the converter is
+/// flushed, and the filter tail is yielded before the iterator ends too.
+///
+/// Each item is a sample, or an error if the conversion failed, the buffers could not be
+/// allocated, or the inner iterator ended in the middle of a frame, in which case the
+/// error is of kind [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) with
+/// the number of samples it yielded. The iterator ends after an error.
+pub struct Resampled<I> {
+    converter: Converter,
+    ratio: f64,
+    inner: I,
+    block_frames: usize,
+    /// Interleaved samples taken from the inner iterator, of which ``input[start..end]``
+    /// are not yet used by the converter.
+    input: Vec<f32>,
+    start: usize,
+    end: usize,
+    /// Number of samples taken from the inner iterator.
+    seen: usize,
+    /// The inner iterator ended.
+    exhausted: bool,
+    /// Interleaved samples generated by the converter, of which
+    /// ``output[output_pos..output_len]`` are not yet yielded.
+    output: Vec<f32>,
+    output_pos: usize,
+    output_len: usize,
+    /// The filter tail was yielded, or an error was.
+    finished: bool,
+}
+
+impl<I> Resampled<I> where I: Iterator<Item = f32> {
+    /// Retrieve the conversion ratio.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Retrieve the converter.
+    pub fn converter(&self) -> &Converter {
+        &self.converter
+    }
+
+    /// Unwrap the converter and the inner iterator, discarding the samples taken from it but
+    /// not yet yielded.
+    pub fn into_inner(self) -> (Converter, I) {
+        (self.converter, self.inner)
+    }
+
+    /// Convert until there is a sample to yield or the filter tail was generated.
+    fn fill(&mut self) -> Result<()> {
+        let channels = self.converter.channels();
+        if self.output.is_empty() {
+            let len = self.block_frames.saturating_mul(channels);
+            self.input = try_vec(0.0, len)?;
+            self.output = try_vec(0.0, len)?;
+        }
+        let mut stalled = false;
+        while self.output_pos == self.output_len && !self.finished {
+            if (stalled || self.start == self.end) && !self.exhausted {
+                self.refill()?;
+            }
+            // A partial frame can only be left at the end of the input.
+            let whole = self.end - (self.end - self.start) % channels;
+            let input = if self.start < whole {
+                Some(&self.input[self.start..whole])
+            } else if self.end > whole {
+                return Err(Error::bad_buffer_length(self.seen, channels))
+            } else {
+                None
+            };
+            let status = self.converter.convert_with_status(self.ratio, input,
+                                                            &mut self.output)?;
+            self.start += status.result.input_samples_used(channels);
+            self.output_pos = 0;
+            self.output_len = status.result.output_samples_generated(channels);
+            self.finished = status.done && self.output_len == 0;
+            stalled = status.result.input_frames_used == 0 && self.output_len == 0;
+        }
+        Ok(())
+    }
+
+    /// Move the unused input to the start of the input block and take samples from the
+    /// inner iterator until the rest of it is full or the inner iterator ends.
+    fn refill(&mut self) -> Result<()> {
+        self.input.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        if self.end == self.input.len() {
+            // The converter held back a whole block of input, so make room for more.
+            let len = self.input.len();
+            let mut input = try_vec(0.0, len.saturating_mul(2))?;
+            input[..len].copy_from_slice(&self.input);
+            self.input = input;
+        }
+        while self.end < self.input.len() {
+            match self.inner.next() {
+                Some(sample) => {
+                    self.input[self.end] = sample;
+                    self.end += 1;
+                    self.seen += 1;
+                }
+                None => {
+                    self.exhausted = true;
+                    break
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<I> Iterator for Resampled<I> where I: Iterator<Item = f32> {
+    type Item = Result<f32>;
+
+    fn next(&mut self) -> Option<Result<f32>> {
+        if self.output_pos == self.output_len {
+            if let Err(error) = self.fill() {
+                self.finished = true;
+                self.output_pos = self.output_len;
+                return Some(Err(error))
+            }
+            if self.output_pos == self.output_len {
+                return None
+            }
+        }
+        let sample = self.output[self.output_pos];
+        self.output_pos += 1;
+        Some(Ok(sample))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec;
+    use std::iter;
+    use {Drain, ErrorKind, Interpolator};
+    use test::sine;
+    use super::*;
+
+    /// Convert ``input`` in one call, then drain the converter.
+    fn convert_and_drain(interpolator: Interpolator, channels: usize, ratio: f64,
+                         input: &[f32]) -> Vec<f32> {
+        let mut conv = Converter::new(interpolator, channels).unwrap();
+        let frames = input.len() / channels;
+        let mut output = vec![0.0; ((frames as f64 * ratio) as usize + 8) * channels];
+        let (used, generated) = conv.convert(ratio, Some(input), &mut output).unwrap();
+        assert_eq!(used, input.len());
+        output.truncate(generated);
+        conv.drain_into(ratio, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn test_matches_convert() {
+        for &interpolator in &[Interpolator::SincMediumQuality, Interpolator::SincFastest,
+                               Interpolator::Linear] {
+            for &channels in &[1, 2] {
+                for &ratio in &[2.0, 0.5, 44100.0 / 48000.0] {
+                    let input = sine(5000, channels);
+                    let expected = convert_and_drain(interpolator, channels, ratio, &input);
+                    // 5000 frames do not make a whole number of blocks.
+                    let conv = Converter::new(interpolator, channels).unwrap();
+                    let output: Result<Vec<f32>> = conv
+                        .into_iter_resampler_with(ratio, input.iter().cloned(), 256)
+                        .collect();
+                    assert_eq!(output.unwrap(), expected, "{:?}, {} channels at {}",
+                               interpolator, channels, ratio);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_sample_at_a_time() {
+        let input = sine(3000, 2);
+        let expected = convert_and_drain(Interpolator::SincFastest, 2, 1.5, &input);
+        // An inner iterator that cannot tell how long it is, taken one frame at a time.
+        let mut samples = input.iter().cloned();
+        let inner = iter::from_fn(move || samples.next());
+        let conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let mut resampled = conv.into_iter_resampler_with(1.5, inner, 1);
+        let output: Vec<f32> = resampled.by_ref().map(|sample| sample.unwrap()).collect();
+        assert_eq!(output, expected);
+        assert!(resampled.next().is_none());
+        assert_eq!(resampled.converter().frames_in(), 3000);
+
+        let conv = Converter::new(Interpolator::SincFastest, 2).unwrap();
+        let output: Vec<f32> = conv.into_iter_resampler(1.5, input.iter().cloned())
+            .map(|sample| sample.unwrap()).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_partial_frame() {
+        let input = sine(1000, 2);
+        let conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        let mut resampled = conv.into_iter_resampler_with(2.0, input[..1999].iter().cloned(),
+                                                          100);
+        let mut generated = 0;
+        let error = loop {
+            match resampled.next().unwrap() {
+                Ok(_) => generated += 1,
+                Err(error) => break error,
+            }
+        };
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 1999, channels: 2 });
+        // The whole frames are converted before the error.
+        assert!(generated >= 2 * 1990, "{}", generated);
+        assert!(resampled.next().is_none());
+
+        let conv = Converter::new(Interpolator::Linear, 2).unwrap();
+        let mut resampled = conv.into_iter_resampler(2.0, iter::empty());
+        assert!(resampled.next().is_none());
+    }
+}
@@ -0,0 +1,285 @@
+use std::boxed::Box;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::vec::Vec;
+
+use kira::{Frame, PlaybackRate};
+use kira::info::Info;
+use kira::sound::{Sound, SoundData};
+
+use {Converter, Error, Interpolator, Result};
+
+/// Number of output frames generated per call to the converter.
+const SCRATCH_FRAMES: usize = 256;
+
+/// Bounds of the conversion ratio accepted by libsamplerate.
+const MIN_RATIO: f64 = 1.0 / 256.0;
+const MAX_RATIO: f64 = 256.0;
+
+struct Shared {
+    playback_rate: AtomicU64,
+    position: AtomicU64,
+    finished: AtomicBool,
+}
+
+/// kira sound that plays frames at their own sample rate, converted to the sample rate of
+/// the audio backend with libsamplerate rather than with the interpolation kira uses for
+/// its own sounds.
+///
+/// The frames are shared between clones, so the same data can be played several times
+/// without copying it.
+#[derive(Clone)]
+pub struct ResampledSoundData {
+    frames: Arc<[f32]>,
+    sample_rate: u32,
+    interpolator: Interpolator,
+    playback_rate: PlaybackRate,
+}
+
+impl ResampledSoundData {
+    /// Create sound data playing ``frames``, which were recorded at ``sample_rate``.
+    pub fn new(frames: &[Frame], sample_rate: u32,
+               interpolator: Interpolator) -> ResampledSoundData {
+        ResampledSoundData {
+            frames: frames.iter().flat_map(|frame| [frame.left, frame.right]).collect(),
+            sample_rate,
+            interpolator,
+            playback_rate: PlaybackRate(1.0),
+        }
+    }
+
+    /// Set the playback rate the sound starts at.
+    pub fn playback_rate(mut self, playback_rate: impl Into<PlaybackRate>) -> ResampledSoundData {
+        self.playback_rate = playback_rate.into();
+        self
+    }
+
+    /// Create the playing sound and its handle without handing them to kira, e.g. to drive
+    /// the sound directly.
+    pub fn into_parts(self) -> Result<(ResampledSound, ResampledSoundHandle)> {
+        let shared = Arc::new(Shared {
+            playback_rate: AtomicU64::new(self.playback_rate.0.to_bits()),
+            position: AtomicU64::new(0),
+            finished: AtomicBool::new(false),
+        });
+        let sound = ResampledSound {
+            converter: Converter::new(self.interpolator, 2)?,
+            frames: self.frames,
+            sample_rate: self.sample_rate as f64,
+            playback_rate: self.playback_rate.0,
+            position: 0,
+            played: 0.0,
+            scratch: std::vec![0.0; SCRATCH_FRAMES * 2],
+            finished: false,
+            shared: shared.clone(),
+        };
+        Ok((sound, ResampledSoundHandle { shared }))
+    }
+}
+
+impl SoundData for ResampledSoundData {
+    type Error = Error;
+    type Handle = ResampledSoundHandle;
+
+    fn into_sound(self) -> Result<(Box<dyn Sound>, ResampledSoundHandle)> {
+        let (sound, handle) = self.into_parts()?;
+        Ok((Box::new(sound), handle))
+    }
+}
+
+/// Playing sound created from [``ResampledSoundData``](struct.ResampledSoundData.html).
+///
+/// Whenever the playback rate changes, the converter slews from the previous ratio to the
+/// new one over the next call to ``process``, so the pitch glides instead of jumping. Once
+/// all of the frames are used, the converter is flushed, so the tail of the sound is not
+/// cut off, and then the sound reports that it is finished.
+pub struct ResampledSound {
+    converter: Converter,
+    frames: Arc<[f32]>,
+    sample_rate: f64,
+    playback_rate: f64,
+    /// Number of samples of ``frames`` used by the converter.
+    position: usize,
+    /// Number of source frames played, which the converter lags behind ``position`` by.
+    played: f64,
+    scratch: Vec<f32>,
+    finished: bool,
+    shared: Arc<Shared>,
+}
+
+impl Sound for ResampledSound {
+    fn on_start_processing(&mut self) {
+        self.playback_rate = f64::from_bits(self.shared.playback_rate.load(Ordering::Relaxed));
+    }
+
+    fn process(&mut self, out: &mut [Frame], dt: f64, _info: &Info) {
+        let ratio = 1.0 / (dt * self.sample_rate * self.playback_rate);
+        if !(MIN_RATIO..=MAX_RATIO).contains(&ratio) {
+            // Stopped or impossibly fast; the sound does not advance.
+            out.fill(Frame::ZERO);
+            return
+        }
+
+        let mut done = 0;
+        while done < out.len() && !self.finished {
+            let frames = (out.len() - done).min(SCRATCH_FRAMES);
+            let input = if self.position < self.frames.len() {
+                Some(&self.frames[self.position..])
+            } else {
+                None
+            };
+            let (used, generated) = self.converter
+                .convert(ratio, input, &mut self.scratch[..frames * 2])
+                .expect("conversion with a valid ratio failed");
+            let samples = self.scratch[..generated].chunks(2);
+            for (frame, samples) in out[done..].iter_mut().zip(samples) {
+                *frame = Frame::new(samples[0], samples[1]);
+            }
+            self.position += used;
+            self.played += (generated / 2) as f64 / ratio;
+            done += generated / 2;
+            if input.is_none() && generated == 0 {
+                self.finished = true;
+            }
+        }
+        out[done..].fill(Frame::ZERO);
+
+        let played = if self.finished { self.frames.len() / 2 } else { self.played as usize };
+        self.shared.position.store(played.min(self.frames.len() / 2) as u64, Ordering::Relaxed);
+        self.shared.finished.store(self.finished, Ordering::Relaxed);
+    }
+
+    fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Handle controlling a [``ResampledSound``](struct.ResampledSound.html) from outside of
+/// the audio thread.
+pub struct ResampledSoundHandle {
+    shared: Arc<Shared>,
+}
+
+impl ResampledSoundHandle {
+    /// Set the playback rate, which changes both the speed and the pitch. It takes effect
+    /// at the start of the next batch of frames the backend requests.
+    pub fn set_playback_rate(&self, playback_rate: impl Into<PlaybackRate>) {
+        self.shared.playback_rate.store(playback_rate.into().0.to_bits(), Ordering::Relaxed)
+    }
+
+    /// Retrieve the playback rate last set.
+    pub fn playback_rate(&self) -> PlaybackRate {
+        PlaybackRate(f64::from_bits(self.shared.playback_rate.load(Ordering::Relaxed)))
+    }
+
+    /// Retrieve the number of source frames played so far. While the playback rate slews,
+    /// this is an estimate.
+    pub fn position(&self) -> u64 {
+        self.shared.position.load(Ordering::Relaxed)
+    }
+
+    /// Check whether the sound has played to the end, including the tail of the converter.
+    pub fn finished(&self) -> bool {
+        self.shared.finished.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32::consts::PI;
+    use std::vec::Vec;
+    use kira::info::MockInfoBuilder;
+    use super::*;
+
+    const OUTPUT_RATE: f64 = 48000.0;
+
+    fn make_sine(frames: usize, rate: f32, frequency: f32) -> Vec<Frame> {
+        (0..frames)
+            .map(|n| Frame::from_mono((2.0 * PI * frequency * n as f32 / rate).sin() * 0.5))
+            .collect()
+    }
+
+    /// Play the sound in batches of ``batch`` frames until it finishes.
+    fn play(sound: &mut ResampledSound, batch: usize) -> Vec<Frame> {
+        let info = MockInfoBuilder::new().build();
+        let mut output = Vec::new();
+        let mut out = std::vec![Frame::ZERO; batch];
+        while !sound.finished() {
+            sound.on_start_processing();
+            sound.process(&mut out, 1.0 / OUTPUT_RATE, &info);
+            output.extend_from_slice(&out);
+            assert!(output.len() < 1_000_000, "sound never finishes");
+        }
+        output
+    }
+
+    /// Estimate the frequency of the left channel from its zero crossings.
+    fn frequency(frames: &[Frame]) -> f64 {
+        let crossings = frames.windows(2)
+            .filter(|pair| (pair[0].left < 0.0) != (pair[1].left < 0.0))
+            .count();
+        crossings as f64 / 2.0 / (frames.len() as f64 / OUTPUT_RATE)
+    }
+
+    #[test]
+    fn test_frame_counts() {
+        for &(source_rate, playback_rate) in &[(24000, 1.0), (44100, 1.0), (48000, 0.5),
+                                               (96000, 1.5)] {
+            let data = ResampledSoundData::new(&make_sine(10000, source_rate as f32, 440.0),
+                                               source_rate, Interpolator::SincFastest)
+                .playback_rate(playback_rate);
+            let (mut sound, handle) = data.into_parts().unwrap();
+            let output = play(&mut sound, 512);
+            assert!(handle.finished());
+            assert_eq!(handle.position(), 10000);
+
+            // The sound is followed by silence up to the end of the last batch.
+            let expected = 10000.0 * OUTPUT_RATE / source_rate as f64 / playback_rate;
+            let last = output.iter().rposition(|frame| frame.left != 0.0).unwrap();
+            assert!((last as f64 - expected).abs() < 8.0,
+                    "{} Hz at {}: {} frames instead of {}", source_rate, playback_rate, last,
+                    expected);
+            assert!(output.len() - expected as usize <= 512);
+        }
+    }
+
+    #[test]
+    fn test_pitch() {
+        let data = ResampledSoundData::new(&make_sine(48000, 48000.0, 500.0), 48000,
+                                           Interpolator::SincMediumQuality);
+        let (mut sound, handle) = data.into_parts().unwrap();
+        let info = MockInfoBuilder::new().build();
+        let mut out = std::vec![Frame::ZERO; 4800];
+
+        sound.on_start_processing();
+        sound.process(&mut out, 1.0 / OUTPUT_RATE, &info);
+        assert!((frequency(&out[100..]) - 500.0).abs() < 10.0);
+
+        handle.set_playback_rate(2.0);
+        assert_eq!(handle.playback_rate(), PlaybackRate(2.0));
+        // The first batch after the change slews to the new ratio.
+        for _ in 0..2 {
+            sound.on_start_processing();
+            sound.process(&mut out, 1.0 / OUTPUT_RATE, &info);
+        }
+        assert!((frequency(&out) - 1000.0).abs() < 10.0);
+        // One batch at the original rate and two at twice the rate play 0.1 + 0.2 + 0.2
+        // seconds of the source.
+        assert!((handle.position() as i64 - 24000).abs() < 10, "{}", handle.position());
+    }
+
+    #[test]
+    fn test_stopped() {
+        let data = ResampledSoundData::new(&make_sine(1000, 48000.0, 500.0), 48000,
+                                           Interpolator::Linear)
+            .playback_rate(0.0);
+        let (mut sound, handle) = data.into_parts().unwrap();
+        let info = MockInfoBuilder::new().build();
+        let mut out = std::vec![Frame::new(1.0, 1.0); 256];
+        sound.on_start_processing();
+        sound.process(&mut out, 1.0 / OUTPUT_RATE, &info);
+        assert!(out.iter().all(|frame| *frame == Frame::ZERO));
+        assert_eq!(handle.position(), 0);
+        assert!(!sound.finished());
+    }
+}
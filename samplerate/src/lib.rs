@@ -3,253 +3,301 @@
 //!
 //! Quickstart:
 //!   * Use [``convert``](fn.convert.html) to process a single batch of samples.
+//!   * Use [``convert_to_vec``](fn.convert_to_vec.html) to do the same into a newly allocated
+//!     buffer, with the ``alloc`` feature.
 //!   * Use [``Converter``](struct.Converter.html) to process a continuous stream of samples.
-
-// It's impossible to usefully expose the callback-based libsamplerate API because it captures
-// a pointer provided by the callback indefinitely, effectively leaking the buffer until the end
-// of the converter lifetime.
+//!   * Use [``AudioBuffer``](struct.AudioBuffer.html) to resample a buffer that carries its
+//!     own sample rate, with the ``alloc`` feature.
+//!
+//! The converters, sample formats and errors live in the
+//! [``samplerate-core``](https://docs.rs/samplerate-core/) crate and are re-exported here;
+//! this crate adds the helpers that need ``alloc`` or ``std``.
 
 #![no_std]
 
-#[cfg(any(test, doctest))]
+#[cfg(any(feature = "std", test, doctest))]
 extern crate std;
-extern crate libc;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+extern crate samplerate_core;
 extern crate samplerate_sys;
+#[cfg(feature = "wasm-bindgen")]
+extern crate wasm_bindgen;
+#[cfg(feature = "wasm-bindgen")]
+extern crate js_sys;
+#[cfg(feature = "hound")]
+extern crate hound;
+#[cfg(feature = "fundsp")]
+extern crate fundsp;
+#[cfg(feature = "kira")]
+extern crate kira;
+#[cfg(feature = "dasp")]
+extern crate dasp_frame;
+#[cfg(feature = "dasp")]
+extern crate dasp_signal;
+#[cfg(feature = "tokio-codec")]
+extern crate tokio_util;
+#[cfg(feature = "tokio-codec")]
+extern crate bytes;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(all(test, feature = "tokio-codec"))]
+extern crate futures_core;
 
-use core::{slice, str, fmt};
-
-use libc::{c_int, c_long, strlen};
-use samplerate_sys::*;
-
-/// Interpolator type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(u32)]
-pub enum Interpolator {
-    SincBestQuality = SRC_SINC_BEST_QUALITY,
-    SincMediumQuality = SRC_SINC_MEDIUM_QUALITY,
-    SincFastest = SRC_SINC_FASTEST,
-    ZeroOrderHold = SRC_ZERO_ORDER_HOLD,
-    Linear = SRC_LINEAR,
-    #[doc(hidden)]
-    __Nonexhaustive
-}
+// First, so that its macros can be used in the tests of the other modules.
+#[cfg(any(feature = "test-util", test))]
+#[macro_use]
+pub mod test_util;
 
-/// Conversion error.
-#[derive(Debug, Eq)]
-pub struct Error {
-    code: c_int,
-    desc: Option<&'static str>
-}
+#[cfg(feature = "std")]
+mod adaptive;
+#[cfg(feature = "alloc")]
+mod bank;
+#[cfg(feature = "rayon")]
+mod batch;
+#[cfg(feature = "alloc")]
+mod buffer;
+#[cfg(feature = "alloc")]
+mod buffered;
+#[cfg(feature = "std")]
+mod cached;
+#[cfg(feature = "std")]
+mod calibrate;
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "std")]
+mod clicks;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
+#[cfg(feature = "alloc")]
+pub mod display;
+#[cfg(feature = "double-precision")]
+mod double;
+#[cfg(feature = "alloc")]
+mod drain;
+#[cfg(feature = "fundsp")]
+mod fundsp_node;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+mod ir;
+#[cfg(feature = "alloc")]
+mod iter;
+#[cfg(feature = "kira")]
+mod kira_sound;
+#[cfg(feature = "std")]
+mod progressive;
+#[cfg(feature = "alloc")]
+mod pull;
+#[cfg(feature = "pure-rust-sinc")]
+mod pure_sinc;
+#[cfg(feature = "alloc")]
+mod queue;
+#[cfg(feature = "std")]
+mod resample_cache;
+#[cfg(feature = "std")]
+mod resampled_clip;
+#[cfg(feature = "dasp")]
+pub mod signal;
+#[cfg(feature = "alloc")]
+mod sync_group;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+#[cfg(feature = "hound")]
+pub mod wav;
 
-impl Error {
-    fn from_code(code: c_int) -> Error {
-        unsafe {
-            let msg = src_strerror(code);
-            let desc = if msg.is_null() {
-                None
-            } else {
-                let len = strlen(msg);
-                Some(str::from_utf8_unchecked(slice::from_raw_parts(msg as *const u8, len)))
-            };
-            Error { code, desc }
-        }
-    }
-}
+pub use samplerate_core::*;
+#[cfg(feature = "alloc")]
+use core::ffi::c_int;
+#[cfg(feature = "alloc")]
+pub(crate) use samplerate_core::internal::ConverterInternals;
+#[cfg(any(feature = "alloc", feature = "double-precision"))]
+pub(crate) use samplerate_core::internal::ErrorInternals;
+
+#[cfg(feature = "std")]
+pub use adaptive::{AdaptiveConverter, SwitchEvent, CROSSFADE_FRAMES, DEFAULT_LEVELS,
+                   DOWNGRADE_LOAD, UPGRADE_CALLS, UPGRADE_LOAD};
+#[cfg(feature = "alloc")]
+pub use bank::RateConverterBank;
+#[cfg(feature = "alloc")]
+pub use buffer::AudioBuffer;
+#[cfg(feature = "alloc")]
+pub use buffered::BufferedConverter;
+#[cfg(feature = "std")]
+pub use calibrate::{CALIBRATION_BLOCKS, CALIBRATION_LEVELS, DEFAULT_SAFETY_MARGIN, Calibration,
+                    InterpolatorTiming, calibrate};
+#[cfg(feature = "std")]
+pub use cached::{MAX_CACHED_CONVERTERS, convert_cached};
+#[cfg(feature = "std")]
+pub use clicks::{CLICK_WINDOW, DEFAULT_CLICK_THRESHOLD, ClickDetector, detect_clicks};
+#[cfg(feature = "double-precision")]
+pub use double::{ConverterF64, convert_f64_precise};
+#[cfg(feature = "alloc")]
+pub use drain::Drain;
+#[cfg(feature = "std")]
+pub use io::{ResamplingReader, ResamplingWriter};
+#[cfg(feature = "std")]
+pub use ir::{resample_ir, trim_trailing_silence};
+#[cfg(feature = "alloc")]
+pub use iter::{IntoResampler, RESAMPLER_BLOCK_FRAMES, Resampled};
+#[cfg(feature = "fundsp")]
+pub use fundsp_node::ResampleNode;
+#[cfg(feature = "kira")]
+pub use kira_sound::{ResampledSound, ResampledSoundData, ResampledSoundHandle};
+#[cfg(feature = "std")]
+pub use progressive::ProgressiveConvert;
+#[cfg(feature = "alloc")]
+pub use pull::PullConverter;
+#[cfg(feature = "alloc")]
+pub use queue::{OverflowPolicy, QueueConverter};
+#[cfg(feature = "std")]
+pub use resample_cache::{CacheKey, CacheStats, ResampleCache};
+#[cfg(feature = "std")]
+pub use resampled_clip::{ClipRender, ResampledClip};
+#[cfg(feature = "alloc")]
+pub use sync_group::SyncGroup;
+#[cfg(feature = "pure-rust-sinc")]
+pub use pure_sinc::{PureSincConverter, convert_pure_sinc};
+
+// Error codes of libsamplerate that the crate reports itself.
+#[cfg(feature = "alloc")]
+pub(crate) const SRC_ERR_BAD_CHANNEL_COUNT: c_int = 11;
+#[cfg(feature = "pure-rust-sinc")]
+pub(crate) const SRC_ERR_SINC_PREPARE_DATA_BAD_LEN: c_int = 21;
+#[cfg(feature = "alloc")]
+pub(crate) const SRC_ERR_BAD_INTERNAL_STATE: c_int = 22;
 
-impl PartialEq for Error {
-    fn eq(&self, other: &Error) -> bool {
-        self.code == other.code
+/// Check that libsamplerate accepts ``ratio``.
+#[cfg(any(feature = "alloc", feature = "double-precision"))]
+pub(crate) fn check_ratio(ratio: f64) -> Result<()> {
+    if !is_valid_ratio(ratio) {
+        return Err(Error::bad_ratio(ratio))
     }
+    Ok(())
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.desc {
-            Some(desc) => write!(f, "{}", desc),
-            None => write!(f, "unknown ({})", self.code)
+/// Check that buffers of ``input_len`` and ``output_len`` samples hold whole frames.
+#[cfg(any(feature = "alloc", feature = "double-precision"))]
+pub(crate) fn check_frames(channels: usize, input_len: usize, output_len: usize) -> Result<()> {
+    for &len in &[input_len, output_len] {
+        if channels == 0 || len % channels != 0 {
+            return Err(Error::bad_buffer_length(len, channels))
         }
     }
+    Ok(())
 }
 
-/// Conversion result.
-type Result<T> = core::result::Result<T, Error>;
-
-fn make_data(channels: usize, ratio: f64, end: bool,
-             input: &[f32], output: &mut [f32]) -> SRC_DATA {
-    assert!(input.len() % channels == 0, "input must be an even number of frames");
-    assert!(output.len() % channels == 0, "output must be an even number of frames");
-    SRC_DATA {
-        data_in:            input.as_ptr(),
-        data_out:           output.as_mut_ptr(),
-        input_frames:       (input.len() / channels) as c_long,
-        output_frames:      (output.len() / channels) as c_long,
-        input_frames_used:  0,
-        output_frames_gen:  0,
-        end_of_input:       end as c_int,
-        src_ratio:          ratio,
-    }
+/// Create the error reporting that ``additional`` more elements of ``T`` could not be
+/// allocated.
+#[cfg(feature = "alloc")]
+fn reserve_error<T>(additional: usize) -> Error {
+    Error::out_of_memory(additional.saturating_mul(core::mem::size_of::<T>()))
 }
 
-/// Perform a single conversion from input buffer to output buffer with a fixed conversion ratio.
-///
-/// This function should only be used to convert a complete buffer at once; to convert a buffer
-/// chunk by chunk, use [``Converter``](struct.Converter.html). Otherwise, artifacts will appear
-/// at chunk boundaries.
-///
-/// Returns the number of used input samples and generated output samples, respectively.
-pub fn convert(interpolator: Interpolator, channels: usize, ratio: f64,
-               input: &[f32], output: &mut [f32]) -> Result<(usize, usize)> {
-    let mut data = make_data(channels, ratio, /*end=*/true, input, output);
-    let error = unsafe { src_simple(&mut data as *mut _, interpolator as c_int,
-                                    channels as c_int) };
-    if error != 0 {
-        return Err(Error::from_code(error))
-    }
-    Ok((data.input_frames_used as usize * channels as usize,
-        data.output_frames_gen as usize * channels as usize))
+/// Reserve room for at least ``additional`` more elements in ``vec``, returning an error of
+/// kind [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) instead of aborting if
+/// it cannot be allocated. ``vec`` is left unchanged on failure.
+#[cfg(feature = "alloc")]
+pub(crate) fn try_reserve<T>(vec: &mut alloc::vec::Vec<T>, additional: usize) -> Result<()> {
+    vec.try_reserve(additional).map_err(|_| reserve_error::<T>(additional))
 }
 
-/// Interface for performing a continuous conversion from input stream to output stream with
-/// a variable, smoothly interpolated conversion ratio.
-pub struct Converter {
-    state: *mut SRC_STATE
+/// Like [``try_reserve``](fn.try_reserve.html), for a ``VecDeque``.
+#[cfg(feature = "alloc")]
+pub(crate) fn try_reserve_deque<T>(deque: &mut alloc::collections::VecDeque<T>,
+                                   additional: usize) -> Result<()> {
+    deque.try_reserve(additional).map_err(|_| reserve_error::<T>(additional))
 }
 
-impl Converter {
-    /// Create a converter.
-    pub fn new(interpolator: Interpolator, channels: usize) -> Result<Converter> {
-        let mut error: c_int = 0;
-        let state = unsafe { src_new(interpolator as c_int, channels as c_int,
-                                     &mut error as *mut _) };
-        if state.is_null() {
-            return Err(Error::from_code(error))
-        }
-        Ok(Converter { state })
-    }
-
-    /// Retrieve the number of channels used by the converter.
-    pub fn channels(&self) -> usize {
-        unsafe { src_get_channels(self.state) as usize }
-    }
-
-    /// Reset the internal state to the same state it had after [``new``](#method.new).
-    pub fn reset(&mut self) -> Result<()> {
-        let error = unsafe { src_reset(self.state) };
-        if error != 0 {
-            return Err(Error::from_code(error))
-        }
-        Ok(())
-    }
+/// Allocate a buffer of ``len`` copies of ``value``, like ``vec![value; len]``, but
+/// returning an error instead of aborting if it cannot be allocated.
+#[cfg(feature = "alloc")]
+pub(crate) fn try_vec<T: Clone>(value: T, len: usize) -> Result<alloc::vec::Vec<T>> {
+    let mut vec = alloc::vec::Vec::new();
+    try_reserve(&mut vec, len)?;
+    vec.resize(len, value);
+    Ok(vec)
+}
 
-    /// Set the starting conversion ratio for the next call to [``convert``](#method.convert).
-    ///
-    /// Calling this function achieves a step response in conversion ratio instead of smooth
-    /// interpolation.
-    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
-        let error = unsafe { src_set_ratio(self.state, ratio) };
-        if error != 0 {
-            return Err(Error::from_code(error))
-        }
-        Ok(())
+/// Perform a single conversion like [``convert``](fn.convert.html), into a newly
+/// allocated buffer that fits all of the output.
+///
+/// The output buffer is sized from ``ratio``, and grown and converted again in the unlikely
+/// case that libsamplerate fills it; the returned buffer holds exactly the generated
+/// samples. Empty input gives empty output.
+///
+/// Returns an error of kind [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if
+/// ``ratio`` is out of range, of kind
+/// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``input`` is not a
+/// whole number of frames, of kind
+/// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory) if the output cannot be
+/// allocated, and of kind
+/// [``BadInternalState``](enum.ErrorKind.html#variant.BadInternalState) if libsamplerate
+/// stops before converting all of the input.
+#[cfg(feature = "alloc")]
+pub fn convert_to_vec(interpolator: Interpolator, channels: usize, ratio: f64,
+                      input: &[f32]) -> Result<alloc::vec::Vec<f32>> {
+    check_ratio(ratio)?;
+    check_frames(channels, input.len(), 0)?;
+    if input.is_empty() {
+        return Ok(alloc::vec::Vec::new())
     }
-
-    /// Convert samples using internal state, smoothly interpolating ratio.
-    ///
-    /// The size of both ``input`` and ``output`` must be a multiple of the converter's channel
-    /// count. If there is no more input data, provide ``None`` as ``input``, and the converter
-    /// will flush its internal state.
-    ///
-    /// Returns the number of used input samples and generated output samples, respectively.
-    /// The sample numbers may be used to partition the input and output arrays.
-    pub fn convert(&mut self, ratio: f64, input: Option<&[f32]>, output: &mut [f32])
-            -> Result<(usize, usize)> {
-        let channels = self.channels();
-        let mut data = make_data(channels, ratio, input.is_none(), input.unwrap_or(&[]), output);
-        let error = unsafe { src_process(self.state, &mut data as *mut _) };
-        if error != 0 {
-            return Err(Error::from_code(error))
+    let mut len = output_len_hint(interpolator, channels, ratio, input.len());
+    loop {
+        let mut output = try_vec(0.0, len)?;
+        let (used, generated) = convert(interpolator, channels, ratio, input, &mut output)?;
+        if generated < output.len() {
+            // libsamplerate stopped with room left, so it would not consume more. Linear and
+            // ZeroOrderHold count the last frame as unused, although they have converted it.
+            if used + channels < input.len() {
+                return Err(Error::from_code(SRC_ERR_BAD_INTERNAL_STATE))
+            }
+            output.truncate(generated);
+            return Ok(output)
         }
-        Ok((data.input_frames_used as usize * channels,
-            data.output_frames_gen as usize * channels))
-    }
-}
-
-impl Drop for Converter {
-    fn drop(&mut self) {
-        unsafe { src_delete(self.state); }
+        // The output may have been cut short.
+        len = len.saturating_mul(2);
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test {
-    use std::f32;
-    use std::vec::Vec;
-    use std::vec;
+    use alloc::vec;
     use super::*;
 
-    fn make_fixture(size: usize, cos: bool) -> Vec<f32> {
-        let step = f32::consts::PI * 2.0 / size as f32;
-        let mut data = Vec::new();
-        let mut value = 0.0f32;
-        for _ in 0..size {
-            data.push(value.sin());
-            if cos { data.push(value.cos()); }
-            value += step;
-        }
-        data
-    }
-
-    fn test_convert_ch(ch2: bool) {
-        let input = make_fixture(1000, ch2);
-        let expect = make_fixture(2000, ch2);
-        let mut output = vec![0.; expect.len()];
-        let channels = if ch2 { 2 } else { 1 };
-        let (used, gen) = convert(Interpolator::SincBestQuality, channels, 2.0,
-                                  &input, &mut output).unwrap();
-        assert_eq!(used, input.len());
-        assert_eq!(gen, output.len());
-        for (o, e) in output.iter().zip(expect.iter())
-                .skip(10 * channels)
-                .take(output.len() - 20 * channels) {
-            assert!((o - e).abs() < 0.05);
-        }
+    pub(crate) fn sine(frames: usize, channels: usize) -> alloc::vec::Vec<f32> {
+        (0..frames * channels)
+            .map(|i| ((i / channels) as f32 * 0.05 * (1 + i % channels) as f32).sin())
+            .collect()
     }
 
     #[test]
-    fn test_convert_1ch() {
-        test_convert_ch(false)
-    }
-
-    #[test]
-    fn test_convert_2ch() {
-        test_convert_ch(true)
-    }
-
-    fn test_push_converter_ch(ch2: bool) {
-        let input = make_fixture(1000, ch2);
-        let expect = make_fixture(2000, ch2);
-        let mut output = vec![0.; expect.len()];
-        let ch = if ch2 { 2 } else { 1 };
-        let mut conv = Converter::new(Interpolator::SincBestQuality, ch).unwrap();
-        assert_eq!(conv.convert(2.0, Some(&input[..500 * ch]), &mut output[..]).unwrap(),
-                   (500 * ch, 712  * ch));
-        assert_eq!(conv.convert(2.0, Some(&input[500 * ch..]), &mut output[712 * ch..]).unwrap(),
-                   (500 * ch, 1000 * ch));
-        assert_eq!(conv.convert(2.0, None, &mut output[1712 * ch..]).unwrap(),
-                   (0   * ch, 288  * ch));
-        for (o, e) in output.iter().zip(expect.iter())
-                .skip(10).take(output.len() - 20) {
-            assert!((o - e).abs() < 0.05);
+    fn test_convert_to_vec() {
+        let cases = [(Interpolator::SincFastest, 1, 2.0),
+                     (Interpolator::SincFastest, 2, 44100.0 / 48000.0),
+                     (Interpolator::SincFastest, 2, 1.0 / 256.0),
+                     (Interpolator::SincFastest, 1, 256.0), (Interpolator::Linear, 3, 0.5),
+                     (Interpolator::ZeroOrderHold, 2, 1.5)];
+        for &(interpolator, channels, ratio) in &cases {
+            let input = sine(1000, channels);
+            let output = convert_to_vec(interpolator, channels, ratio, &input).unwrap();
+            let mut expect = vec![0.0; (1000.0 * ratio) as usize * channels + 8 * channels];
+            let (_, generated) = convert(interpolator, channels, ratio, &input, &mut expect)
+                .unwrap();
+            assert_eq!(output, expect[..generated]);
         }
     }
 
     #[test]
-    fn test_push_converter_1ch() {
-        test_push_converter_ch(false)
-    }
-
-    #[test]
-    fn test_push_converter_2ch() {
-        test_push_converter_ch(true)
+    fn test_convert_to_vec_edge_cases() {
+        assert_eq!(convert_to_vec(Interpolator::Linear, 2, 2.0, &[]), Ok(vec![]));
+        assert_eq!(convert_to_vec(Interpolator::Linear, 2, 2.0, &[0.0; 3]).unwrap_err().kind(),
+                   ErrorKind::BadBufferLength { len: 3, channels: 2 });
+        assert_eq!(convert_to_vec(Interpolator::Linear, 0, 2.0, &[]).unwrap_err().kind(),
+                   ErrorKind::BadBufferLength { len: 0, channels: 0 });
+        for &ratio in &[1e-300, 0.0, -1.0, 1e300, f64::INFINITY, f64::NAN] {
+            assert_eq!(convert_to_vec(Interpolator::Linear, 1, ratio, &[0.0; 16])
+                           .unwrap_err().kind(), ErrorKind::BadRatio, "{}", ratio);
+        }
     }
 }
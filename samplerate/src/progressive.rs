@@ -0,0 +1,213 @@
+use std::boxed::Box;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::vec::Vec;
+
+use {Converter, Interpolator, Result, convert_to_vec, try_reserve, try_vec};
+
+/// Number of frames the refinement converts between checks for cancellation.
+const REFINE_CHUNK_FRAMES: usize = 8192;
+
+type Callback = Box<dyn FnOnce(&[f32]) + Send>;
+
+struct Shared {
+    refined: OnceLock<Vec<f32>>,
+    cancelled: AtomicBool,
+}
+
+/// Conversion that is available at once in a low quality and later in the best quality,
+/// e.g. for displaying a waveform while the user changes the sample rate.
+///
+/// Creating a ``ProgressiveConvert`` converts the input with a fast interpolator on the
+/// calling thread, and starts converting it with ``SincBestQuality`` on a worker thread.
+/// Both conversions are cut or padded with silence to ``round(frames * ratio)`` frames, so
+/// the refined buffer can replace the preview in place.
+///
+/// If the preview cannot be allocated, creation returns an error of kind
+/// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory); if the refined buffer cannot
+/// be, the refinement ends without a result, as if it were cancelled.
+pub struct ProgressiveConvert {
+    preview: Vec<f32>,
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<()>>,
+}
+
+/// Cut or pad ``output`` to the length shared by the preview and the refined buffer.
+fn fit(output: &mut Vec<f32>, channels: usize, ratio: f64, input_len: usize) -> Result<()> {
+    let frames = ((input_len / channels) as f64 * ratio).round() as usize;
+    try_reserve(output, (frames * channels).saturating_sub(output.len()))?;
+    output.resize(frames * channels, 0.0);
+    Ok(())
+}
+
+fn refine(mut converter: Converter, ratio: f64, input: &[f32], shared: &Shared)
+        -> Result<Option<Vec<f32>>> {
+    let channels = converter.channels();
+    let chunk_len = REFINE_CHUNK_FRAMES * channels;
+    let mut buffer = try_vec(0.0, ((REFINE_CHUNK_FRAMES as f64 * ratio) as usize + 2) *
+                                  channels)?;
+    let mut output = Vec::new();
+    try_reserve(&mut output, ((input.len() / channels) as f64 * ratio) as usize * channels +
+                             buffer.len())?;
+    let mut rest = input;
+    loop {
+        if shared.cancelled.load(Ordering::Relaxed) {
+            return Ok(None)
+        }
+        let chunk = &rest[..rest.len().min(chunk_len)];
+        let (used, generated) =
+            converter.convert(ratio, if rest.is_empty() { None } else { Some(chunk) },
+                              &mut buffer)?;
+        try_reserve(&mut output, generated)?;
+        output.extend_from_slice(&buffer[..generated]);
+        rest = &rest[used..];
+        if rest.is_empty() && used == 0 && generated == 0 {
+            break
+        }
+    }
+    fit(&mut output, channels, ratio, input.len())?;
+    Ok(Some(output))
+}
+
+impl ProgressiveConvert {
+    /// Convert ``input`` with ``preview``, which should be ``ZeroOrderHold`` or ``Linear``,
+    /// and start refining it.
+    pub fn new(preview: Interpolator, channels: usize, ratio: f64,
+               input: Arc<[f32]>) -> Result<ProgressiveConvert> {
+        ProgressiveConvert::start(preview, channels, ratio, input, None)
+    }
+
+    /// Like [``new``](#method.new), but also call ``callback`` on the worker thread with
+    /// the refined buffer once it is ready. The callback is not called if the refinement is
+    /// cancelled.
+    ///
+    /// The refined buffer is stored before ``callback`` is called, so if it panics, the
+    /// panic ends the worker thread, but the buffer is still available from
+    /// [``refined``](#method.refined) and [``wait``](#method.wait).
+    pub fn with_callback<F>(preview: Interpolator, channels: usize, ratio: f64,
+                            input: Arc<[f32]>, callback: F) -> Result<ProgressiveConvert>
+            where F: FnOnce(&[f32]) + Send + 'static {
+        ProgressiveConvert::start(preview, channels, ratio, input, Some(Box::new(callback)))
+    }
+
+    fn start(preview: Interpolator, channels: usize, ratio: f64, input: Arc<[f32]>,
+             callback: Option<Callback>) -> Result<ProgressiveConvert> {
+        let mut preview = convert_to_vec(preview, channels, ratio, &input)?;
+        fit(&mut preview, channels, ratio, input.len())?;
+
+        let converter = Converter::new(Interpolator::SincBestQuality, channels)?;
+        let shared = Arc::new(Shared { refined: OnceLock::new(),
+                                       cancelled: AtomicBool::new(false) });
+        let worker = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                if let Ok(Some(refined)) = refine(converter, ratio, &input, &shared) {
+                    let refined = shared.refined.get_or_init(|| refined);
+                    if let Some(callback) = callback {
+                        callback(refined)
+                    }
+                }
+            })
+        };
+        Ok(ProgressiveConvert { preview, shared, worker: Some(worker) })
+    }
+
+    /// Retrieve the fast conversion.
+    pub fn preview(&self) -> &[f32] {
+        &self.preview
+    }
+
+    /// Retrieve the best quality conversion, if it is ready.
+    pub fn refined(&self) -> Option<&[f32]> {
+        self.shared.refined.get().map(|refined| &refined[..])
+    }
+
+    /// Wait until the refinement finishes or is cancelled, and retrieve its result.
+    pub fn wait(&mut self) -> Option<&[f32]> {
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        self.refined()
+    }
+
+    /// Stop the refinement, if it is not finished yet. The worker thread exits after
+    /// converting at most one more chunk.
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed)
+    }
+}
+
+impl Drop for ProgressiveConvert {
+    /// Cancel the refinement, without waiting for the worker thread to exit.
+    fn drop(&mut self) {
+        self.cancel()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+    use super::*;
+
+    fn make_input(frames: usize, channels: usize) -> Arc<[f32]> {
+        (0..frames * channels).map(|i| ((i / channels) as f32 * 0.05).sin() * 0.5).collect()
+    }
+
+    #[test]
+    fn test_lengths_match() {
+        for &(frames, channels, ratio) in &[(10000, 1, 48000.0 / 44100.0), (3333, 2, 0.5),
+                                            (1001, 2, 3.0), (0, 1, 2.0)] {
+            let input = make_input(frames, channels);
+            for &preview in &[Interpolator::ZeroOrderHold, Interpolator::Linear] {
+                let mut progressive = ProgressiveConvert::new(preview, channels, ratio,
+                                                              input.clone()).unwrap();
+                let preview_len = progressive.preview().len();
+                assert_eq!(preview_len,
+                           (frames as f64 * ratio).round() as usize * channels);
+                assert_eq!(progressive.wait().unwrap().len(), preview_len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_refined_delivered() {
+        let input = make_input(20000, 2);
+        let (sender, receiver) = mpsc::channel();
+        let progressive = ProgressiveConvert::with_callback(
+            Interpolator::Linear, 2, 1.5, input.clone(),
+            move |refined| sender.send(refined.to_vec()).unwrap()).unwrap();
+        let from_callback = receiver.recv_timeout(Duration::from_secs(60)).unwrap();
+
+        let mut expected = convert_to_vec(Interpolator::SincBestQuality, 2, 1.5, &input).unwrap();
+        fit(&mut expected, 2, 1.5, input.len()).unwrap();
+        assert_eq!(from_callback, expected);
+        assert_eq!(progressive.refined(), Some(&expected[..]));
+        assert_ne!(progressive.preview(), &expected[..]);
+    }
+
+    #[test]
+    fn test_panicking_callback() {
+        let input = make_input(1000, 1);
+        let mut progressive = ProgressiveConvert::with_callback(
+            Interpolator::Linear, 1, 2.0, input, |_| panic!("callback failed")).unwrap();
+        assert_eq!(progressive.wait().map(|refined| refined.len()), Some(2000));
+    }
+
+    #[test]
+    fn test_cancel() {
+        // Long enough that the best quality conversion takes several seconds.
+        let input = make_input(1_000_000, 1);
+        let (sender, receiver) = mpsc::channel();
+        let mut progressive = ProgressiveConvert::with_callback(
+            Interpolator::ZeroOrderHold, 1, 2.0, input,
+            move |_| sender.send(()).unwrap()).unwrap();
+        let started = Instant::now();
+        progressive.cancel();
+        assert_eq!(progressive.wait(), None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(receiver.recv().is_err());
+        assert_eq!(progressive.preview().len(), 2_000_000);
+    }
+}
@@ -0,0 +1,299 @@
+use alloc::vec::Vec;
+
+use {Converter, ConverterInternals, Error, ErrorInternals, Interpolator, Result, try_vec};
+
+/// A [``Converter``](struct.Converter.html) that pulls its input from a closure as it needs
+/// it, like the callback-based libsamplerate API, but by copying the input into a buffer of
+/// its own rather than keeping a pointer into the caller's.
+///
+/// ``Converter`` itself never allocates, so the scratch buffer lives here instead.
+///
+/// libsamplerate is never given a single frame of input: its linear interpolator reads
+/// before the start of such an input. The last frames of the scratch buffer are held back
+/// until the stream ends, and then converted together into a buffer of their own that has
+/// room for all of their output. A stream of a single frame generates no output with the
+/// linear interpolator.
+pub struct PullConverter {
+    converter: Converter,
+    scratch: Vec<f32>,
+    /// Range of ``scratch`` holding input not yet used by libsamplerate.
+    start: usize,
+    end: usize,
+    /// The closure returned no more input.
+    ended: bool,
+    /// Output of the last frames of input, and the range of it not yet read.
+    tail: Vec<f32>,
+    tail_start: usize,
+    tail_end: usize,
+    /// libsamplerate generated all of the output of the stream.
+    flushed: bool,
+    /// The stream was flushed and all of its output was read.
+    finished: bool,
+}
+
+/// Frames of input held back from libsamplerate until the stream ends.
+const HELD_FRAMES: usize = 2;
+
+impl PullConverter {
+    /// Create a converter for ``channels`` interleaved channels that pulls at most
+    /// ``scratch_frames`` frames of input at once.
+    ///
+    /// Returns an error of kind [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory)
+    /// if the scratch buffer cannot be allocated.
+    ///
+    /// Panics if ``scratch_frames`` is zero.
+    pub fn new(interpolator: Interpolator, channels: usize, scratch_frames: usize)
+            -> Result<PullConverter> {
+        PullConverter::from_converter(Converter::new(interpolator, channels)?, scratch_frames)
+    }
+
+    /// Create a converter like [``new``](#method.new) that converts with ``converter``.
+    pub fn from_converter(converter: Converter, scratch_frames: usize)
+            -> Result<PullConverter> {
+        assert!(scratch_frames > 0, "scratch buffer must hold at least one frame");
+        let scratch_frames = scratch_frames.max(HELD_FRAMES + 2);
+        let scratch = try_vec(0.0, scratch_frames.saturating_mul(converter.channels()))?;
+        Ok(PullConverter {
+            converter,
+            scratch,
+            start: 0,
+            end: 0,
+            ended: false,
+            tail: Vec::new(),
+            tail_start: 0,
+            tail_end: 0,
+            flushed: false,
+            finished: false,
+        })
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        self.converter.channels()
+    }
+
+    /// Retrieve the number of frames of input the scratch buffer holds.
+    ///
+    /// This is the number given at creation, or 4 if that is fewer, unless the interpolator
+    /// held back a whole buffer of input at once, in which case the buffer grew to make room
+    /// for more.
+    pub fn scratch_frames(&self) -> usize {
+        self.scratch.len() / self.channels()
+    }
+
+    /// Check whether the closure returned no more input, and all of the output was read.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Convert at ``ratio`` into ``output`` until it is full or the input runs out, calling
+    /// ``fill`` whenever more input is needed.
+    ///
+    /// ``fill`` writes interleaved input into the buffer it is given and returns the number
+    /// of samples written, which must be a whole number of frames. It is called until the
+    /// scratch buffer is full, so it may write less than it is given. Returning 0 marks the end
+    /// of the stream: the internal state is flushed into ``output`` over this and the
+    /// following reads, and ``fill`` is not called again until the converter is
+    /// [``reset``](#method.reset).
+    ///
+    /// Returns the number of generated samples, which is less than the length of ``output``
+    /// only if the stream has no more output. Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``output``, or
+    /// the input written by ``fill``, is not a whole number of frames, in which case that
+    /// input is discarded.
+    ///
+    /// Panics if ``fill`` returns more samples than its buffer holds.
+    pub fn read_with<F>(&mut self, ratio: f64, output: &mut [f32], mut fill: F) -> Result<usize>
+            where F: FnMut(&mut [f32]) -> usize {
+        let channels = self.channels();
+        if output.len() % channels != 0 {
+            return Err(Error::bad_buffer_length(output.len(), channels))
+        }
+        let held = HELD_FRAMES * channels;
+        let mut generated = 0;
+        let mut stalled = false;
+        while generated < output.len() && !self.finished {
+            if self.tail_start < self.tail_end {
+                let len = (self.tail_end - self.tail_start).min(output.len() - generated);
+                output[generated..generated + len]
+                    .copy_from_slice(&self.tail[self.tail_start..self.tail_start + len]);
+                self.tail_start += len;
+                generated += len;
+            } else if self.flushed {
+                self.finished = true;
+            } else if !self.ended && (stalled || self.end - self.start < held + 2 * channels) {
+                self.refill(&mut fill)?;
+                stalled = false;
+            } else if !self.ended || (self.end - self.start >= held + 2 * channels && !stalled) {
+                let (used, g) = self.converter.process(ratio,
+                                                       &self.scratch[self.start..self.end - held],
+                                                       false, &mut output[generated..])?;
+                self.start += used;
+                generated += g;
+                stalled = used == 0 && g == 0;
+            } else {
+                self.convert_tail(ratio)?;
+            }
+        }
+        Ok(generated)
+    }
+
+    /// Discard the input pulled so far and reset the internal state, to start a new stream.
+    pub fn reset(&mut self) -> Result<()> {
+        self.converter.reset()?;
+        self.start = 0;
+        self.end = 0;
+        self.ended = false;
+        self.tail_start = 0;
+        self.tail_end = 0;
+        self.flushed = false;
+        self.finished = false;
+        Ok(())
+    }
+
+    /// Convert the input left after the stream ended into ``tail``, which has room for all of
+    /// its output, so that libsamplerate is done with it unless the filter tail of a sinc
+    /// interpolator is longer.
+    fn convert_tail(&mut self, ratio: f64) -> Result<()> {
+        let len = self.converter.output_len_hint(ratio, self.end - self.start);
+        if self.tail.len() < len {
+            self.tail = try_vec(0.0, len)?;
+        }
+        let mut input = &self.scratch[self.start..self.end];
+        let linear = self.converter.interpolator() == Some(Interpolator::Linear);
+        if linear && input.len() == self.channels() {
+            // Only a stream of a single frame gets here, and generates no output.
+            input = &[];
+        }
+        let (used, generated) = self.converter.process(ratio, input, true,
+                                                       &mut self.tail[..len])?;
+        self.start += used;
+        self.tail_start = 0;
+        self.tail_end = generated;
+        self.flushed = generated < len;
+        Ok(())
+    }
+
+    /// Move the unused input to the start of the scratch buffer and call ``fill`` until the
+    /// rest of it is full.
+    fn refill<F>(&mut self, fill: &mut F) -> Result<()>
+            where F: FnMut(&mut [f32]) -> usize {
+        let channels = self.channels();
+        self.scratch.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        if self.end == self.scratch.len() {
+            let len = self.scratch.len();
+            let mut scratch = try_vec(0.0, len.saturating_mul(2))?;
+            scratch[..len].copy_from_slice(&self.scratch);
+            self.scratch = scratch;
+        }
+        while self.end < self.scratch.len() {
+            let written = fill(&mut self.scratch[self.end..]);
+            assert!(self.end + written <= self.scratch.len(),
+                    "fill returned more samples than its buffer holds");
+            if written % channels != 0 {
+                return Err(Error::bad_buffer_length(written, channels))
+            }
+            self.end += written;
+            if written == 0 {
+                self.ended = true;
+                break
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use {BufferedConverter, ErrorKind, convert};
+    use test::sine;
+    use super::*;
+
+    /// Convert ``input`` by pushing it all at once.
+    fn push_all(interpolator: Interpolator, ratio: f64, input: &[f32]) -> Vec<f32> {
+        let mut conv = BufferedConverter::new(interpolator, 2, ratio).unwrap();
+        let mut output = vec![0.0; 2 * 8000];
+        let mut generated = conv.push(input, &mut output).unwrap();
+        loop {
+            let g = conv.flush(&mut output[generated..]).unwrap();
+            generated += g;
+            if g == 0 {
+                output.truncate(generated);
+                return output
+            }
+        }
+    }
+
+    /// Read all of ``input`` through ``block``, handing out at most ``dribble`` frames of it
+    /// at once.
+    fn pull_all(conv: &mut PullConverter, ratio: f64, input: &[f32], dribble: usize,
+                block: &mut [f32]) -> Vec<f32> {
+        let mut remaining = input;
+        let mut output = Vec::new();
+        loop {
+            let generated = conv.read_with(ratio, block, |buffer| {
+                let len = buffer.len().min(remaining.len()).min(2 * dribble);
+                buffer[..len].copy_from_slice(&remaining[..len]);
+                remaining = &remaining[len..];
+                len
+            }).unwrap();
+            output.extend_from_slice(&block[..generated]);
+            if generated < block.len() {
+                assert!(conv.is_finished());
+                return output
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_push() {
+        let input = sine(5000, 2);
+        // As (scratch frames, frames handed out by the closure at once, output frames).
+        let configs = [(256, 256, 300), (256, 1, 512), (1, 1, 64), (4096, 777, 1)];
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::Linear] {
+            let expected = push_all(interpolator, 1.5, &input);
+            for &(scratch_frames, dribble, block_frames) in &configs {
+                let mut conv = PullConverter::new(interpolator, 2, scratch_frames).unwrap();
+                let mut block = vec![0.0; 2 * block_frames];
+                let output = pull_all(&mut conv, 1.5, &input, dribble, &mut block);
+                assert_eq!(output, expected, "{:?} {}", interpolator, scratch_frames);
+
+                // A finished stream stays finished, until it is reset.
+                assert_eq!(conv.read_with(1.5, &mut block, |_| unreachable!()).unwrap(), 0);
+                conv.reset().unwrap();
+                assert_eq!(pull_all(&mut conv, 1.5, &input, dribble, &mut block), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_short_streams() {
+        let input = sine(5, 2);
+        for frames in 1..6 {
+            let mut expected = vec![0.0; 2 * 16];
+            if frames == 1 {
+                expected.clear();
+            } else {
+                let (_, generated) = convert(Interpolator::Linear, 2, 1.5, &input[..2 * frames],
+                                             &mut expected).unwrap();
+                expected.truncate(generated);
+            }
+            let mut conv = PullConverter::new(Interpolator::Linear, 2, 1).unwrap();
+            let output = pull_all(&mut conv, 1.5, &input[..2 * frames], 1, &mut [0.0; 2]);
+            assert_eq!(output, expected, "{}", frames);
+        }
+    }
+
+    #[test]
+    fn test_bad_fill_length() {
+        let mut conv = PullConverter::new(Interpolator::Linear, 2, 16).unwrap();
+        let error = conv.read_with(1.0, &mut [0.0; 8], |_| 3).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 3, channels: 2 });
+        let error = conv.read_with(1.0, &mut [0.0; 7], |_| 2).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::BadBufferLength { len: 7, channels: 2 });
+    }
+}
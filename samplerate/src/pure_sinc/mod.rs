@@ -0,0 +1,497 @@
+// A pure-Rust port of the libsamplerate SincFastest converter. It follows src_sinc.c
+// closely, down to the buffer management, so that the two can be compared sample by sample.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use {Error, ErrorInternals, Result, SRC_ERR_BAD_CHANNEL_COUNT, SRC_ERR_BAD_INTERNAL_STATE,
+     SRC_ERR_SINC_PREPARE_DATA_BAD_LEN, check_frames};
+
+mod coeffs;
+
+use self::coeffs::{FASTEST_COEFFS, FASTEST_INCREMENT};
+
+const SHIFT_BITS: u32 = 12;
+const FP_ONE: f64 = (1u32 << SHIFT_BITS) as f64;
+const INV_FP_ONE: f64 = 1.0 / FP_ONE;
+
+const SRC_MAX_RATIO: f64 = 256.0;
+const MAX_CHANNELS: usize = 128;
+
+/// Round to nearest with ties to even, like C ``lrint`` in the default rounding mode.
+fn lrint(x: f64) -> isize {
+    // Adding and subtracting 1.5 * 2^52 discards the fraction, rounding it the IEEE way.
+    // Exact for |x| < 2^51, which covers every value the filter ever rounds.
+    const MAGIC: f64 = 6755399441055744.0;
+    ((x + MAGIC) - MAGIC) as isize
+}
+
+fn fmod_one(x: f64) -> f64 {
+    let res = x - lrint(x) as f64;
+    if res < 0.0 { res + 1.0 } else { res }
+}
+
+fn double_to_fp(x: f64) -> i32 {
+    lrint(x * FP_ONE) as i32
+}
+
+fn fp_to_int(x: i32) -> usize {
+    (x >> SHIFT_BITS) as usize
+}
+
+fn fp_to_double(x: i32) -> f64 {
+    (x & ((1 << SHIFT_BITS) - 1)) as f64 * INV_FP_ONE
+}
+
+fn is_bad_ratio(ratio: f64) -> bool {
+    !(1.0 / SRC_MAX_RATIO..=SRC_MAX_RATIO).contains(&ratio)
+}
+
+/// Streaming windowed-sinc converter equivalent to ``Interpolator::SincFastest``, implemented
+/// without calling into libsamplerate.
+///
+/// This is a port of libsamplerate's sinc converter using the same coefficient table and
+/// fixed-point filter phase arithmetic, so its output matches that of ``SincFastest`` up to
+/// floating point rounding differences (the test suite checks every sample to within ``1e-6``).
+///
+/// The interface mirrors [``Converter``](struct.Converter.html): sample counts are in samples,
+/// buffers are interleaved, and the ratio is smoothly interpolated between calls.
+pub struct PureSincConverter {
+    channels: usize,
+    coeffs: &'static [f32],
+    coeff_half_len: isize,
+    index_inc: isize,
+
+    last_ratio: f64,
+    last_position: f64,
+
+    b_current: isize,
+    b_end: isize,
+    b_real_end: isize,
+    b_len: isize,
+
+    left: Vec<f64>,
+    right: Vec<f64>,
+    buffer: Vec<f32>,
+}
+
+impl PureSincConverter {
+    /// Create a converter.
+    pub fn new(channels: usize) -> Result<PureSincConverter> {
+        if !(1..=MAX_CHANNELS).contains(&channels) {
+            return Err(Error::from_code(SRC_ERR_BAD_CHANNEL_COUNT))
+        }
+
+        let coeffs = &FASTEST_COEFFS[..];
+        let coeff_half_len = coeffs.len() as isize - 2;
+        let index_inc = FASTEST_INCREMENT as isize;
+
+        let b_len = lrint(2.5 * coeff_half_len as f64 / (index_inc as f64) * SRC_MAX_RATIO);
+        let b_len = b_len.max(4096) * channels as isize;
+
+        let mut converter = PureSincConverter {
+            channels, coeffs, coeff_half_len, index_inc,
+            last_ratio: 0.0,
+            last_position: 0.0,
+            b_current: 0,
+            b_end: 0,
+            b_real_end: -1,
+            b_len,
+            left: vec![0.0; channels],
+            right: vec![0.0; channels],
+            buffer: vec![0.0; b_len as usize + channels],
+        };
+        converter.reset()?;
+        Ok(converter)
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Reset the internal state to the same state it had after [``new``](#method.new).
+    pub fn reset(&mut self) -> Result<()> {
+        self.b_current = 0;
+        self.b_end = 0;
+        self.b_real_end = -1;
+        for sample in self.buffer.iter_mut() {
+            *sample = 0.0;
+        }
+        self.last_position = 0.0;
+        self.last_ratio = 0.0;
+        Ok(())
+    }
+
+    /// Set the starting conversion ratio for the next call to [``convert``](#method.convert).
+    ///
+    /// Calling this function achieves a step response in conversion ratio instead of smooth
+    /// interpolation.
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        if is_bad_ratio(ratio) {
+            return Err(Error::bad_ratio(ratio))
+        }
+        self.last_ratio = ratio;
+        Ok(())
+    }
+
+    /// Convert samples using internal state, smoothly interpolating ratio.
+    ///
+    /// The size of both ``input`` and ``output`` must be a multiple of the converter's channel
+    /// count, or an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) is returned. If there
+    /// is no more input data, provide ``None`` as ``input``, and the converter will flush its
+    /// internal state.
+    ///
+    /// Returns the number of used input samples and generated output samples, respectively.
+    pub fn convert(&mut self, ratio: f64, input: Option<&[f32]>, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        self.process(ratio, input.unwrap_or(&[]), input.is_none(), output)
+    }
+
+    fn process(&mut self, ratio: f64, input: &[f32], end: bool, output: &mut [f32])
+            -> Result<(usize, usize)> {
+        let channels = self.channels as isize;
+        check_frames(self.channels, input.len(), output.len())?;
+
+        if is_bad_ratio(ratio) {
+            return Err(Error::bad_ratio(ratio))
+        }
+        if self.last_ratio < 1.0 / SRC_MAX_RATIO {
+            self.last_ratio = ratio;
+        }
+
+        let out_count = output.len() as isize;
+        let mut in_used = 0;
+        let mut out_gen = 0;
+
+        let mut src_ratio = self.last_ratio;
+        if is_bad_ratio(src_ratio) {
+            return Err(Error::from_code(SRC_ERR_BAD_INTERNAL_STATE))
+        }
+
+        // Check the sample rate ratio wrt the buffer len.
+        let mut count = (self.coeff_half_len as f64 + 2.0) / self.index_inc as f64;
+        let min_ratio = if self.last_ratio < ratio { self.last_ratio } else { ratio };
+        if min_ratio < 1.0 {
+            count /= min_ratio;
+        }
+
+        // Maximum coefficients on either side of center point.
+        let half_filter_chan_len = channels * (lrint(count) + 1);
+
+        let mut input_index = self.last_position;
+        let rem = fmod_one(input_index);
+        self.b_current = (self.b_current + channels * lrint(input_index - rem)) % self.b_len;
+        input_index = rem;
+
+        let terminate = 1.0 / src_ratio + 1e-20;
+
+        while out_gen < out_count {
+            // Need to reload buffer?
+            let mut samples_in_hand = (self.b_end - self.b_current + self.b_len) % self.b_len;
+            if samples_in_hand <= half_filter_chan_len {
+                self.prepare_data(input, &mut in_used, end, half_filter_chan_len)?;
+
+                samples_in_hand = (self.b_end - self.b_current + self.b_len) % self.b_len;
+                if samples_in_hand <= half_filter_chan_len {
+                    break
+                }
+            }
+
+            // This is the termination condition. (The mono converter in libsamplerate
+            // uses a strict comparison, and the multichannel ones do not.)
+            if self.b_real_end >= 0 {
+                let position = self.b_current as f64 + input_index + terminate;
+                let real_end = self.b_real_end as f64;
+                if (channels == 1 && position > real_end) ||
+                        (channels > 1 && position >= real_end) {
+                    break
+                }
+            }
+
+            if out_count > 0 && (self.last_ratio - ratio).abs() > 1e-10 {
+                src_ratio = self.last_ratio +
+                    out_gen as f64 * (ratio - self.last_ratio) / out_count as f64;
+            }
+
+            let float_increment =
+                self.index_inc as f64 * if src_ratio < 1.0 { src_ratio } else { 1.0 };
+            let increment = double_to_fp(float_increment);
+            let start_filter_index = double_to_fp(input_index * float_increment);
+
+            self.calc_output(increment, start_filter_index,
+                             float_increment / self.index_inc as f64,
+                             &mut output[out_gen as usize..(out_gen + channels) as usize]);
+            out_gen += channels;
+
+            // Figure out the next index.
+            input_index += 1.0 / src_ratio;
+            let rem = fmod_one(input_index);
+            self.b_current =
+                (self.b_current + channels * lrint(input_index - rem)) % self.b_len;
+            input_index = rem;
+        }
+
+        self.last_position = input_index;
+        // Save current ratio rather then target ratio.
+        self.last_ratio = src_ratio;
+
+        Ok((in_used as usize, out_gen as usize))
+    }
+
+    fn calc_output(&mut self, increment: i32, start_filter_index: i32, scale: f64,
+                   output: &mut [f32]) {
+        let channels = self.channels;
+        let coeffs = self.coeffs;
+        let max_filter_index = (self.coeff_half_len as i32) << SHIFT_BITS;
+
+        let interpolate = |filter_index: i32| {
+            let fraction = fp_to_double(filter_index);
+            let indx = fp_to_int(filter_index);
+            coeffs[indx] as f64 + fraction * (coeffs[indx + 1] as f64 - coeffs[indx] as f64)
+        };
+
+        // First apply the left half of the filter.
+        let mut filter_index = start_filter_index;
+        let coeff_count = (max_filter_index - filter_index) / increment;
+        filter_index += coeff_count * increment;
+        let mut data_index = (self.b_current - channels as isize * coeff_count as isize) as usize;
+
+        for left in self.left.iter_mut() {
+            *left = 0.0;
+        }
+        loop {
+            let icoeff = interpolate(filter_index);
+            let frame = &self.buffer[data_index..data_index + channels];
+            for (left, &sample) in self.left.iter_mut().zip(frame) {
+                *left += icoeff * sample as f64;
+            }
+
+            filter_index -= increment;
+            data_index += channels;
+            if filter_index < 0 { break }
+        }
+
+        // Now apply the right half of the filter.
+        let mut filter_index = increment - start_filter_index;
+        let coeff_count = (max_filter_index - filter_index) / increment;
+        filter_index += coeff_count * increment;
+        let mut data_index =
+            (self.b_current + channels as isize * (1 + coeff_count as isize)) as usize;
+
+        for right in self.right.iter_mut() {
+            *right = 0.0;
+        }
+        loop {
+            let icoeff = interpolate(filter_index);
+            let frame = &self.buffer[data_index..data_index + channels];
+            for (right, &sample) in self.right.iter_mut().zip(frame) {
+                *right += icoeff * sample as f64;
+            }
+
+            filter_index -= increment;
+            if filter_index <= 0 { break }
+            data_index -= channels;
+        }
+
+        for ((out, left), right) in output.iter_mut().zip(&self.left).zip(&self.right) {
+            *out = (scale * (left + right)) as f32;
+        }
+    }
+
+    fn prepare_data(&mut self, input: &[f32], in_used: &mut isize, end: bool,
+                    half_filter_chan_len: isize) -> Result<()> {
+        let channels = self.channels as isize;
+
+        if self.b_real_end >= 0 {
+            // Should be terminating. Just return.
+            return Ok(())
+        }
+
+        let mut len;
+        if self.b_current == 0 {
+            // Initial state. Set up zeros at the start of the buffer and then load new data
+            // after that.
+            len = self.b_len - 2 * half_filter_chan_len;
+            self.b_current = half_filter_chan_len;
+            self.b_end = half_filter_chan_len;
+        } else if self.b_end + half_filter_chan_len + channels < self.b_len {
+            // Load data at current end position.
+            len = (self.b_len - self.b_current - half_filter_chan_len).max(0);
+        } else {
+            // Move data at end of buffer back to the start of the buffer.
+            len = self.b_end - self.b_current;
+            self.move_to_start(half_filter_chan_len, len);
+
+            // Now load data at current end of buffer.
+            len = (self.b_len - self.b_current - half_filter_chan_len).max(0);
+        }
+
+        len = len.min(input.len() as isize - *in_used);
+        len -= len % channels;
+
+        if len < 0 || self.b_end + len > self.b_len {
+            return Err(Error::from_code(SRC_ERR_SINC_PREPARE_DATA_BAD_LEN))
+        }
+
+        let (b_end, start) = (self.b_end as usize, *in_used as usize);
+        self.buffer[b_end..b_end + len as usize]
+            .copy_from_slice(&input[start..start + len as usize]);
+
+        self.b_end += len;
+        *in_used += len;
+
+        if *in_used == input.len() as isize &&
+                self.b_end - self.b_current < 2 * half_filter_chan_len && end {
+            // Handle the case where all data in the current buffer has been consumed and this
+            // is the last buffer.
+            if self.b_len - self.b_end < half_filter_chan_len + 5 {
+                // If necessary, move data down to the start of the buffer.
+                let len = self.b_end - self.b_current;
+                self.move_to_start(half_filter_chan_len, len);
+            }
+
+            self.b_real_end = self.b_end;
+            let mut len = half_filter_chan_len + 5;
+            if len < 0 || self.b_end + len > self.b_len {
+                len = self.b_len - self.b_end;
+            }
+
+            let b_end = self.b_end as usize;
+            for sample in self.buffer[b_end..b_end + len as usize].iter_mut() {
+                *sample = 0.0;
+            }
+            self.b_end += len;
+        }
+
+        Ok(())
+    }
+
+    fn move_to_start(&mut self, half_filter_chan_len: isize, len: isize) {
+        let start = (self.b_current - half_filter_chan_len) as usize;
+        let end = (self.b_current + len) as usize;
+        self.buffer.copy_within(start..end, 0);
+        self.b_current = half_filter_chan_len;
+        self.b_end = self.b_current + len;
+    }
+}
+
+/// Perform a single conversion from input buffer to output buffer with a fixed conversion
+/// ratio using [``PureSincConverter``](struct.PureSincConverter.html).
+///
+/// This is the pure-Rust counterpart of [``convert``](fn.convert.html) with
+/// ``Interpolator::SincFastest``.
+///
+/// Returns the number of used input samples and generated output samples, respectively.
+pub fn convert_pure_sinc(channels: usize, ratio: f64, input: &[f32], output: &mut [f32])
+        -> Result<(usize, usize)> {
+    let mut converter = PureSincConverter::new(channels)?;
+    converter.process(ratio, input, /*end=*/true, output)
+}
+
+#[cfg(test)]
+mod test {
+    use std::f32;
+    use std::vec::Vec;
+    use std::vec;
+    use {convert, Converter, Interpolator};
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-6;
+
+    fn make_fixture(frames: usize, channels: usize) -> Vec<f32> {
+        let mut data = Vec::new();
+        for i in 0..frames {
+            for ch in 0..channels {
+                let phase = f32::consts::PI * 2.0 * i as f32 / frames as f32;
+                data.push((phase * (ch + 1) as f32).sin() * 0.9);
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_matches_c_one_shot() {
+        for &channels in &[1, 2, 3, 6] {
+            for &ratio in &[2.0, 0.5, 1.0, 44100.0 / 48000.0, 0.1, 7.3] {
+                let input = make_fixture(1000, channels);
+                let len = ((1000.0 * ratio) as usize + 10) * channels;
+
+                let mut expected = vec![0.; len];
+                let (used, gen) = convert(Interpolator::SincFastest, channels, ratio,
+                                          &input, &mut expected).unwrap();
+                let mut actual = vec![0.; len];
+                assert_eq!(convert_pure_sinc(channels, ratio, &input, &mut actual).unwrap(),
+                           (used, gen));
+                assert_audio_eq!(actual[..gen], expected[..gen], TOLERANCE, channels = channels);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_c_variable_ratio() {
+        for &channels in &[1, 2] {
+            let input = make_fixture(4000, channels);
+            let mut c_conv = Converter::new(Interpolator::SincFastest, channels).unwrap();
+            let mut rust_conv = PureSincConverter::new(channels).unwrap();
+            let (mut expected, mut actual) = (vec![0.; 1000 * channels], vec![0.; 1000 * channels]);
+            for (n, chunk) in input.chunks(250 * channels).enumerate() {
+                let ratio = 1.0 + n as f64 * 0.15;
+                let c = c_conv.convert(ratio, Some(chunk), &mut expected).unwrap();
+                let r = rust_conv.convert(ratio, Some(chunk), &mut actual).unwrap();
+                assert_eq!(c, r);
+                assert_audio_eq!(actual[..r.1], expected[..c.1], TOLERANCE, channels = channels);
+            }
+            let c = c_conv.convert(3.0, None, &mut expected).unwrap();
+            let r = rust_conv.convert(3.0, None, &mut actual).unwrap();
+            assert_eq!(c, r);
+            assert_audio_eq!(actual[..r.1], expected[..c.1], TOLERANCE, channels = channels);
+        }
+    }
+
+    fn convert_chunked(conv: &mut PureSincConverter, ratio: f64, input: &[f32],
+                       in_chunk: usize, out_chunk: usize) -> Vec<f32> {
+        let channels = conv.channels();
+        let mut output = Vec::new();
+        let mut buffer = vec![0.; out_chunk * channels];
+        let mut pos = 0;
+        while pos < input.len() {
+            let end = (pos + in_chunk * channels).min(input.len());
+            let (used, gen) = conv.convert(ratio, Some(&input[pos..end]), &mut buffer).unwrap();
+            output.extend_from_slice(&buffer[..gen]);
+            pos += used;
+        }
+        loop {
+            let (_, gen) = conv.convert(ratio, None, &mut buffer).unwrap();
+            if gen == 0 { break }
+            output.extend_from_slice(&buffer[..gen]);
+        }
+        output
+    }
+
+    #[test]
+    fn test_chunk_invariance() {
+        for &channels in &[1, 2] {
+            for &ratio in &[2.0, 0.5, 1.1] {
+                let input = make_fixture(3000, channels);
+                let mut conv = PureSincConverter::new(channels).unwrap();
+                let reference = convert_chunked(&mut conv, ratio, &input, 3000, 10000);
+                for &(in_chunk, out_chunk) in &[(7, 13), (13, 501), (501, 7), (1, 1)] {
+                    conv.reset().unwrap();
+                    let output = convert_chunked(&mut conv, ratio, &input, in_chunk, out_chunk);
+                    assert_audio_eq!(output, reference, TOLERANCE, channels = channels);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bad_arguments() {
+        assert!(PureSincConverter::new(0).is_err());
+        assert!(PureSincConverter::new(129).is_err());
+        let mut conv = PureSincConverter::new(1).unwrap();
+        assert!(conv.set_ratio(1000.0).is_err());
+        assert!(conv.convert(0.0, None, &mut [0.; 16]).is_err());
+    }
+}
@@ -0,0 +1,522 @@
+use alloc::collections::VecDeque;
+
+use {Converter, ConverterInternals, Error, ErrorInternals, Interpolator, Result, check_frames,
+     check_ratio, try_reserve_deque};
+
+/// Number of samples converted at once into the output queue.
+const EAGER_BLOCK_LEN: usize = 1024;
+
+/// What [``QueueConverter::push``](struct.QueueConverter.html#method.push) does when the
+/// input queue has no room for the samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Return an error of kind [``QueueFull``](enum.ErrorKind.html#variant.QueueFull), and
+    /// queue none of the samples.
+    Error,
+    /// Discard the oldest queued input to make room, counting the discarded frames in
+    /// [``dropped_frames``](struct.QueueConverter.html#method.dropped_frames). If there are
+    /// more new frames than the input queue holds, the oldest of them are discarded too.
+    DropOldest,
+    /// Convert queued input into the output queue to make room. If the output queue fills
+    /// up first, return an error of kind [``QueueFull``](enum.ErrorKind.html#variant.QueueFull)
+    /// and queue none of the samples; the input converted so far stays in the output queue.
+    ConvertEagerly,
+}
+
+/// Converter that buffers its input, for producers and consumers that work in blocks of
+/// unrelated sizes, e.g. a network stream feeding an audio callback.
+///
+/// Any amount of input can be [``push``](#method.push)ed at any time, and any amount of
+/// output [``pop``](#method.pop)ped; the input is converted when output is requested, and
+/// whatever libsamplerate does not use yet stays queued for the next pop. At the end of the
+/// stream, [``flush``](#method.flush) lets the remaining input and the frames libsamplerate
+/// holds back for its filter come out.
+///
+/// A converter created with [``new``](#method.new) queues as much input as it is given. One
+/// created with [``with_max_buffered_frames``](#method.with_max_buffered_frames) allocates
+/// bounded queues up front, and never allocates afterwards; when its input queue is full,
+/// [``push``](#method.push) follows the [``OverflowPolicy``](enum.OverflowPolicy.html), and
+/// [``try_push``](#method.try_push) queues only what fits.
+pub struct QueueConverter {
+    converter: Converter,
+    ratio: f64,
+    input: VecDeque<f32>,
+    /// Output converted ahead of time by ``OverflowPolicy::ConvertEagerly``.
+    output: VecDeque<f32>,
+    max_input_frames: Option<usize>,
+    max_output_frames: usize,
+    policy: OverflowPolicy,
+    dropped_frames: u64,
+    flushed: bool,
+    finished: bool,
+}
+
+impl QueueConverter {
+    /// Create a converter for ``channels`` interleaved channels at ``ratio``.
+    pub fn new(interpolator: Interpolator, channels: usize, ratio: f64)
+            -> Result<QueueConverter> {
+        check_ratio(ratio)?;
+        Ok(QueueConverter {
+            converter: Converter::new(interpolator, channels)?,
+            ratio,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            max_input_frames: None,
+            max_output_frames: 0,
+            policy: OverflowPolicy::Error,
+            dropped_frames: 0,
+            flushed: false,
+            finished: false,
+        })
+    }
+
+    /// Create a converter like [``new``](#method.new) that queues at most
+    /// ``max_input_frames`` frames of input, and at most ``max_output_frames`` frames of
+    /// output converted ahead of time by
+    /// [``OverflowPolicy::ConvertEagerly``](enum.OverflowPolicy.html#variant.ConvertEagerly).
+    /// The overflow policy is ``OverflowPolicy::Error``.
+    ///
+    /// Returns an error of kind [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory)
+    /// if the queues cannot be allocated.
+    ///
+    /// Panics if ``max_input_frames`` is zero.
+    pub fn with_max_buffered_frames(interpolator: Interpolator, channels: usize, ratio: f64,
+                                    max_input_frames: usize, max_output_frames: usize)
+            -> Result<QueueConverter> {
+        assert!(max_input_frames > 0, "input queue must hold at least one frame");
+        let mut queue = QueueConverter::new(interpolator, channels, ratio)?;
+        try_reserve_deque(&mut queue.input, max_input_frames.saturating_mul(channels))?;
+        try_reserve_deque(&mut queue.output, max_output_frames.saturating_mul(channels))?;
+        queue.max_input_frames = Some(max_input_frames);
+        queue.max_output_frames = max_output_frames;
+        Ok(queue)
+    }
+
+    /// Retrieve what [``push``](#method.push) does when the input queue is full.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Change what [``push``](#method.push) does when the input queue is full.
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
+    /// Retrieve the number of channels used by the converter.
+    pub fn channels(&self) -> usize {
+        self.converter.channels()
+    }
+
+    /// Retrieve the conversion ratio.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Change the conversion ratio, which is interpolated smoothly over the next pop, like
+    /// with [``Converter::convert``](struct.Converter.html#method.convert).
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        check_ratio(ratio)?;
+        self.ratio = ratio;
+        Ok(())
+    }
+
+    /// Retrieve the number of input frames queued and not yet used by libsamplerate.
+    pub fn buffered_frames(&self) -> usize {
+        self.input.len() / self.channels()
+    }
+
+    /// Retrieve the number of output frames converted ahead of time and not yet popped.
+    pub fn buffered_output_frames(&self) -> usize {
+        self.output.len() / self.channels()
+    }
+
+    /// Retrieve the number of input frames discarded by
+    /// [``OverflowPolicy::DropOldest``](enum.OverflowPolicy.html#variant.DropOldest) since
+    /// the converter was created or [``reset``](#method.reset).
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    /// Check whether [``flush``](#method.flush) was called since the converter was created
+    /// or [``reset``](#method.reset).
+    pub fn is_flushed(&self) -> bool {
+        self.flushed
+    }
+
+    /// Check whether the stream was flushed and all of its output was popped.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Queue ``input`` for conversion.
+    ///
+    /// Returns an error of kind [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory)
+    /// if the queue cannot grow, in which case none of ``input`` is queued. If the queue is
+    /// bounded and has no room for ``input``, follows the
+    /// [``OverflowPolicy``](enum.OverflowPolicy.html). Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``input`` is not
+    /// a whole number of frames.
+    ///
+    /// Panics if the stream was flushed; [``reset``](#method.reset) to start a new one.
+    pub fn push(&mut self, mut input: &[f32]) -> Result<()> {
+        self.check_push(input)?;
+        let capacity = match self.max_input_frames {
+            Some(max_input_frames) => max_input_frames * self.channels(),
+            None => {
+                try_reserve_deque(&mut self.input, input.len())?;
+                self.input.extend(input);
+                return Ok(())
+            }
+        };
+        if self.input.len() + input.len() > capacity {
+            match self.policy {
+                OverflowPolicy::Error =>
+                    return Err(Error::queue_full(capacity / self.channels())),
+                OverflowPolicy::DropOldest => {
+                    let excess = self.input.len() + input.len() - capacity;
+                    let queued = excess.min(self.input.len());
+                    self.input.drain(..queued);
+                    input = &input[excess - queued..];
+                    self.dropped_frames += (excess / self.channels()) as u64;
+                }
+                OverflowPolicy::ConvertEagerly => {
+                    if input.len() > capacity {
+                        return Err(Error::queue_full(capacity / self.channels()))
+                    }
+                    self.convert_eagerly(capacity - input.len())?;
+                    if self.input.len() + input.len() > capacity {
+                        return Err(Error::queue_full(capacity / self.channels()))
+                    }
+                }
+            }
+        }
+        // The queues of a bounded converter were allocated up front, so this never fails.
+        try_reserve_deque(&mut self.input, input.len())?;
+        self.input.extend(input);
+        Ok(())
+    }
+
+    /// Queue as much of ``input`` as fits, without following the overflow policy.
+    ///
+    /// Returns the number of samples queued, in whole frames from the start of ``input``,
+    /// or an error of kind [``QueueFull``](enum.ErrorKind.html#variant.QueueFull) if none of
+    /// a non-empty ``input`` fits. A converter created with [``new``](#method.new) queues
+    /// all of ``input``, or returns an error of kind
+    /// [``OutOfMemory``](enum.ErrorKind.html#variant.OutOfMemory). Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``input`` is not
+    /// a whole number of frames.
+    ///
+    /// Panics if the stream was flushed; [``reset``](#method.reset) to start a new one.
+    pub fn try_push(&mut self, input: &[f32]) -> Result<usize> {
+        self.check_push(input)?;
+        let capacity = match self.max_input_frames {
+            Some(max_input_frames) => max_input_frames * self.channels(),
+            None => {
+                try_reserve_deque(&mut self.input, input.len())?;
+                self.input.extend(input);
+                return Ok(input.len())
+            }
+        };
+        let accepted = input.len().min(capacity - self.input.len());
+        if accepted == 0 && !input.is_empty() {
+            return Err(Error::queue_full(capacity / self.channels()))
+        }
+        try_reserve_deque(&mut self.input, accepted)?;
+        self.input.extend(&input[..accepted]);
+        Ok(accepted)
+    }
+
+    fn check_push(&self, input: &[f32]) -> Result<()> {
+        assert!(!self.flushed, "cannot push after flushing");
+        check_frames(self.channels(), input.len(), 0)
+    }
+
+    /// Convert queued input into the output queue until at most ``max_input`` samples of
+    /// input are left, or the output queue is full.
+    fn convert_eagerly(&mut self, max_input: usize) -> Result<()> {
+        let channels = self.channels();
+        let mut block = [0.0; EAGER_BLOCK_LEN];
+        while self.input.len() > max_input {
+            let room = self.max_output_frames * channels - self.output.len();
+            let len = room.min(EAGER_BLOCK_LEN / channels * channels);
+            if len == 0 {
+                break
+            }
+            try_reserve_deque(&mut self.output, len)?;
+            let input = self.input.make_contiguous();
+            let (used, generated) = self.converter.process(self.ratio, input, false,
+                                                           &mut block[..len])?;
+            self.input.drain(..used);
+            self.output.extend(&block[..generated]);
+            if used == 0 && generated == 0 {
+                break
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark the end of the stream, so that the following pops convert all of the remaining
+    /// input and then flush the internal state.
+    pub fn flush(&mut self) {
+        self.flushed = true;
+    }
+
+    /// Convert queued input into ``output``, until it is full or more input is needed.
+    ///
+    /// Returns the number of generated samples, which is less than the length of ``output``
+    /// only if the queue ran dry, or if the stream was flushed and has no more output.
+    /// Returns an error of kind
+    /// [``BadBufferLength``](enum.ErrorKind.html#variant.BadBufferLength) if ``output`` is not
+    /// a whole number of frames.
+    pub fn pop(&mut self, output: &mut [f32]) -> Result<usize> {
+        check_frames(self.channels(), 0, output.len())?;
+        let mut generated = self.output.len().min(output.len());
+        for (sample, converted) in output.iter_mut().zip(self.output.drain(..generated)) {
+            *sample = converted;
+        }
+        while generated < output.len() && !self.finished {
+            let input = self.input.make_contiguous();
+            let (used, g) = self.converter.process(self.ratio, input, self.flushed,
+                                                   &mut output[generated..])?;
+            self.input.drain(..used);
+            generated += g;
+            if used == 0 && g == 0 {
+                self.finished = self.flushed && self.input.is_empty();
+                break
+            }
+        }
+        Ok(generated)
+    }
+
+    /// Discard the queued input and output and reset the internal state, to start a new
+    /// stream.
+    pub fn reset(&mut self) -> Result<()> {
+        self.converter.reset()?;
+        self.input.clear();
+        self.output.clear();
+        self.dropped_frames = 0;
+        self.flushed = false;
+        self.finished = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec;
+    use std::vec::Vec;
+    use test::sine;
+    use super::*;
+    use {ErrorKind, convert};
+
+    #[test]
+    fn test_matches_one_shot() {
+        let input = sine(10000, 2);
+        let mut expected = vec![0.0; 2 * 15100];
+        let (_, generated) = convert(Interpolator::SincFastest, 2, 1.5, &input, &mut expected)
+            .unwrap();
+        expected.truncate(generated);
+
+        let mut queue = QueueConverter::new(Interpolator::SincFastest, 2, 1.5).unwrap();
+        let mut output = Vec::new();
+        let mut block = [0.0; 2 * 300];
+        for chunk in input.chunks(2 * 777) {
+            queue.push(chunk).unwrap();
+            loop {
+                let generated = queue.pop(&mut block).unwrap();
+                output.extend_from_slice(&block[..generated]);
+                if generated < block.len() {
+                    break
+                }
+            }
+        }
+        queue.flush();
+        while !queue.is_finished() {
+            let generated = queue.pop(&mut block[..2 * 7]).unwrap();
+            output.extend_from_slice(&block[..generated]);
+        }
+        assert_eq!(queue.buffered_frames(), 0);
+        assert_eq!(queue.pop(&mut block).unwrap(), 0);
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reset() {
+        let input = sine(1000, 2);
+        let mut queue = QueueConverter::new(Interpolator::Linear, 2, 0.5).unwrap();
+        queue.push(&input).unwrap();
+        queue.flush();
+        assert!(queue.is_flushed());
+        let mut first = [0.0; 2 * 600];
+        let generated = queue.pop(&mut first).unwrap();
+
+        queue.reset().unwrap();
+        assert!(!queue.is_flushed() && !queue.is_finished());
+        assert_eq!(queue.buffered_frames(), 0);
+        queue.push(&input).unwrap();
+        queue.flush();
+        let mut second = [0.0; 2 * 600];
+        assert_eq!(queue.pop(&mut second).unwrap(), generated);
+        assert_eq!(first[..], second[..]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(QueueConverter::new(Interpolator::Linear, 2, 1000.0).is_err());
+        let mut queue = QueueConverter::new(Interpolator::Linear, 2, 2.0).unwrap();
+        assert!(queue.set_ratio(0.0).is_err());
+        assert_eq!(queue.ratio(), 2.0);
+        queue.set_ratio(0.5).unwrap();
+        assert_eq!(queue.ratio(), 0.5);
+
+        let bad_length = ErrorKind::BadBufferLength { len: 3, channels: 2 };
+        assert_eq!(queue.push(&[0.0; 3]).unwrap_err().kind(), bad_length);
+        assert_eq!(queue.try_push(&[0.0; 3]).unwrap_err().kind(), bad_length);
+        assert_eq!(queue.pop(&mut [0.0; 3]).unwrap_err().kind(), bad_length);
+        assert_eq!(queue.buffered_frames(), 0);
+    }
+
+    fn drain(queue: &mut QueueConverter, output: &mut Vec<f32>) {
+        let mut block = [0.0; 2 * 300];
+        loop {
+            let generated = queue.pop(&mut block).unwrap();
+            output.extend_from_slice(&block[..generated]);
+            if generated < block.len() {
+                break
+            }
+        }
+    }
+
+    fn one_shot(input: &[f32], ratio: f64) -> Vec<f32> {
+        let mut expected = vec![0.0; input.len() * 2 + 64];
+        let (_, generated) = convert(Interpolator::SincFastest, 2, ratio, input, &mut expected)
+            .unwrap();
+        expected.truncate(generated);
+        expected
+    }
+
+    #[test]
+    fn test_overflow_error() {
+        let input = sine(1000, 2);
+        let mut queue = QueueConverter::with_max_buffered_frames(
+            Interpolator::SincFastest, 2, 1.5, 600, 0).unwrap();
+        assert_eq!(queue.overflow_policy(), OverflowPolicy::Error);
+        queue.push(&input[..2 * 500]).unwrap();
+        let error = queue.push(&input[2 * 500..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::QueueFull { capacity_frames: 600 });
+        assert_eq!(queue.buffered_frames(), 500);
+
+        // Nothing was queued, so pushing the rejected samples again loses nothing.
+        let mut output = Vec::new();
+        drain(&mut queue, &mut output);
+        queue.push(&input[2 * 500..]).unwrap();
+        queue.flush();
+        drain(&mut queue, &mut output);
+        assert!(queue.is_finished());
+        assert_eq!(output, one_shot(&input, 1.5));
+    }
+
+    #[test]
+    fn test_overflow_drop_oldest() {
+        let input = sine(1000, 2);
+        let mut queue = QueueConverter::with_max_buffered_frames(
+            Interpolator::SincFastest, 2, 1.5, 600, 0).unwrap();
+        queue.set_overflow_policy(OverflowPolicy::DropOldest);
+        queue.push(&input[..2 * 500]).unwrap();
+        queue.push(&input[2 * 500..2 * 800]).unwrap();
+        assert_eq!((queue.buffered_frames(), queue.dropped_frames()), (600, 200));
+        // More than the queue holds at once drops the oldest of the new frames too.
+        queue.push(&input[..2 * 700]).unwrap();
+        assert_eq!((queue.buffered_frames(), queue.dropped_frames()), (600, 900));
+
+        // Exactly the newest frames are left.
+        queue.flush();
+        let mut output = Vec::new();
+        drain(&mut queue, &mut output);
+        assert_eq!(output, one_shot(&input[2 * 100..2 * 700], 1.5));
+
+        queue.reset().unwrap();
+        assert_eq!(queue.dropped_frames(), 0);
+    }
+
+    #[test]
+    fn test_overflow_convert_eagerly() {
+        let input = sine(3000, 2);
+        let mut queue = QueueConverter::with_max_buffered_frames(
+            Interpolator::SincFastest, 2, 1.5, 600, 300).unwrap();
+        queue.set_overflow_policy(OverflowPolicy::ConvertEagerly);
+        queue.push(&input[..2 * 600]).unwrap();
+        assert_eq!(queue.buffered_output_frames(), 0);
+        // Room is made by converting the queued input.
+        queue.push(&input[2 * 600..2 * 700]).unwrap();
+        assert!(queue.buffered_output_frames() > 0);
+
+        // Once the output queue is full, nothing more is queued.
+        let mut pushed = 2 * 700;
+        let error = loop {
+            match queue.push(&input[pushed..pushed + 2 * 100]) {
+                Ok(()) => pushed += 2 * 100,
+                Err(error) => break error,
+            }
+        };
+        assert_eq!(error.kind(), ErrorKind::QueueFull { capacity_frames: 600 });
+        assert_eq!((queue.buffered_frames(), queue.buffered_output_frames()), (600, 300));
+        let error = queue.push(&input[..2 * 601]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::QueueFull { capacity_frames: 600 });
+
+        // The converted output comes out first, and nothing is lost.
+        let mut output = Vec::new();
+        let mut block = [0.0; 2 * 10];
+        assert_eq!(queue.pop(&mut block).unwrap(), block.len());
+        output.extend_from_slice(&block);
+        assert_eq!(queue.buffered_output_frames(), 290);
+        drain(&mut queue, &mut output);
+        assert_eq!(queue.buffered_output_frames(), 0);
+        for chunk in input[pushed..].chunks(2 * 500) {
+            while queue.push(chunk).is_err() {
+                drain(&mut queue, &mut output);
+            }
+        }
+        queue.flush();
+        drain(&mut queue, &mut output);
+        assert!(queue.is_finished());
+        assert_eq!(output, one_shot(&input, 1.5));
+    }
+
+    #[test]
+    fn test_try_push() {
+        let input = sine(1000, 2);
+        let mut queue = QueueConverter::with_max_buffered_frames(
+            Interpolator::SincFastest, 2, 0.5, 300, 0).unwrap();
+        queue.set_overflow_policy(OverflowPolicy::DropOldest);
+        assert_eq!(queue.try_push(&[]).unwrap(), 0);
+        let mut pushed = queue.try_push(&input[..2 * 200]).unwrap();
+        // Only whole frames that fit are queued, regardless of the policy.
+        pushed += queue.try_push(&input[pushed..]).unwrap();
+        assert_eq!(pushed, 2 * 300);
+        let error = queue.try_push(&input[pushed..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::QueueFull { capacity_frames: 300 });
+        assert_eq!((queue.buffered_frames(), queue.dropped_frames()), (300, 0));
+
+        let mut output = Vec::new();
+        while pushed < input.len() {
+            drain(&mut queue, &mut output);
+            pushed += queue.try_push(&input[pushed..]).unwrap();
+        }
+        queue.flush();
+        drain(&mut queue, &mut output);
+        assert_eq!(queue.buffered_frames(), 0);
+        assert_eq!(output, one_shot(&input, 0.5));
+
+        let mut unbounded = QueueConverter::new(Interpolator::Linear, 2, 2.0).unwrap();
+        assert_eq!(unbounded.try_push(&input).unwrap(), input.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot push after flushing")]
+    fn test_push_after_flush() {
+        let mut queue = QueueConverter::new(Interpolator::Linear, 1, 2.0).unwrap();
+        queue.flush();
+        let _ = queue.push(&[0.0]);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+use {Interpolator, Result, convert_to_vec};
+
+/// Key identifying a converted buffer in a [``ResampleCache``](struct.ResampleCache.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    source: u64,
+    ratio: u64,
+    interpolator: Interpolator,
+    channels: usize,
+}
+
+impl CacheKey {
+    /// Create a key for the source buffer identified by ``id``.
+    ///
+    /// The caller is responsible for never using the same ``id`` for different buffers.
+    pub fn new(id: u64, interpolator: Interpolator, channels: usize, ratio: f64) -> CacheKey {
+        CacheKey { source: id, ratio: ratio.to_bits(), interpolator, channels }
+    }
+
+    /// Create a key for the source buffer identified by a hash of its contents.
+    ///
+    /// Hashing is linear in the size of ``samples``, although much cheaper than converting it.
+    pub fn for_content(samples: &[f32], interpolator: Interpolator, channels: usize,
+                       ratio: f64) -> CacheKey {
+        let mut hasher = DefaultHasher::new();
+        samples.len().hash(&mut hasher);
+        for sample in samples {
+            sample.to_bits().hash(&mut hasher);
+        }
+        CacheKey::new(hasher.finish(), interpolator, channels, ratio)
+    }
+}
+
+/// Hit and miss counts of a [``ResampleCache``](struct.ResampleCache.html).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+struct Inner {
+    /// Entries in the order of use, the least recently used first.
+    entries: Vec<(CacheKey, Arc<[f32]>)>,
+    frames: usize,
+    stats: CacheStats,
+}
+
+/// Cache of converted buffers, for repeatedly converting the same short buffers with the same
+/// small set of ratios, e.g. pitching sound effects.
+///
+/// The cache is bounded by the total number of cached frames; when inserting a buffer would
+/// exceed the bound, the least recently used buffers are evicted. A converted buffer that
+/// is larger than the bound on its own is returned without being cached.
+///
+/// The cache can be shared between threads. Conversions are performed without holding the
+/// lock, so a slow conversion does not block hits on other threads; if several threads miss
+/// on the same key at once, each of them converts the buffer, and all of them receive the
+/// first result that was inserted.
+pub struct ResampleCache {
+    max_frames: usize,
+    inner: Mutex<Inner>,
+}
+
+impl ResampleCache {
+    /// Create a cache holding at most ``max_frames`` converted frames in total.
+    pub fn new(max_frames: usize) -> ResampleCache {
+        ResampleCache {
+            max_frames,
+            inner: Mutex::new(Inner { entries: Vec::new(), frames: 0,
+                                      stats: CacheStats::default() }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        // The cached data is always consistent, even if a thread panicked.
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Retrieve the buffer converted with the parameters in ``key``, converting the buffer
+    /// returned by ``source`` with [``convert``](fn.convert.html) if it is not cached.
+    ///
+    /// ``source`` is only called on a miss, without holding the lock; if it panics, nothing
+    /// is cached for ``key`` and the cache stays usable.
+    pub fn get_or_convert<'a, F>(&self, key: CacheKey, source: F) -> Result<Arc<[f32]>>
+            where F: FnOnce() -> &'a [f32] {
+        {
+            let mut inner = self.lock();
+            if let Some(index) = inner.entries.iter().position(|&(k, _)| k == key) {
+                let entry = inner.entries.remove(index);
+                let buffer = entry.1.clone();
+                inner.entries.push(entry);
+                inner.stats.hits += 1;
+                return Ok(buffer)
+            }
+            inner.stats.misses += 1;
+        }
+
+        let ratio = f64::from_bits(key.ratio);
+        let buffer: Arc<[f32]> =
+            convert_to_vec(key.interpolator, key.channels, ratio, source())?.into();
+        let frames = buffer.len() / key.channels;
+
+        let mut inner = self.lock();
+        if let Some((_, buffer)) = inner.entries.iter().find(|&&(k, _)| k == key) {
+            return Ok(buffer.clone())
+        }
+        if frames > self.max_frames {
+            return Ok(buffer)
+        }
+        while inner.frames + frames > self.max_frames {
+            let (key, buffer) = inner.entries.remove(0);
+            inner.frames -= buffer.len() / key.channels;
+            inner.stats.evictions += 1;
+        }
+        inner.frames += frames;
+        inner.entries.push((key, buffer.clone()));
+        Ok(buffer)
+    }
+
+    /// Retrieve the hit, miss and eviction counts.
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Retrieve the total number of cached frames.
+    pub fn cached_frames(&self) -> usize {
+        self.lock().frames
+    }
+
+    /// Evict all cached buffers.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.frames = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::thread;
+    use super::*;
+
+    fn make_sfx(frames: usize) -> Vec<f32> {
+        (0..frames).map(|i| (i as f32 * 0.3).sin() * 0.5).collect()
+    }
+
+    #[test]
+    fn test_hit_returns_same_arc() {
+        let cache = ResampleCache::new(10000);
+        let sfx = make_sfx(500);
+        let key = CacheKey::new(1, Interpolator::SincFastest, 1, 1.5);
+        let first = cache.get_or_convert(key, || &sfx).unwrap();
+        let second = cache.get_or_convert(key, || panic!("converted on a hit")).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1, evictions: 0 });
+
+        let mut expected = std::vec![0.; 1000];
+        let (_, generated) = ::convert(Interpolator::SincFastest, 1, 1.5,
+                                       &sfx, &mut expected).unwrap();
+        assert_eq!(&first[..], &expected[..generated]);
+    }
+
+    #[test]
+    fn test_ratios_do_not_collide() {
+        let cache = ResampleCache::new(10000);
+        let sfx = make_sfx(500);
+        let up = cache.get_or_convert(CacheKey::for_content(&sfx, Interpolator::Linear, 1, 2.0),
+                                      || &sfx).unwrap();
+        let down = cache.get_or_convert(CacheKey::for_content(&sfx, Interpolator::Linear, 1, 0.5),
+                                        || &sfx).unwrap();
+        assert_eq!(up.len(), 1000);
+        assert_eq!(down.len(), 250);
+        let other = cache.get_or_convert(CacheKey::for_content(&sfx, Interpolator::ZeroOrderHold,
+                                                               1, 2.0), || &sfx).unwrap();
+        assert!(!Arc::ptr_eq(&up, &other));
+        assert_eq!(cache.stats().misses, 3);
+        assert_eq!(cache.cached_frames(), up.len() + down.len() + other.len());
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let cache = ResampleCache::new(2500);
+        let sfx = make_sfx(1000);
+        let key = |id| CacheKey::new(id, Interpolator::Linear, 1, 1.0);
+        cache.get_or_convert(key(1), || &sfx).unwrap();
+        cache.get_or_convert(key(2), || &sfx).unwrap();
+        // Touch 1, so that 2 is the least recently used.
+        cache.get_or_convert(key(1), || &sfx).unwrap();
+        cache.get_or_convert(key(3), || &sfx).unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 3, evictions: 1 });
+        assert_eq!(cache.cached_frames(), 2000);
+
+        cache.get_or_convert(key(1), || &sfx).unwrap();
+        cache.get_or_convert(key(3), || &sfx).unwrap();
+        assert_eq!(cache.stats().hits, 3);
+        cache.get_or_convert(key(2), || &sfx).unwrap();
+        assert_eq!(cache.stats().misses, 4);
+
+        // Too large to cache at all.
+        let long = make_sfx(3000);
+        assert_eq!(cache.get_or_convert(key(4), || &long).unwrap().len(), 3000);
+        assert_eq!(cache.cached_frames(), 2000);
+    }
+
+    #[test]
+    fn test_panicking_source() {
+        let cache = ResampleCache::new(10000);
+        let key = CacheKey::new(1, Interpolator::Linear, 1, 2.0);
+        let result = std::panic::catch_unwind(|| {
+            cache.get_or_convert(key, || panic!("no source"))
+        });
+        assert!(result.is_err());
+        assert_eq!(cache.cached_frames(), 0);
+        let sfx = make_sfx(500);
+        assert_eq!(cache.get_or_convert(key, || &sfx).unwrap().len(), 1000);
+        assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2, evictions: 0 });
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let cache = Arc::new(ResampleCache::new(10000));
+        let sfx = Arc::new(make_sfx(500));
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let (cache, sfx) = (cache.clone(), sfx.clone());
+                thread::spawn(move || {
+                    cache.get_or_convert(CacheKey::new(7, Interpolator::Linear, 1, 2.0),
+                                         || &sfx).unwrap()
+                })
+            })
+            .collect();
+        let results: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+        assert!(results.iter().all(|r| Arc::ptr_eq(r, &results[0])));
+        assert_eq!(cache.cached_frames(), 1000);
+    }
+}
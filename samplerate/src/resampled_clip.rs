@@ -0,0 +1,287 @@
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::vec::Vec;
+
+use {Interpolator, Result, check_ratio, convert_to_vec};
+
+/// Samples served by a [``ResampledClip``](struct.ResampledClip.html), and the ratio they
+/// were rendered at.
+///
+/// The ratio is the requested one once the render for it is ready; until then, it is the
+/// ratio of the nearest cached render, or 1 for the source itself.
+#[derive(Debug, Clone)]
+pub struct ClipRender {
+    pub ratio: f64,
+    pub samples: Arc<[f32]>,
+}
+
+struct State {
+    source: Arc<[f32]>,
+    /// Incremented whenever the source is replaced, so that renders of the old source that
+    /// finish afterwards are discarded.
+    generation: u64,
+    /// Renders in the order of use, the least recently used first.
+    renders: Vec<(f64, Arc<[f32]>)>,
+    /// Ratio to render next; only the latest request is kept.
+    wanted: Option<f64>,
+    /// Ratio and source generation of the render in progress.
+    rendering: Option<(f64, u64)>,
+    /// Whether a worker thread is running.
+    working: bool,
+    render_count: u64,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    idle: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is always consistent, even if a thread panicked.
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Clip of audio played back at a ratio that changes occasionally and stays constant in
+/// between, e.g. a clip in an arrangement stretched by dragging its edge.
+///
+/// The clip owns its source audio and caches complete renders, flush included, of the
+/// ``max_renders`` most recently used ratios. Requesting a ratio that is not cached starts
+/// rendering it on a worker thread, and serves the cached render with the nearest ratio, or
+/// the source if there is none, in the meantime. Only one ratio is rendered at a time; if
+/// several uncached ratios are requested during a render, only the last of them is rendered
+/// next. At a ratio of 1, the source is served as it is.
+///
+/// If a render fails, e.g. because its output cannot be allocated, nothing is cached, and
+/// the ratio is rendered again when it is next requested.
+pub struct ResampledClip {
+    interpolator: Interpolator,
+    channels: usize,
+    max_renders: usize,
+    shared: Arc<Shared>,
+}
+
+fn work(shared: Arc<Shared>, interpolator: Interpolator, channels: usize, max_renders: usize) {
+    let mut state = shared.lock();
+    while let Some(ratio) = state.wanted.take() {
+        let (source, generation) = (state.source.clone(), state.generation);
+        state.rendering = Some((ratio, generation));
+        state.render_count += 1;
+        drop(state);
+
+        let result = convert_to_vec(interpolator, channels, ratio, &source);
+
+        state = shared.lock();
+        state.rendering = None;
+        if let Ok(samples) = result {
+            if state.generation == generation {
+                if state.renders.len() == max_renders {
+                    state.renders.remove(0);
+                }
+                state.renders.push((ratio, samples.into()));
+            }
+        }
+    }
+    state.working = false;
+    shared.idle.notify_all();
+}
+
+impl ResampledClip {
+    /// Create a clip of ``channels`` interleaved channels, which caches renders of
+    /// ``source`` with ``interpolator`` at ``max_renders`` ratios at most.
+    ///
+    /// Panics if ``max_renders`` is zero.
+    pub fn new(interpolator: Interpolator, channels: usize, max_renders: usize,
+               source: Arc<[f32]>) -> ResampledClip {
+        assert!(max_renders > 0, "at least one render must be cached");
+        let state = State {
+            source,
+            generation: 0,
+            renders: Vec::new(),
+            wanted: None,
+            rendering: None,
+            working: false,
+            render_count: 0,
+        };
+        ResampledClip {
+            interpolator,
+            channels,
+            max_renders,
+            shared: Arc::new(Shared { state: Mutex::new(state), idle: Condvar::new() }),
+        }
+    }
+
+    /// Retrieve the number of channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Retrieve the source audio.
+    pub fn source(&self) -> Arc<[f32]> {
+        self.shared.lock().source.clone()
+    }
+
+    /// Replace the source audio, discarding all renders of the previous one, including
+    /// one in progress.
+    pub fn replace_source(&self, source: Arc<[f32]>) {
+        let mut state = self.shared.lock();
+        state.source = source;
+        state.generation += 1;
+        state.renders.clear();
+    }
+
+    /// Retrieve the clip rendered at ``ratio``, or the nearest available version while it
+    /// is being rendered.
+    ///
+    /// Returns an error if ``ratio`` is out of the range libsamplerate supports.
+    pub fn get(&self, ratio: f64) -> Result<ClipRender> {
+        check_ratio(ratio)?;
+        let mut state = self.shared.lock();
+        if ratio == 1.0 {
+            return Ok(ClipRender { ratio, samples: state.source.clone() })
+        }
+        if let Some(index) = state.renders.iter().position(|&(r, _)| r == ratio) {
+            let render = state.renders.remove(index);
+            state.renders.push(render.clone());
+            return Ok(ClipRender { ratio, samples: render.1 })
+        }
+
+        if state.rendering == Some((ratio, state.generation)) {
+            // Superseded by this request.
+            state.wanted = None;
+        } else {
+            state.wanted = Some(ratio);
+            if !state.working {
+                state.working = true;
+                let shared = self.shared.clone();
+                let (interpolator, channels, max_renders) =
+                    (self.interpolator, self.channels, self.max_renders);
+                thread::spawn(move || work(shared, interpolator, channels, max_renders));
+            }
+        }
+
+        let distance = |r: f64| (r / ratio).ln().abs();
+        let nearest = state.renders.iter()
+            .min_by(|a, b| distance(a.0).total_cmp(&distance(b.0)));
+        Ok(match nearest {
+            Some(&(ratio, ref samples)) => ClipRender { ratio, samples: samples.clone() },
+            None => ClipRender { ratio: 1.0, samples: state.source.clone() },
+        })
+    }
+
+    /// Retrieve the number of renders started so far.
+    pub fn render_count(&self) -> u64 {
+        self.shared.lock().render_count
+    }
+
+    /// Wait until no render is in progress or requested.
+    pub fn wait(&self) {
+        let state = self.shared.lock();
+        let _state = self.shared.idle.wait_while(state, |state| state.working)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+    }
+}
+
+impl Drop for ResampledClip {
+    fn drop(&mut self) {
+        // Let the worker thread finish the render in progress and exit.
+        self.shared.lock().wanted = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use super::*;
+
+    fn make_clip(frames: usize, phase: f32) -> Arc<[f32]> {
+        (0..frames * 2).map(|i| ((i / 2) as f32 * 0.05 + phase).sin() * 0.5).collect()
+    }
+
+    fn render(source: &[f32], ratio: f64) -> Vec<f32> {
+        convert_to_vec(Interpolator::SincFastest, 2, ratio, source).unwrap()
+    }
+
+    #[test]
+    fn test_same_ratio_hits_cache() {
+        let source = make_clip(2000, 0.0);
+        let clip = ResampledClip::new(Interpolator::SincFastest, 2, 4, source.clone());
+        let first = clip.get(1.5).unwrap();
+        assert_eq!(first.ratio, 1.0);
+        assert!(Arc::ptr_eq(&first.samples, &source));
+
+        clip.wait();
+        let rendered = clip.get(1.5).unwrap();
+        assert_eq!(rendered.ratio, 1.5);
+        assert_eq!(rendered.samples[..], render(&source, 1.5)[..]);
+        for _ in 0..10 {
+            assert!(Arc::ptr_eq(&clip.get(1.5).unwrap().samples, &rendered.samples));
+        }
+        assert_eq!(clip.render_count(), 1);
+
+        // The source is not rendered at all.
+        assert!(Arc::ptr_eq(&clip.get(1.0).unwrap().samples, &source));
+        assert_eq!(clip.render_count(), 1);
+        assert!(clip.get(1000.0).is_err());
+    }
+
+    #[test]
+    fn test_ratio_change_serves_nearest() {
+        let source = make_clip(2000, 0.0);
+        let clip = ResampledClip::new(Interpolator::SincFastest, 2, 4, source.clone());
+        clip.get(1.5).unwrap();
+        clip.wait();
+
+        // Served the render at 1.5 until the new one is ready.
+        assert_eq!(clip.get(0.5).unwrap().ratio, 1.5);
+        clip.wait();
+        let rendered = clip.get(0.5).unwrap();
+        assert_eq!(rendered.ratio, 0.5);
+        assert_eq!(rendered.samples[..], render(&source, 0.5)[..]);
+
+        assert_eq!(clip.get(0.6).unwrap().ratio, 0.5);
+        assert_eq!(clip.get(1.4).unwrap().ratio, 1.5);
+        clip.wait();
+        // Only the last of the requests made during a render is rendered after it.
+        assert!(clip.render_count() <= 4);
+        assert_eq!(clip.get(1.4).unwrap().ratio, 1.4);
+    }
+
+    #[test]
+    fn test_eviction() {
+        let source = make_clip(1000, 0.0);
+        let clip = ResampledClip::new(Interpolator::Linear, 2, 2, source);
+        for &ratio in &[1.5, 0.5, 1.5, 2.0] {
+            clip.get(ratio).unwrap();
+            clip.wait();
+        }
+        // 0.5 was the least recently used when 2.0 was rendered.
+        assert_eq!(clip.render_count(), 3);
+        assert_eq!(clip.get(1.5).unwrap().ratio, 1.5);
+        assert_eq!(clip.get(0.5).unwrap().ratio, 1.5);
+        clip.wait();
+        assert_eq!(clip.render_count(), 4);
+    }
+
+    #[test]
+    fn test_replace_source() {
+        let (old, new) = (make_clip(2000, 0.0), make_clip(2000, 1.0));
+        let clip = ResampledClip::new(Interpolator::SincFastest, 2, 4, old.clone());
+        clip.get(1.5).unwrap();
+        clip.wait();
+        // A render of the old source may still be in progress when it is replaced.
+        clip.get(2.0).unwrap();
+        clip.replace_source(new.clone());
+        assert!(Arc::ptr_eq(&clip.source(), &new));
+
+        let served = clip.get(2.0).unwrap();
+        assert_eq!(served.ratio, 1.0);
+        assert!(Arc::ptr_eq(&served.samples, &new));
+        clip.wait();
+        let rendered = clip.get(2.0).unwrap();
+        assert_eq!(rendered.ratio, 2.0);
+        assert_eq!(rendered.samples[..], render(&new, 2.0)[..]);
+        assert_eq!(clip.get(1.5).unwrap().ratio, 2.0);
+    }
+}
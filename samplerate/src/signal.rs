@@ -0,0 +1,222 @@
+//! Resampling of [dasp](https://crates.io/crates/dasp_signal) signals.
+//!
+//! [``resample``](fn.resample.html) wraps a signal of ``f32`` frames, of any channel count
+//! dasp has a frame type for, in a [``Resample``](struct.Resample.html) signal that yields
+//! its frames converted by libsamplerate, one at a time.
+
+use alloc::vec::Vec;
+
+use dasp_frame::Frame;
+use dasp_signal::Signal;
+
+use {Converter, Interpolator, Result, try_vec};
+
+/// Number of frames pulled from the inner signal, and generated by the converter, at once.
+const BLOCK_FRAMES: usize = 1024;
+
+/// Signal yielding the frames of another signal converted at a fixed ratio; see
+/// [``resample``](fn.resample.html).
+///
+/// Frames are pulled from the inner signal a block at a time, converted into another
+/// block, and then yielded one by one. Once the inner signal is exhausted, the converter is
+/// flushed, and this signal is exhausted only after the last frame of the tail was yielded.
+pub struct Resample<S: Signal> {
+    inner: S,
+    converter: Converter,
+    ratio: f64,
+    /// Interleaved frames pulled from the inner signal, of which ``input[start..end]`` are
+    /// not yet used by the converter.
+    input: Vec<f32>,
+    start: usize,
+    end: usize,
+    /// Interleaved frames generated by the converter, of which
+    /// ``output[output_pos..output_len]`` are not yet yielded.
+    output: Vec<f32>,
+    output_pos: usize,
+    output_len: usize,
+    /// The converter was flushed and has generated its whole tail.
+    done: bool,
+}
+
+/// Wrap ``inner`` in a signal that yields its frames converted at ``ratio``, i.e. the
+/// ratio of the output sample rate to the sample rate of ``inner``, with ``interpolator``.
+///
+/// The first block of ``inner`` is pulled and converted right away, so that
+/// [``is_exhausted``](struct.Resample.html#method.is_exhausted) can tell from the start
+/// whether there is any output. Returns an error of kind
+/// [``BadRatio``](enum.ErrorKind.html#variant.BadRatio) if ``ratio`` is not
+/// [valid](fn.is_valid_ratio.html), or any error of
+/// [``Converter::new``](struct.Converter.html#method.new) or of that first conversion.
+pub fn resample<S>(inner: S, interpolator: Interpolator, ratio: f64) -> Result<Resample<S>>
+        where S: Signal, S::Frame: Frame<Sample = f32> {
+    let channels = S::Frame::CHANNELS;
+    let mut converter = Converter::new(interpolator, channels)?;
+    converter.set_ratio(ratio)?;
+    let mut signal = Resample {
+        inner,
+        converter,
+        ratio,
+        input: try_vec(0.0, BLOCK_FRAMES * channels)?,
+        start: 0,
+        end: 0,
+        output: try_vec(0.0, BLOCK_FRAMES * channels)?,
+        output_pos: 0,
+        output_len: 0,
+        done: false,
+    };
+    signal.fill()?;
+    Ok(signal)
+}
+
+impl<S> Resample<S> where S: Signal, S::Frame: Frame<Sample = f32> {
+    /// Retrieve the conversion ratio.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Retrieve the inner signal.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Unwrap the inner signal, discarding the frames pulled from it but not yet yielded.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Convert until there is a frame to yield or the tail of the stream was generated.
+    fn fill(&mut self) -> Result<()> {
+        let channels = S::Frame::CHANNELS;
+        let mut stalled = self.start == self.end;
+        while self.output_pos == self.output_len && !self.done {
+            if stalled && !self.inner.is_exhausted() {
+                self.refill()?;
+            }
+            let input = if self.start == self.end && self.inner.is_exhausted() {
+                None
+            } else {
+                Some(&self.input[self.start..self.end])
+            };
+            let status = self.converter.convert_with_status(self.ratio, input,
+                                                            &mut self.output)?;
+            self.start += status.result.input_samples_used(channels);
+            self.output_pos = 0;
+            self.output_len = status.result.output_samples_generated(channels);
+            self.done = status.done;
+            stalled = status.result.input_frames_used == 0 && self.output_len == 0;
+        }
+        Ok(())
+    }
+
+    /// Move the unused input to the start of the input buffer and pull frames from the inner
+    /// signal until the rest of it is full or the inner signal is exhausted.
+    fn refill(&mut self) -> Result<()> {
+        let channels = S::Frame::CHANNELS;
+        self.input.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+        if self.end == self.input.len() {
+            // The converter held back a whole buffer of input, so make room for more.
+            let len = self.input.len();
+            let mut input = try_vec(0.0, len.saturating_mul(2))?;
+            input[..len].copy_from_slice(&self.input);
+            self.input = input;
+        }
+        while self.end < self.input.len() && !self.inner.is_exhausted() {
+            let frame = self.inner.next();
+            for (sample, value) in self.input[self.end..].iter_mut().zip(frame.channels()) {
+                *sample = value;
+            }
+            self.end += channels;
+        }
+        Ok(())
+    }
+}
+
+impl<S> Signal for Resample<S> where S: Signal, S::Frame: Frame<Sample = f32> {
+    type Frame = S::Frame;
+
+    /// Yield the next converted frame, or silence once the signal is exhausted.
+    ///
+    /// Panics if libsamplerate fails to convert, which it does not for a valid ratio short
+    /// of running out of memory.
+    fn next(&mut self) -> S::Frame {
+        if self.output_pos == self.output_len {
+            return S::Frame::EQUILIBRIUM
+        }
+        let frame = S::Frame::from_fn(|channel| self.output[self.output_pos + channel]);
+        self.output_pos += S::Frame::CHANNELS;
+        self.fill().expect("conversion with a valid ratio failed");
+        frame
+    }
+
+    /// Check whether the inner signal is exhausted and the whole tail of the converter was
+    /// yielded.
+    fn is_exhausted(&self) -> bool {
+        self.output_pos == self.output_len
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+    use dasp_signal;
+    use {ErrorKind, convert};
+    use test::sine;
+    use super::*;
+
+    /// Resample the sine fixture of ``frames`` frames as a signal of ``F`` frames, and
+    /// check that it yields what converting it in one go generates.
+    fn check<F>(interpolator: Interpolator, ratio: f64, frames: usize)
+            where F: Frame<Sample = f32> {
+        let channels = F::CHANNELS;
+        let input = sine(frames, channels);
+        let mut expect = vec![0.0; (frames as f64 * ratio) as usize * channels + 8 * channels];
+        let (_, generated) = convert(interpolator, channels, ratio, &input, &mut expect)
+            .unwrap();
+        expect.truncate(generated);
+
+        let frames = input.chunks(channels).map(|chunk| F::from_fn(|channel| chunk[channel]));
+        let signal = resample(dasp_signal::from_iter(frames), interpolator, ratio).unwrap();
+        let output: Vec<f32> = signal.until_exhausted().flat_map(|frame| frame.channels())
+            .collect();
+        assert_eq!(output.len(), expect.len(), "{:?} at {}", interpolator, ratio);
+        assert_eq!(output, expect, "{:?} at {}", interpolator, ratio);
+    }
+
+    #[test]
+    fn test_matches_convert() {
+        for &interpolator in &[Interpolator::SincFastest, Interpolator::SincMediumQuality] {
+            for &ratio in &[2.0, 0.5] {
+                check::<[f32; 1]>(interpolator, ratio, 5000);
+                check::<[f32; 2]>(interpolator, ratio, 5000);
+            }
+        }
+        // Shorter than a block, and than the filter of the interpolator.
+        check::<[f32; 2]>(Interpolator::SincFastest, 2.0, 10);
+    }
+
+    #[test]
+    fn test_exhausted() {
+        let empty = dasp_signal::from_iter(Vec::<[f32; 2]>::new());
+        let mut signal = resample(empty, Interpolator::SincFastest, 2.0).unwrap();
+        assert!(signal.is_exhausted());
+        assert_eq!(signal.next(), [0.0; 2]);
+
+        // An endless signal never runs out.
+        let mut signal = resample(dasp_signal::gen(|| [0.5f32]), Interpolator::Linear, 0.5)
+            .unwrap();
+        for _ in 0..BLOCK_FRAMES * 4 {
+            signal.next();
+        }
+        assert!(!signal.is_exhausted());
+        assert_eq!(signal.next(), [0.5]);
+    }
+
+    #[test]
+    fn test_bad_ratio() {
+        let empty = dasp_signal::from_iter(Vec::<[f32; 1]>::new());
+        let error = resample(empty, Interpolator::Linear, 1000.0).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::BadRatio);
+    }
+}
@@ -0,0 +1,151 @@
+use alloc::vec::Vec;
+
+use {Converter, Error, Interpolator, Result, SRC_ERR_BAD_INTERNAL_STATE, try_reserve};
+
+/// Group of converters with the same configuration, kept frame-locked, e.g. for resampling
+/// several stems that are mixed afterwards.
+///
+/// On every call, each member is given the same number of input frames (that of the shortest
+/// input) and the same room for output frames (that of the shortest output). Since the
+/// members have the same interpolator, channel count and history, they use and generate the
+/// same number of frames, so their positions in the stream never drift apart. Input beyond
+/// the returned count, including the excess of longer inputs, is left for the caller to
+/// provide again on the next call, exactly like with [``Converter``](struct.Converter.html).
+pub struct SyncGroup {
+    members: Vec<Converter>,
+}
+
+impl SyncGroup {
+    /// Create a group of ``count`` converters.
+    pub fn new(count: usize, interpolator: Interpolator, channels: usize) -> Result<SyncGroup> {
+        let mut members = Vec::new();
+        try_reserve(&mut members, count)?;
+        for _ in 0..count {
+            members.push(Converter::new(interpolator, channels)?);
+        }
+        Ok(SyncGroup { members })
+    }
+
+    /// Retrieve the members of the group.
+    pub fn members(&self) -> &[Converter] {
+        &self.members
+    }
+
+    /// Reset the internal state of every member.
+    pub fn reset(&mut self) -> Result<()> {
+        self.members.iter_mut().try_for_each(|member| member.reset())
+    }
+
+    /// Set the starting conversion ratio of every member, like
+    /// [``Converter::set_ratio``](struct.Converter.html#method.set_ratio).
+    pub fn set_ratio(&mut self, ratio: f64) -> Result<()> {
+        self.members.iter_mut().try_for_each(|member| member.set_ratio(ratio))
+    }
+
+    fn process(&mut self, ratio: f64, inputs: Option<&[&[f32]]>, outputs: &mut [&mut [f32]])
+            -> Result<(usize, usize)> {
+        assert_eq!(outputs.len(), self.members.len(), "need one output per member");
+        if let Some(inputs) = inputs {
+            assert_eq!(inputs.len(), self.members.len(), "need one input per member");
+        }
+        let input_len = inputs.map(|inputs| inputs.iter().map(|input| input.len()).min());
+        let output_len = outputs.iter().map(|output| output.len()).min().unwrap_or(0);
+
+        let mut result = None;
+        for (index, (member, output)) in self.members.iter_mut().zip(outputs).enumerate() {
+            let input = inputs.map(|inputs| &inputs[index][..input_len.unwrap().unwrap_or(0)]);
+            let counts = member.convert(ratio, input, &mut output[..output_len])?;
+            match result {
+                None => result = Some(counts),
+                Some(expected) if expected == counts => (),
+                // Only possible if the members were desynchronized through `members`.
+                Some(_) => return Err(Error::from_code(SRC_ERR_BAD_INTERNAL_STATE)),
+            }
+        }
+        Ok(result.unwrap_or((0, 0)))
+    }
+
+    /// Convert one input per member into one output per member, using the same number of
+    /// frames for every member.
+    ///
+    /// Returns the number of used input samples and generated output samples, respectively,
+    /// which are the same for every member.
+    pub fn convert_all_locked(&mut self, ratio: f64, inputs: &[&[f32]],
+                              outputs: &mut [&mut [f32]]) -> Result<(usize, usize)> {
+        self.process(ratio, Some(inputs), outputs)
+    }
+
+    /// Flush the internal state of every member into one output per member, like
+    /// calling [``Converter::convert``](struct.Converter.html#method.convert) with ``None``.
+    ///
+    /// Returns the number of generated output samples, which is the same for every member.
+    pub fn flush_all_locked(&mut self, ratio: f64, outputs: &mut [&mut [f32]])
+            -> Result<usize> {
+        Ok(self.process(ratio, None, outputs)?.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use std::vec;
+    use super::*;
+
+    #[test]
+    fn test_irregular_chunks_stay_locked() {
+        let stems: Vec<Vec<f32>> = (0..4)
+            .map(|stem| (0..2 * 3000).map(|i| (i as f32 * 0.01 * (stem + 1) as f32).sin())
+                                     .collect())
+            .collect();
+        let mut outputs: Vec<Vec<f32>> = (0..4).map(|_| vec![0.; 2 * 7000]).collect();
+        let mut group = SyncGroup::new(4, Interpolator::SincMediumQuality, 2).unwrap();
+
+        // A different amount of each stem arrives on every step, and the output buffers have
+        // different sizes.
+        let (mut offsets, mut generated) = ([0; 4], 0);
+        let mut step = 0;
+        while offsets.iter().any(|&offset| offset < stems[0].len()) {
+            step += 1;
+            let inputs: Vec<&[f32]> = (0..4)
+                .map(|stem| {
+                    let end = (offsets[stem] + 2 * (37 * (step + stem) % 211)).min(6000);
+                    &stems[stem][offsets[stem]..end]
+                })
+                .collect();
+            let mut chunks: Vec<&mut [f32]> = outputs.iter_mut()
+                .enumerate()
+                .map(|(stem, output)| {
+                    let len = (2 * (53 * (step + 2 * stem) % 401)).min(output.len() - generated);
+                    &mut output[generated..generated + len]
+                })
+                .collect();
+            let (used, gen) = group.convert_all_locked(1.7, &inputs, &mut chunks).unwrap();
+            for offset in offsets.iter_mut() {
+                *offset += used;
+            }
+            generated += gen;
+            let frames_in = group.members()[0].frames_in();
+            let frames_out = group.members()[0].frames_out();
+            for member in group.members() {
+                assert_eq!((member.frames_in(), member.frames_out()), (frames_in, frames_out));
+            }
+            assert_eq!(frames_in as usize * 2, offsets[0]);
+        }
+        let mut chunks: Vec<&mut [f32]> = outputs.iter_mut()
+            .map(|output| &mut output[generated..])
+            .collect();
+        generated += group.flush_all_locked(1.7, &mut chunks).unwrap();
+        for member in group.members() {
+            assert_eq!(member.frames_out() as usize * 2, generated);
+        }
+
+        // Every stem matches what a single converter produces from the whole input.
+        for (stem, output) in stems.iter().zip(outputs.iter()) {
+            let mut expected = vec![0.; 2 * 7000];
+            let (_, gen) = ::convert(Interpolator::SincMediumQuality, 2, 1.7,
+                                     stem, &mut expected).unwrap();
+            assert_eq!(gen, generated);
+            assert_audio_eq!(output[..gen], expected[..gen], 1e-3, channels = 2);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! Assertions for tests that compare audio, with the ``test-util`` feature.
+//!
+//! [``assert_audio_eq!``](../macro.assert_audio_eq.html) compares two buffers sample by
+//! sample within a tolerance, and [``assert_audio_snr!``](../macro.assert_audio_snr.html)
+//! by their signal-to-noise ratio. When an assertion fails, it reports where and by how much
+//! the buffers differ, with the frames around that point. If the ``SAMPLERATE_DUMP_AUDIO``
+//! environment variable is set, it also writes both buffers to WAV files in the temporary
+//! directory, so that they can be listened to or compared in an audio editor.
+
+use std::convert::TryFrom;
+use std::env;
+use std::fmt::Write as FmtWrite;
+use std::format;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::string::String;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Environment variable that makes failed assertions write both buffers to WAV files.
+pub const DUMP_AUDIO_VAR: &str = "SAMPLERATE_DUMP_AUDIO";
+
+/// Sample rate of the WAV files written by assertions that do not specify one.
+pub const DEFAULT_DUMP_RATE: u32 = 48000;
+
+/// Number of frames shown before and after the frame a failure points at.
+const CONTEXT_FRAMES: usize = 2;
+
+/// Assert that two buffers of interleaved ``f32`` samples have the same length and differ by
+/// at most a tolerance in every sample.
+///
+/// The buffers can be anything that is ``AsRef<[f32]>``, such as slices, arrays and vectors.
+/// The number of channels, 1 by default, sets how the failure report groups samples into
+/// frames, and the sample rate, ``DEFAULT_DUMP_RATE`` by default, is written to the dumped
+/// WAV files; see the [``test_util``](test_util/index.html) module.
+///
+/// ```
+/// # #[macro_use] extern crate samplerate;
+/// # fn main() {
+/// let actual = [0.5, -0.25, 0.5001, -0.25];
+/// assert_audio_eq!(actual, [0.5, -0.25, 0.5, -0.25], 1e-3);
+/// assert_audio_eq!(actual, [0.5, -0.25, 0.5, -0.25], 1e-3, channels = 2);
+/// assert_audio_eq!(actual, [0.5, -0.25, 0.5, -0.25], 1e-3, channels = 2, rate = 44100);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_audio_eq {
+    ($actual:expr, $expected:expr, $tolerance:expr) => {
+        $crate::assert_audio_eq!($actual, $expected, $tolerance, channels = 1)
+    };
+    ($actual:expr, $expected:expr, $tolerance:expr, channels = $channels:expr) => {
+        $crate::assert_audio_eq!($actual, $expected, $tolerance, channels = $channels,
+                         rate = $crate::test_util::DEFAULT_DUMP_RATE)
+    };
+    ($actual:expr, $expected:expr, $tolerance:expr, channels = $channels:expr,
+     rate = $rate:expr) => {
+        if let Err(message) = $crate::test_util::check_audio_eq(&$actual, &$expected,
+                                                                $tolerance, $channels, $rate) {
+            panic!("{}", message)
+        }
+    };
+}
+
+/// Assert that two buffers of interleaved ``f32`` samples have the same length, and that the
+/// signal-to-noise ratio of the first, taking the second as the signal, is at least a number
+/// of decibels.
+///
+/// The arguments are like those of [``assert_audio_eq!``](macro.assert_audio_eq.html), with
+/// the minimum ratio in place of the tolerance.
+///
+/// ```
+/// # #[macro_use] extern crate samplerate;
+/// # fn main() {
+/// let expected: Vec<f32> = (0..1000).map(|n| (n as f32 * 0.1).sin()).collect();
+/// let actual: Vec<f32> = expected.iter().map(|sample| sample * 1.001).collect();
+/// assert_audio_snr!(actual, expected, 50.0);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_audio_snr {
+    ($actual:expr, $expected:expr, $min_db:expr) => {
+        $crate::assert_audio_snr!($actual, $expected, $min_db, channels = 1)
+    };
+    ($actual:expr, $expected:expr, $min_db:expr, channels = $channels:expr) => {
+        $crate::assert_audio_snr!($actual, $expected, $min_db, channels = $channels,
+                          rate = $crate::test_util::DEFAULT_DUMP_RATE)
+    };
+    ($actual:expr, $expected:expr, $min_db:expr, channels = $channels:expr,
+     rate = $rate:expr) => {
+        if let Err(message) = $crate::test_util::check_audio_snr(&$actual, &$expected,
+                                                                 $min_db, $channels, $rate) {
+            panic!("{}", message)
+        }
+    };
+}
+
+/// Differences between two buffers, over the frames both of them have.
+struct Errors {
+    /// First frame with a sample that differs by more than the tolerance, or by NaN.
+    first_divergent: Option<usize>,
+    max: f32,
+    max_frame: usize,
+    mean: f64,
+    signal_power: f64,
+    noise_power: f64,
+}
+
+impl Errors {
+    fn measure(actual: &[f32], expected: &[f32], channels: usize, tolerance: f32) -> Errors {
+        let mut errors = Errors {
+            first_divergent: None,
+            max: 0.0,
+            max_frame: 0,
+            mean: 0.0,
+            signal_power: 0.0,
+            noise_power: 0.0,
+        };
+        let mut sum = 0.0;
+        for (index, (&a, &e)) in actual.iter().zip(expected).enumerate() {
+            let error = (a - e).abs();
+            // NaN counts as divergent, and as the largest error.
+            let divergent = error.is_nan() || error > tolerance;
+            if divergent && errors.first_divergent.is_none() {
+                errors.first_divergent = Some(index / channels);
+            }
+            if !errors.max.is_nan() && (error.is_nan() || error > errors.max) {
+                errors.max = error;
+                errors.max_frame = index / channels;
+            }
+            sum += error as f64;
+            errors.signal_power += e as f64 * e as f64;
+            errors.noise_power += error as f64 * error as f64;
+        }
+        let len = actual.len().min(expected.len());
+        if len > 0 {
+            errors.mean = sum / len as f64;
+        }
+        errors
+    }
+
+    fn snr_db(&self) -> f64 {
+        10.0 * (self.signal_power / self.noise_power).log10()
+    }
+}
+
+/// Compute the signal-to-noise ratio of ``actual`` in decibels, taking ``expected`` as the
+/// signal, over the samples both of them have.
+///
+/// Identical buffers have an infinite ratio, and silent ones a NaN ratio.
+pub fn snr_db(actual: &[f32], expected: &[f32]) -> f64 {
+    Errors::measure(actual, expected, 1, 0.0).snr_db()
+}
+
+/// Check the condition of [``assert_audio_eq!``](../macro.assert_audio_eq.html), returning
+/// the failure report as an error.
+///
+/// Panics if ``channels`` is zero, or if either buffer is not a whole number of frames.
+pub fn check_audio_eq<A, E>(actual: &A, expected: &E, tolerance: f32, channels: usize,
+                            rate: u32) -> Result<(), String>
+        where A: AsRef<[f32]> + ?Sized, E: AsRef<[f32]> + ?Sized {
+    let (actual, expected) = (actual.as_ref(), expected.as_ref());
+    check_frames(actual, expected, channels);
+    let errors = Errors::measure(actual, expected, channels, tolerance);
+    let (headline, frame) = match errors.first_divergent {
+        Some(frame) =>
+            (format!("audio differs by more than {} from frame {}", tolerance, frame), frame),
+        None if actual.len() == expected.len() => return Ok(()),
+        None => {
+            let frame = actual.len().min(expected.len()) / channels;
+            (format!("audio differs in length from frame {}", frame), frame)
+        }
+    };
+    Err(report(&headline, actual, expected, channels, rate, &errors, frame))
+}
+
+/// Check the condition of [``assert_audio_snr!``](../macro.assert_audio_snr.html), returning
+/// the failure report as an error.
+///
+/// Panics if ``channels`` is zero, or if either buffer is not a whole number of frames.
+pub fn check_audio_snr<A, E>(actual: &A, expected: &E, min_db: f64, channels: usize,
+                             rate: u32) -> Result<(), String>
+        where A: AsRef<[f32]> + ?Sized, E: AsRef<[f32]> + ?Sized {
+    let (actual, expected) = (actual.as_ref(), expected.as_ref());
+    check_frames(actual, expected, channels);
+    let errors = Errors::measure(actual, expected, channels, 0.0);
+    let snr = errors.snr_db();
+    let headline = if actual.len() != expected.len() {
+        format!("audio differs in length from frame {}",
+                actual.len().min(expected.len()) / channels)
+    } else if snr.is_nan() || snr < min_db {
+        format!("audio has a signal-to-noise ratio of {:.1} dB, less than {:.1} dB",
+                snr, min_db)
+    } else {
+        return Ok(())
+    };
+    Err(report(&headline, actual, expected, channels, rate, &errors, errors.max_frame))
+}
+
+fn check_frames(actual: &[f32], expected: &[f32], channels: usize) {
+    assert!(channels > 0, "channel count must be non-zero");
+    assert!(actual.len() % channels == 0 && expected.len() % channels == 0,
+            "audio must be a whole number of frames");
+}
+
+/// Format the failure report: the headline, the lengths, the errors, the frames around
+/// ``frame``, and where the buffers were dumped.
+fn report(headline: &str, actual: &[f32], expected: &[f32], channels: usize, rate: u32,
+          errors: &Errors, frame: usize) -> String {
+    let mut report = String::from(headline);
+    let _ = writeln!(report);
+    let _ = writeln!(report, "  length: {} frames actual, {} expected",
+                     actual.len() / channels, expected.len() / channels);
+    let _ = writeln!(report, "  max error {:.3e} at frame {}, mean error {:.3e}",
+                     errors.max, errors.max_frame, errors.mean);
+    let _ = writeln!(report, "  {:>9}  {:<width$}  expected", "frame", "actual",
+                     width = channels * 10 - 1);
+    let frames = actual.len().max(expected.len()) / channels;
+    for index in frame.saturating_sub(CONTEXT_FRAMES)..(frame + CONTEXT_FRAMES + 1).min(frames) {
+        let marker = if index == frame { '>' } else { ' ' };
+        let _ = write!(report, "  {} {:>7} ", marker, index);
+        for samples in &[actual, expected] {
+            for channel in 0..channels {
+                match samples.get(index * channels + channel) {
+                    Some(sample) => { let _ = write!(report, " {:>+9.6}", sample); }
+                    None => { let _ = write!(report, " {:>9}", "-"); }
+                }
+            }
+            report.push(' ');
+        }
+        report.truncate(report.trim_end().len());
+        report.push('\n');
+    }
+    if env::var_os(DUMP_AUDIO_VAR).is_some() {
+        match dump(actual, expected, channels, rate) {
+            Ok((actual, expected)) =>
+                { let _ = write!(report, "  dumped to {} and {}", actual.display(),
+                                 expected.display()); }
+            Err(error) => { let _ = write!(report, "  could not dump the audio: {}", error); }
+        }
+    } else {
+        let _ = write!(report, "  set {} to dump both buffers to WAV files", DUMP_AUDIO_VAR);
+    }
+    report
+}
+
+/// Write both buffers to WAV files in the temporary directory, returning their paths.
+fn dump(actual: &[f32], expected: &[f32], channels: usize, rate: u32)
+        -> io::Result<(PathBuf, PathBuf)> {
+    static DUMPS: AtomicUsize = AtomicUsize::new(0);
+    let stem = format!("samplerate-{}-{}", process::id(), DUMPS.fetch_add(1, Ordering::Relaxed));
+    let dir = env::temp_dir();
+    let paths = (dir.join(format!("{}-actual.wav", stem)),
+                 dir.join(format!("{}-expected.wav", stem)));
+    write_wav(&paths.0, actual, channels, rate)?;
+    write_wav(&paths.1, expected, channels, rate)?;
+    Ok(paths)
+}
+
+/// Write ``samples`` to a WAV file of 32-bit float samples.
+fn write_wav(path: &Path, samples: &[f32], channels: usize, rate: u32) -> io::Result<()> {
+    const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+    let too_long = || io::Error::new(io::ErrorKind::InvalidInput, "too long for a WAV file");
+    let data_len = u32::try_from(samples.len() * 4).map_err(|_| too_long())?;
+    let riff_len = data_len.checked_add(36).ok_or_else(too_long)?;
+    let channels = u16::try_from(channels).map_err(|_| too_long())?;
+    let block_align = channels * 4;
+
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(b"RIFF")?;
+    file.write_all(&riff_len.to_le_bytes())?;
+    file.write_all(b"WAVEfmt ")?;
+    file.write_all(&16u32.to_le_bytes())?;
+    file.write_all(&WAVE_FORMAT_IEEE_FLOAT.to_le_bytes())?;
+    file.write_all(&channels.to_le_bytes())?;
+    file.write_all(&rate.to_le_bytes())?;
+    file.write_all(&(rate * block_align as u32).to_le_bytes())?;
+    file.write_all(&block_align.to_le_bytes())?;
+    file.write_all(&32u16.to_le_bytes())?;
+    file.write_all(b"data")?;
+    file.write_all(&data_len.to_le_bytes())?;
+    for sample in samples {
+        file.write_all(&sample.to_le_bytes())?;
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::vec;
+    use std::vec::Vec;
+    use super::*;
+
+    /// Drop the last line, which depends on whether ``DUMP_AUDIO_VAR`` is set.
+    fn without_dump_line(report: &str) -> &str {
+        &report[..report.rfind('\n').unwrap()]
+    }
+
+    #[test]
+    fn test_eq_report() {
+        let expected: Vec<f32> = (0..20).map(|n| n as f32 * 0.01).collect();
+        let mut actual = expected.clone();
+        actual[12] += 0.5;
+        actual[15] -= 1.0;
+        let report = check_audio_eq(&actual, &expected, 1e-3, 2, 48000).unwrap_err();
+        assert_eq!(without_dump_line(&report), "\
+audio differs by more than 0.001 from frame 6
+  length: 10 frames actual, 10 expected
+  max error 1.000e0 at frame 7, mean error 7.500e-2
+      frame  actual               expected
+          4  +0.080000 +0.090000  +0.080000 +0.090000
+          5  +0.100000 +0.110000  +0.100000 +0.110000
+  >       6  +0.620000 +0.130000  +0.120000 +0.130000
+          7  +0.140000 -0.850000  +0.140000 +0.150000
+          8  +0.160000 +0.170000  +0.160000 +0.170000");
+        assert!(check_audio_eq(&actual, &expected, 1.0, 2, 48000).is_ok());
+    }
+
+    #[test]
+    fn test_eq_report_length() {
+        let expected = vec![0.25; 10];
+        let report = check_audio_eq(&expected[..8], &expected, 1e-3, 1, 48000).unwrap_err();
+        assert_eq!(without_dump_line(&report), "\
+audio differs in length from frame 8
+  length: 8 frames actual, 10 expected
+  max error 0.000e0 at frame 0, mean error 0.000e0
+      frame  actual     expected
+          6  +0.250000  +0.250000
+          7  +0.250000  +0.250000
+  >       8          -  +0.250000
+          9          -  +0.250000");
+    }
+
+    #[test]
+    fn test_eq_report_nan() {
+        let report = check_audio_eq(&[0.0, f32::NAN, 0.0], &[0.0; 3], 1.0, 1, 48000)
+            .unwrap_err();
+        assert!(report.starts_with("audio differs by more than 1 from frame 1\n"), "{}", report);
+        assert!(report.contains("max error NaN at frame 1"), "{}", report);
+    }
+
+    #[test]
+    fn test_snr_report() {
+        let expected: Vec<f32> = (0..1000).map(|n| (n as f32 * 0.1).sin()).collect();
+        let mut actual = expected.clone();
+        assert_eq!(snr_db(&actual, &expected), f64::INFINITY);
+        assert!(check_audio_snr(&actual, &expected, 200.0, 1, 48000).is_ok());
+        actual[500] += 0.1;
+        let snr = snr_db(&actual, &expected);
+        assert!((snr - 10.0 * (500.0f64 / 0.01).log10()).abs() < 0.1, "{}", snr);
+        assert!(check_audio_snr(&actual, &expected, 40.0, 1, 48000).is_ok());
+        let report = check_audio_snr(&actual, &expected, 60.0, 1, 48000).unwrap_err();
+        assert!(report.starts_with(&format!(
+            "audio has a signal-to-noise ratio of {:.1} dB, less than 60.0 dB\n", snr)),
+            "{}", report);
+        assert!(report.contains("\n  >     500  "), "{}", report);
+    }
+
+    #[test]
+    #[should_panic(expected = "audio differs by more than 0.1 from frame 1")]
+    fn test_assert_audio_eq_panics() {
+        assert_audio_eq!(vec![0.0, 0.0, 0.5, 0.0], [0.0; 4], 0.1, channels = 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "less than 20.0 dB")]
+    fn test_assert_audio_snr_panics() {
+        assert_audio_snr!([1.0, 1.0], [1.0, 0.5], 20.0);
+    }
+
+    #[test]
+    fn test_dump() {
+        let (actual, expected) = dump(&[0.5, -0.5], &[0.25, -0.25, 0.0, 0.0], 2, 44100)
+            .unwrap();
+        let bytes = fs::read(&actual).unwrap();
+        assert_eq!(bytes.len(), 44 + 2 * 4);
+        assert_eq!((&bytes[..4], &bytes[8..16]), (&b"RIFF"[..], &b"WAVEfmt "[..]));
+        assert_eq!(bytes[24..28], 44100u32.to_le_bytes());
+        assert_eq!(bytes[44..48], 0.5f32.to_le_bytes());
+        assert_eq!(fs::read(&expected).unwrap().len(), 44 + 4 * 4);
+        fs::remove_file(actual).unwrap();
+        fs::remove_file(expected).unwrap();
+    }
+}
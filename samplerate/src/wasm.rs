@@ -0,0 +1,97 @@
+//! JavaScript-facing resampler, for use e.g. inside an `AudioWorklet`.
+//!
+//! Memory model: every call to [`JsResampler::process`](struct.JsResampler.html#method.process)
+//! copies its input into wasm memory once, converts it into an output buffer owned by the
+//! resampler, and returns a `Float32Array` *view* of that buffer rather than a copy. The view
+//! is only valid until the next call to `process` or `flush` on the same resampler, or until
+//! wasm memory grows (which detaches every view). Consume the samples (or copy them out with
+//! `.slice()`) before calling into the resampler again.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use js_sys::Float32Array;
+use wasm_bindgen::prelude::*;
+
+use {Converter, Error, Interpolator};
+
+fn js_error(error: Error) -> JsError {
+    JsError::new(&format!("{}", error))
+}
+
+/// Streaming resampler exported to JavaScript.
+#[wasm_bindgen]
+pub struct JsResampler {
+    converter: Converter,
+    ratio: f64,
+    input: Vec<f32>,
+    output: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl JsResampler {
+    /// Create a resampler. ``quality`` is the name of an interpolator, such as ``"best"``,
+    /// ``"medium"``, ``"fastest"``, ``"zoh"`` or ``"linear"``, parsed like
+    /// [``Interpolator::from_str``](../enum.Interpolator.html#method.from_str).
+    #[wasm_bindgen(constructor)]
+    pub fn new(quality: &str, channels: u32) -> core::result::Result<JsResampler, JsError> {
+        let interpolator = quality.parse::<Interpolator>().map_err(js_error)?;
+        let converter = Converter::new(interpolator, channels as usize).map_err(js_error)?;
+        Ok(JsResampler { converter, ratio: 1.0, input: Vec::new(), output: Vec::new() })
+    }
+
+    /// Convert interleaved samples, smoothly interpolating towards ``ratio``.
+    ///
+    /// Returns a view of the internal output buffer; see the module documentation for
+    /// how long it stays valid.
+    pub fn process(&mut self, ratio: f64, input: &Float32Array)
+            -> core::result::Result<Float32Array, JsError> {
+        self.ratio = ratio;
+        self.input.resize(input.length() as usize, 0.0);
+        input.copy_to(&mut self.input[..]);
+        self.run(false)
+    }
+
+    /// Flush the internal state of the converter at the end of the stream.
+    ///
+    /// Returns a view of the internal output buffer, like `process`.
+    pub fn flush(&mut self) -> core::result::Result<Float32Array, JsError> {
+        self.input.clear();
+        self.run(true)
+    }
+
+    /// Reset the internal state of the converter, to start a new stream after `flush`.
+    pub fn reset(&mut self) -> core::result::Result<(), JsError> {
+        self.converter.reset().map_err(js_error)
+    }
+}
+
+impl JsResampler {
+    fn run(&mut self, end: bool) -> core::result::Result<Float32Array, JsError> {
+        let channels = self.converter.channels();
+        let frames = self.input.len() / channels;
+        let estimate = ((frames as f64 * self.ratio).ceil() as usize + 64) * channels;
+        if self.output.len() < estimate {
+            self.output.resize(estimate, 0.0);
+        }
+
+        let (mut used, mut generated) = (0, 0);
+        loop {
+            let input = if end { None } else { Some(&self.input[used..]) };
+            let (u, g) = self.converter.convert(self.ratio, input, &mut self.output[generated..])
+                .map_err(js_error)?;
+            used += u;
+            generated += g;
+            if generated == self.output.len() {
+                // Out of space; there may be more to come.
+                let len = self.output.len();
+                self.output.resize(len * 2, 0.0);
+            } else if g == 0 && (end || u == 0) || !end && used == self.input.len() {
+                break
+            }
+        }
+        // The view borrows `self.output`, which is not touched again until the next call;
+        // this is the lifetime documented above.
+        Ok(unsafe { Float32Array::view(&self.output[..generated]) })
+    }
+}
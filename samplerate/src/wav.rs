@@ -0,0 +1,416 @@
+//! Resampling of WAV files using [hound](https://crates.io/crates/hound).
+//!
+//! Files are converted in fixed-size chunks, so memory use does not depend on the length of
+//! the file. Integer samples are scaled so that full scale corresponds to ±1.0, and saturated
+//! on the way back; the output has the same channel count and sample format as the input.
+//!
+//! Sampler loop points stored in the ``smpl`` chunk (which hound itself does not understand)
+//! can be carried over to the output, rescaled to the new sample rate.
+//!
+//! With the ``rayon`` feature, [``resample_dir``](fn.resample_dir.html) converts a directory
+//! of WAV files, several at a time.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::vec::Vec;
+use std::{fmt, error};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use {Converter, Error, Interpolator, try_reserve, try_vec};
+
+#[cfg(feature = "rayon")]
+pub use batch::{BatchOptions, BatchReport, ChannelMode, ConvertedFile, ExistingOutput,
+                FailedFile, resample_dir};
+
+/// Number of frames read from the input file per chunk.
+const CHUNK_FRAMES: usize = 4096;
+
+/// Size of the fixed part of the ``smpl`` chunk, preceding the loops.
+const SMPL_HEADER_LEN: usize = 36;
+/// Size of one loop record in the ``smpl`` chunk.
+const SMPL_LOOP_LEN: usize = 24;
+
+/// WAV resampling error.
+#[derive(Debug)]
+pub enum WavError {
+    /// The input could not be read or the output could not be written.
+    Wav(hound::Error),
+    /// The samples could not be converted.
+    Convert(Error),
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WavError::Wav(ref error) => write!(f, "{}", error),
+            WavError::Convert(ref error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for WavError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            WavError::Wav(ref error) => Some(error),
+            WavError::Convert(ref error) => Some(error),
+        }
+    }
+}
+
+impl From<hound::Error> for WavError {
+    fn from(error: hound::Error) -> WavError {
+        WavError::Wav(error)
+    }
+}
+
+impl From<io::Error> for WavError {
+    fn from(error: io::Error) -> WavError {
+        WavError::Wav(hound::Error::IoError(error))
+    }
+}
+
+impl From<Error> for WavError {
+    fn from(error: Error) -> WavError {
+        WavError::Convert(error)
+    }
+}
+
+/// Sampler loop, as stored in the ``smpl`` chunk.
+///
+/// Both ``start`` and ``end`` are frame indices, and ``end`` is the last frame played before
+/// jumping back to ``start``.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopPoint {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Scale loop points by a conversion ratio.
+///
+/// The start of each loop is rounded to the nearest frame. The length of each loop (rather
+/// than its end) is then scaled and rounded to the nearest number of frames, ties to even, so
+/// that the loop period, which determines the pitch of a sustained loop, is as accurate as
+/// possible; in particular, an even length stays even whenever ``ratio`` is an integer, and
+/// a loop is never shorter than one frame. Frame indices saturate at ``u32::MAX``.
+pub fn scale_loop_points(points: &[LoopPoint], ratio: f64) -> Vec<LoopPoint> {
+    points.iter()
+        .map(|point| {
+            let start = (point.start as f64 * ratio).round();
+            let length = (point.end.saturating_sub(point.start) as f64 + 1.0) * ratio;
+            let length = length.round_ties_even().max(1.0);
+            let clamp = |value: f64| value.min(u32::MAX as f64) as u32;
+            LoopPoint { start: clamp(start), end: clamp(start + length - 1.0) }
+        })
+        .collect()
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn smpl_loop_count(smpl: &[u8]) -> usize {
+    if smpl.len() < SMPL_HEADER_LEN {
+        return 0
+    }
+    (read_u32(smpl, 28) as usize).min((smpl.len() - SMPL_HEADER_LEN) / SMPL_LOOP_LEN)
+}
+
+fn smpl_loop_points(smpl: &[u8]) -> Vec<LoopPoint> {
+    (0..smpl_loop_count(smpl))
+        .map(|index| {
+            let offset = SMPL_HEADER_LEN + index * SMPL_LOOP_LEN;
+            LoopPoint { start: read_u32(smpl, offset + 8), end: read_u32(smpl, offset + 12) }
+        })
+        .collect()
+}
+
+/// Rewrite the sample period and the loop points of a ``smpl`` chunk for a new sample rate.
+fn scale_smpl(smpl: &mut [u8], ratio: f64, sample_rate: u32) {
+    if smpl.len() < SMPL_HEADER_LEN {
+        return
+    }
+    write_u32(smpl, 8, (1e9 / sample_rate as f64).round() as u32);
+    let points = scale_loop_points(&smpl_loop_points(smpl), ratio);
+    for (index, point) in points.iter().enumerate() {
+        let offset = SMPL_HEADER_LEN + index * SMPL_LOOP_LEN;
+        write_u32(smpl, offset + 8, point.start);
+        write_u32(smpl, offset + 12, point.end);
+    }
+}
+
+/// Find the contents of the first chunk with the given identifier in a RIFF file.
+fn read_chunk<R: Read + Seek>(mut file: R, id: &[u8; 4]) -> Result<Option<Vec<u8>>, WavError> {
+    let mut header = [0; 12];
+    file.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not a RIFF WAVE file").into())
+    }
+    loop {
+        let mut header = [0; 8];
+        match file.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error.into()),
+        }
+        let len = read_u32(&header, 4);
+        if &header[0..4] == id {
+            // The length comes from the file, so it may be far larger than the file itself.
+            let mut data = try_vec(0, len as usize)?;
+            file.read_exact(&mut data)?;
+            return Ok(Some(data))
+        }
+        // Chunks are padded to an even length.
+        file.seek(SeekFrom::Current(len as i64 + (len & 1) as i64))?;
+    }
+}
+
+/// Append a chunk to a RIFF file and update the RIFF size.
+fn append_chunk(path: &Path, id: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut len = file.seek(SeekFrom::End(0))?;
+    if len & 1 == 1 {
+        file.write_all(&[0])?;
+        len += 1;
+    }
+    file.write_all(id)?;
+    file.write_all(&(data.len() as u32).to_le_bytes())?;
+    file.write_all(data)?;
+    len += 8 + data.len() as u64;
+    if data.len() & 1 == 1 {
+        file.write_all(&[0])?;
+        len += 1;
+    }
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&((len - 8) as u32).to_le_bytes())?;
+    Ok(())
+}
+
+fn read_samples<R: Read>(reader: &mut WavReader<R>, spec: WavSpec, output: &mut Vec<f32>)
+        -> hound::Result<()> {
+    output.clear();
+    let len = CHUNK_FRAMES * spec.channels as usize;
+    match spec.sample_format {
+        SampleFormat::Float => {
+            for sample in reader.samples::<f32>().take(len) {
+                output.push(sample?);
+            }
+        }
+        SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f64;
+            for sample in reader.samples::<i32>().take(len) {
+                output.push((sample? as f64 / scale) as f32);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_samples<W: Write + Seek>(writer: &mut WavWriter<W>, spec: WavSpec, samples: &[f32])
+        -> hound::Result<()> {
+    match spec.sample_format {
+        SampleFormat::Float => {
+            for &sample in samples {
+                writer.write_sample(sample)?;
+            }
+        }
+        SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f64;
+            let (min, max) = (-scale, scale - 1.0);
+            for &sample in samples {
+                let value = (sample as f64 * scale).round().clamp(min, max);
+                writer.write_sample(value as i32)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Convert all samples from ``reader`` to ``writer`` at ``ratio``, averaging the channels of
+/// each frame if ``downmix`` is true, in chunks of ``CHUNK_FRAMES`` frames.
+///
+/// Returns the number of frames read and written.
+pub(crate) fn convert_stream<R: Read, W: Write + Seek>(reader: &mut WavReader<R>,
+                                                    writer: &mut WavWriter<W>,
+                                                    interpolator: Interpolator, ratio: f64,
+                                                    downmix: bool)
+        -> Result<(u64, u64), WavError> {
+    let (spec, out_spec) = (reader.spec(), writer.spec());
+    let channels = out_spec.channels as usize;
+    let mut converter = Converter::new(interpolator, channels)?;
+    let mut input_buf = Vec::new();
+    try_reserve(&mut input_buf, CHUNK_FRAMES * spec.channels as usize)?;
+    let mut output_buf = try_vec(0.0, (CHUNK_FRAMES * 2 + 64) * channels)?;
+    let (mut frames_in, mut frames_out) = (0, 0);
+    loop {
+        read_samples(reader, spec, &mut input_buf)?;
+        if downmix {
+            downmix_in_place(&mut input_buf, spec.channels as usize);
+        }
+        frames_in += (input_buf.len() / channels) as u64;
+        let mut pending = if input_buf.is_empty() { None } else { Some(&input_buf[..]) };
+        loop {
+            let (used, generated) = converter.convert(ratio, pending, &mut output_buf)?;
+            write_samples(writer, out_spec, &output_buf[..generated])?;
+            frames_out += (generated / channels) as u64;
+            match pending {
+                Some(input) if used < input.len() => pending = Some(&input[used..]),
+                Some(_) => break,
+                None if generated == 0 => break,
+                None => (),
+            }
+        }
+        if input_buf.is_empty() {
+            break
+        }
+    }
+    Ok((frames_in, frames_out))
+}
+
+/// Replace the interleaved ``samples`` with the average of the channels of each frame.
+fn downmix_in_place(samples: &mut Vec<f32>, channels: usize) {
+    for frame in 0..samples.len() / channels {
+        let frame_samples = &samples[frame * channels..(frame + 1) * channels];
+        samples[frame] = frame_samples.iter().sum::<f32>() / channels as f32;
+    }
+    samples.truncate(samples.len() / channels);
+}
+
+/// Resample a WAV file to a new sample rate.
+///
+/// If ``scale_loops`` is true and the input has a ``smpl`` chunk, it is copied to the output
+/// with its sample period and loop points rescaled using
+/// [``scale_loop_points``](fn.scale_loop_points.html); otherwise, the output only contains
+/// the audio data.
+pub fn resample_wav_file<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q, sample_rate: u32,
+                                                         interpolator: Interpolator,
+                                                         scale_loops: bool)
+        -> Result<(), WavError> {
+    let (input, output) = (input.as_ref(), output.as_ref());
+    let mut reader = WavReader::open(input)?;
+    let spec = reader.spec();
+    let ratio = sample_rate as f64 / spec.sample_rate as f64;
+    let mut writer = WavWriter::create(output, WavSpec { sample_rate, ..spec })?;
+    convert_stream(&mut reader, &mut writer, interpolator, ratio, false)?;
+    writer.finalize()?;
+
+    if scale_loops {
+        if let Some(mut smpl) = read_chunk(File::open(input)?, b"smpl")? {
+            scale_smpl(&mut smpl, ratio, sample_rate);
+            append_chunk(output, b"smpl", &smpl)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::vec::Vec;
+    use std::vec;
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(std::format!("samplerate-{}-{}.wav", std::process::id(), name))
+    }
+
+    fn make_smpl(points: &[LoopPoint], sample_rate: u32) -> Vec<u8> {
+        let mut smpl = vec![0; SMPL_HEADER_LEN + points.len() * SMPL_LOOP_LEN];
+        write_u32(&mut smpl, 8, (1e9 / sample_rate as f64) as u32);
+        write_u32(&mut smpl, 12, 60);
+        write_u32(&mut smpl, 28, points.len() as u32);
+        for (index, point) in points.iter().enumerate() {
+            let offset = SMPL_HEADER_LEN + index * SMPL_LOOP_LEN;
+            write_u32(&mut smpl, offset, index as u32);
+            write_u32(&mut smpl, offset + 8, point.start);
+            write_u32(&mut smpl, offset + 12, point.end);
+        }
+        smpl
+    }
+
+    fn write_sine(path: &Path, frames: usize, spec: WavSpec) {
+        let mut writer = WavWriter::create(path, spec).unwrap();
+        for i in 0..frames * spec.channels as usize {
+            let value = (i as f32 * 0.01).sin() * 0.5;
+            writer.write_sample((value * 32767.0) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn test_scale_loop_points() {
+        let points = [LoopPoint { start: 100, end: 199 }, LoopPoint { start: 7, end: 7 }];
+        assert_eq!(scale_loop_points(&points, 2.0),
+                   [LoopPoint { start: 200, end: 399 }, LoopPoint { start: 14, end: 15 }]);
+        assert_eq!(scale_loop_points(&points, 0.5),
+                   [LoopPoint { start: 50, end: 99 }, LoopPoint { start: 4, end: 4 }]);
+        // 75 * 0.5 = 37.5 frames rounds to 38, not 37.
+        assert_eq!(scale_loop_points(&[LoopPoint { start: 0, end: 74 }], 0.5),
+                   [LoopPoint { start: 0, end: 37 }]);
+    }
+
+    #[test]
+    fn test_resample_wav_file_loops() {
+        let (input, output) = (temp_path("loops-in"), temp_path("loops-out"));
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        write_sine(&input, 30001, spec);
+        let points = [LoopPoint { start: 1000, end: 21049 }];
+        append_chunk(&input, b"smpl", &make_smpl(&points, 44100)).unwrap();
+
+        resample_wav_file(&input, &output, 48000, Interpolator::SincFastest, true).unwrap();
+        let reader = WavReader::open(&output).unwrap();
+        assert_eq!(reader.spec(), WavSpec { sample_rate: 48000, ..spec });
+        let frames = (30001.0 * 48000.0 / 44100.0) as i64;
+        assert!((reader.duration() as i64 - frames).abs() <= 1);
+        drop(reader);
+
+        let smpl = read_chunk(File::open(&output).unwrap(), b"smpl").unwrap().unwrap();
+        assert_eq!(read_u32(&smpl, 8), 20833);
+        let scaled = smpl_loop_points(&smpl);
+        assert_eq!(scaled.len(), 1);
+        let start = 1000.0 * 48000.0 / 44100.0;
+        let end = 21050.0 * 48000.0 / 44100.0 - 1.0;
+        assert!((scaled[0].start as f64 - start).abs() <= 1.0);
+        assert!((scaled[0].end as f64 - end).abs() <= 1.0);
+
+        fs::remove_file(&input).unwrap();
+        fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn test_resample_wav_file_no_loops() {
+        let (input, output) = (temp_path("plain-in"), temp_path("plain-out"));
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        write_sine(&input, 10000, spec);
+        append_chunk(&input, b"smpl", &make_smpl(&[LoopPoint { start: 0, end: 99 }], 48000))
+            .unwrap();
+
+        resample_wav_file(&input, &output, 16000, Interpolator::Linear, false).unwrap();
+        let mut reader = WavReader::open(&output).unwrap();
+        assert_eq!(reader.spec(), WavSpec { sample_rate: 16000, ..spec });
+        assert!((reader.duration() as i64 - 3333).abs() <= 1);
+        let samples: Vec<i16> = reader.samples().map(|s| s.unwrap()).collect();
+        assert!(samples.iter().any(|&s| s > 10000));
+        assert_eq!(read_chunk(File::open(&output).unwrap(), b"smpl").unwrap(), None);
+
+        fs::remove_file(&input).unwrap();
+        fs::remove_file(&output).unwrap();
+    }
+}
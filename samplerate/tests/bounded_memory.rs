@@ -0,0 +1,73 @@
+// Checks that the chunked conversions of samples in other formats than f32 allocate nothing
+// in proportion to the length of the buffers, using a global allocator that counts the
+// bytes allocated while it is armed. libsamplerate allocates its state with malloc, which
+// the allocator does not see, but that state has the same size whatever the buffers are.
+
+extern crate samplerate;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use samplerate::{Converter, Interpolator, convert_f64};
+
+struct CountingAllocator {
+    armed: AtomicBool,
+    allocated: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if self.armed.load(Ordering::Relaxed) {
+            self.allocated.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if self.armed.load(Ordering::Relaxed) {
+            self.allocated.fetch_add(new_size, Ordering::Relaxed);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator =
+    CountingAllocator { armed: AtomicBool::new(false), allocated: AtomicUsize::new(0) };
+
+/// Run ``f`` with the allocator armed, returning the number of bytes it allocated.
+fn allocated_by<F: FnOnce()>(f: F) -> usize {
+    ALLOCATOR.allocated.store(0, Ordering::Relaxed);
+    ALLOCATOR.armed.store(true, Ordering::Relaxed);
+    f();
+    ALLOCATOR.armed.store(false, Ordering::Relaxed);
+    ALLOCATOR.allocated.load(Ordering::Relaxed)
+}
+
+// The allocator is shared by the whole process, so the cases run in one test, one after
+// another.
+#[test]
+fn test_convert_f64_bounded() {
+    // 80 MB of input, and 40 MB of output.
+    let input: Vec<f64> = (0..10_000_000).map(|n| (n as f64 * 0.01).sin() * 0.5).collect();
+    let mut output = vec![0.0; 5_000_100];
+
+    let bytes = allocated_by(|| {
+        let (used, generated) = convert_f64(Interpolator::Linear, 2, 0.5, &input, &mut output)
+            .unwrap();
+        assert_eq!(used, input.len());
+        assert!(generated >= 5_000_000);
+    });
+    assert_eq!(bytes, 0);
+
+    let mut conv = Converter::new(Interpolator::Linear, 2).unwrap();
+    let bytes = allocated_by(|| {
+        let (used, _) = conv.convert_f64(0.5, Some(&input), &mut output).unwrap();
+        assert!(used >= input.len() - 2);
+    });
+    assert_eq!(bytes, 0);
+}
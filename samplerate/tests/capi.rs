@@ -0,0 +1,85 @@
+// Builds the library as a static library with the `capi` feature, links
+// tests/capi/round_trip.c against it with the C compiler (`CC`, or `cc`), and runs it. Skipped
+// if there is no C compiler.
+#![cfg(feature = "capi")]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Build the static library, returning its path and the native libraries it needs.
+fn build_staticlib(target_dir: &Path) -> (PathBuf, Vec<String>) {
+    let output = Command::new(env!("CARGO"))
+        .args(["rustc", "-p", "samplerate", "--lib", "--crate-type", "staticlib",
+               "--features", "capi", "--message-format", "short", "--",
+               "--print", "native-static-libs"])
+        .current_dir(manifest_dir())
+        // A separate target directory avoids waiting on the lock held by `cargo test`.
+        .env("CARGO_TARGET_DIR", target_dir)
+        .output()
+        .unwrap();
+    let diagnostics = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "cannot build the static library:\n{}", diagnostics);
+    let native_libs = diagnostics.lines()
+        .find_map(|line| line.split("native-static-libs: ").nth(1))
+        .map(|libs| libs.split_whitespace().map(str::to_owned).collect())
+        .unwrap_or_default();
+    (target_dir.join("debug").join("libsamplerate.a"), native_libs)
+}
+
+#[test]
+#[cfg(unix)]
+fn test_c_round_trip() {
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    if Command::new(&compiler).arg("--version").output().is_err() {
+        eprintln!("skipping: cannot run the C compiler {:?}", compiler);
+        return
+    }
+
+    let target_dir = manifest_dir().join("../target/capi");
+    let (library, native_libs) = build_staticlib(&target_dir);
+    fs::create_dir_all(&target_dir).unwrap();
+    let program = target_dir.join("round_trip");
+    let output = Command::new(&compiler)
+        .args(["-std=c99", "-Wall", "-Werror", "-o"])
+        .arg(&program)
+        .arg("-I").arg(manifest_dir().join("include"))
+        .arg(manifest_dir().join("tests/capi/round_trip.c"))
+        .arg(&library)
+        .args(&native_libs)
+        .arg("-lm")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "cannot compile round_trip.c:\n{}",
+            String::from_utf8_lossy(&output.stderr));
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(output.status.success(), "round_trip failed:\n{}",
+            String::from_utf8_lossy(&output.stderr));
+}
+
+// cbindgen is not run by the build, so check that the header declares everything exported.
+#[test]
+fn test_header_is_current() {
+    let source = fs::read_to_string(manifest_dir().join("src/capi.rs")).unwrap();
+    let header = fs::read_to_string(manifest_dir().join("include/samplerate_rs.h")).unwrap();
+    // The tests at the end of the module export nothing.
+    let source = source.split("#[cfg(test)]").next().unwrap();
+    for line in source.lines() {
+        let name = if let Some(rest) = line.split("extern \"C\" fn ").nth(1) {
+            format!("{}(", rest.split('(').next().unwrap())
+        } else if let Some(rest) = line.strip_prefix("pub const ") {
+            format!("#define {} {}", rest.split(':').next().unwrap(),
+                    rest.split("= ").nth(1).unwrap().trim_end_matches(';'))
+        } else {
+            continue
+        };
+        assert!(header.contains(&name), "include/samplerate_rs.h lacks {:?}; regenerate it",
+                name);
+    }
+}
@@ -0,0 +1,157 @@
+/* Converts a sine up and back down through two queues, in blocks of unrelated sizes, and
+ * checks the result against the input; then pulls it through a fixed-output converter. Built
+ * and run by tests/capi.rs. */
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "samplerate_rs.h"
+
+#define CHANNELS 2
+#define FRAMES 20000
+#define SINC_BEST_QUALITY 0
+
+static int failures;
+
+#define CHECK(condition) do { \
+        if (!(condition)) { \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+            failures++; \
+        } \
+    } while (0)
+
+#define CHECK_CODE(call, expected) do { \
+        int code_ = (call); \
+        if (code_ != (expected)) { \
+            fprintf(stderr, "%s:%d: %s returned %d (%s), expected %d\n", __FILE__, __LINE__, \
+                    #call, code_, srs_strerror(code_), (expected)); \
+            failures++; \
+        } \
+    } while (0)
+
+/* Push all of input to queue in blocks of push_frames, popping blocks of pop_frames in
+ * between and after flushing; return the number of frames written to output. */
+static size_t convert(SrsQueue *queue, const float *input, size_t frames, size_t push_frames,
+                      size_t pop_frames, float *output, size_t capacity) {
+    size_t pushed = 0, popped = 0, generated;
+    while (pushed < frames) {
+        size_t block = frames - pushed < push_frames ? frames - pushed : push_frames;
+        CHECK_CODE(srs_queue_push(queue, input + pushed * CHANNELS, block), SRS_OK);
+        pushed += block;
+        do {
+            size_t room = capacity - popped < pop_frames ? capacity - popped : pop_frames;
+            CHECK_CODE(srs_queue_pop(queue, output + popped * CHANNELS, room, &generated),
+                       SRS_OK);
+            popped += generated;
+        } while (generated == pop_frames);
+    }
+    CHECK_CODE(srs_queue_flush(queue), SRS_OK);
+    CHECK_CODE(srs_queue_push(queue, input, 1), SRS_ERR_FLUSHED);
+    while (!srs_queue_is_finished(queue) && popped < capacity) {
+        size_t room = capacity - popped < pop_frames ? capacity - popped : pop_frames;
+        CHECK_CODE(srs_queue_pop(queue, output + popped * CHANNELS, room, &generated), SRS_OK);
+        popped += generated;
+    }
+    CHECK(srs_queue_is_finished(queue));
+    CHECK(srs_queue_buffered_frames(queue) == 0);
+    return popped;
+}
+
+/* Input handed out by fill: the frames not yet read. */
+struct source {
+    const float *input;
+    size_t frames;
+};
+
+static size_t fill(void *user_data, float *buffer, size_t frames) {
+    struct source *source = user_data;
+    size_t written = source->frames < frames ? source->frames : frames;
+    size_t sample;
+    for (sample = 0; sample < written * CHANNELS; sample++)
+        buffer[sample] = source->input[sample];
+    source->input += written * CHANNELS;
+    source->frames -= written;
+    return written;
+}
+
+int main(void) {
+    float *input = malloc(sizeof(float) * CHANNELS * FRAMES);
+    float *up = malloc(sizeof(float) * CHANNELS * (2 * FRAMES + 16));
+    float *down = malloc(sizeof(float) * CHANNELS * (FRAMES + 16));
+    SrsQueue *upsampler = NULL, *downsampler = NULL, *bounded = NULL;
+    SrsPull *pull = NULL;
+    struct source source;
+    size_t frame, up_frames, down_frames, generated;
+    double max_error = 0.0;
+
+    for (frame = 0; frame < FRAMES; frame++) {
+        input[frame * CHANNELS] = (float)(0.5 * sin(frame * 0.01));
+        input[frame * CHANNELS + 1] = (float)(-0.25 * sin(frame * 0.003));
+    }
+
+    CHECK_CODE(srs_queue_new(SINC_BEST_QUALITY, CHANNELS, 2.0, &upsampler), SRS_OK);
+    CHECK_CODE(srs_queue_new(SINC_BEST_QUALITY, CHANNELS, 0.5, &downsampler), SRS_OK);
+    if (!upsampler || !downsampler)
+        return 1;
+
+    up_frames = convert(upsampler, input, FRAMES, 441, 512, up, 2 * FRAMES + 16);
+    CHECK(up_frames == 2 * FRAMES);
+    down_frames = convert(downsampler, up, up_frames, 1000, 37, down, FRAMES + 16);
+    CHECK(down_frames == FRAMES);
+
+    /* Away from the edges, the round trip only adds the filter's small ripple. */
+    for (frame = 100; frame + 100 < FRAMES && frame < down_frames; frame++) {
+        int channel;
+        for (channel = 0; channel < CHANNELS; channel++) {
+            double error = fabs(down[frame * CHANNELS + channel] -
+                                input[frame * CHANNELS + channel]);
+            if (error > max_error)
+                max_error = error;
+        }
+    }
+    if (max_error > 1e-3) {
+        fprintf(stderr, "round trip error %g is too large\n", max_error);
+        failures++;
+    }
+
+    /* Starting over gives a new stream. */
+    CHECK_CODE(srs_queue_reset(upsampler), SRS_OK);
+    CHECK_CODE(srs_queue_push(upsampler, input, 10), SRS_OK);
+    CHECK_CODE(srs_queue_set_ratio(upsampler, 1000.0), SRS_ERR_BAD_RATIO);
+
+    /* Pulling the downsampled output back up in fixed blocks gives what the queue gave. */
+    source.input = down;
+    source.frames = down_frames;
+    CHECK_CODE(srs_pull_new(SINC_BEST_QUALITY, CHANNELS, 300, &pull), SRS_OK);
+    if (!pull)
+        return 1;
+    up_frames = 0;
+    while (!srs_pull_is_finished(pull) && up_frames < 2 * FRAMES + 16) {
+        size_t room = 2 * FRAMES + 16 - up_frames < 256 ? 2 * FRAMES + 16 - up_frames : 256;
+        CHECK_CODE(srs_pull_read(pull, 2.0, up + up_frames * CHANNELS, room, fill, &source,
+                                 &generated), SRS_OK);
+        up_frames += generated;
+    }
+    CHECK(srs_pull_is_finished(pull));
+    CHECK(up_frames == 2 * down_frames);
+    CHECK_CODE(srs_pull_read(pull, 2.0, up, 1, NULL, NULL, &generated), SRS_ERR_NULL_POINTER);
+
+    CHECK_CODE(srs_queue_new_bounded(SINC_BEST_QUALITY, CHANNELS, 1.0, 8, &bounded), SRS_OK);
+    CHECK_CODE(srs_queue_push(bounded, input, 9), SRS_ERR_QUEUE_FULL);
+    CHECK_CODE(srs_queue_push(bounded, input, (size_t)-1), SRS_ERR_BAD_FRAME_COUNT);
+
+    CHECK_CODE(srs_queue_new(7, CHANNELS, 1.0, &downsampler), SRS_ERR_BAD_INTERPOLATOR);
+    CHECK_CODE(srs_queue_pop(NULL, down, 1, &down_frames), SRS_ERR_NULL_POINTER);
+    CHECK(srs_strerror(SRS_ERR_PANIC) != NULL);
+
+    srs_queue_free(upsampler);
+    srs_queue_free(downsampler);
+    srs_queue_free(bounded);
+    srs_queue_free(NULL);
+    srs_pull_free(pull);
+    free(input);
+    free(up);
+    free(down);
+    return failures != 0;
+}
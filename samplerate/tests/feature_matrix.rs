@@ -0,0 +1,163 @@
+// Checks that every optional feature builds on its own and together with the others, and
+// that invalid feature combinations fail with the intended error. This runs `cargo check`
+// dozens of times, so it is skipped unless `SAMPLERATE_FEATURE_MATRIX` is set:
+//
+//     SAMPLERATE_FEATURE_MATRIX=1 cargo test -p samplerate --test feature_matrix
+//
+// The `fixed-point` feature is also checked for `thumbv6m-none-eabi`, a Cortex-M0+ without
+// an FPU, if the target is installed together with a C compiler for it, such as
+// `arm-none-eabi-gcc` or one given in `CC_thumbv6m_none_eabi`; otherwise it is skipped.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Optional features, as declared in `[features]` or implied by optional dependencies that
+/// are not referred to with `dep:`.
+fn optional_features() -> Vec<String> {
+    let manifest = fs::read_to_string(manifest_dir().join("Cargo.toml")).unwrap();
+    let mut section = String::new();
+    let mut features = Vec::new();
+    let mut optional_deps = Vec::new();
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            section = line.to_owned();
+            continue
+        }
+        let name = match line.split('=').next() {
+            Some(name) if !name.trim().is_empty() && !line.starts_with('#') => name.trim(),
+            _ => continue,
+        };
+        if section == "[features]" {
+            features.push(name.to_owned());
+        } else if section == "[dependencies]" && line.contains("optional = true") {
+            optional_deps.push(name.to_owned());
+        }
+    }
+    for dep in optional_deps {
+        if !manifest.contains(&format!("\"dep:{}\"", dep)) && !features.contains(&dep) {
+            features.push(dep);
+        }
+    }
+    features.retain(|feature| {
+        feature != "default" && feature != "system" && !feature.starts_with("vendored-")
+    });
+    features
+}
+
+fn vendored_features() -> Vec<String> {
+    let manifest = fs::read_to_string(manifest_dir().join("Cargo.toml")).unwrap();
+    manifest.lines()
+        .filter_map(|line| line.split('=').next())
+        .map(|name| name.trim())
+        .filter(|name| name.starts_with("vendored-"))
+        .map(|name| name.to_owned())
+        .collect()
+}
+
+/// Run `cargo check` on the library with the given arguments, returning whether it succeeded
+/// and its diagnostics.
+fn check(args: &[&str]) -> (bool, String) {
+    check_package("samplerate", args)
+}
+
+fn check_package(package: &str, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "-p", package, "--lib", "--quiet"])
+        .args(args)
+        .current_dir(manifest_dir())
+        // A separate target directory avoids waiting on the lock held by `cargo test`.
+        .env("CARGO_TARGET_DIR", manifest_dir().join("../target/feature-matrix"))
+        .output()
+        .unwrap();
+    (output.status.success(), String::from_utf8_lossy(&output.stderr).into_owned())
+}
+
+fn assert_builds(args: &[&str]) {
+    let (success, stderr) = check(args);
+    assert!(success, "`cargo check {}` failed:\n{}", args.join(" "), stderr);
+}
+
+/// Check whether the standard library for ``target`` is installed.
+fn target_installed(target: &str) -> bool {
+    let output = Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned()))
+        .args(["--print", "sysroot"])
+        .output()
+        .unwrap();
+    let sysroot = String::from_utf8(output.stdout).unwrap();
+    PathBuf::from(sysroot.trim()).join("lib/rustlib").join(target).exists()
+}
+
+/// Check whether the C compiler the `cc` crate would use for ``target``, or ``default`` if
+/// none is configured, can be run.
+fn cross_compiler_installed(target: &str, default: &str) -> bool {
+    let compiler = [format!("CC_{}", target), format!("CC_{}", target.replace('-', "_")),
+                    "TARGET_CC".to_owned()].iter()
+        .find_map(|name| env::var(name).ok())
+        .unwrap_or_else(|| default.to_owned());
+    Command::new(&compiler).arg("--version").output()
+        .is_ok_and(|output| output.status.success())
+}
+
+fn assert_fails(args: &[&str], message: &str) {
+    let (success, stderr) = check(args);
+    assert!(!success, "`cargo check {}` unexpectedly succeeded", args.join(" "));
+    assert!(stderr.contains(message),
+            "`cargo check {}` failed without mentioning {:?}:\n{}",
+            args.join(" "), message, stderr);
+}
+
+#[test]
+fn test_feature_matrix() {
+    if env::var_os("SAMPLERATE_FEATURE_MATRIX").is_none() {
+        eprintln!("skipping the feature matrix; set SAMPLERATE_FEATURE_MATRIX to run it");
+        return
+    }
+
+    // The default build uses std. Without it, `#![no_std]` makes any use of `std` without
+    // the feature gate a compile error.
+    assert_builds(&[]);
+    // `system` takes precedence over the default `vendored-0_1`.
+    assert_builds(&["--features", "system"]);
+    assert_builds(&["--no-default-features", "--features", "vendored-0_1"]);
+
+    let features = optional_features();
+    assert!(features.iter().any(|f| f == "alloc") && features.iter().any(|f| f == "std"));
+    for feature in &features {
+        assert_builds(&["--no-default-features", "--features",
+                        &format!("vendored-0_1,{}", feature)]);
+        assert_builds(&["--features", feature]);
+    }
+    assert_builds(&["--features", &features.join(",")]);
+
+    assert_fails(&["--no-default-features"], "no libsamplerate selected");
+    let vendored = vendored_features();
+    for (index, first) in vendored.iter().enumerate() {
+        for second in &vendored[index + 1..] {
+            assert_fails(&["--no-default-features", "--features",
+                           &format!("{},{}", first, second)], "mutually exclusive");
+        }
+    }
+
+    const NO_FPU_TARGET: &str = "thumbv6m-none-eabi";
+    const NO_FPU_CC: &str = "arm-none-eabi-gcc";
+    if !target_installed(NO_FPU_TARGET) {
+        eprintln!("skipping {}; install it with `rustup target add {}`", NO_FPU_TARGET,
+                  NO_FPU_TARGET);
+    } else if !cross_compiler_installed(NO_FPU_TARGET, NO_FPU_CC) {
+        eprintln!("skipping {}; install {} or set CC_{} to a C compiler for it",
+                  NO_FPU_TARGET, NO_FPU_CC, NO_FPU_TARGET.replace('-', "_"));
+    } else {
+        let args = ["--no-default-features", "--features", "vendored-0_1,fixed-point",
+                    "--target", NO_FPU_TARGET];
+        let (success, stderr) = check_package("samplerate-core", &args);
+        assert!(success, "`cargo check -p samplerate-core {}` failed:\n{}", args.join(" "),
+                stderr);
+    }
+}
@@ -0,0 +1,82 @@
+// Checks that the allocating helpers report allocation failure as an error instead of
+// aborting, using a global allocator that refuses large allocations while it is armed.
+#![cfg(feature = "std")]
+
+extern crate samplerate;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use samplerate::{AdaptiveConverter, CacheKey, ErrorKind, Interpolator, ResampleCache,
+                 SystemClock, resample_ir};
+
+/// Largest allocation that succeeds while the allocator is armed.
+const LIMIT: usize = 16 << 20;
+
+struct CappedAllocator {
+    armed: AtomicBool,
+}
+
+unsafe impl GlobalAlloc for CappedAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if self.armed.load(Ordering::Relaxed) && layout.size() > LIMIT {
+            return std::ptr::null_mut()
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if self.armed.load(Ordering::Relaxed) && new_size > LIMIT {
+            return std::ptr::null_mut()
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CappedAllocator = CappedAllocator { armed: AtomicBool::new(false) };
+
+fn make_input(frames: usize) -> Vec<f32> {
+    (0..frames).map(|n| (n as f32 * 0.01).sin() * 0.5).collect()
+}
+
+// The allocator is shared by the whole process, so the cases run in one test, one after
+// another.
+#[test]
+fn test_out_of_memory() {
+    // 4 MiB of input converted to 100 times as many samples.
+    let input = make_input(1 << 20);
+    ALLOCATOR.armed.store(true, Ordering::Relaxed);
+
+    // The output buffer is allocated before converting, for the whole output.
+    let expected_bytes = (((1 << 20) as f64 * 100.0) as usize + 2) * 4;
+    let error = resample_ir(&input, 1, 1000, 100000, Interpolator::Linear).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::OutOfMemory { requested_bytes: expected_bytes });
+    assert_eq!(error.to_string(), format!("cannot allocate {} bytes", expected_bytes));
+
+    // A failed conversion is not cached.
+    let cache = ResampleCache::new(usize::MAX);
+    let key = CacheKey::new(1, Interpolator::Linear, 1, 100.0);
+    let error = cache.get_or_convert(key, || &input).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::OutOfMemory { .. }));
+    assert_eq!(cache.cached_frames(), 0);
+
+    // The pending output grows while converting, and fails partway; nothing is appended.
+    let mut converter = AdaptiveConverter::with_clock(&[Interpolator::Linear], 1,
+                                                      Duration::from_secs(3600),
+                                                      SystemClock::new()).unwrap();
+    let mut output = vec![1.0, 2.0, 3.0];
+    let error = converter.convert(100.0, &input, &mut output).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::OutOfMemory { .. }));
+    assert_eq!(output, [1.0, 2.0, 3.0]);
+
+    // Conversions that fit still succeed while the allocator is armed.
+    let output = resample_ir(&input[..1000], 1, 1000, 2000, Interpolator::Linear).unwrap();
+    assert_eq!(output.len(), 2000);
+    ALLOCATOR.armed.store(false, Ordering::Relaxed);
+}
@@ -0,0 +1,127 @@
+// Converts a temporary directory with a mix of valid, corrupt and unrelated files with
+// `wav::resample_dir`, and checks the report and the output files.
+#![cfg(feature = "rayon")]
+
+extern crate hound;
+extern crate samplerate;
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use samplerate::wav::{BatchOptions, BatchReport, ChannelMode, ExistingOutput, WavError,
+                      resample_dir};
+
+fn write_wav(path: &Path, frames: usize, spec: WavSpec) {
+    let mut writer = WavWriter::create(path, spec).unwrap();
+    for i in 0..frames * spec.channels as usize {
+        let value = ((i / spec.channels as usize) as f32 * 0.01).sin() * 0.5;
+        match spec.sample_format {
+            SampleFormat::Int => writer.write_sample((value * 32767.0) as i16).unwrap(),
+            SampleFormat::Float => writer.write_sample(value).unwrap(),
+        }
+    }
+    writer.finalize().unwrap();
+}
+
+fn file_names(paths: &[PathBuf]) -> Vec<String> {
+    paths.iter().map(|path| path.file_name().unwrap().to_string_lossy().into_owned()).collect()
+}
+
+fn failed_names(report: &BatchReport) -> Vec<String> {
+    file_names(&report.failed.iter().map(|file| file.input.clone()).collect::<Vec<_>>())
+}
+
+#[test]
+fn test_resample_dir() {
+    let root = env::temp_dir().join(format!("samplerate-batch-{}", std::process::id()));
+    let (input_dir, output_dir) = (root.join("in"), root.join("out"));
+    fs::create_dir_all(&input_dir).unwrap();
+
+    write_wav(&input_dir.join("a.wav"), 48000, WavSpec {
+        channels: 2,
+        sample_rate: 48000,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    });
+    write_wav(&input_dir.join("b.WAV"), 22050, WavSpec {
+        channels: 1,
+        sample_rate: 44100,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    });
+    fs::write(input_dir.join("corrupt.wav"), b"RIFF\x10\0\0\0not a wave file").unwrap();
+    // The data chunk claims more samples than the file holds.
+    let truncated = input_dir.join("truncated.wav");
+    write_wav(&truncated, 20000, WavSpec {
+        channels: 1,
+        sample_rate: 48000,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    });
+    let len = fs::metadata(&truncated).unwrap().len();
+    OpenOptions::new().write(true).open(&truncated).unwrap().set_len(len / 2).unwrap();
+    fs::write(input_dir.join("notes.txt"), b"not audio").unwrap();
+
+    let mut options = BatchOptions::new(16000);
+    options.channels = ChannelMode::Downmix;
+    options.file_name = "{stem}_{rate}.wav".to_owned();
+    options.max_parallel = 2;
+    let report = resample_dir(&input_dir, &output_dir, &options).unwrap();
+
+    let outputs: Vec<PathBuf> = report.converted.iter().map(|file| file.output.clone())
+        .collect();
+    assert_eq!(file_names(&outputs), ["a_16000.wav", "b_16000.wav"]);
+    assert_eq!(report.converted.iter().map(|file| file.frames_in).collect::<Vec<_>>(),
+               [48000, 22050]);
+    assert_eq!(report.total_frames, 48000 + 22050);
+    assert!(report.skipped.is_empty());
+    assert_eq!(failed_names(&report), ["corrupt.wav", "truncated.wav"]);
+    for file in &report.failed {
+        // Both are reported as unreadable input, rather than as a conversion error.
+        assert!(matches!(file.error, WavError::Wav(_)), "{}: {}", file.input.display(),
+                file.error);
+    }
+
+    for (file, frames) in report.converted.iter().zip([16000, 8000]) {
+        let reader = WavReader::open(&file.output).unwrap();
+        assert_eq!((reader.spec().sample_rate, reader.spec().channels), (16000, 1));
+        assert_eq!(reader.duration() as u64, file.frames_out);
+        assert!((file.frames_out as i64 - frames).abs() <= 1, "{}", file.frames_out);
+    }
+    // Failed files leave nothing behind, not even a partial output.
+    let mut written: Vec<PathBuf> = fs::read_dir(&output_dir).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    written.sort();
+    assert_eq!(written, outputs);
+
+    // Existing outputs are failed, skipped, or replaced, as the options say.
+    let report = resample_dir(&input_dir, &output_dir, &options).unwrap();
+    assert_eq!(failed_names(&report), ["a.wav", "b.WAV", "corrupt.wav", "truncated.wav"]);
+    match report.failed[0].error {
+        WavError::Wav(hound::Error::IoError(ref error)) =>
+            assert_eq!(error.kind(), io::ErrorKind::AlreadyExists),
+        ref error => panic!("unexpected error {}", error),
+    }
+    options.existing_output = ExistingOutput::Skip;
+    let report = resample_dir(&input_dir, &output_dir, &options).unwrap();
+    assert_eq!(file_names(&report.skipped), ["a.wav", "b.WAV"]);
+    assert!(report.converted.is_empty() && report.total_frames == 0);
+    options.existing_output = ExistingOutput::Overwrite;
+    let report = resample_dir(&input_dir, &output_dir, &options).unwrap();
+    assert_eq!(report.converted.len(), 2);
+    assert_eq!(failed_names(&report), ["corrupt.wav", "truncated.wav"]);
+
+    // Without a ``{stem}``, every file maps to the same output, and only the first claims it.
+    options.file_name = "all.wav".to_owned();
+    options.channels = ChannelMode::Keep;
+    let report = resample_dir(&input_dir, root.join("collide"), &options).unwrap();
+    assert_eq!(file_names(&report.converted.iter().map(|file| file.output.clone())
+                              .collect::<Vec<_>>()), ["all.wav"]);
+    assert_eq!(failed_names(&report), ["b.WAV", "corrupt.wav", "truncated.wav"]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,179 @@
+// Streams many hours of synthetic audio through one converter, in chunks of varying sizes
+// and with occasional ratio changes, to catch problems that only show up in long-running
+// streams: drift of the output position, counters overflowing, memory growing, and glitches.
+// It is ignored by default, and runs for the number of hours of audio set in
+// `SAMPLERATE_SOAK_HOURS`. Build it optimized; it converts about 100 hours per hour then:
+//
+//     SAMPLERATE_SOAK_HOURS=48 cargo test -p samplerate --release --features std \
+//         --test soak -- --ignored --nocapture
+#![cfg(feature = "std")]
+
+extern crate samplerate;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use samplerate::{ClickDetector, Converter, Interpolator};
+
+const RATE: u64 = 48000;
+const CHANNELS: usize = 2;
+const MAX_CHUNK_FRAMES: usize = 4096;
+
+/// Conversion ratios, as fractions: between 44.1 and 48 kHz, and clock drift correction.
+const RATIOS: [(u64, u64); 5] = [(1, 1), (160, 147), (147, 160), (1001, 1000), (999, 1000)];
+/// Least common multiple of the numerators of ``RATIOS``.
+const NUMERATOR_LCM: u64 = 160 * 147 * 1001 * 999 / (7 * 3);
+/// Mean number of chunks between ratio changes, about a minute of audio.
+const CHUNKS_PER_CHANGE: u64 = 1500;
+
+/// Largest difference between the number of input frames used and the exact input position
+/// of the output generated, i.e. the number of output frames at each ratio divided by it.
+/// The converter reads ahead of the position by the width of its filter, which depends on
+/// the ratio, but not on the length of the stream.
+const MAX_DRIFT_FRAMES: f64 = 32.0;
+
+/// Largest growth of the resident set after the first hour, for allocator noise.
+const MAX_RSS_GROWTH: usize = 1 << 20;
+
+struct CountingAllocator {
+    allocations: AtomicUsize,
+    live_bytes: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.live_bytes.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.live_bytes.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.live_bytes.fetch_add(new_size, Ordering::Relaxed);
+        self.live_bytes.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    allocations: AtomicUsize::new(0),
+    live_bytes: AtomicUsize::new(0),
+};
+
+/// Retrieve the resident set size of the process, which includes the memory libsamplerate
+/// allocates, where it is known.
+fn resident_bytes() -> Option<usize> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: usize = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+/// Deterministic pseudo-random numbers, so that a failure can be reproduced.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// Two sines, at 440 Hz on the left and 1 kHz on the right. The phases are kept in range so
+/// that the signal does not lose precision as the stream goes on.
+struct Signal {
+    phases: [f64; CHANNELS],
+}
+
+impl Signal {
+    fn fill(&mut self, samples: &mut [f32]) {
+        const STEPS: [f64; CHANNELS] = [2.0 * PI * 440.0 / RATE as f64,
+                                        2.0 * PI * 1000.0 / RATE as f64];
+        for frame in samples.chunks_mut(CHANNELS) {
+            for ((sample, phase), step) in frame.iter_mut().zip(&mut self.phases).zip(STEPS) {
+                *sample = (phase.sin() * 0.5) as f32;
+                *phase = (*phase + step) % (2.0 * PI);
+            }
+        }
+    }
+}
+
+#[test]
+#[ignore = "runs for a long time; set SAMPLERATE_SOAK_HOURS"]
+fn test_soak() {
+    let hours: u64 = match env::var("SAMPLERATE_SOAK_HOURS") {
+        Ok(hours) => hours.parse().expect("SAMPLERATE_SOAK_HOURS must be a number of hours"),
+        Err(_) => {
+            eprintln!("skipping the soak test; set SAMPLERATE_SOAK_HOURS to run it");
+            return
+        }
+    };
+
+    let (max_num, max_den) = RATIOS[1];
+    let mut input = vec![0.0; MAX_CHUNK_FRAMES * CHANNELS];
+    let mut output = vec![0.0; (MAX_CHUNK_FRAMES * max_num as usize / max_den as usize + 64) *
+                               CHANNELS];
+    let mut signal = Signal { phases: [0.0; CHANNELS] };
+    let mut random = Lcg(1);
+    let mut detector =
+        ClickDetector::new(Converter::new(Interpolator::SincFastest, CHANNELS).unwrap());
+
+    let (mut num, mut den) = RATIOS[0];
+    // Exact input position of the output generated so far, times ``NUMERATOR_LCM``.
+    let mut position: u128 = 0;
+    let mut frames_in: u64 = 0;
+    let mut first_resident = None;
+    for hour in 1..=hours {
+        let allocations = ALLOCATOR.allocations.load(Ordering::Relaxed);
+        let live_bytes = ALLOCATOR.live_bytes.load(Ordering::Relaxed);
+        while frames_in < hour * 3600 * RATE {
+            if random.next(CHUNKS_PER_CHANGE) == 0 {
+                let (n, d) = RATIOS[random.next(RATIOS.len() as u64) as usize];
+                num = n;
+                den = d;
+                detector.converter().set_ratio(num as f64 / den as f64).unwrap();
+            }
+            let frames = 1 + random.next(MAX_CHUNK_FRAMES as u64) as usize;
+            let chunk = &mut input[..frames * CHANNELS];
+            signal.fill(chunk);
+
+            let mut used = 0;
+            while used < chunk.len() {
+                let (u, g) = detector.convert(num as f64 / den as f64, Some(&chunk[used..]),
+                                              &mut output).unwrap();
+                assert!(u > 0, "converter stalled after {} input frames", frames_in);
+                used += u;
+                position += (g / CHANNELS) as u128 * (den * (NUMERATOR_LCM / num)) as u128;
+            }
+            frames_in += frames as u64;
+        }
+
+        let converter = detector.converter();
+        assert_eq!(converter.frames_in(), frames_in);
+        let frames_out = converter.frames_out();
+        assert_eq!(detector.frames(), frames_out);
+        let drift = frames_in as f64 - position as f64 / NUMERATOR_LCM as f64;
+        assert!((0.0..=MAX_DRIFT_FRAMES).contains(&drift),
+                "output drifted by {} frames after {} hours", drift, hour);
+        assert_eq!(detector.clicks(), [0u64; 0], "glitches after {} hours", hour);
+
+        assert_eq!(ALLOCATOR.allocations.load(Ordering::Relaxed), allocations,
+                   "allocated while streaming in hour {}", hour);
+        assert_eq!(ALLOCATOR.live_bytes.load(Ordering::Relaxed), live_bytes,
+                   "memory grew in hour {}", hour);
+        let resident = resident_bytes();
+        if let (Some(first), Some(resident)) = (*first_resident.get_or_insert(resident), resident) {
+            assert!(resident <= first + MAX_RSS_GROWTH,
+                    "resident set grew from {} to {} bytes in {} hours", first, resident, hour);
+        }
+        eprintln!("hour {}: drift {:.1} frames, {} bytes resident", hour, drift,
+                  resident.map_or("?".to_owned(), |resident| resident.to_string()));
+    }
+}
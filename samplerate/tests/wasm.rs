@@ -0,0 +1,39 @@
+// Run with `wasm-pack test --node -- --features wasm-bindgen`.
+#![cfg(all(target_arch = "wasm32", feature = "wasm-bindgen"))]
+
+extern crate js_sys;
+extern crate samplerate;
+extern crate wasm_bindgen_test;
+
+use js_sys::Float32Array;
+use samplerate::wasm::JsResampler;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn test_js_resampler_2x() {
+    let input: Vec<f32> = (0..1000)
+        .map(|i| (i as f32 * std::f32::consts::PI * 2.0 / 1000.0).sin())
+        .collect();
+    let mut resampler = JsResampler::new("best", 1).unwrap();
+    let mut output = resampler.process(2.0, &Float32Array::from(&input[..])).unwrap().to_vec();
+    output.extend(resampler.flush().unwrap().to_vec());
+    assert_eq!(output.len(), 2000);
+    for (i, o) in output.iter().enumerate().skip(10).take(1980) {
+        let e = (i as f32 * std::f32::consts::PI * 2.0 / 2000.0).sin();
+        assert!((o - e).abs() < 0.05);
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_js_resampler_reset() {
+    let input: Vec<f32> = (0..1000).map(|i| (i as f32 * 0.05).sin()).collect();
+    let mut resampler = JsResampler::new("Sinc-Fastest", 1).unwrap();
+    let mut first = resampler.process(1.5, &Float32Array::from(&input[..])).unwrap().to_vec();
+    first.extend(resampler.flush().unwrap().to_vec());
+    assert!(resampler.process(1.5, &Float32Array::from(&input[..])).is_err());
+
+    resampler.reset().unwrap();
+    let mut second = resampler.process(1.5, &Float32Array::from(&input[..])).unwrap().to_vec();
+    second.extend(resampler.flush().unwrap().to_vec());
+    assert_eq!(first, second);
+}
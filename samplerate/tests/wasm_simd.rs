@@ -0,0 +1,113 @@
+// Differential tests for the `wasm-simd` feature: the SIMD128 build must give the same output
+// as the scalar one. They also run natively, where they check the scalar build against the
+// same references. Run on wasm32 with
+// `RUSTFLAGS="-C target-feature=+simd128" wasm-pack test --node -- --features wasm-simd`.
+
+#[cfg(target_arch = "wasm32")]
+extern crate wasm_bindgen_test;
+extern crate samplerate;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use samplerate::{Interpolator, Sample, convert_generic};
+
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Samples that exercise rounding and saturation on the way back to ``i16``, followed by
+/// random bit patterns, which include NaNs and infinities.
+fn make_floats(len: usize) -> Vec<f32> {
+    let edges = [0.0, -0.0, 1.0, -1.0, 2.0, -2.0, 0.5 / 32768.0, -0.5 / 32768.0,
+                 1.5 / 32768.0, -1.5 / 32768.0, 32767.5 / 32768.0, -32768.5 / 32768.0,
+                 f32::NAN, f32::INFINITY, f32::NEG_INFINITY, f32::MIN_POSITIVE];
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let mut floats = edges.to_vec();
+    while floats.len() < len {
+        let bits = xorshift(&mut state);
+        floats.push(if bits & 1 == 0 {
+            f32::from_bits(bits as u32)
+        } else {
+            (bits >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+        });
+    }
+    floats
+}
+
+/// Digest of the bit patterns of ``samples``, to compare long outputs against a reference.
+#[cfg(not(any(feature = "system", feature = "double-precision")))]
+fn digest(samples: &[f32]) -> u64 {
+    samples.iter().fold(0xcbf2_9ce4_8422_2325, |hash, sample| {
+        (hash ^ sample.to_bits() as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// The lengths are not multiples of the vector width, so the scalar tails are covered too.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_i16_slice_conversions() {
+    let samples: Vec<i16> = (i16::MIN..=i16::MAX).chain([0, 1, 2]).collect();
+    let mut floats = vec![0.0; samples.len()];
+    i16::slice_to_f32(&samples, &mut floats);
+    for (&sample, &float) in samples.iter().zip(&floats) {
+        assert_eq!(float.to_bits(), sample.to_f32().to_bits(), "{}", sample);
+    }
+
+    let floats = make_floats(100_003);
+    let mut samples = vec![0; floats.len()];
+    i16::slice_from_f32(&floats, &mut samples);
+    for (&float, &sample) in floats.iter().zip(&samples) {
+        assert_eq!(sample, i16::from_f32(float), "{:e}", float);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_i16_conversion_matches_f32() {
+    let mut state = 1;
+    let input: Vec<i16> = (0..20_002).map(|_| xorshift(&mut state) as i16 / 2).collect();
+    let mut output = vec![0; 48_000];
+    let (used, generated) = convert_generic(Interpolator::SincFastest, 2, 48000.0 / 44100.0,
+                                            &input, &mut output).unwrap();
+
+    let input_f32: Vec<f32> = input.iter().map(|&sample| sample.to_f32()).collect();
+    let mut output_f32 = vec![0.0; output.len()];
+    let expected = convert_generic(Interpolator::SincFastest, 2, 48000.0 / 44100.0,
+                                   &input_f32, &mut output_f32).unwrap();
+    assert_eq!((used, generated), expected);
+    for (&sample, &float) in output[..generated].iter().zip(&output_f32) {
+        assert_eq!(sample, i16::from_f32(float));
+    }
+}
+
+// libsamplerate does not reassociate floating point operations, so vectorizing it leaves the
+// output unchanged; the references were produced by the scalar build of the vendored library
+// in single precision.
+#[cfg(not(any(feature = "system", feature = "double-precision")))]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_converter_matches_scalar_build() {
+    let mut state = 2;
+    let input: Vec<f32> = (0..2 * 4410)
+        .map(|_| (xorshift(&mut state) >> 40) as f32 / (1u64 << 24) as f32 - 0.5)
+        .collect();
+    // Number of generated samples and their digest.
+    let references = [
+        (Interpolator::SincBestQuality, (9602, 0xe6c9_84e3_601f_e16b)),
+        (Interpolator::SincFastest, (9602, 0xb635_b014_784c_f135)),
+        (Interpolator::Linear, (9600, 0x4719_fe7a_f87c_3dce)),
+    ];
+    for &(interpolator, reference) in &references {
+        let mut converter = samplerate::Converter::new(interpolator, 2).unwrap();
+        let mut output = vec![0.0; 2 * 4800 + 64];
+        let (_, mut generated) =
+            converter.convert(48000.0 / 44100.0, Some(&input), &mut output).unwrap();
+        generated += converter.convert(48000.0 / 44100.0, None, &mut output[generated..])
+            .unwrap().1;
+        assert_eq!((generated, digest(&output[..generated])), reference, "{:?}", interpolator);
+    }
+}